@@ -0,0 +1,47 @@
+//! Steady-state calls of an async function whose future doesn't fit in the default inline
+//! storage, with and without allocation reuse.
+use std::{
+    hint::black_box,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use divan::{AllocProfiler, Bencher};
+use dyn_fn::{LocalDynAsyncFn, storage};
+use higher_kinded_types::ForFixed;
+
+#[global_allocator]
+static ALLOC: AllocProfiler = AllocProfiler::system();
+
+// see async_trait bench
+pub trait FutureExt: Future + Sized {
+    #[inline(always)]
+    fn now_or_never(self) -> Option<Self::Output> {
+        match pin!(self).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(x) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+impl<F: Future> FutureExt for F {}
+
+fn callback<S: storage::StorageMut>()
+-> LocalDynAsyncFn<'static, ForFixed<usize>, ForFixed<usize>, storage::Box, S> {
+    LocalDynAsyncFn::new(async |x, _| {
+        // keep a large buffer across an await point, so the future spills
+        let buffer = black_box([x; 32]);
+        async {}.await;
+        buffer.iter().sum()
+    })
+}
+
+#[divan::bench(types = [storage::Box, storage::BoxCached])]
+fn call<S: storage::StorageMut>(b: Bencher) {
+    let f = black_box(callback::<S>());
+    b.bench_local(|| f.call(black_box(1)).now_or_never());
+}
+
+fn main() {
+    divan::main();
+}
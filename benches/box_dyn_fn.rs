@@ -1,7 +1,7 @@
 use std::hint::black_box;
 
 use divan::Bencher;
-use dyn_fn::LocalDynFn;
+use dyn_fn::{LocalDynFn, LocalFastDynFn};
 use higher_kinded_types::{ForFixed, ForRef};
 
 #[divan::bench]
@@ -18,6 +18,14 @@ fn dyn_fn(b: Bencher) {
     b.bench_local(|| f.call("test"));
 }
 
+#[divan::bench]
+fn fast_dyn_fn(b: Bencher) {
+    let f = black_box(LocalFastDynFn::<ForRef<str>, ForFixed<usize>>::new(
+        |s: &str, _| s.len(),
+    ));
+    b.bench_local(|| f.call("test"));
+}
+
 fn main() {
     divan::main();
 }
@@ -0,0 +1,38 @@
+use std::{hint::black_box, mem::MaybeUninit};
+
+use divan::Bencher;
+use dyn_fn::LocalDynFn;
+use higher_kinded_types::ForFixed;
+
+type Telemetry = [u64; 64];
+
+fn decode(seed: u64) -> Telemetry {
+    let mut telemetry = [0; 64];
+    for (i, x) in telemetry.iter_mut().enumerate() {
+        *x = seed.wrapping_mul(i as u64);
+    }
+    telemetry
+}
+
+#[divan::bench]
+fn call(b: Bencher) {
+    let f = black_box(LocalDynFn::<ForFixed<u64>, ForFixed<Telemetry>>::new(
+        |seed, _| decode(seed),
+    ));
+    b.bench_local(|| f.call(black_box(42)));
+}
+
+#[divan::bench]
+fn call_into(b: Bencher) {
+    let f = black_box(LocalDynFn::<ForFixed<u64>, ForFixed<Telemetry>>::new(
+        |seed, _| decode(seed),
+    ));
+    let mut out = MaybeUninit::uninit();
+    b.bench_local(|| {
+        f.call_into(black_box(42), &mut out);
+    });
+}
+
+fn main() {
+    divan::main();
+}
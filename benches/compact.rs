@@ -0,0 +1,40 @@
+//! Calls over a large array of callbacks, whose handles and allocations don't fit in cache.
+use std::hint::black_box;
+
+use divan::Bencher;
+use dyn_fn::{CompactDynFn, DynFn};
+use higher_kinded_types::ForFixed;
+
+const LEN: usize = 1 << 16;
+
+fn callbacks<F>(new: impl Fn(usize) -> F) -> Vec<F> {
+    (0..LEN).map(new).collect()
+}
+
+#[divan::bench]
+fn box_dyn_fn(b: Bencher) {
+    let callbacks = black_box(callbacks(|i| {
+        Box::new(move |x: usize| x ^ i) as Box<dyn Fn(usize) -> usize + Send + Sync>
+    }));
+    b.bench_local(|| callbacks.iter().fold(0, |acc, f| f(acc)));
+}
+
+#[divan::bench]
+fn dyn_fn(b: Bencher) {
+    let callbacks = black_box(callbacks(|i| {
+        DynFn::<ForFixed<usize>, ForFixed<usize>>::new(move |x, _| x ^ i)
+    }));
+    b.bench_local(|| callbacks.iter().fold(0, |acc, f| f.call(acc)));
+}
+
+#[divan::bench]
+fn compact_dyn_fn(b: Bencher) {
+    let callbacks = black_box(callbacks(|i| {
+        CompactDynFn::<ForFixed<usize>, ForFixed<usize>>::new(move |x, _| x ^ i)
+    }));
+    b.bench_local(|| callbacks.iter().fold(0, |acc, f| f.call(acc)));
+}
+
+fn main() {
+    divan::main();
+}
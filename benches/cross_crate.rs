@@ -0,0 +1,81 @@
+//! Bench targets are compiled as separate crates, linked to `dyn_fn` without LTO,
+//! so these benchmarks measure the call path as seen from a downstream crate.
+use std::{
+    hint::black_box,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use divan::Bencher;
+use dyn_fn::{DynAsyncFn, DynFn, DynFnMut, storage};
+use higher_kinded_types::{ForFixed, ForRef};
+
+// see async_trait bench
+pub trait FutureExt: Future + Sized {
+    #[inline(always)]
+    fn now_or_never(self) -> Option<Self::Output> {
+        match pin!(self).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(x) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+impl<F: Future> FutureExt for F {}
+
+#[divan::bench]
+fn box_dyn_fn(b: Bencher) {
+    let f = black_box(Box::new(|s: &str| s.len()) as Box<dyn Fn(&str) -> usize + Send + Sync>);
+    b.bench_local(|| f(black_box("test")));
+}
+
+#[divan::bench]
+fn dyn_fn(b: Bencher) {
+    let f = black_box(DynFn::<ForRef<str>, ForFixed<usize>>::new(|s: &str, _| {
+        s.len()
+    }));
+    b.bench_local(|| f.call(black_box("test")));
+}
+
+#[divan::bench]
+fn dyn_fn_raw(b: Bencher) {
+    let f = black_box(DynFn::<ForRef<str>, ForFixed<usize>, storage::Raw<0>>::new(
+        |s: &str, _| s.len(),
+    ));
+    b.bench_local(|| f.call(black_box("test")));
+}
+
+#[divan::bench]
+fn box_dyn_fn_mut(b: Bencher) {
+    let mut f =
+        black_box(Box::new(|s: &str| s.len()) as Box<dyn FnMut(&str) -> usize + Send + Sync>);
+    b.bench_local(|| f(black_box("test")));
+}
+
+#[divan::bench]
+fn dyn_fn_mut(b: Bencher) {
+    let mut f = black_box(DynFnMut::<ForRef<str>, ForFixed<usize>>::new(
+        |s: &str, _| s.len(),
+    ));
+    b.bench_local(|| f.call(black_box("test")));
+}
+
+#[divan::bench]
+fn dyn_async_fn_call_sync(b: Bencher) {
+    let f = black_box(DynAsyncFn::<ForRef<str>, ForFixed<usize>>::new_sync(
+        |s: &str, _| s.len(),
+    ));
+    b.bench_local(|| f.call_sync(black_box("test")));
+}
+
+#[divan::bench]
+fn dyn_async_fn_call_try_sync(b: Bencher) {
+    let f = black_box(DynAsyncFn::<ForRef<str>, ForFixed<usize>>::new_sync(
+        |s: &str, _| s.len(),
+    ));
+    b.bench_local(|| f.call_try_sync(black_box("test")).now_or_never());
+}
+
+fn main() {
+    divan::main();
+}
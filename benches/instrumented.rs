@@ -0,0 +1,33 @@
+//! Calls of a dynamic function, with and without instrumentation.
+use std::hint::black_box;
+
+use divan::Bencher;
+use dyn_fn::{DynCallable, DynFn, hkt::ForFixed, storage};
+
+type Callback = DynFn<'static, ForFixed<u64>, ForFixed<Result<u64, ()>>, storage::Raw<8>>;
+
+fn new() -> Callback {
+    Callback::new(|x, _| x.checked_mul(3).ok_or(()))
+}
+
+#[divan::bench]
+fn plain(b: Bencher) {
+    let f = black_box(new());
+    b.bench_local(|| f.call(black_box(42)));
+}
+
+#[divan::bench]
+fn instrumented(b: Bencher) {
+    let f = black_box(new().instrumented());
+    b.bench_local(|| f.call(black_box(42)));
+}
+
+#[divan::bench]
+fn instrumented_errors(b: Bencher) {
+    let f = black_box(new().instrumented().count_errors());
+    b.bench_local(|| f.call(black_box(42)));
+}
+
+fn main() {
+    divan::main();
+}
@@ -0,0 +1,76 @@
+//! Dispatch over a heterogeneous registry, alternating synchronous and asynchronous callbacks.
+use std::{
+    hint::black_box,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use divan::Bencher;
+use dyn_fn::LocalDynAsyncFn;
+use higher_kinded_types::{ForFixed, ForRef};
+
+// see async_trait bench
+pub trait FutureExt: Future + Sized {
+    #[inline(always)]
+    fn now_or_never(self) -> Option<Self::Output> {
+        match pin!(self).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(x) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+impl<F: Future> FutureExt for F {}
+
+const LEN: usize = 64;
+
+type Callback = LocalDynAsyncFn<'static, ForRef<str>, ForFixed<usize>>;
+
+fn registry() -> Vec<Callback> {
+    (0..LEN)
+        .map(|i| {
+            if i % 2 == 0 {
+                Callback::new_sync(move |s: &str, _| s.len() + i)
+            } else {
+                Callback::new(async move |s: &str, _| s.len() + i)
+            }
+        })
+        .collect()
+}
+
+#[divan::bench]
+fn call_try_sync(b: Bencher) {
+    let registry = black_box(registry());
+    b.bench_local(|| {
+        let mut sum = 0;
+        for f in &registry {
+            sum += f.call_try_sync(black_box("test")).now_or_never().unwrap();
+        }
+        sum
+    });
+}
+
+#[divan::bench]
+fn call_sync_or_call(b: Bencher) {
+    let registry = black_box(registry());
+    b.bench_local(|| {
+        let mut sum = 0;
+        for f in &registry {
+            sum += match f.call_sync(black_box("test")) {
+                Some(ret) => ret,
+                None => f.call(black_box("test")).now_or_never().unwrap(),
+            };
+        }
+        sum
+    });
+}
+
+#[divan::bench]
+fn is_sync(b: Bencher) {
+    let registry = black_box(registry());
+    b.bench_local(|| registry.iter().filter(|f| f.is_sync()).count());
+}
+
+fn main() {
+    divan::main();
+}
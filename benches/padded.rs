@@ -0,0 +1,42 @@
+//! Two threads replacing and calling adjacent entries of a callback array, with and without
+//! padding the entries to a cache line.
+use std::{hint::black_box, thread};
+
+use divan::Bencher;
+use dyn_fn::{DynFn, Padded, hkt::ForFixed, storage};
+
+const ITERATIONS: usize = 1 << 16;
+
+type Callback = DynFn<'static, ForFixed<usize>, ForFixed<usize>, storage::Raw<8>>;
+
+fn hammer<T: Send>(entries: &mut [T; 2], new: impl Fn(usize) -> T + Sync, call: fn(&T) -> usize) {
+    let new = &new;
+    thread::scope(|s| {
+        for entry in entries {
+            s.spawn(move || {
+                for i in 0..ITERATIONS {
+                    *entry = new(i);
+                    black_box(call(black_box(&*entry)));
+                }
+            });
+        }
+    });
+}
+
+#[divan::bench]
+fn unpadded(b: Bencher) {
+    let new = |i: usize| Callback::new(move |x, _| x ^ i);
+    let mut entries = [new(0), new(1)];
+    b.bench_local(|| hammer(&mut entries, new, |f| f.call(0)));
+}
+
+#[divan::bench]
+fn padded(b: Bencher) {
+    let new = |i: usize| Padded::<_, 128>::new(Callback::new(move |x, _| x ^ i));
+    let mut entries = [new(0), new(1)];
+    b.bench_local(|| hammer(&mut entries, new, |f| f.call(0)));
+}
+
+fn main() {
+    divan::main();
+}
@@ -0,0 +1,31 @@
+//! Inline and spilled paths of [`RawOrBox`](storage::RawOrBox), compared to plain storages.
+use std::hint::black_box;
+
+use divan::Bencher;
+use dyn_fn::{LocalDynFn, storage};
+use higher_kinded_types::ForFixed;
+
+type Callback<S> = LocalDynFn<'static, ForFixed<usize>, ForFixed<usize>, S>;
+
+type Inline = storage::RawOrBox<{ 4 * size_of::<usize>() }>;
+type Spilled = storage::RawOrBox<0>;
+
+fn new<S: storage::Storage>() -> Callback<S> {
+    let captured = black_box([1usize, 2, 3]);
+    Callback::new(move |x, _| x + captured[0] + captured[1] + captured[2])
+}
+
+#[divan::bench(types = [storage::Raw<{ 4 * size_of::<usize>() }>, Inline, Spilled, storage::Box])]
+fn call<S: storage::Storage>(b: Bencher) {
+    let f = black_box(new::<S>());
+    b.bench_local(|| f.call(black_box(0)));
+}
+
+#[divan::bench(types = [storage::Raw<{ 4 * size_of::<usize>() }>, Inline, Spilled, storage::Box])]
+fn new_drop<S: storage::Storage>(b: Bencher) {
+    b.bench_local(|| drop(black_box(new::<S>())));
+}
+
+fn main() {
+    divan::main();
+}
@@ -0,0 +1,107 @@
+//! Polls asynchronous functions with arbitrary poll sequences, dropping the returned future
+//! at an arbitrary point, and checks that the future storage is handled soundly.
+//!
+//! Run with `cargo +nightly fuzz run async_poll` (ASAN is enabled by default).
+#![no_main]
+
+use std::{
+    cell::Cell,
+    future::{Future, poll_fn},
+    pin::pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use arbitrary::Arbitrary;
+use dyn_fn::{
+    LocalDynAsyncFn,
+    hkt::ForFixed,
+    storage::{Box, Raw, RawOrBox, StorageMut},
+};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Arbitrary)]
+enum FutureStorageKind {
+    Raw,
+    RawOrBox,
+    Box,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    bytes: [u8; 64],
+    yields: u8,
+    polls: u8,
+    sync: bool,
+    future_storage: FutureStorageKind,
+}
+
+/// Counts the drops of the captures/future state.
+struct DropCounter(Rc<Cell<usize>>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+async fn yield_now() {
+    let mut yielded = false;
+    poll_fn(|cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await;
+}
+
+fn run<FutureStorage: StorageMut>(input: &Input) {
+    let bytes = input.bytes;
+    let expected: u64 = bytes.iter().map(|&b| u64::from(b)).sum();
+    let yields = input.yields % 16;
+    let drops = Rc::new(Cell::new(0));
+    let fn_counter = DropCounter(drops.clone());
+    let f = if input.sync {
+        LocalDynAsyncFn::<ForFixed<u8>, ForFixed<u64>, Box, FutureStorage>::new_sync(move |_, _| {
+            let _ = &fn_counter;
+            bytes.iter().map(|&b| u64::from(b)).sum()
+        })
+    } else {
+        LocalDynAsyncFn::<ForFixed<u8>, ForFixed<u64>, Box, FutureStorage>::new(
+            async move |yields, _| {
+                let _ = &fn_counter;
+                // the future state is bigger than the `Raw` future storage for some inputs
+                let state = bytes;
+                for _ in 0..yields {
+                    yield_now().await;
+                }
+                state.iter().map(|&b| u64::from(b)).sum()
+            },
+        )
+    };
+    let mut cx = Context::from_waker(Waker::noop());
+    {
+        // the future is dropped at the end of the scope, completed or not
+        let mut future = pin!(f.call(yields));
+        for polls in 1..=input.polls {
+            if let Poll::Ready(res) = future.as_mut().poll(&mut cx) {
+                assert_eq!(res, expected);
+                assert!(input.sync || polls > yields);
+                break;
+            }
+        }
+    }
+    drop(f);
+    assert_eq!(drops.get(), 1);
+}
+
+fuzz_target!(|input: Input| {
+    match input.future_storage {
+        FutureStorageKind::Raw => run::<Raw<256, 16>>(&input),
+        FutureStorageKind::RawOrBox => run::<RawOrBox<32>>(&input),
+        FutureStorageKind::Box => run::<Box>(&input),
+    }
+});
@@ -0,0 +1,366 @@
+//! Runs random sequences of operations — creation, clone, call, call once, drop — against every
+//! storage, with payloads of various sizes and alignments, some of them panicking when called or
+//! dropped.
+//!
+//! Every allocation must be released, and every capture dropped exactly once, when all the
+//! functions have been dropped, including the ones whose drop panicked.
+//!
+//! Run with `cargo +nightly fuzz run storage_ops` (ASAN is enabled by default).
+#![no_main]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Once,
+        atomic::{AtomicUsize, Ordering::Relaxed},
+    },
+};
+
+use arbitrary::Arbitrary;
+use dyn_fn::{
+    LocalDynFn, LocalDynFnMut, LocalDynFnOnce,
+    hkt::ForFixed,
+    storage::{Arc, Box, BoxCached, Raw, RawOrBox, Rc, Storage, StorageMut},
+};
+use libfuzzer_sys::fuzz_target;
+
+/// Counts the live allocations of the global allocator.
+struct CountingAlloc;
+
+static LIVE_ALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+// SAFETY: allocations are forwarded to the system allocator
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: same contract
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            LIVE_ALLOCS.fetch_add(1, Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_ALLOCS.fetch_sub(1, Relaxed);
+        // SAFETY: same contract
+        unsafe { System.dealloc(ptr, layout) };
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// Captures created, and dropped, since the start of the process; each run drops all the
+/// captures it creates.
+static CREATED: AtomicUsize = AtomicUsize::new(0);
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+/// Panic payload of the injected panics, which must not abort the fuzzer.
+struct InjectedPanic;
+
+fn inject_panic() -> ! {
+    panic::panic_any(InjectedPanic)
+}
+
+trait Payload: 'static {
+    fn new(bytes: &[u8]) -> Self;
+    fn sum(&self) -> u64;
+}
+
+macro_rules! payload {
+    ($($name:ident: $align:literal),*) => {$(
+        #[repr(align($align))]
+        struct $name<const N: usize>([u8; N]);
+
+        impl<const N: usize> Payload for $name<N> {
+            fn new(bytes: &[u8]) -> Self {
+                Self(core::array::from_fn(|i| bytes[i]))
+            }
+            fn sum(&self) -> u64 {
+                self.0.iter().map(|&b| u64::from(b)).sum()
+            }
+        }
+    )*};
+}
+payload!(Align1: 1, Align8: 8, Align16: 16, Align64: 64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Arbitrary)]
+enum PanicOn {
+    Never,
+    Call,
+    Drop,
+}
+
+/// The captures of a function, counting their drops.
+struct Captures<P> {
+    payload: P,
+    panic_on: PanicOn,
+}
+
+impl<P: Payload> Captures<P> {
+    fn new(payload: P, panic_on: PanicOn) -> Self {
+        CREATED.fetch_add(1, Relaxed);
+        Self { payload, panic_on }
+    }
+
+    fn sum(&self) -> u64 {
+        if self.panic_on == PanicOn::Call {
+            inject_panic();
+        }
+        self.payload.sum()
+    }
+}
+
+impl<P> Drop for Captures<P> {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Relaxed);
+        if self.panic_on == PanicOn::Drop {
+            inject_panic();
+        }
+    }
+}
+
+/// A stored function, whatever its kind and storage.
+trait Slot {
+    /// Calls the function, returning `None` if it has already been called once.
+    fn call(&mut self) -> Option<u64>;
+    /// Clones the function, if its storage is cloneable.
+    fn try_clone(&self) -> Option<std::boxed::Box<dyn Slot>>;
+}
+
+struct SharedFn<S: Storage + Clone>(LocalDynFn<'static, ForFixed<()>, ForFixed<u64>, S>);
+
+impl<S: Storage + Clone> Slot for SharedFn<S> {
+    fn call(&mut self) -> Option<u64> {
+        Some(self.0.call(()))
+    }
+    fn try_clone(&self) -> Option<std::boxed::Box<dyn Slot>> {
+        Some(std::boxed::Box::new(Self(self.0.clone())))
+    }
+}
+
+struct UniqueFn<S: Storage>(LocalDynFn<'static, ForFixed<()>, ForFixed<u64>, S>);
+
+impl<S: Storage> Slot for UniqueFn<S> {
+    fn call(&mut self) -> Option<u64> {
+        Some(self.0.call(()))
+    }
+    fn try_clone(&self) -> Option<std::boxed::Box<dyn Slot>> {
+        None
+    }
+}
+
+struct FnMut<S: StorageMut>(LocalDynFnMut<'static, ForFixed<()>, ForFixed<u64>, S>);
+
+impl<S: StorageMut> Slot for FnMut<S> {
+    fn call(&mut self) -> Option<u64> {
+        Some(self.0.call(()))
+    }
+    fn try_clone(&self) -> Option<std::boxed::Box<dyn Slot>> {
+        None
+    }
+}
+
+struct FnOnce<S: StorageMut>(Option<LocalDynFnOnce<'static, ForFixed<()>, ForFixed<u64>, S>>);
+
+impl<S: StorageMut> Slot for FnOnce<S> {
+    fn call(&mut self) -> Option<u64> {
+        Some(self.0.take()?.call(()))
+    }
+    fn try_clone(&self) -> Option<std::boxed::Box<dyn Slot>> {
+        None
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum Size {
+    S0,
+    S1,
+    S7,
+    S32,
+    S128,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Alignment {
+    A1,
+    A8,
+    A16,
+    A64,
+}
+
+#[derive(Debug, Arbitrary)]
+enum StorageKind {
+    Raw,
+    RawOrBox,
+    Box,
+    BoxCached,
+    Rc,
+    Arc,
+}
+
+#[derive(Debug, Arbitrary)]
+enum FnKind {
+    Fn,
+    FnMut,
+    FnOnce,
+}
+
+#[derive(Debug, Arbitrary)]
+struct New {
+    storage: StorageKind,
+    kind: FnKind,
+    size: Size,
+    align: Alignment,
+    panic_on: PanicOn,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Op {
+    New(New),
+    Clone(u8),
+    Call(u8),
+    Drop(u8),
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    bytes: [u8; 128],
+    ops: Vec<Op>,
+}
+
+const MAX_SLOTS: usize = 16;
+
+/// A stored function, with the result expected from its calls.
+struct Entry {
+    slot: std::boxed::Box<dyn Slot>,
+    expected: u64,
+    panic_on: PanicOn,
+}
+
+fn new_slot<P: Payload>(new: &New, bytes: &[u8]) -> Entry {
+    let captures = Captures::new(P::new(bytes), new.panic_on);
+    let expected = captures.payload.sum();
+    macro_rules! new_slot {
+        ($storage:ty) => {
+            match new.kind {
+                FnKind::Fn => std::boxed::Box::new(UniqueFn(
+                    LocalDynFn::<_, _, $storage>::new(move |(), _| captures.sum()),
+                )) as std::boxed::Box<dyn Slot>,
+                FnKind::FnMut => std::boxed::Box::new(FnMut(LocalDynFnMut::<_, _, $storage>::new(
+                    move |(), _| captures.sum(),
+                ))),
+                FnKind::FnOnce => std::boxed::Box::new(FnOnce(Some(
+                    LocalDynFnOnce::<_, _, $storage>::new(move |(), _| captures.sum()),
+                ))),
+            }
+        };
+    }
+    let slot: std::boxed::Box<dyn Slot> = match new.storage {
+        StorageKind::Raw => new_slot!(Raw<256, 64>),
+        StorageKind::RawOrBox => new_slot!(RawOrBox<64>),
+        StorageKind::Box => new_slot!(Box),
+        StorageKind::BoxCached => new_slot!(BoxCached),
+        StorageKind::Rc => std::boxed::Box::new(SharedFn(LocalDynFn::<_, _, Rc>::new(
+            move |(), _| captures.sum(),
+        ))),
+        StorageKind::Arc => std::boxed::Box::new(SharedFn(LocalDynFn::<_, _, Arc>::new(
+            move |(), _| captures.sum(),
+        ))),
+    };
+    Entry {
+        slot,
+        expected,
+        panic_on: new.panic_on,
+    }
+}
+
+fn new_slot_align<const N: usize>(new: &New, bytes: &[u8]) -> Entry {
+    match new.align {
+        Alignment::A1 => new_slot::<Align1<N>>(new, bytes),
+        Alignment::A8 => new_slot::<Align8<N>>(new, bytes),
+        Alignment::A16 => new_slot::<Align16<N>>(new, bytes),
+        Alignment::A64 => new_slot::<Align64<N>>(new, bytes),
+    }
+}
+
+/// Runs `f`, returning whether it has panicked; only injected panics are expected.
+fn catch_injected(f: impl core::ops::FnOnce()) -> bool {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(()) => false,
+        Err(payload) => {
+            assert!(payload.is::<InjectedPanic>());
+            true
+        }
+    }
+}
+
+fn run(input: &Input) {
+    let mut entries: Vec<Entry> = Vec::with_capacity(MAX_SLOTS);
+    for op in &input.ops {
+        match op {
+            Op::New(new) if entries.len() < MAX_SLOTS => {
+                entries.push(match new.size {
+                    Size::S0 => new_slot_align::<0>(new, &input.bytes),
+                    Size::S1 => new_slot_align::<1>(new, &input.bytes),
+                    Size::S7 => new_slot_align::<7>(new, &input.bytes),
+                    Size::S32 => new_slot_align::<32>(new, &input.bytes),
+                    Size::S128 => new_slot_align::<128>(new, &input.bytes),
+                });
+            }
+            Op::New(_) => {}
+            _ if entries.is_empty() => {}
+            &Op::Clone(i) if entries.len() < MAX_SLOTS => {
+                let entry = &entries[usize::from(i) % entries.len()];
+                if let Some(slot) = entry.slot.try_clone() {
+                    let (expected, panic_on) = (entry.expected, entry.panic_on);
+                    entries.push(Entry {
+                        slot,
+                        expected,
+                        panic_on,
+                    });
+                }
+            }
+            Op::Clone(_) => {}
+            &Op::Call(i) => {
+                let len = entries.len();
+                let entry = &mut entries[usize::from(i) % len];
+                let mut ret = None;
+                let panicked = catch_injected(|| ret = entry.slot.call());
+                // a function called once panics when its captures are dropped after the call
+                match entry.panic_on {
+                    PanicOn::Never => assert!(!panicked),
+                    PanicOn::Call => assert!(panicked || ret.is_none()),
+                    PanicOn::Drop => {}
+                }
+                if let Some(ret) = ret {
+                    assert_eq!(ret, entry.expected);
+                }
+            }
+            &Op::Drop(i) => {
+                let entry = entries.swap_remove(usize::from(i) % entries.len());
+                catch_injected(|| drop(entry));
+            }
+        }
+    }
+    for entry in entries {
+        catch_injected(|| drop(entry));
+    }
+}
+
+fuzz_target!(|input: Input| {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(std::boxed::Box::new(move |info| {
+            if !info.payload().is::<InjectedPanic>() {
+                hook(info);
+            }
+        }));
+    });
+    let live_allocs = LIVE_ALLOCS.load(Relaxed);
+    run(&input);
+    assert_eq!(DROPPED.load(Relaxed), CREATED.load(Relaxed));
+    assert_eq!(LIVE_ALLOCS.load(Relaxed), live_allocs);
+});
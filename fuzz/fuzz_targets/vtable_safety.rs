@@ -0,0 +1,168 @@
+//! Stores functions of various sizes, alignments and drop implementations in every storage,
+//! and checks that calling and dropping them through their vtable is sound.
+//!
+//! Run with `cargo +nightly fuzz run vtable_safety` (ASAN is enabled by default).
+#![no_main]
+
+use std::{cell::Cell, rc::Rc};
+
+use arbitrary::Arbitrary;
+use dyn_fn::{
+    LocalDynFn, LocalDynFnMut, LocalDynFnOnce,
+    hkt::ForFixed,
+    storage::{Arc, Box, Raw, RawOrBox, Rc as RcStorage, Storage, StorageMut},
+};
+use libfuzzer_sys::fuzz_target;
+
+trait Payload: 'static {
+    fn new(bytes: &[u8]) -> Self;
+    fn sum(&self) -> u64;
+}
+
+macro_rules! payload {
+    ($($name:ident: $align:literal),*) => {$(
+        #[repr(align($align))]
+        struct $name<const N: usize>([u8; N]);
+
+        impl<const N: usize> Payload for $name<N> {
+            fn new(bytes: &[u8]) -> Self {
+                Self(core::array::from_fn(|i| bytes[i]))
+            }
+            fn sum(&self) -> u64 {
+                self.0.iter().map(|&b| u64::from(b)).sum()
+            }
+        }
+    )*};
+}
+payload!(Align1: 1, Align8: 8, Align16: 16, Align64: 64);
+
+#[derive(Debug, Arbitrary)]
+enum Size {
+    S0,
+    S1,
+    S7,
+    S32,
+    S128,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Alignment {
+    A1,
+    A8,
+    A16,
+    A64,
+}
+
+#[derive(Debug, Arbitrary)]
+enum StorageKind {
+    Raw,
+    RawOrBox,
+    Box,
+    Rc,
+    Arc,
+}
+
+#[derive(Debug, Arbitrary)]
+enum FnKind {
+    Fn,
+    FnMut,
+    FnOnce,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    bytes: [u8; 128],
+    size: Size,
+    align: Alignment,
+    storage: StorageKind,
+    kind: FnKind,
+    calls: u8,
+}
+
+/// Counts the drops of the function captures.
+struct DropCounter(Rc<Cell<usize>>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn run_fn<S: Storage, P: Payload>(payload: P, calls: u8, counter: DropCounter) {
+    let expected = payload.sum();
+    let f = LocalDynFn::<ForFixed<()>, ForFixed<u64>, S>::new(move |(), _| {
+        let _ = &counter;
+        payload.sum()
+    });
+    for _ in 0..calls {
+        assert_eq!(f.call(()), expected);
+    }
+}
+
+fn run_fn_mut<S: StorageMut, P: Payload>(payload: P, calls: u8, counter: DropCounter) {
+    let expected = payload.sum();
+    let mut call_count = 0;
+    let mut f = LocalDynFnMut::<ForFixed<()>, ForFixed<u64>, S>::new(move |(), _| {
+        let _ = &counter;
+        call_count += 1;
+        payload.sum() * call_count
+    });
+    for i in 1..=u64::from(calls) {
+        assert_eq!(f.call(()), expected * i);
+    }
+}
+
+fn run_fn_once<S: StorageMut, P: Payload>(payload: P, calls: u8, counter: DropCounter) {
+    let expected = payload.sum();
+    let f = LocalDynFnOnce::<ForFixed<()>, ForFixed<u64>, S>::new(move |(), _| {
+        drop(counter);
+        payload.sum()
+    });
+    // an uncalled function must also drop its captures
+    if calls % 2 == 1 {
+        assert_eq!(f.call(()), expected);
+    }
+}
+
+fn run<P: Payload>(input: &Input) {
+    let payload = P::new(&input.bytes);
+    let drops = Rc::new(Cell::new(0));
+    let counter = DropCounter(drops.clone());
+    let calls = input.calls;
+    macro_rules! run_mut {
+        ($storage:ty) => {
+            match input.kind {
+                FnKind::Fn => run_fn::<$storage, P>(payload, calls, counter),
+                FnKind::FnMut => run_fn_mut::<$storage, P>(payload, calls, counter),
+                FnKind::FnOnce => run_fn_once::<$storage, P>(payload, calls, counter),
+            }
+        };
+    }
+    match input.storage {
+        StorageKind::Raw => run_mut!(Raw<256, 64>),
+        StorageKind::RawOrBox => run_mut!(RawOrBox<64>),
+        StorageKind::Box => run_mut!(Box),
+        StorageKind::Rc => run_fn::<RcStorage, P>(payload, calls, counter),
+        StorageKind::Arc => run_fn::<Arc, P>(payload, calls, counter),
+    }
+    assert_eq!(drops.get(), 1);
+}
+
+fn run_align<const N: usize>(input: &Input) {
+    match input.align {
+        Alignment::A1 => run::<Align1<N>>(input),
+        Alignment::A8 => run::<Align8<N>>(input),
+        Alignment::A16 => run::<Align16<N>>(input),
+        Alignment::A64 => run::<Align64<N>>(input),
+    }
+}
+
+fuzz_target!(|input: Input| {
+    match input.size {
+        Size::S0 => run_align::<0>(&input),
+        Size::S1 => run_align::<1>(&input),
+        Size::S7 => run_align::<7>(&input),
+        Size::S32 => run_align::<32>(&input),
+        Size::S128 => run_align::<128>(&input),
+    }
+});
@@ -0,0 +1,277 @@
+//! Procedural macros of [`dyn-fn`](https://docs.rs/dyn-fn), re-exported by it with the `macros`
+//! feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    Error, FnArg, GenericParam, ItemFn, Lifetime, ParenthesizedGenericArguments, ReturnType, Type,
+    TypeBareFn, TypeImplTrait, TypeReference, parse_macro_input, spanned::Spanned, visit::Visit,
+    visit_mut::VisitMut,
+};
+
+/// Turns a function into a constructor of the matching dynamic function.
+///
+/// A synchronous function becomes a constructor of `DynFn`, and an asynchronous one a constructor
+/// of `DynAsyncFn`; the constructor keeps the name and the visibility of the function, and is
+/// generic over the storage(s) of the returned dynamic function. The function itself is
+/// zero-sized, so it fits any storage, including `Raw<0>`.
+///
+/// The function can take up to three arguments, passed as a tuple when there are more than one
+/// (see `dyn_fn::args`). Argument and return types are mapped to their higher-kinded
+/// counterparts: types without lifetime become `ForFixed<T>`, references become
+/// `ForRef<T>`/`ForRefMut<T>`, and other types borrowing for the call use a `ForLt!` invocation.
+/// Elided lifetimes, `'_` and the function lifetime parameter, if any, are all bound to the
+/// call lifetime; several lifetime parameters would be ambiguous, so they are rejected.
+///
+/// Receivers and generic parameters are not supported. The function is moved into the body of
+/// the constructor, so it cannot refer to `Self` when used in an `impl` block.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{DynAsyncFn, DynFn, hkt::*, storage};
+/// use futures_util::FutureExt;
+///
+/// mod handlers {
+///     #[dyn_fn::callback]
+///     pub fn first_word(msg: &str) -> &str {
+///         msg.split(' ').next().unwrap_or_default()
+///     }
+///
+///     #[dyn_fn::callback]
+///     pub async fn on_message(msg: &str) -> usize {
+///         msg.len()
+///     }
+/// }
+///
+/// let mut registry = Vec::<DynFn<ForRef<str>, ForRef<str>, storage::Raw<0>>>::new();
+/// registry.push(handlers::first_word());
+/// assert_eq!(registry[0].call("hello world"), "hello");
+///
+/// let mut registry = Vec::<DynAsyncFn<ForRef<str>, ForFixed<usize>>>::new();
+/// registry.push(handlers::on_message());
+/// assert_eq!(registry[0].call("hello").now_or_never(), Some(5));
+/// ```
+#[proc_macro_attribute]
+pub fn callback(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as ItemFn);
+    if !attr.is_empty() {
+        let attr = TokenStream2::from(attr);
+        return Error::new(attr.span(), "`callback` takes no argument")
+            .into_compile_error()
+            .into();
+    }
+    callback_impl(item)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn callback_impl(item: ItemFn) -> syn::Result<TokenStream2> {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = item;
+    if let Some(unsafety) = sig.unsafety {
+        return Err(Error::new(unsafety.span(), "callbacks cannot be `unsafe`"));
+    }
+    if let Some(abi) = &sig.abi {
+        return Err(Error::new(abi.span(), "callbacks cannot be `extern`"));
+    }
+    if let Some(receiver) = sig.receiver() {
+        return Err(Error::new(
+            receiver.span(),
+            "callbacks cannot take a `self` receiver",
+        ));
+    }
+    let mut call_lifetime = None;
+    for param in &sig.generics.params {
+        match param {
+            GenericParam::Lifetime(param) if call_lifetime.is_none() => {
+                call_lifetime = Some(param.lifetime.clone());
+            }
+            GenericParam::Lifetime(param) => {
+                return Err(Error::new(
+                    param.span(),
+                    "callbacks can have at most one lifetime parameter, bound to the call lifetime",
+                ));
+            }
+            _ => {
+                return Err(Error::new(
+                    param.span(),
+                    "generic callbacks are not supported",
+                ));
+            }
+        }
+    }
+    if let Some(where_clause) = &sig.generics.where_clause {
+        return Err(Error::new(
+            where_clause.span(),
+            "callbacks cannot have a `where` clause",
+        ));
+    }
+    let call_lifetime =
+        call_lifetime.unwrap_or_else(|| Lifetime::new("'__dyn_fn", Span::call_site()));
+
+    let mut args = Vec::new();
+    let mut arg_hkts = Vec::new();
+    for (i, arg) in sig.inputs.iter().enumerate() {
+        let FnArg::Typed(arg) = arg else {
+            unreachable!("receiver has been checked");
+        };
+        if i == 3 {
+            return Err(Error::new(
+                arg.span(),
+                "callbacks take at most three arguments",
+            ));
+        }
+        args.push(format_ident!("__arg{i}"));
+        arg_hkts.push(hkt(&arg.ty, &call_lifetime)?);
+    }
+    let arg = match &arg_hkts[..] {
+        [] => quote!(::dyn_fn::hkt::ForFixed<()>),
+        [arg] => arg.clone(),
+        [a1, a2] => quote!(::dyn_fn::args::ForTuple2<#a1, #a2>),
+        [a1, a2, a3] => quote!(::dyn_fn::args::ForTuple3<#a1, #a2, #a3>),
+        _ => unreachable!(),
+    };
+    let pat = match &args[..] {
+        [arg] => quote!(#arg),
+        args => quote!((#(#args),*)),
+    };
+    let ret = match &sig.output {
+        ReturnType::Default => quote!(::dyn_fn::hkt::ForFixed<()>),
+        ReturnType::Type(_, ty) => hkt(ty, &call_lifetime)?,
+    };
+
+    // The function is kept as is in the constructor body, with the same name for recursion
+    let name = &sig.ident;
+    Ok(if sig.asyncness.is_some() {
+        quote! {
+            #(#attrs)*
+            #vis fn #name<
+                __FnStorage: ::dyn_fn::storage::Storage + ::dyn_fn::storage::StorageSend,
+                __FutureStorage: ::dyn_fn::storage::StorageMut,
+            >() -> ::dyn_fn::DynAsyncFn<'static, #arg, #ret, __FnStorage, __FutureStorage> {
+                #sig #block
+                struct Callback;
+                impl<'capture> ::dyn_fn::AsyncFnSend<'capture, #arg, #ret> for Callback {
+                    async fn call<'__dyn_fn>(
+                        &self,
+                        #pat: <#arg as ::dyn_fn::ForLt>::Of<'__dyn_fn>,
+                    ) -> <#ret as ::dyn_fn::ForLt>::Of<'__dyn_fn> {
+                        #name(#(#args),*).await
+                    }
+                }
+                ::dyn_fn::DynAsyncFn::new(Callback)
+            }
+        }
+    } else {
+        quote! {
+            #(#attrs)*
+            #vis fn #name<
+                __FnStorage: ::dyn_fn::storage::Storage + ::dyn_fn::storage::StorageSend,
+            >() -> ::dyn_fn::DynFn<'static, #arg, #ret, __FnStorage> {
+                #sig #block
+                ::dyn_fn::DynFn::new(|#pat, _| #name(#(#args),*))
+            }
+        }
+    })
+}
+
+/// Maps a type to its higher-kinded counterpart, `call_lifetime` being the call lifetime.
+fn hkt(ty: &Type, call_lifetime: &Lifetime) -> syn::Result<TokenStream2> {
+    if let Type::Reference(TypeReference {
+        lifetime,
+        mutability,
+        elem,
+        ..
+    }) = ty
+    {
+        let is_call = lifetime
+            .as_ref()
+            .is_none_or(|lt| is_call_lifetime(lt, call_lifetime));
+        if is_call && !borrows(elem, call_lifetime)? {
+            return Ok(match mutability {
+                Some(_) => quote!(::dyn_fn::hkt_ext::ForRefMut<#elem>),
+                None => quote!(::dyn_fn::hkt::ForRef<#elem>),
+            });
+        }
+    }
+    if !borrows(ty, call_lifetime)? {
+        return Ok(quote!(::dyn_fn::hkt::ForFixed<#ty>));
+    }
+    let mut ty = ty.clone();
+    BindCallLifetime(call_lifetime).visit_type_mut(&mut ty);
+    Ok(quote!(::dyn_fn::ForLt!(<#call_lifetime> = #ty)))
+}
+
+fn is_call_lifetime(lifetime: &Lifetime, call_lifetime: &Lifetime) -> bool {
+    lifetime.ident == "_" || lifetime.ident == call_lifetime.ident
+}
+
+/// Returns whether the type borrows for the call lifetime.
+fn borrows(ty: &Type, call_lifetime: &Lifetime) -> syn::Result<bool> {
+    let mut visitor = Borrows {
+        call_lifetime,
+        borrows: false,
+        error: None,
+    };
+    visitor.visit_type(ty);
+    match visitor.error {
+        Some(error) => Err(error),
+        None => Ok(visitor.borrows),
+    }
+}
+
+struct Borrows<'a> {
+    call_lifetime: &'a Lifetime,
+    borrows: bool,
+    error: Option<Error>,
+}
+
+impl<'ast> Visit<'ast> for Borrows<'_> {
+    fn visit_type_reference(&mut self, ty: &'ast TypeReference) {
+        self.borrows |= ty.lifetime.is_none();
+        syn::visit::visit_type_reference(self, ty);
+    }
+
+    fn visit_lifetime(&mut self, lifetime: &'ast Lifetime) {
+        self.borrows |= is_call_lifetime(lifetime, self.call_lifetime);
+    }
+
+    fn visit_type_impl_trait(&mut self, ty: &'ast TypeImplTrait) {
+        self.error.get_or_insert_with(|| {
+            Error::new(
+                ty.span(),
+                "`impl Trait` is not supported in callback signatures",
+            )
+        });
+    }
+
+    // elided lifetimes of function pointers and `Fn` traits are higher-ranked
+    fn visit_type_bare_fn(&mut self, _: &'ast TypeBareFn) {}
+    fn visit_parenthesized_generic_arguments(&mut self, _: &'ast ParenthesizedGenericArguments) {}
+}
+
+/// Replaces elided lifetimes and `'_` with the call lifetime.
+struct BindCallLifetime<'a>(&'a Lifetime);
+
+impl VisitMut for BindCallLifetime<'_> {
+    fn visit_type_reference_mut(&mut self, ty: &mut TypeReference) {
+        ty.lifetime.get_or_insert_with(|| self.0.clone());
+        syn::visit_mut::visit_type_reference_mut(self, ty);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.0.clone();
+        }
+    }
+
+    fn visit_type_bare_fn_mut(&mut self, _: &mut TypeBareFn) {}
+    fn visit_parenthesized_generic_arguments_mut(&mut self, _: &mut ParenthesizedGenericArguments) {
+    }
+}
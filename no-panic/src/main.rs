@@ -0,0 +1,92 @@
+//! Link-time check that the call paths cannot panic.
+//!
+//! The panic handler references an undefined symbol, so the build fails to link if a panic is
+//! still reachable after optimization, from the wrappers below or from the vtables of the
+//! functions they call, built from synchronous or asynchronous functions. Run it with
+//! `cargo build --release` in this directory.
+#![no_std]
+#![no_main]
+
+use core::{
+    future::Future,
+    hint::black_box,
+    panic::PanicInfo,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use dyn_fn::{
+    AsyncFnSend, DynAsyncFn, DynFnMut, LocalDynFn, SpinDynFnMut, hkt::ForFixed, storage::Raw,
+};
+
+type SyncFn = LocalDynFn<'static, ForFixed<u32>, ForFixed<u32>, Raw<0>>;
+type AsyncFn = DynAsyncFn<'static, ForFixed<u32>, ForFixed<u32>, Raw<0>, Raw<16>>;
+type SpinFn = SpinDynFnMut<'static, ForFixed<u32>, ForFixed<u32>, Raw<4>>;
+
+/// An asynchronous function, whose vtable has a `call_sync` placeholder.
+struct Halve;
+
+/// A future ready on first poll which, unlike `async` blocks, doesn't panic when polled again.
+struct Halved(u32);
+
+impl Future for Halved {
+    type Output = u32;
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<u32> {
+        Poll::Ready(self.0 / 2)
+    }
+}
+
+impl AsyncFnSend<'static, ForFixed<u32>, ForFixed<u32>> for Halve {
+    fn call<'a>(&self, arg: u32) -> impl Future<Output = u32> + Send {
+        Halved(arg)
+    }
+}
+
+#[inline(never)]
+fn call(f: &SyncFn, arg: u32) -> u32 {
+    f.call(arg)
+}
+
+#[inline(never)]
+fn call_sync(f: &AsyncFn, arg: u32) -> Option<u32> {
+    f.call_sync(arg)
+}
+
+#[inline(never)]
+fn call_spin(f: &SpinFn, arg: u32) -> u32 {
+    f.call(arg)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn main(argc: i32, _argv: *const *const u8) -> i32 {
+    // functions are hidden from the optimizer, so their vtables are kept
+    let f = black_box(SyncFn::new(|x, _| x.wrapping_add(1)));
+    let g = black_box(AsyncFn::new_sync(|x, _| x.wrapping_mul(2)));
+    let a = black_box(AsyncFn::new(Halve));
+    let mut total = 0u32;
+    let h = black_box(SpinFn::new(DynFnMut::new(move |x, _| {
+        total = total.wrapping_add(x);
+        total
+    })));
+    let arg = argc.cast_unsigned();
+    let ret = call(&f, arg)
+        ^ call_sync(&g, arg).unwrap_or_default()
+        ^ call_sync(&a, arg).unwrap_or_default()
+        ^ call_spin(&h, arg);
+    ret.cast_signed()
+}
+
+// `main` is called by the C runtime
+#[link(name = "c")]
+unsafe extern "C" {}
+
+#[panic_handler]
+fn panic(_: &PanicInfo) -> ! {
+    unsafe extern "C" {
+        /// Never defined, so that referencing the panic handler fails the link.
+        fn dyn_fn_call_path_may_panic() -> !;
+    }
+    // SAFETY: the symbol doesn't exist, the build fails if this is reachable
+    unsafe { dyn_fn_call_path_may_panic() }
+}
@@ -0,0 +1,287 @@
+use core::{any::TypeId, fmt, mem::ManuallyDrop, ptr};
+
+use higher_kinded_types::ForFixed;
+
+#[cfg(feature = "async")]
+use crate::{
+    DynAsyncFn,
+    storage::{DefaultFutureStorage, StorageMut},
+};
+use crate::{
+    DynFn, ForLt, SignatureMismatch,
+    storage::{DefaultFnStorage, Storage, StorageSend},
+};
+
+/// The argument and return HKTs of an erased function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Signature {
+    arg: TypeId,
+    ret: TypeId,
+}
+
+impl Signature {
+    fn of<Arg: ForLt + 'static, Ret: ForLt + 'static>() -> Self {
+        Self {
+            arg: TypeId::of::<Arg>(),
+            ret: TypeId::of::<Ret>(),
+        }
+    }
+}
+
+/// Placeholder signature of erased functions, never called.
+type Erased<'capture, FnStorage> = DynFn<'capture, ForFixed<()>, ForFixed<()>, FnStorage>;
+
+/// # Safety
+///
+/// `f` must have been erased from a `DynFn<'capture, Arg, Ret, FnStorage>`, and must not be
+/// used after.
+unsafe fn drop_as<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage>(
+    f: &mut ManuallyDrop<Erased<'capture, FnStorage>>,
+) where
+    FnStorage: Storage + StorageSend,
+{
+    let f = ptr::from_mut(f).cast::<DynFn<'capture, Arg, Ret, FnStorage>>();
+    // SAFETY: `f` has been erased from a `DynFn<'capture, Arg, Ret, FnStorage>`, which has the
+    // same layout, and is not used after
+    unsafe { ptr::drop_in_place(f) };
+}
+
+/// A [`DynFn`] whose signature is erased too, checked at runtime.
+///
+/// Dynamic functions with different signatures can then be stored together, e.g. in a map of
+/// event handlers, and be called with [`call_as`](Self::call_as), or retrieved with
+/// [`downcast`](Self::downcast).
+///
+/// Signatures are compared using the [`TypeId`] of the argument and return HKTs, so they must
+/// be spelled the same way: `ForFixed<&'static str>` doesn't match `ForRef<str>`.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use dyn_fn::{AnyDynFn, DynFn, SignatureMismatch, hkt::ForFixed};
+///
+/// let mut handlers = HashMap::<&'static str, AnyDynFn>::new();
+/// handlers.insert("incr", DynFn::<ForFixed<u32>, ForFixed<u32>>::new(|x, _| x + 1).into());
+/// handlers.insert("ready", DynFn::<ForFixed<()>, ForFixed<bool>>::new(|_, _| true).into());
+/// let incr = &handlers["incr"];
+/// assert_eq!(incr.call_as::<ForFixed<u32>, ForFixed<u32>>(41), Ok(42));
+/// assert_eq!(incr.call_as::<ForFixed<()>, ForFixed<bool>>(()), Err(SignatureMismatch));
+/// ```
+pub struct AnyDynFn<'capture, FnStorage: Storage + StorageSend = DefaultFnStorage> {
+    f: ManuallyDrop<Erased<'capture, FnStorage>>,
+    signature: Signature,
+    drop: unsafe fn(&mut ManuallyDrop<Erased<'capture, FnStorage>>),
+}
+
+impl<'capture, FnStorage: Storage + StorageSend> AnyDynFn<'capture, FnStorage> {
+    /// Erases the signature of a dynamic function.
+    pub fn new<Arg: ForLt + 'static, Ret: ForLt + 'static>(
+        f: DynFn<'capture, Arg, Ret, FnStorage>,
+    ) -> Self {
+        let f = ManuallyDrop::new(f);
+        Self {
+            // SAFETY: `DynFn` has the same layout whatever its signature, and `f` is not dropped
+            f: unsafe { ptr::read(ptr::from_ref(&f).cast()) },
+            signature: Signature::of::<Arg, Ret>(),
+            drop: drop_as::<Arg, Ret, FnStorage>,
+        }
+    }
+
+    /// Returns whether the function has the given signature.
+    pub fn is<Arg: ForLt + 'static, Ret: ForLt + 'static>(&self) -> bool {
+        self.signature == Signature::of::<Arg, Ret>()
+    }
+
+    /// Returns the function if it has the given signature.
+    pub fn downcast<Arg: ForLt + 'static, Ret: ForLt + 'static>(
+        &self,
+    ) -> Option<&DynFn<'capture, Arg, Ret, FnStorage>> {
+        let f = ptr::from_ref(&*self.f).cast::<DynFn<'capture, Arg, Ret, FnStorage>>();
+        // SAFETY: the signature matches the one the function has been erased from
+        self.is::<Arg, Ret>().then(|| unsafe { &*f })
+    }
+
+    /// Returns the function if it has the given signature, or `self` otherwise.
+    pub fn downcast_into<Arg: ForLt + 'static, Ret: ForLt + 'static>(
+        self,
+    ) -> Result<DynFn<'capture, Arg, Ret, FnStorage>, Self> {
+        if !self.is::<Arg, Ret>() {
+            return Err(self);
+        }
+        let this = ManuallyDrop::new(self);
+        let f = ptr::from_ref(&*this.f).cast::<DynFn<'capture, Arg, Ret, FnStorage>>();
+        // SAFETY: the signature matches the one the function has been erased from,
+        // and `this` is not dropped
+        Ok(unsafe { ptr::read(f) })
+    }
+
+    /// Calls the function if it has the given signature.
+    pub fn call_as<'a, Arg: ForLt + 'static, Ret: ForLt + 'static>(
+        &self,
+        arg: Arg::Of<'a>,
+    ) -> Result<Ret::Of<'a>, SignatureMismatch> {
+        let f = self.downcast::<Arg, Ret>().ok_or(SignatureMismatch)?;
+        Ok(f.call(arg))
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    From<DynFn<'capture, Arg, Ret, FnStorage>> for AnyDynFn<'capture, FnStorage>
+{
+    fn from(value: DynFn<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<FnStorage: Storage + StorageSend> Drop for AnyDynFn<'_, FnStorage> {
+    fn drop(&mut self) {
+        // SAFETY: `drop` matches the signature the function has been erased from,
+        // and `self.f` is not used after
+        unsafe { (self.drop)(&mut self.f) }
+    }
+}
+
+impl<FnStorage: Storage + StorageSend> fmt::Debug for AnyDynFn<'_, FnStorage> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyDynFn")
+            .field("signature", &self.signature)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Placeholder signature of erased asynchronous functions, never called.
+#[cfg(feature = "async")]
+type ErasedAsync<'capture, FnStorage, FutureStorage> =
+    DynAsyncFn<'capture, ForFixed<()>, ForFixed<()>, FnStorage, FutureStorage>;
+
+/// # Safety
+///
+/// `f` must have been erased from a `DynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>`,
+/// and must not be used after.
+#[cfg(feature = "async")]
+unsafe fn drop_async_as<'capture, Arg, Ret, FnStorage, FutureStorage>(
+    f: &mut ManuallyDrop<ErasedAsync<'capture, FnStorage, FutureStorage>>,
+) where
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: Storage + StorageSend,
+    FutureStorage: StorageMut,
+{
+    let f = ptr::from_mut(f).cast::<DynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>>();
+    // SAFETY: `f` has been erased from a `DynAsyncFn<'capture, Arg, Ret, FnStorage,
+    // FutureStorage>`, which has the same layout, and is not used after
+    unsafe { ptr::drop_in_place(f) };
+}
+
+/// A [`DynAsyncFn`] whose signature is erased too, checked at runtime.
+///
+/// See [`AnyDynFn`].
+#[cfg(feature = "async")]
+pub struct AnyDynAsyncFn<
+    'capture,
+    FnStorage: Storage + StorageSend = DefaultFnStorage,
+    FutureStorage: StorageMut = DefaultFutureStorage,
+> {
+    f: ManuallyDrop<ErasedAsync<'capture, FnStorage, FutureStorage>>,
+    signature: Signature,
+    drop: unsafe fn(&mut ManuallyDrop<ErasedAsync<'capture, FnStorage, FutureStorage>>),
+}
+
+#[cfg(feature = "async")]
+impl<'capture, FnStorage: Storage + StorageSend, FutureStorage: StorageMut>
+    AnyDynAsyncFn<'capture, FnStorage, FutureStorage>
+{
+    /// Erases the signature of a dynamic asynchronous function.
+    pub fn new<Arg: ForLt + 'static, Ret: ForLt + 'static>(
+        f: DynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>,
+    ) -> Self {
+        let f = ManuallyDrop::new(f);
+        Self {
+            // SAFETY: `DynAsyncFn` has the same layout whatever its signature,
+            // and `f` is not dropped
+            f: unsafe { ptr::read(ptr::from_ref(&f).cast()) },
+            signature: Signature::of::<Arg, Ret>(),
+            drop: drop_async_as::<Arg, Ret, FnStorage, FutureStorage>,
+        }
+    }
+
+    /// Returns whether the function has the given signature.
+    pub fn is<Arg: ForLt + 'static, Ret: ForLt + 'static>(&self) -> bool {
+        self.signature == Signature::of::<Arg, Ret>()
+    }
+
+    /// Returns the function if it has the given signature.
+    pub fn downcast<Arg: ForLt + 'static, Ret: ForLt + 'static>(
+        &self,
+    ) -> Option<&DynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>> {
+        let f = ptr::from_ref(&*self.f)
+            .cast::<DynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>>();
+        // SAFETY: the signature matches the one the function has been erased from
+        self.is::<Arg, Ret>().then(|| unsafe { &*f })
+    }
+
+    /// Returns the function if it has the given signature, or `self` otherwise.
+    pub fn downcast_into<Arg: ForLt + 'static, Ret: ForLt + 'static>(
+        self,
+    ) -> Result<DynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>, Self> {
+        if !self.is::<Arg, Ret>() {
+            return Err(self);
+        }
+        let this = ManuallyDrop::new(self);
+        let f = ptr::from_ref(&*this.f)
+            .cast::<DynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>>();
+        // SAFETY: the signature matches the one the function has been erased from,
+        // and `this` is not dropped
+        Ok(unsafe { ptr::read(f) })
+    }
+
+    /// Calls the function if it has the given signature.
+    pub async fn call_as<'a, Arg: ForLt + 'static, Ret: ForLt + 'static>(
+        &self,
+        arg: Arg::Of<'a>,
+    ) -> Result<Ret::Of<'a>, SignatureMismatch> {
+        let f = self.downcast::<Arg, Ret>().ok_or(SignatureMismatch)?;
+        Ok(f.call(arg).await)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: Storage + StorageSend,
+    FutureStorage: StorageMut,
+> From<DynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>>
+    for AnyDynAsyncFn<'capture, FnStorage, FutureStorage>
+{
+    fn from(value: DynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<FnStorage: Storage + StorageSend, FutureStorage: StorageMut> Drop
+    for AnyDynAsyncFn<'_, FnStorage, FutureStorage>
+{
+    fn drop(&mut self) {
+        // SAFETY: `drop` matches the signature the function has been erased from,
+        // and `self.f` is not used after
+        unsafe { (self.drop)(&mut self.f) }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<FnStorage: Storage + StorageSend, FutureStorage: StorageMut> fmt::Debug
+    for AnyDynAsyncFn<'_, FnStorage, FutureStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyDynAsyncFn")
+            .field("signature", &self.signature)
+            .finish_non_exhaustive()
+    }
+}
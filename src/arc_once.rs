@@ -0,0 +1,151 @@
+use core::{fmt, mem::MaybeUninit, sync::atomic::Ordering};
+
+use higher_kinded_types::ForFixed;
+
+use crate::{
+    DynFnOnce, ForLt,
+    primitives::{Arc, AtomicU32, UnsafeCell},
+    storage::{DefaultFnStorage, StorageMut, StorageSend},
+};
+
+/// A value which can be taken at most once, through a shared reference.
+struct Claim<T> {
+    claimed: AtomicU32,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY: the value is never shared, it is only moved out by the single thread claiming it
+unsafe impl<T: Send> Sync for Claim<T> {}
+
+impl<T> Claim<T> {
+    fn new(value: T) -> Self {
+        Self {
+            claimed: AtomicU32::new(0),
+            value: UnsafeCell::new(MaybeUninit::new(value)),
+        }
+    }
+
+    fn is_claimed(&self) -> bool {
+        self.claimed.load(Ordering::Relaxed) != 0
+    }
+
+    /// Takes the value, if it has not been claimed yet.
+    fn take(&self) -> Option<T> {
+        // the value is written before the claim is shared, and the claim is only dropped once all
+        // its references are, so the swap doesn't need to synchronize anything
+        if self.claimed.swap(1, Ordering::Relaxed) != 0 {
+            return None;
+        }
+        // SAFETY: the value is initialized, and the claim being won, it is read only once
+        Some(
+            self.value
+                .with(|value| unsafe { (*value).assume_init_read() }),
+        )
+    }
+}
+
+impl<T> Drop for Claim<T> {
+    fn drop(&mut self) {
+        if !self.is_claimed() {
+            // SAFETY: the value has not been claimed, so it is still initialized
+            self.value
+                .with_mut(|value| unsafe { (*value).assume_init_drop() });
+        }
+    }
+}
+
+/// A [`DynFnOnce`] shared between several owners, and called by at most one of them.
+///
+/// It is typically a completion callback handed to racing tasks, where the first one to finish
+/// consumes it. [`try_call`](Self::try_call) calls the function only if the handle is the last
+/// one, while [`call_first_wins`](Self::call_first_wins) lets exactly one of the handles call it,
+/// whichever comes first. Once called, the other handles become inert.
+///
+/// The function is stored in `FnStorage` inside the shared allocation, so
+/// [`Raw`](crate::storage::Raw) storage avoids a second allocation.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{ArcDynFnOnce, DynFnOnce, hkt::ForFixed};
+///
+/// let done = ArcDynFnOnce::new(DynFnOnce::<ForFixed<u32>, ForFixed<u32>>::new(|x, _| x + 1));
+/// let racers: Vec<_> = (0..4)
+///     .map(|i| {
+///         let done = done.clone();
+///         std::thread::spawn(move || done.call_first_wins(41 + i))
+///     })
+///     .collect();
+/// let results: Vec<_> = racers.into_iter().map(|r| r.join().unwrap()).collect();
+/// assert_eq!(results.iter().flatten().count(), 1);
+/// assert!(done.is_called());
+/// ```
+pub struct ArcDynFnOnce<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: StorageMut + StorageSend = DefaultFnStorage,
+>(Arc<Claim<DynFnOnce<'capture, Arg, Ret, FnStorage>>>);
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
+    ArcDynFnOnce<'capture, Arg, Ret, FnStorage>
+{
+    /// Wraps a function.
+    pub fn new(f: DynFnOnce<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self(Arc::new(Claim::new(f)))
+    }
+
+    /// Calls the function if this handle is the only one left.
+    ///
+    /// The handle is given back if it is shared, or if the function has already been called by
+    /// [`call_first_wins`](Self::call_first_wins).
+    pub fn try_call(mut self, arg: Arg::Of<'_>) -> Result<Ret::Of<'_>, Self> {
+        if Arc::get_mut(&mut self.0).is_none() {
+            return Err(self);
+        }
+        match self.0.take() {
+            Some(f) => Ok(f.call(arg)),
+            None => Err(self),
+        }
+    }
+
+    /// Calls the function, unless it has already been called through another handle.
+    ///
+    /// Exactly one of the concurrent calls wins, and the other ones return `None` without
+    /// waiting for it to complete.
+    pub fn call_first_wins<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        Some(self.0.take()?.call(arg))
+    }
+
+    /// Returns whether the function has already been called.
+    pub fn is_called(&self) -> bool {
+        self.0.is_claimed()
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
+    From<DynFnOnce<'capture, Arg, Ret, FnStorage>> for ArcDynFnOnce<'capture, Arg, Ret, FnStorage>
+{
+    fn from(value: DynFnOnce<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
+    Clone for ArcDynFnOnce<'capture, Arg, Ret, FnStorage>
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
+    fmt::Debug for ArcDynFnOnce<'capture, Arg, Ret, FnStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArcDynFnOnce")
+            .field("is_called", &self.is_called())
+            .finish_non_exhaustive()
+    }
+}
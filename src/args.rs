@@ -0,0 +1,116 @@
+//! Multiple arguments support.
+//!
+//! Dynamic functions take a single argument, but it can be a tuple of arguments, each one
+//! borrowing for the call lifetime, e.g. `(&'a str, &'a [u8])`. [`ForTuple2`]/[`ForTuple3`] build
+//! such tuple from the argument types, but any [`ForLt`](trait@crate::ForLt) whose type is a tuple can be used.
+//!
+//! Dynamic functions taking a tuple argument can then be constructed from a function taking
+//! multiple arguments with `new2`/`new3`, and called with multiple arguments with
+//! `call2`/`call3`.
+//!
+//! # Examples
+//!
+//! ```
+//! use dyn_fn::{LocalDynFn, args::ForTuple2, hkt::*};
+//!
+//! type Callback<'a> = LocalDynFn<'a, ForTuple2<ForRef<str>, ForRef<[u8]>>, ForFixed<bool>>;
+//! let callback = Callback::new2(|s, b, _| s.as_bytes() == b);
+//! assert!(callback.call2("test", b"test"));
+//! ```
+
+use crate::ForLt;
+
+/// A tuple of two arguments, each one borrowing for the call lifetime.
+pub type ForTuple2<A1, A2> = ForLt!(<'a> = (<A1 as ForLt>::Of<'a>, <A2 as ForLt>::Of<'a>));
+/// A tuple of three arguments, each one borrowing for the call lifetime.
+pub type ForTuple3<A1, A2, A3> =
+    ForLt!(<'a> = (<A1 as ForLt>::Of<'a>, <A2 as ForLt>::Of<'a>, <A3 as ForLt>::Of<'a>));
+
+/// An argument made of a tuple of two arguments.
+pub trait ForArgs2: ForLt {
+    /// The first argument.
+    type A1<'a>;
+    /// The second argument.
+    type A2<'a>;
+    /// Builds the tuple from the arguments.
+    fn from_args<'a>(a1: Self::A1<'a>, a2: Self::A2<'a>) -> Self::Of<'a>;
+    /// Splits the tuple into the arguments.
+    fn into_args(args: Self::Of<'_>) -> (Self::A1<'_>, Self::A2<'_>);
+}
+
+impl<T: ?Sized + for<'a> ForLt<Of<'a>: private::Tuple2>> ForArgs2 for T {
+    type A1<'a> = <T::Of<'a> as private::Tuple2>::A1;
+    type A2<'a> = <T::Of<'a> as private::Tuple2>::A2;
+    fn from_args<'a>(a1: Self::A1<'a>, a2: Self::A2<'a>) -> Self::Of<'a> {
+        private::Tuple2::from_args(a1, a2)
+    }
+    fn into_args(args: Self::Of<'_>) -> (Self::A1<'_>, Self::A2<'_>) {
+        private::Tuple2::into_args(args)
+    }
+}
+
+/// An argument made of a tuple of three arguments.
+pub trait ForArgs3: ForLt {
+    /// The first argument.
+    type A1<'a>;
+    /// The second argument.
+    type A2<'a>;
+    /// The third argument.
+    type A3<'a>;
+    /// Builds the tuple from the arguments.
+    fn from_args<'a>(a1: Self::A1<'a>, a2: Self::A2<'a>, a3: Self::A3<'a>) -> Self::Of<'a>;
+    /// Splits the tuple into the arguments.
+    fn into_args(args: Self::Of<'_>) -> (Self::A1<'_>, Self::A2<'_>, Self::A3<'_>);
+}
+
+impl<T: ?Sized + for<'a> ForLt<Of<'a>: private::Tuple3>> ForArgs3 for T {
+    type A1<'a> = <T::Of<'a> as private::Tuple3>::A1;
+    type A2<'a> = <T::Of<'a> as private::Tuple3>::A2;
+    type A3<'a> = <T::Of<'a> as private::Tuple3>::A3;
+    fn from_args<'a>(a1: Self::A1<'a>, a2: Self::A2<'a>, a3: Self::A3<'a>) -> Self::Of<'a> {
+        private::Tuple3::from_args(a1, a2, a3)
+    }
+    fn into_args(args: Self::Of<'_>) -> (Self::A1<'_>, Self::A2<'_>, Self::A3<'_>) {
+        private::Tuple3::into_args(args)
+    }
+}
+
+mod private {
+    pub trait Tuple2 {
+        type A1;
+        type A2;
+        fn from_args(a1: Self::A1, a2: Self::A2) -> Self;
+        fn into_args(self) -> (Self::A1, Self::A2);
+    }
+
+    impl<A1, A2> Tuple2 for (A1, A2) {
+        type A1 = A1;
+        type A2 = A2;
+        fn from_args(a1: A1, a2: A2) -> Self {
+            (a1, a2)
+        }
+        fn into_args(self) -> (A1, A2) {
+            self
+        }
+    }
+
+    pub trait Tuple3 {
+        type A1;
+        type A2;
+        type A3;
+        fn from_args(a1: Self::A1, a2: Self::A2, a3: Self::A3) -> Self;
+        fn into_args(self) -> (Self::A1, Self::A2, Self::A3);
+    }
+
+    impl<A1, A2, A3> Tuple3 for (A1, A2, A3) {
+        type A1 = A1;
+        type A2 = A2;
+        type A3 = A3;
+        fn from_args(a1: A1, a2: A2, a3: A3) -> Self {
+            (a1, a2, a3)
+        }
+        fn into_args(self) -> (A1, A2, A3) {
+            self
+        }
+    }
+}
@@ -1,22 +1,41 @@
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, sync::Arc};
 use core::{
     future::poll_fn,
     marker::PhantomData,
     mem,
     mem::{ManuallyDrop, MaybeUninit},
-    pin::Pin,
+    pin::{Pin, pin},
     ptr::NonNull,
     task::{Context, Poll},
 };
 
-use higher_kinded_types::{ForFixed, ForLt};
+use higher_kinded_types::ForFixed;
 
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+use crate::macros::impl_instrumented;
+#[cfg(feature = "poll-stats")]
+use crate::macros::impl_poll_stats;
+#[cfg(feature = "fn_type_name")]
+use crate::macros::impl_type_name;
 use crate::{
-    macros::{impl_clone, impl_debug, new_impls, unsafe_impl_send_sync},
+    DynFnMut, DynFnOnce, ForLt, LocalDynFnMut, LocalDynFnOnce,
+    args::ForTuple2,
+    macros::{
+        impl_async_callable, impl_clone, impl_constant, impl_debug, impl_id, impl_into_storage,
+        impl_multi_args, impl_named, impl_nullary, impl_resize, impl_with_state, new_impls,
+        unsafe_impl_send_sync,
+    },
+    named::MaybeName,
+    poll_stats::{PollCounters, PollRecorder},
     storage::{
-        DefaultFnStorage, DefaultFutureStorage, DropVTable, DynStorage, Storage, StorageMoved,
-        StorageMut, StorageSend, VTable,
+        DefaultFnStorage, DefaultFutureStorage, DropVTable, DynStorage, Storage, StorageCache,
+        StorageMoved, StorageMut, StorageSend, StorageSendOnly, VTable,
     },
+    sync::SyncVTable,
 };
+#[cfg(feature = "alloc")]
+use crate::{hkt_ext::ForResult, macros::impl_leak};
 
 /// A [`Send`] + [`Sync`] [`AsyncFn`] whose returned future is [`Send`]
 pub trait AsyncFnSend<'capture, Arg: ForLt + 'static, Ret: ForLt>: Send + Sync + 'capture {
@@ -24,99 +43,161 @@ pub trait AsyncFnSend<'capture, Arg: ForLt + 'static, Ret: ForLt>: Send + Sync +
     fn call<'a>(&self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> + Send;
 }
 
-/// A [`Send`] + [`Sync`] [`AsyncFnMut`] whose returned future is [`Send`]
-pub trait AsyncFnMutSend<'capture, Arg: ForLt + 'static, Ret: ForLt>:
-    Send + Sync + 'capture
-{
+/// A [`Send`] [`AsyncFnMut`] whose returned future is [`Send`]
+pub trait AsyncFnMutSend<'capture, Arg: ForLt + 'static, Ret: ForLt>: Send + 'capture {
     /// Calls the function, returns a borrowed future.
     fn call<'a>(&mut self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> + Send;
 }
 
-/// A [`Send`] + [`Sync`] [`AsyncFnOnce`] whose returned future is [`Send`]
-pub trait AsyncFnOnceSend<'capture, Arg: ForLt + 'static, Ret: ForLt>:
-    Send + Sync + 'capture
-{
+/// A [`Send`] [`AsyncFnOnce`] whose returned future is [`Send`]
+pub trait AsyncFnOnceSend<'capture, Arg: ForLt + 'static, Ret: ForLt>: Send + 'capture {
     /// Calls the function, returns a borrowed future.
     fn call(self, arg: Arg::Of<'_>) -> impl Future<Output = Ret::Of<'_>> + Send;
 }
 
-#[expect(type_alias_bounds)]
-type PollFn<Ret: ForLt> =
-    for<'a> fn(NonNull<()>, &mut Context<'_>, PhantomData<&'a ()>) -> Poll<Ret::Of<'a>>;
+/// Polls the future, and writes its output in the last pointer when it's ready.
+///
+/// The output is passed type-erased, so the polling code doesn't depend on the output type.
+type PollFn = fn(NonNull<()>, &mut Context<'_>, NonNull<()>) -> Poll<()>;
 
-struct FutureVTable<Ret: ForLt> {
-    poll: PollFn<Ret>,
+pub(crate) struct FutureVTable {
+    poll: PollFn,
     drop_vtable: DropVTable,
 }
 
+/// Storage of a returned future, with the future cache of the called function.
+struct FutureSlot<'c, FutureStorage: StorageMut> {
+    future: MaybeUninit<FutureStorage>,
+    cache: &'c StorageCache<FutureStorage>,
+}
+
+impl<'c, FutureStorage: StorageMut> FutureSlot<'c, FutureStorage> {
+    fn new(cache: &'c StorageCache<FutureStorage>) -> Self {
+        Self {
+            future: MaybeUninit::uninit(),
+            cache,
+        }
+    }
+}
+
+/// # Safety
+///
+/// `slot` must be a valid `&mut FutureSlot<'_, FutureStorage>`.
 #[cfg_attr(coverage_nightly, coverage(off))]
-fn store_future<
-    'a,
-    Ret: ForLt + 'static,
-    FutureStorage: StorageMut,
-    Fut: Future<Output = Ret::Of<'a>>,
->(
-    storage: &mut MaybeUninit<FutureStorage>,
+unsafe fn store_future<FutureStorage: StorageMut, Fut: Future>(
+    slot: NonNull<()>,
     future: Fut,
-) -> &'static FutureVTable<Ret> {
-    storage.write(FutureStorage::new(future));
+) -> &'static FutureVTable {
+    // SAFETY: same precondition
+    let slot = unsafe { slot.cast::<FutureSlot<'_, FutureStorage>>().as_mut() };
+    // SAFETY: the future storage is dropped in `poll_future`, which borrows the slot,
+    // so before the cache borrowed by the slot
+    slot.future
+        .write(unsafe { FutureStorage::new_cached(future, slot.cache) });
     &FutureVTable {
-        // SAFETY: `poll` is called in poll_future, and
+        // SAFETY: `poll` is called in `poll_stored`, and
         // - `fut` is the future `Fut` written in the storage
-        // - the lifetime passed is the real one, so it can be transmuted
+        // - `out` points to the output slot of `poll_future`, whose type only differs from
+        //   `Fut::Output` by its lifetime, which is the real one
         // - the future is never moved during the polling
-        poll: |fut, cx, _| unsafe {
-            mem::transmute::<Poll<Ret::Of<'a>>, Poll<Ret::Of<'_>>>(
-                Pin::new_unchecked(fut.cast::<Fut>().as_mut()).poll(cx),
-            )
+        poll: |fut, cx, out| unsafe {
+            let poll = Pin::new_unchecked(fut.cast::<Fut>().as_mut()).poll(cx);
+            poll.map(|output| out.cast::<Fut::Output>().write(output))
         },
         drop_vtable: const { DropVTable::new::<FutureStorage, Fut>() },
     }
 }
 
+/// Drops the stored future, even if polling panics.
+///
+/// It doesn't depend on the future output type, so its code is shared between all the functions
+/// using the same future storage.
+struct FutureGuard<'s, FutureStorage: StorageMut> {
+    drop_vtable: &'static DropVTable,
+    future: &'s mut MaybeUninit<FutureStorage>,
+}
+
+impl<FutureStorage: StorageMut> Drop for FutureGuard<'_, FutureStorage> {
+    #[inline(never)]
+    fn drop(&mut self) {
+        // SAFETY: `future` is initialized and `drop_vtable` matches the future stored,
+        // as per `poll_future` contract; the storage is no longer accessed after the call
+        // (because it's dropped)
+        unsafe { (self.drop_vtable).drop_storage(self.future.assume_init_mut()) }
+    }
+}
+
+/// Polls the stored future once, and records the poll.
+///
+/// It's neither generic over the future nor over its output, so its code is shared between all
+/// the functions.
+#[inline(never)]
+fn poll_stored(
+    vtable: &FutureVTable,
+    future: NonNull<()>,
+    output: NonNull<()>,
+    recorder: &mut PollRecorder<'_>,
+    cx: &mut Context<'_>,
+) -> Poll<()> {
+    let poll = (vtable.poll)(future, cx, output);
+    recorder.record(&poll);
+    poll
+}
+
 /// # Safety
 ///
-/// `future` must be initialized, and `vtable` must match the data stored in `future`.
+/// `future` must be initialized, `vtable` must match the data stored in `future`, and the
+/// output of the future stored must be `Ret::Of<'a>`.
 async unsafe fn poll_future<'a, FutureStorage: StorageMut, Ret: ForLt + 'static>(
-    vtable: &'static FutureVTable<Ret>,
+    vtable: &'static FutureVTable,
     future: &mut MaybeUninit<FutureStorage>,
+    mut recorder: PollRecorder<'_>,
 ) -> Ret::Of<'a> {
-    let future = future.as_mut_ptr();
-    struct DropGuard<F: FnMut()>(F);
-    impl<F: FnMut()> Drop for DropGuard<F> {
-        fn drop(&mut self) {
-            self.0();
-        }
-    }
-    // SAFETY: `future` is initialized and `vtable` matches the future stored;
-    // the storage is no longer accessed after the call (because it's dropped)
-    let _guard = DropGuard(|| unsafe { vtable.drop_vtable.drop_storage(&mut *future) });
-    // SAFETY: `future` is initialized
-    poll_fn(|cx| unsafe { (vtable.poll)((*future).ptr_mut(), cx, PhantomData) }).await
+    let guard = FutureGuard {
+        drop_vtable: &vtable.drop_vtable,
+        future,
+    };
+    // SAFETY: `future` is initialized; the pointers are computed once, as the storage and the
+    // output are pinned in the calling future
+    let ptr = unsafe { guard.future.assume_init_mut() }.ptr_mut();
+    let mut output = MaybeUninit::<Ret::Of<'a>>::uninit();
+    let output_ptr = NonNull::from(&mut output).cast();
+    poll_fn(|cx| poll_stored(vtable, ptr, output_ptr, &mut recorder, cx)).await;
+    // SAFETY: the output is written by the future vtable when the future is ready
+    unsafe { output.assume_init() }
 }
 
+/// The future slot is passed type-erased, so the vtable doesn't depend on the future storage,
+/// and the vtable of a synchronous function can be built without knowing it. The returned
+/// future vtable doesn't depend on the output type either, see [`poll_future`].
 #[expect(type_alias_bounds)]
-type Call<Arg: ForLt, Ret: ForLt + 'static, FutureStorage, T> =
-    for<'a> fn(
-        NonNull<T>,
-        Arg::Of<'a>,
-        &mut MaybeUninit<FutureStorage>,
-        PhantomData<&'a ()>,
-    ) -> &'static FutureVTable<Ret>;
+type Call<Arg: ForLt, T> =
+    for<'a> fn(NonNull<T>, Arg::Of<'a>, NonNull<()>, PhantomData<&'a ()>) -> &'static FutureVTable;
 
 #[expect(type_alias_bounds)]
 type CallSync<Arg: ForLt, Ret: ForLt, T> =
     for<'a, 'b> fn(NonNull<T>, Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a>;
 
-struct AsyncVTable<Arg: ForLt, Ret: ForLt + 'static, FutureStorage, T: 'static = ()> {
-    call: Call<Arg, Ret, FutureStorage, T>,
-    call_sync: Option<CallSync<Arg, Ret, T>>,
-    drop_vtable: DropVTable,
+/// How a function is called, depending on whether it is synchronous.
+///
+/// A function has only the call matching its kind, so dispatching doesn't have to probe an
+/// optional function pointer, and there is no placeholder for the other call.
+pub(crate) enum Dispatch<Arg: ForLt, Ret: ForLt + 'static, T> {
+    Async(Call<Arg, T>),
+    Sync(CallSync<Arg, Ret, T>),
 }
 
-impl<Arg: ForLt + 'static, Ret: ForLt + 'static, FutureStorage: StorageMut, T: 'static> VTable
-    for AsyncVTable<Arg, Ret, FutureStorage, T>
-{
+pub(crate) struct AsyncVTable<Arg: ForLt, Ret: ForLt + 'static, T: 'static = ()> {
+    pub(crate) dispatch: Dispatch<Arg, Ret, T>,
+    pub(crate) drop_vtable: DropVTable,
+    /// The vtable of the function as a synchronous function, if it has one, so it can be
+    /// converted back without being moved into a new storage.
+    ///
+    /// It is returned by a function, as both vtables refer to each other.
+    pub(crate) sync_vtable: Option<fn() -> &'static SyncVTable<Arg, Ret, T>>,
+}
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static, T: 'static> VTable for AsyncVTable<Arg, Ret, T> {
     fn drop_vtable(&self) -> &DropVTable {
         &self.drop_vtable
     }
@@ -141,7 +222,23 @@ impl<F: Future> Future for SendFuture<F> {
     }
 }
 
+/// Calls the hook when dropped, unless forgotten once the call completes.
+struct OnCancel<H: Fn()>(H);
+
+impl<H: Fn()> Drop for OnCancel<H> {
+    fn drop(&mut self) {
+        (self.0)();
+    }
+}
+
 /// [`DynAsyncFn`], but without the [`Send`] + [`Sync`] requirement.
+///
+/// Its returned futures are not required to be [`Send`] either, so it can capture and hold
+/// thread-local state across `.await`, as commonly done with single-threaded executors, e.g.
+/// embassy's. With [`Raw`](crate::storage::Raw) function and future storages, calls never
+/// allocate.
+// `repr(C)`, so that the layout doesn't depend on the signature, see `AnyDynAsyncFn`
+#[repr(C)]
 pub struct LocalDynAsyncFn<
     'capture,
     Arg: ForLt + 'static,
@@ -149,7 +246,9 @@ pub struct LocalDynAsyncFn<
     FnStorage: Storage = DefaultFnStorage,
     FutureStorage: StorageMut = DefaultFutureStorage,
 > {
-    storage: DynStorage<FnStorage, AsyncVTable<Arg, Ret, FutureStorage>>,
+    storage: DynStorage<FnStorage, AsyncVTable<Arg, Ret>>,
+    future_cache: StorageCache<FutureStorage>,
+    stats: PollCounters,
     _capture: PhantomData<&'capture ()>,
 }
 
@@ -164,22 +263,28 @@ impl<
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_impl<
         F: for<'a> AsyncFn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
         let vtable = &AsyncVTable {
-            call: |func, arg, fut, _| {
+            dispatch: Dispatch::Async(|func, arg, fut, _| {
                 // SAFETY: func comes from `self.storage.ptr()`, so it's a valid `&F`
-                store_future(fut, unsafe { func.cast::<F>().as_ref()(arg, PhantomData) })
-            },
-            call_sync: None,
-            drop_vtable: const { DropVTable::new::<FnStorage, F>() },
+                let future = unsafe { func.cast::<F>().as_ref()(arg, PhantomData) };
+                // SAFETY: `fut` is the future slot passed to `call`
+                unsafe { store_future::<FutureStorage, _>(fut, future) }
+            }),
+            drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+            sync_vtable: None,
         };
         Self {
             // SAFETY: `drop_vtable` matches the storage
             storage: unsafe { DynStorage::new(storage, vtable) },
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
             _capture: PhantomData,
         }
     }
@@ -187,50 +292,78 @@ impl<
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_sync_impl<
         F: for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
         let vtable = &AsyncVTable {
-            call: |func, arg, fut, _| {
-                store_future(fut, async move {
-                    // SAFETY: func comes from `self.storage.ptr()`, so it's a valid `&F`
-                    unsafe { func.cast::<F>().as_ref()(arg, PhantomData) }
-                })
-            },
             // SAFETY: func comes from `self.storage.ptr()`, so it's a valid `&F`
-            call_sync: Some(|func, arg, _| unsafe { func.cast::<F>().as_ref()(arg, PhantomData) }),
-            drop_vtable: const { DropVTable::new::<FnStorage, F>() },
+            dispatch: Dispatch::Sync(|func, arg, _| unsafe {
+                func.cast::<F>().as_ref()(arg, PhantomData)
+            }),
+            drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+            sync_vtable: None,
         };
         Self {
             // SAFETY: `drop_vtable` matches the storage
             storage: unsafe { DynStorage::new(storage, vtable) },
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
             _capture: PhantomData,
         }
     }
 
     /// Returns whether the underlying function is synchronous.
+    #[inline]
     pub fn is_sync(&self) -> bool {
-        self.storage.vtable().call_sync.is_some()
+        matches!(self.storage.vtable().dispatch, Dispatch::Sync(_))
+    }
+
+    /// Returns the synchronous call of the underlying function, if it is synchronous.
+    #[inline]
+    fn sync_call(&self) -> Option<CallSync<Arg, Ret, ()>> {
+        match self.storage.vtable().dispatch {
+            Dispatch::Sync(call_sync) => Some(call_sync),
+            Dispatch::Async(_) => None,
+        }
     }
 
     /// Calls the underlying function.
+    ///
+    /// # Panics
+    ///
+    /// Besides the underlying function and its future, the returned future panics if it is polled
+    /// after completion, as any `async fn`. Allocated future storages also handle allocation
+    /// failures like `Box::new`.
     pub async fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
-        let mut future = MaybeUninit::uninit();
-        let vtable =
-            (self.storage.vtable().call)(self.storage.ptr(), arg, &mut future, PhantomData);
+        let call = match self.storage.vtable().dispatch {
+            Dispatch::Async(call) => call,
+            Dispatch::Sync(call_sync) => {
+                self.stats.recorder().record(&Poll::Ready(()));
+                return call_sync(self.storage.ptr(), arg, PhantomData);
+            }
+        };
+        let mut future = FutureSlot::<FutureStorage>::new(&self.future_cache);
+        let slot = NonNull::from(&mut future).cast();
+        let vtable = call(self.storage.ptr(), arg, slot, PhantomData);
         // SAFETY: `future` has been initialized in `call`, and the vtable
         // returned by `store_future` matches the future stored
-        unsafe { poll_future(vtable, &mut future) }.await
+        unsafe { poll_future(vtable, &mut future.future, self.stats.recorder()) }.await
     }
 
     /// Calls the underlying function if is synchronous.
+    ///
+    /// The call itself never panics, only the underlying function may.
     // TODO I've no idea why this code is not fully covered when alloc feature is enabled
     // Anyway, it surely comes from https://github.com/taiki-e/cargo-llvm-cov/issues/394
     #[cfg_attr(coverage_nightly, coverage(off))]
+    #[inline]
     pub fn call_sync<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
-        self.storage.vtable().call_sync?(self.storage.ptr(), arg, PhantomData).into()
+        let call_sync = self.sync_call()?;
+        Some(call_sync(self.storage.ptr(), arg, PhantomData))
     }
 
     /// Tries calling the underlying function as synchronous, falling back to asynchronous call.
@@ -244,10 +377,160 @@ impl<
     /// }
     /// ```
     pub async fn call_try_sync<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        match self.sync_call() {
+            Some(call_sync) => call_sync(self.storage.ptr(), arg, PhantomData),
+            None => self.call(arg).await,
+        }
+    }
+
+    /// Wraps the function with an argument validation.
+    ///
+    /// The function is only called if `validator` returns `Ok`, and its result is then wrapped in
+    /// `Ok`; otherwise, the validation error is returned.
+    ///
+    /// If the function is synchronous, the wrapped function is synchronous too.
+    #[cfg(feature = "alloc")]
+    pub fn with_async_arg_validation<E: 'static>(
+        self,
+        validator: impl Fn(&Arg::Of<'_>) -> Result<(), E> + 'capture,
+    ) -> LocalDynAsyncFn<'capture, Arg, ForResult<Ret, E>, crate::storage::Box, FutureStorage> {
+        if let Some(call_sync) = self.sync_call() {
+            LocalDynAsyncFn::new_sync(move |arg, _| {
+                validator(&arg)?;
+                Ok(call_sync(self.storage.ptr(), arg, PhantomData))
+            })
+        } else {
+            LocalDynAsyncFn::<Arg, ForResult<Ret, E>, _, _>::new(async move |arg, _| {
+                validator(&arg)?;
+                Ok(self.call(arg).await)
+            })
+        }
+    }
+
+    /// Combines the function with another one taking the same argument, returning both results.
+    ///
+    /// Functions are awaited sequentially, the argument being cloned for the first one.
+    ///
+    /// If both functions are synchronous, the combined function is synchronous too.
+    pub fn fanout<Ret2: ForLt + 'static>(
+        self,
+        other: LocalDynAsyncFn<'capture, Arg, Ret2, FnStorage, FutureStorage>,
+    ) -> LocalDynAsyncFn<'capture, Arg, ForTuple2<Ret, Ret2>, FnStorage, FutureStorage>
+    where
+        Arg: for<'a> ForLt<Of<'a>: Clone>,
+    {
+        if let (Some(call_sync), Some(other_call_sync)) = (self.sync_call(), other.sync_call()) {
+            LocalDynAsyncFn::new_sync(move |arg: Arg::Of<'_>, _| {
+                let ret = call_sync(self.storage.ptr(), arg.clone(), PhantomData);
+                (ret, other_call_sync(other.storage.ptr(), arg, PhantomData))
+            })
+        } else {
+            LocalDynAsyncFn::<Arg, ForTuple2<Ret, Ret2>, _, _>::new(
+                async move |arg: Arg::Of<'_>, _| {
+                    let ret = self.call(arg.clone()).await;
+                    (ret, other.call(arg).await)
+                },
+            )
+        }
+    }
+
+    /// Wraps the function with observers called with the argument when each call starts, and
+    /// with the result when it completes.
+    ///
+    /// The returned function has the same signature and storage, so it can replace the original
+    /// one transparently; the wrapper must fit in the storage, like any function passed to
+    /// [`new`](Self::new). If a call is cancelled, i.e. its future dropped before completion,
+    /// `on_complete` is not called; see [`inspect_cancel`](Self::inspect_cancel).
+    ///
+    /// If the function is synchronous, the wrapped function is synchronous too.
+    pub fn inspect(
+        self,
+        on_start: impl Fn(&Arg::Of<'_>) + 'capture,
+        on_complete: impl Fn(&Ret::Of<'_>) + 'capture,
+    ) -> Self {
+        if let Some(call_sync) = self.sync_call() {
+            Self::new_sync(move |arg, _| {
+                on_start(&arg);
+                let ret = call_sync(self.storage.ptr(), arg, PhantomData);
+                on_complete(&ret);
+                ret
+            })
+        } else {
+            Self::new(async move |arg, _| {
+                on_start(&arg);
+                let ret = self.call(arg).await;
+                on_complete(&ret);
+                ret
+            })
+        }
+    }
+
+    /// Wraps the function with a hook called when a call is cancelled, i.e. when its future is
+    /// dropped after having been polled, but before completion.
+    ///
+    /// A synchronous function completes its calls in their first poll, so they cannot be
+    /// cancelled; the function is then returned unchanged.
+    pub fn inspect_cancel(self, on_cancel: impl Fn() + 'capture) -> Self {
         if self.is_sync() {
-            self.call_sync(arg).unwrap()
+            return self;
+        }
+        Self::new(async move |arg, _| {
+            let guard = OnCancel(&on_cancel);
+            let ret = self.call(arg).await;
+            mem::forget(guard);
+            ret
+        })
+    }
+
+    /// Combines the function with another one taking the same argument, returning both results.
+    ///
+    /// Contrary to [`fanout`](Self::fanout), functions are awaited concurrently, the argument
+    /// being cloned for the first one; dropping the combined future drops both futures. As the
+    /// combined future embeds both futures with their storage, it never fits in a
+    /// [`Raw`](crate::storage::Raw) future storage, which fails to build; an allocated storage
+    /// like [`RawOrBox`](crate::storage::RawOrBox) moves it to the heap instead.
+    ///
+    /// If both functions are synchronous, the combined function is synchronous too.
+    pub fn zip<Ret2: ForLt + 'static>(
+        self,
+        other: LocalDynAsyncFn<'capture, Arg, Ret2, FnStorage, FutureStorage>,
+    ) -> LocalDynAsyncFn<'capture, Arg, ForTuple2<Ret, Ret2>, FnStorage, FutureStorage>
+    where
+        Arg: for<'a> ForLt<Of<'a>: Clone>,
+    {
+        if let (Some(call_sync), Some(other_call_sync)) = (self.sync_call(), other.sync_call()) {
+            LocalDynAsyncFn::new_sync(move |arg: Arg::Of<'_>, _| {
+                let ret = call_sync(self.storage.ptr(), arg.clone(), PhantomData);
+                (ret, other_call_sync(other.storage.ptr(), arg, PhantomData))
+            })
         } else {
-            self.call(arg).await
+            LocalDynAsyncFn::<Arg, ForTuple2<Ret, Ret2>, _, _>::new(
+                async move |arg: Arg::Of<'_>, _| {
+                    let mut fut1 = pin!(self.call(arg.clone()));
+                    let mut fut2 = pin!(other.call(arg));
+                    let (mut ret1, mut ret2) = (None, None);
+                    poll_fn(|cx| {
+                        if ret1.is_none() {
+                            if let Poll::Ready(ret) = fut1.as_mut().poll(cx) {
+                                ret1 = Some(ret);
+                            }
+                        }
+                        if ret2.is_none() {
+                            if let Poll::Ready(ret) = fut2.as_mut().poll(cx) {
+                                ret2 = Some(ret);
+                            }
+                        }
+                        match (ret1.take(), ret2.take()) {
+                            (Some(r1), Some(r2)) => Poll::Ready((r1, r2)),
+                            (r1, r2) => {
+                                (ret1, ret2) = (r1, r2);
+                                Poll::Pending
+                            }
+                        }
+                    })
+                    .await
+                },
+            )
         }
     }
 }
@@ -255,7 +538,32 @@ impl<
 new_impls!(async LocalDynAsyncFn, Storage, [for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture], for<'a> AsyncFn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture);
 
 impl_clone!(async LocalDynAsyncFn, Storage);
+impl_async_callable!(Fn LocalDynAsyncFn, Storage);
 impl_debug!(async LocalDynAsyncFn, Storage);
+impl_id!(async LocalDynAsyncFn, Storage);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(async LocalDynAsyncFn, Storage);
+impl_named!(async LocalDynAsyncFn, Storage);
+#[cfg(feature = "poll-stats")]
+impl_poll_stats!(LocalDynAsyncFn, Storage);
+impl_resize!(async LocalDynAsyncFn, Storage);
+impl_into_storage!(async LocalDynAsyncFn, Storage);
+#[cfg(feature = "alloc")]
+impl_leak!(async LocalDynAsyncFn, Storage);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(async LocalDynAsyncFn, Storage);
+impl_multi_args!(async Fn [AsyncFn] LocalDynAsyncFn, Storage, 'capture);
+impl_nullary!(async Fn [AsyncFn] LocalDynAsyncFn, Storage, 'capture);
+impl_with_state!(async Fn LocalDynAsyncFn, Storage, 'capture);
+impl_constant!(async LocalDynAsyncFn, Storage, 'capture);
+
+/// [`LocalDynAsyncFn`] taking no argument.
+pub type LocalDynAsyncFn0<
+    'capture,
+    R = (),
+    FnStorage = DefaultFnStorage,
+    FutureStorage = DefaultFutureStorage,
+> = LocalDynAsyncFn<'capture, ForFixed<()>, ForFixed<R>, FnStorage, FutureStorage>;
 
 /// A dynamic [`AsyncFn`] stored in `FnStorage`, whose returned future is stored in `FutureStorage`.
 ///
@@ -267,6 +575,7 @@ impl_debug!(async LocalDynAsyncFn, Storage);
 /// `DynAsyncFn` can also be initialized with a synchronous function, in which case
 /// [`call_try_sync`](Self::call_try_sync) offers a lot better performance than
 /// [`call`](Self::call).
+#[repr(transparent)]
 pub struct DynAsyncFn<
     'capture,
     Arg: ForLt + 'static,
@@ -289,18 +598,25 @@ impl<
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
-    const unsafe fn new_impl<F: AsyncFnSend<'capture, Arg, Ret>>(storage: FnStorage) -> Self {
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    const unsafe fn new_impl<F: AsyncFnSend<'capture, Arg, Ret>, N: MaybeName>(
+        storage: FnStorage,
+    ) -> Self {
         let vtable = &AsyncVTable {
-            call: |func, arg, fut, _| {
+            dispatch: Dispatch::Async(|func, arg, fut, _| {
                 // SAFETY: func comes from `self.storage.ptr()`, so it's a valid `&F`
-                store_future(fut, unsafe { func.cast::<F>().as_ref().call(arg) })
-            },
-            call_sync: None,
-            drop_vtable: const { DropVTable::new::<FnStorage, F>() },
+                let future = unsafe { func.cast::<F>().as_ref().call(arg) };
+                // SAFETY: `fut` is the future slot passed to `call`
+                unsafe { store_future::<FutureStorage, _>(fut, future) }
+            }),
+            drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+            sync_vtable: None,
         };
         Self(LocalDynAsyncFn {
             // SAFETY: `drop_vtable` matches the storage
             storage: unsafe { DynStorage::new(storage, vtable) },
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
             _capture: PhantomData,
         })
     }
@@ -308,16 +624,19 @@ impl<
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_sync_impl<
         F: for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
         // SAFETY: same precondition
-        Self(unsafe { LocalDynAsyncFn::new_sync_impl::<F>(storage) })
+        Self(unsafe { LocalDynAsyncFn::new_sync_impl::<F, N>(storage) })
     }
 
     /// Returns whether the underlying function is synchronous.
+    #[inline]
     pub fn is_sync(&self) -> bool {
         self.0.is_sync()
     }
@@ -330,6 +649,9 @@ impl<
     }
 
     /// Calls the underlying function if is synchronous.
+    ///
+    /// The call itself never panics, only the underlying function may.
+    #[inline]
     pub fn call_sync<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
         self.0.call_sync(arg)
     }
@@ -349,14 +671,317 @@ impl<
         // and futures capturing A [`Send`] + [`Sync`] function also implements `Send`
         unsafe { SendFuture::new(self.0.call_try_sync(arg)).await }
     }
+
+    /// Wraps the function with observers called with the argument when each call starts, and
+    /// with the result when it completes.
+    ///
+    /// See [`LocalDynAsyncFn::inspect`].
+    pub fn inspect(
+        self,
+        on_start: impl Fn(&Arg::Of<'_>) + Send + Sync + 'capture,
+        on_complete: impl Fn(&Ret::Of<'_>) + Send + Sync + 'capture,
+    ) -> Self {
+        // The wrapper only captures the function and `Send + Sync` observers, and its futures
+        // only hold references to them besides the future of the function, which is `Send`.
+        Self(self.0.inspect(on_start, on_complete))
+    }
+
+    /// Wraps the function with a hook called when a call is cancelled, i.e. when its future is
+    /// dropped after having been polled, but before completion.
+    ///
+    /// See [`LocalDynAsyncFn::inspect_cancel`].
+    pub fn inspect_cancel(self, on_cancel: impl Fn() + Send + Sync + 'capture) -> Self {
+        // The wrapper only captures the function and a `Send + Sync` hook, and its futures
+        // only hold a reference to the hook besides the future of the function, which is `Send`.
+        Self(self.0.inspect_cancel(on_cancel))
+    }
+
+    /// Combines the function with another one taking the same argument, returning both results.
+    ///
+    /// See [`LocalDynAsyncFn::zip`].
+    pub fn zip<Ret2: ForLt + 'static>(
+        self,
+        other: DynAsyncFn<'capture, Arg, Ret2, FnStorage, FutureStorage>,
+    ) -> DynAsyncFn<'capture, Arg, ForTuple2<Ret, Ret2>, FnStorage, FutureStorage>
+    where
+        Arg: for<'a> ForLt<Of<'a>: Clone>,
+    {
+        // The wrapper only captures both `Send + Sync` functions, and its futures only hold
+        // their futures, which are `Send`, besides the argument.
+        DynAsyncFn(self.0.zip(other.0))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<A: 'static, R: 'static, FnStorage: Storage + StorageSend, FutureStorage: StorageMut>
+    DynAsyncFn<'static, ForFixed<A>, ForFixed<R>, FnStorage, FutureStorage>
+{
+    /// Converts the function into a closure calling it, if it is synchronous, so it can be run
+    /// on a blocking thread; the function is returned unchanged otherwise.
+    pub(crate) fn try_into_blocking(self) -> Result<impl FnOnce(A) -> R + Send, Self> {
+        match self.0.sync_call() {
+            Some(call_sync) => Ok(move |arg| {
+                // The whole function is captured, as it is `Send` contrary to its storage
+                let this = &self;
+                call_sync(this.0.storage.ptr(), arg, PhantomData)
+            }),
+            None => Err(self),
+        }
+    }
 }
 
 new_impls!(async DynAsyncFn, Storage + StorageSend, [for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture], AsyncFnSend<'capture, Arg, Ret>);
 
 impl_clone!(async DynAsyncFn, Storage + StorageSend);
+impl_async_callable!(Fn DynAsyncFn, Storage + StorageSend);
 impl_debug!(async DynAsyncFn, Storage + StorageSend);
+impl_id!(async DynAsyncFn, Storage + StorageSend);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(async DynAsyncFn, Storage + StorageSend);
+impl_named!(async DynAsyncFn, Storage + StorageSend);
+#[cfg(feature = "poll-stats")]
+impl_poll_stats!(DynAsyncFn, Storage + StorageSend);
+impl_resize!(async DynAsyncFn, Storage + StorageSend);
+impl_into_storage!(async DynAsyncFn, Storage + StorageSend);
+#[cfg(feature = "alloc")]
+impl_leak!(async DynAsyncFn, Storage + StorageSend);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(async DynAsyncFn, Storage + StorageSend);
+impl_multi_args!(async Fn DynAsyncFn, Storage + StorageSend, Send + Sync + 'capture);
+impl_nullary!(async Fn DynAsyncFn, Storage + StorageSend, Send + Sync + 'capture);
+impl_with_state!(async Fn DynAsyncFn, Storage + StorageSend, Send + Sync + 'capture);
+impl_constant!(async DynAsyncFn, Storage + StorageSend, Send + Sync + 'capture);
+
+/// A method returning a boxed future, as returned by `#[async_trait]` methods, taking the
+/// object by [`Arc`] so the future can keep it alive.
+#[cfg(feature = "alloc")]
+type BoxedMethod<T, Arg, R> =
+    for<'a> fn(Arc<T>, <Arg as ForLt>::Of<'a>) -> Pin<Box<dyn Future<Output = R> + Send + 'a>>;
+
+/// A shared object with one of its methods, as an [`AsyncFnSend`].
+#[cfg(feature = "alloc")]
+struct DynMethod<T: ?Sized, Arg: ForLt + 'static, R> {
+    obj: Arc<T>,
+    method: BoxedMethod<T, Arg, R>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'capture, T: ?Sized + Send + Sync + 'static, Arg: ForLt + 'static, R: 'static>
+    AsyncFnSend<'capture, Arg, ForFixed<R>> for DynMethod<T, Arg, R>
+{
+    fn call<'a>(&self, arg: Arg::Of<'a>) -> impl Future<Output = R> + Send {
+        (self.method)(self.obj.clone(), arg)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    R: 'static,
+    FnStorage: Storage + StorageSend,
+    FutureStorage: StorageMut,
+> DynAsyncFn<'capture, Arg, ForFixed<R>, FnStorage, FutureStorage>
+{
+    /// Constructs a new function from a shared object, typically a `dyn Trait`, and one of its
+    /// methods returning a boxed future, e.g. an `#[async_trait]` method.
+    ///
+    /// It eases migrating from boxed trait objects without rewriting the trait. The [`Arc`],
+    /// which may be a fat pointer, is stored with the method, so they fit in
+    /// [`Raw<{ 3 * size_of::<usize>() }>`](crate::storage::Raw) storage. Each call passes a
+    /// clone of the `Arc` to the method, which moves it into the returned future; the object
+    /// cannot be lent to the argument, as it may be dropped before it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use async_trait::async_trait;
+    /// use dyn_fn::{DynAsyncFn, hkt::*};
+    /// use futures_util::FutureExt;
+    ///
+    /// #[async_trait]
+    /// trait Bar: Send + Sync {
+    ///     async fn call(&self, arg: &str) -> usize;
+    /// }
+    ///
+    /// struct Foo;
+    /// #[async_trait]
+    /// impl Bar for Foo {
+    ///     async fn call(&self, arg: &str) -> usize {
+    ///         arg.len()
+    ///     }
+    /// }
+    ///
+    /// let bar: Arc<dyn Bar> = Arc::new(Foo);
+    /// let f = DynAsyncFn::<ForRef<str>, ForFixed<usize>>::from_dyn_method(bar, |b, s| {
+    ///     Box::pin(async move { b.call(s).await })
+    /// });
+    /// assert_eq!(f.call("test").now_or_never(), Some(4));
+    /// ```
+    pub fn from_dyn_method<T: ?Sized + Send + Sync + 'static>(
+        obj: Arc<T>,
+        method: BoxedMethod<T, Arg, R>,
+    ) -> Self {
+        Self::new(DynMethod { obj, method })
+    }
+}
+
+/// [`DynAsyncFn`] taking no argument.
+pub type DynAsyncFn0<
+    'capture,
+    R = (),
+    FnStorage = DefaultFnStorage,
+    FutureStorage = DefaultFutureStorage,
+> = DynAsyncFn<'capture, ForFixed<()>, ForFixed<R>, FnStorage, FutureStorage>;
+
+/// [`DynAsyncFn`], but only requiring the function to be [`Send`].
+///
+/// It can be moved to another thread and called there, e.g. handed to the worker thread of an
+/// actor running a single-threaded executor, but it cannot be shared between threads, so it
+/// doesn't implement [`Sync`]. As a consequence, its returned futures, which borrow it, are not
+/// [`Send`] either.
+///
+/// Shared storages like [`Arc`](crate::storage::Arc) are not supported, as their clones would
+/// share the function.
+///
+/// # Examples
+///
+/// ```
+/// use std::{cell::Cell, thread};
+///
+/// use dyn_fn::{SendDynAsyncFn, hkt::ForFixed};
+///
+/// let count = Cell::new(0);
+/// let f = SendDynAsyncFn::<ForFixed<u64>, ForFixed<u64>>::new(async move |x, _| {
+///     tokio::task::yield_now().await;
+///     count.set(count.get() + x);
+///     count.get()
+/// });
+/// let worker = thread::spawn(move || {
+///     let rt = tokio::runtime::Builder::new_current_thread()
+///         .build()
+///         .unwrap();
+///     rt.block_on(async { f.call(1).await + f.call(2).await })
+/// });
+/// assert_eq!(worker.join().unwrap(), 4);
+/// ```
+pub struct SendDynAsyncFn<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: Storage + StorageSendOnly = DefaultFnStorage,
+    FutureStorage: StorageMut = DefaultFutureStorage,
+>(LocalDynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>);
+
+unsafe_impl_send_sync!(async send SendDynAsyncFn, Storage);
 
-/// [`DynAsyncFnMut`], but without the [`Send`] + [`Sync`] requirement.
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: Storage + StorageSendOnly,
+    FutureStorage: StorageMut,
+> SendDynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>
+{
+    /// # Safety
+    ///
+    /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    const unsafe fn new_impl<
+        F: for<'a> AsyncFn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture,
+        N: MaybeName,
+    >(
+        storage: FnStorage,
+    ) -> Self {
+        // SAFETY: same precondition
+        Self(unsafe { LocalDynAsyncFn::new_impl::<F, N>(storage) })
+    }
+
+    /// # Safety
+    ///
+    /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    const unsafe fn new_sync_impl<
+        F: for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture,
+        N: MaybeName,
+    >(
+        storage: FnStorage,
+    ) -> Self {
+        // SAFETY: same precondition
+        Self(unsafe { LocalDynAsyncFn::new_sync_impl::<F, N>(storage) })
+    }
+
+    /// Returns whether the underlying function is synchronous.
+    #[inline]
+    pub fn is_sync(&self) -> bool {
+        self.0.is_sync()
+    }
+
+    /// Calls the underlying function.
+    pub async fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.0.call(arg).await
+    }
+
+    /// Calls the underlying function if is synchronous.
+    #[inline]
+    pub fn call_sync<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        self.0.call_sync(arg)
+    }
+
+    /// Tries calling the underlying function as synchronous, falling back to asynchronous call.
+    ///
+    /// This is equivalent to
+    /// ```ignore
+    /// if self.is_sync() {
+    ///     self.call_sync(arg).unwrap()
+    /// } else {
+    ///     self.call(arg).await
+    /// }
+    /// ```
+    pub async fn call_try_sync<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.0.call_try_sync(arg).await
+    }
+}
+
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: Storage + StorageSend + StorageSendOnly,
+    FutureStorage: StorageMut,
+> From<DynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>>
+    for SendDynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>
+{
+    fn from(value: DynAsyncFn<'capture, Arg, Ret, FnStorage, FutureStorage>) -> Self {
+        Self(value.0)
+    }
+}
+
+new_impls!(async SendDynAsyncFn, Storage + StorageSendOnly, [for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture], for<'a> AsyncFn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture);
+
+impl_clone!(async SendDynAsyncFn, Storage + StorageSendOnly);
+impl_async_callable!(Fn SendDynAsyncFn, Storage + StorageSendOnly);
+impl_debug!(async SendDynAsyncFn, Storage + StorageSendOnly);
+impl_id!(async SendDynAsyncFn, Storage + StorageSendOnly);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(async SendDynAsyncFn, Storage + StorageSendOnly);
+impl_named!(async SendDynAsyncFn, Storage + StorageSendOnly);
+#[cfg(feature = "poll-stats")]
+impl_poll_stats!(SendDynAsyncFn, Storage + StorageSendOnly);
+impl_resize!(async SendDynAsyncFn, Storage + StorageSendOnly);
+impl_into_storage!(async SendDynAsyncFn, Storage + StorageSendOnly);
+#[cfg(feature = "alloc")]
+impl_leak!(async SendDynAsyncFn, Storage + StorageSendOnly);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(async SendDynAsyncFn, Storage + StorageSendOnly);
+impl_multi_args!(async Fn [AsyncFn] SendDynAsyncFn, Storage + StorageSendOnly, Send + 'capture);
+impl_nullary!(async Fn [AsyncFn] SendDynAsyncFn, Storage + StorageSendOnly, Send + 'capture);
+impl_with_state!(async Fn SendDynAsyncFn, Storage + StorageSendOnly, Send + 'capture);
+impl_constant!(async SendDynAsyncFn, Storage + StorageSendOnly, Send + 'capture);
+
+/// [`DynAsyncFnMut`], but without the [`Send`] requirement.
 pub struct LocalDynAsyncFnMut<
     'capture,
     Arg: ForLt + 'static,
@@ -364,7 +989,9 @@ pub struct LocalDynAsyncFnMut<
     FnStorage: StorageMut = DefaultFnStorage,
     FutureStorage: StorageMut = DefaultFutureStorage,
 > {
-    storage: DynStorage<FnStorage, AsyncVTable<Arg, Ret, FutureStorage>>,
+    storage: DynStorage<FnStorage, AsyncVTable<Arg, Ret>>,
+    future_cache: StorageCache<FutureStorage>,
+    stats: PollCounters,
     _capture: PhantomData<&'capture ()>,
 }
 
@@ -379,22 +1006,28 @@ impl<
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_impl<
         F: for<'a> AsyncFnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
         let vtable = &AsyncVTable {
-            call: |func, arg, fut, _| {
+            dispatch: Dispatch::Async(|func, arg, fut, _| {
                 // SAFETY: func comes from `self.storage.ptr_mut()`, so it's a valid `&mut F`
-                store_future(fut, unsafe { func.cast::<F>().as_mut()(arg, PhantomData) })
-            },
-            call_sync: None,
-            drop_vtable: const { DropVTable::new::<FnStorage, F>() },
+                let future = unsafe { func.cast::<F>().as_mut()(arg, PhantomData) };
+                // SAFETY: `fut` is the future slot passed to `call`
+                unsafe { store_future::<FutureStorage, _>(fut, future) }
+            }),
+            drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+            sync_vtable: None,
         };
         Self {
             // SAFETY: `drop_vtable` matches the storage
             storage: unsafe { DynStorage::new(storage, vtable) },
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
             _capture: PhantomData,
         }
     }
@@ -402,47 +1035,53 @@ impl<
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_sync_impl<
         F: for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
-        let vtable = &AsyncVTable {
-            call: |func, arg, fut, _| {
-                store_future(fut, async move {
-                    // SAFETY: func comes from `self.storage.ptr_mut()`, so it's a valid `&mut F`
-                    unsafe { func.cast::<F>().as_mut()(arg, PhantomData) }
-                })
-            },
-            // SAFETY: func comes from `self.storage.ptr_mut()`, so it's a valid `&mut F`
-            call_sync: Some(|func, arg, _| unsafe { func.cast::<F>().as_mut()(arg, PhantomData) }),
-            drop_vtable: const { DropVTable::new::<FnStorage, F>() },
-        };
+        let vtable = LocalDynFnMut::<'capture, Arg, Ret, FnStorage>::async_vtable::<F, N>();
         Self {
             // SAFETY: `drop_vtable` matches the storage
             storage: unsafe { DynStorage::new(storage, vtable) },
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
             _capture: PhantomData,
         }
     }
 
     /// Returns whether the underlying function is synchronous.
+    #[inline]
     pub fn is_sync(&self) -> bool {
-        self.storage.vtable().call_sync.is_some()
+        matches!(self.storage.vtable().dispatch, Dispatch::Sync(_))
     }
 
     /// Calls the underlying function.
     pub async fn call<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
-        let mut future = MaybeUninit::uninit();
-        let vtable =
-            (self.storage.vtable().call)(self.storage.ptr_mut(), arg, &mut future, PhantomData);
+        let call = match self.storage.vtable().dispatch {
+            Dispatch::Async(call) => call,
+            Dispatch::Sync(call_sync) => {
+                self.stats.recorder().record(&Poll::Ready(()));
+                return call_sync(self.storage.ptr_mut(), arg, PhantomData);
+            }
+        };
+        let mut future = FutureSlot::<FutureStorage>::new(&self.future_cache);
+        let slot = NonNull::from(&mut future).cast();
+        let vtable = call(self.storage.ptr_mut(), arg, slot, PhantomData);
         // SAFETY: `future` has been initialized in `call`, and the vtable
         // returned by `store_future` matches the future stored
-        unsafe { poll_future(vtable, &mut future) }.await
+        unsafe { poll_future(vtable, &mut future.future, self.stats.recorder()) }.await
     }
 
     /// Calls the underlying function if is synchronous.
+    #[inline]
     pub fn call_sync<'a>(&mut self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
-        self.storage.vtable().call_sync?(self.storage.ptr_mut(), arg, PhantomData).into()
+        match self.storage.vtable().dispatch {
+            Dispatch::Sync(call_sync) => Some(call_sync(self.storage.ptr_mut(), arg, PhantomData)),
+            Dispatch::Async(_) => None,
+        }
     }
 
     /// Tries calling the underlying function as synchronous, falling back to asynchronous call.
@@ -456,17 +1095,73 @@ impl<
     /// }
     /// ```
     pub async fn call_try_sync<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
-        if self.is_sync() {
-            self.call_sync(arg).unwrap()
-        } else {
-            self.call(arg).await
+        match self.storage.vtable().dispatch {
+            Dispatch::Sync(call_sync) => call_sync(self.storage.ptr_mut(), arg, PhantomData),
+            Dispatch::Async(_) => self.call(arg).await,
+        }
+    }
+
+    /// Converts the function into a [`LocalDynFnMut`] if it is synchronous, without moving it
+    /// into a new storage, so it keeps its state; the function is returned unchanged otherwise.
+    pub fn try_into_sync(self) -> Result<LocalDynFnMut<'capture, Arg, Ret, FnStorage>, Self> {
+        match LocalDynFnMut::try_from_async_storage(self.storage) {
+            Ok(f) => Ok(f),
+            Err(storage) => Err(Self { storage, ..self }),
         }
     }
 }
 
 new_impls!(async LocalDynAsyncFnMut, StorageMut, [for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture], for<'a> AsyncFnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture);
 
+impl_async_callable!(FnMut LocalDynAsyncFnMut, StorageMut);
 impl_debug!(async LocalDynAsyncFnMut, StorageMut);
+impl_id!(async LocalDynAsyncFnMut, StorageMut);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(async LocalDynAsyncFnMut, StorageMut);
+impl_named!(async LocalDynAsyncFnMut, StorageMut);
+#[cfg(feature = "poll-stats")]
+impl_poll_stats!(LocalDynAsyncFnMut, StorageMut);
+impl_resize!(async LocalDynAsyncFnMut, StorageMut);
+impl_into_storage!(async LocalDynAsyncFnMut, StorageMut);
+#[cfg(feature = "alloc")]
+impl_leak!(async LocalDynAsyncFnMut, StorageMut);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(async LocalDynAsyncFnMut, StorageMut);
+impl_multi_args!(async FnMut [AsyncFnMut] LocalDynAsyncFnMut, StorageMut, 'capture);
+impl_nullary!(async FnMut [AsyncFnMut] LocalDynAsyncFnMut, StorageMut, 'capture);
+impl_with_state!(async FnMut LocalDynAsyncFnMut, StorageMut, 'capture);
+
+#[cfg(feature = "sync-to-async")]
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: StorageMut,
+    FutureStorage: StorageMut,
+> From<LocalDynFnMut<'capture, Arg, Ret, FnStorage>>
+    for LocalDynAsyncFnMut<'capture, Arg, Ret, FnStorage, FutureStorage>
+{
+    /// Converts a synchronous function, which is kept in its storage, without allocating.
+    ///
+    /// The converted function is [synchronous](Self::is_sync), so
+    /// [`call_sync`](Self::call_sync) calls it without building a future.
+    fn from(value: LocalDynFnMut<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self {
+            storage: value.into_async_storage(),
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
+            _capture: PhantomData,
+        }
+    }
+}
+
+/// [`LocalDynAsyncFnMut`] taking no argument.
+pub type LocalDynAsyncFnMut0<
+    'capture,
+    R = (),
+    FnStorage = DefaultFnStorage,
+    FutureStorage = DefaultFutureStorage,
+> = LocalDynAsyncFnMut<'capture, ForFixed<()>, ForFixed<R>, FnStorage, FutureStorage>;
 
 /// A dynamic [`AsyncFnMut`] stored in `FnStorage`, whose returned future is stored in
 /// `FutureStorage`.
@@ -479,6 +1174,8 @@ impl_debug!(async LocalDynAsyncFnMut, StorageMut);
 /// `DynAsyncFnMut` can also be initialized with a synchronous function, in which case
 /// [`call_try_sync`](Self::call_try_sync) offers a lot better performance than
 /// [`call`](Self::call).
+///
+/// As for [`DynFnMut`](crate::DynFnMut), the function only needs to be [`Send`].
 pub struct DynAsyncFnMut<
     'capture,
     Arg: ForLt + 'static,
@@ -487,7 +1184,7 @@ pub struct DynAsyncFnMut<
     FutureStorage: StorageMut = DefaultFutureStorage,
 >(LocalDynAsyncFnMut<'capture, Arg, Ret, FnStorage, FutureStorage>);
 
-unsafe_impl_send_sync!(async DynAsyncFnMut, StorageMut);
+unsafe_impl_send_sync!(async exclusive DynAsyncFnMut, StorageMut);
 
 impl<
     'capture,
@@ -500,18 +1197,25 @@ impl<
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
-    const unsafe fn new_impl<F: AsyncFnMutSend<'capture, Arg, Ret>>(storage: FnStorage) -> Self {
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    const unsafe fn new_impl<F: AsyncFnMutSend<'capture, Arg, Ret>, N: MaybeName>(
+        storage: FnStorage,
+    ) -> Self {
         let vtable = &AsyncVTable {
-            call: |func, arg, fut, _| {
+            dispatch: Dispatch::Async(|func, arg, fut, _| {
                 // SAFETY: func comes from `self.storage.ptr_mut()`, so it's a valid `&mut F`
-                store_future(fut, unsafe { func.cast::<F>().as_mut().call(arg) })
-            },
-            call_sync: None,
-            drop_vtable: const { DropVTable::new::<FnStorage, F>() },
+                let future = unsafe { func.cast::<F>().as_mut().call(arg) };
+                // SAFETY: `fut` is the future slot passed to `call`
+                unsafe { store_future::<FutureStorage, _>(fut, future) }
+            }),
+            drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+            sync_vtable: None,
         };
         Self(LocalDynAsyncFnMut {
             // SAFETY: `drop_vtable` matches the storage
             storage: unsafe { DynStorage::new(storage, vtable) },
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
             _capture: PhantomData,
         })
     }
@@ -519,16 +1223,19 @@ impl<
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_sync_impl<
-        F: for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture,
+        F: for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
         // SAFETY: same precondition
-        Self(unsafe { LocalDynAsyncFnMut::new_sync_impl::<F>(storage) })
+        Self(unsafe { LocalDynAsyncFnMut::new_sync_impl::<F, N>(storage) })
     }
 
     /// Returns whether the underlying function is synchronous.
+    #[inline]
     pub fn is_sync(&self) -> bool {
         self.0.is_sync()
     }
@@ -536,11 +1243,12 @@ impl<
     /// Calls the underlying function.
     pub async fn call<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
         // SAFETY: Future returned by `AsyncFnMutSend` implements `Send`,
-        // and futures capturing A [`Send`] + [`Sync`] function also implements `Send`
+        // and futures capturing a `Send` function by value or `&mut` also implement `Send`
         unsafe { SendFuture::new(self.0.call(arg)).await }
     }
 
     /// Calls the underlying function if is synchronous.
+    #[inline]
     pub fn call_sync<'a>(&mut self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
         self.0.call_sync(arg)
     }
@@ -557,16 +1265,75 @@ impl<
     /// ```
     pub async fn call_try_sync<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
         // SAFETY: Future returned by `AsyncFnMutSend` implements `Send`,
-        // and futures capturing A [`Send`] + [`Sync`] function also implements `Send`
+        // and futures capturing a `Send` function by value or `&mut` also implement `Send`
         unsafe { SendFuture::new(self.0.call_try_sync(arg)).await }
     }
+
+    /// Converts the function into a [`DynFnMut`] if it is synchronous, without moving it into a
+    /// new storage, so it keeps its state; the function is returned unchanged otherwise.
+    pub fn try_into_sync(self) -> Result<DynFnMut<'capture, Arg, Ret, FnStorage>, Self> {
+        // SAFETY: synchronous functions of `DynAsyncFnMut` are `Send`
+        match unsafe { DynFnMut::try_from_async_storage(self.0.storage) } {
+            Ok(f) => Ok(f),
+            Err(storage) => Err(Self(LocalDynAsyncFnMut { storage, ..self.0 })),
+        }
+    }
 }
 
-new_impls!(async DynAsyncFnMut, StorageMut + StorageSend, [for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture], AsyncFnMutSend<'capture, Arg, Ret>);
+new_impls!(async DynAsyncFnMut, StorageMut + StorageSend, [for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture], AsyncFnMutSend<'capture, Arg, Ret>);
 
+impl_async_callable!(FnMut DynAsyncFnMut, StorageMut + StorageSend);
 impl_debug!(async DynAsyncFnMut, StorageMut + StorageSend);
+impl_id!(async DynAsyncFnMut, StorageMut + StorageSend);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(async DynAsyncFnMut, StorageMut + StorageSend);
+impl_named!(async DynAsyncFnMut, StorageMut + StorageSend);
+#[cfg(feature = "poll-stats")]
+impl_poll_stats!(DynAsyncFnMut, StorageMut + StorageSend);
+impl_resize!(async DynAsyncFnMut, StorageMut + StorageSend);
+impl_into_storage!(async DynAsyncFnMut, StorageMut + StorageSend);
+#[cfg(feature = "alloc")]
+impl_leak!(async DynAsyncFnMut, StorageMut + StorageSend);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(async DynAsyncFnMut, StorageMut + StorageSend);
+impl_multi_args!(async FnMut DynAsyncFnMut, StorageMut + StorageSend, Send + 'capture);
+impl_nullary!(async FnMut DynAsyncFnMut, StorageMut + StorageSend, Send + 'capture);
+impl_with_state!(async FnMut DynAsyncFnMut, StorageMut + StorageSend, Send + 'capture);
+
+#[cfg(feature = "sync-to-async")]
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: StorageMut + StorageSend,
+    FutureStorage: StorageMut,
+> From<DynFnMut<'capture, Arg, Ret, FnStorage>>
+    for DynAsyncFnMut<'capture, Arg, Ret, FnStorage, FutureStorage>
+{
+    /// Converts a synchronous function, which is kept in its storage, without allocating.
+    ///
+    /// The converted function is [synchronous](Self::is_sync), so
+    /// [`call_sync`](Self::call_sync) calls it without building a future.
+    fn from(value: DynFnMut<'capture, Arg, Ret, FnStorage>) -> Self {
+        // the function is `Send`, and synchronous, so it never returns a future
+        Self(LocalDynAsyncFnMut {
+            storage: value.into_async_storage(),
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
+            _capture: PhantomData,
+        })
+    }
+}
 
-/// [`DynAsyncFnOnce`], but without the [`Send`] + [`Sync`] requirement.
+/// [`DynAsyncFnMut`] taking no argument.
+pub type DynAsyncFnMut0<
+    'capture,
+    R = (),
+    FnStorage = DefaultFnStorage,
+    FutureStorage = DefaultFutureStorage,
+> = DynAsyncFnMut<'capture, ForFixed<()>, ForFixed<R>, FnStorage, FutureStorage>;
+
+/// [`DynAsyncFnOnce`], but without the [`Send`] requirement.
 pub struct LocalDynAsyncFnOnce<
     'capture,
     Arg: ForLt + 'static,
@@ -574,7 +1341,9 @@ pub struct LocalDynAsyncFnOnce<
     FnStorage: StorageMut = DefaultFnStorage,
     FutureStorage: StorageMut = DefaultFutureStorage,
 > {
-    storage: DynStorage<FnStorage, AsyncVTable<Arg, Ret, FutureStorage, FnStorage>>,
+    storage: DynStorage<FnStorage, AsyncVTable<Arg, Ret, FnStorage>>,
+    future_cache: StorageCache<FutureStorage>,
+    stats: PollCounters,
     _capture: PhantomData<&'capture ()>,
 }
 
@@ -589,25 +1358,32 @@ impl<
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_impl<
         F: for<'a> AsyncFnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
         let vtable = &AsyncVTable {
-            call: |func, arg, fut, _| {
+            dispatch: Dispatch::Async(|func, arg, fut, _| {
+                // If building the future panics, the `StorageMoved` temporary is still dropped,
+                // releasing the storage, while `F` has already been moved into the call.
                 // SAFETY: storage comes from `DynStorage::move_storage`,
-                // so it's a valid `F`, and is never accessed after; `read`is called once
-                store_future(fut, unsafe {
-                    StorageMoved::<FnStorage, F>::new(func).read()(arg, PhantomData)
-                })
-            },
-            call_sync: None,
-            drop_vtable: const { DropVTable::new::<FnStorage, F>() },
+                // so it's a valid `F`, and is never accessed after; `read` is called once
+                let future =
+                    unsafe { StorageMoved::<FnStorage, F>::new(func).read()(arg, PhantomData) };
+                // SAFETY: `fut` is the future slot passed to `call`
+                unsafe { store_future::<FutureStorage, _>(fut, future) }
+            }),
+            drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+            sync_vtable: None,
         };
         Self {
             // SAFETY: `drop_vtable` matches the storage
             storage: unsafe { DynStorage::new(storage, vtable) },
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
             _capture: PhantomData,
         }
     }
@@ -615,57 +1391,74 @@ impl<
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_sync_impl<
         F: for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
-        let vtable = &AsyncVTable {
-            call: |func, arg, fut, _| {
-                store_future(fut, async move {
-                    // SAFETY: storage comes from `DynStorage::move_storage`,
-                    // so it's a valid `F`, and is never accessed after; `read`is called once
-                    unsafe { StorageMoved::<FnStorage, F>::new(func).read()(arg, PhantomData) }
-                })
-            },
-            // SAFETY: storage comes from `DynStorage::move_storage`,
-            // so it's a valid `F`, and is never accessed after; `read`is called once
-            call_sync: Some(|func, arg, _| unsafe {
-                StorageMoved::<FnStorage, F>::new(func).read()(arg, PhantomData)
-            }),
-            drop_vtable: const { DropVTable::new::<FnStorage, F>() },
-        };
+        let vtable = LocalDynFnOnce::<'capture, Arg, Ret, FnStorage>::async_vtable::<F, N>();
         Self {
             // SAFETY: `drop_vtable` matches the storage
             storage: unsafe { DynStorage::new(storage, vtable) },
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
             _capture: PhantomData,
         }
     }
 
     /// Returns whether the underlying function is synchronous.
+    #[inline]
     pub fn is_sync(&self) -> bool {
-        self.storage.vtable().call_sync.is_some()
+        matches!(self.storage.vtable().dispatch, Dispatch::Sync(_))
+    }
+
+    /// Returns the synchronous call of the underlying function, if it is synchronous.
+    #[inline]
+    fn sync_call(&self) -> Option<CallSync<Arg, Ret, FnStorage>> {
+        match self.storage.vtable().dispatch {
+            Dispatch::Sync(call_sync) => Some(call_sync),
+            Dispatch::Async(_) => None,
+        }
+    }
+
+    /// Calls the underlying function through `call_sync`, as returned by
+    /// [`sync_call`](Self::sync_call).
+    #[inline]
+    fn call_sync_with(
+        self,
+        call_sync: CallSync<Arg, Ret, FnStorage>,
+        arg: Arg::Of<'_>,
+    ) -> Ret::Of<'_> {
+        let mut storage = ManuallyDrop::new(self.storage);
+        // SAFETY: `moved_storage` is passed to `StorageMoved` in `call_sync`
+        let moved_storage = unsafe { DynStorage::move_storage(&mut storage) };
+        call_sync(moved_storage, arg, PhantomData)
     }
 
     /// Calls the underlying function.
     pub async fn call<'a>(self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        let call = match self.storage.vtable().dispatch {
+            Dispatch::Async(call) => call,
+            Dispatch::Sync(call_sync) => return self.call_sync_with(call_sync, arg),
+        };
         let mut storage = ManuallyDrop::new(self.storage);
-        let mut future = MaybeUninit::uninit();
+        let mut future = FutureSlot::<FutureStorage>::new(&self.future_cache);
         // SAFETY: `moved_storage` is passed to `StorageMoved` in `call`
         let moved_storage = unsafe { DynStorage::move_storage(&mut storage) };
-        let vtable = (storage.vtable().call)(moved_storage, arg, &mut future, PhantomData);
+        let slot = NonNull::from(&mut future).cast();
+        let vtable = call(moved_storage, arg, slot, PhantomData);
         // SAFETY: `future` has been initialized in `call`, and the vtable
         // returned by `store_future` matches the future stored
-        unsafe { poll_future(vtable, &mut future) }.await
+        unsafe { poll_future(vtable, &mut future.future, self.stats.recorder()) }.await
     }
 
     /// Calls the underlying function if is synchronous.
+    #[inline]
     pub fn call_sync(self, arg: Arg::Of<'_>) -> Option<Ret::Of<'_>> {
-        let call_sync = self.storage.vtable().call_sync?;
-        let mut storage = ManuallyDrop::new(self.storage);
-        // SAFETY: `moved_storage` is passed to `StorageMoved` in `call_sync`
-        let moved_storage = unsafe { DynStorage::move_storage(&mut storage) };
-        call_sync(moved_storage, arg, PhantomData).into()
+        let call_sync = self.sync_call()?;
+        Some(self.call_sync_with(call_sync, arg))
     }
 
     /// Tries calling the underlying function as synchronous, falling back to asynchronous call.
@@ -679,17 +1472,85 @@ impl<
     /// }
     /// ```
     pub async fn call_try_sync<'a>(self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
-        if self.is_sync() {
-            self.call_sync(arg).unwrap()
+        match self.sync_call() {
+            Some(call_sync) => self.call_sync_with(call_sync, arg),
+            None => self.call(arg).await,
+        }
+    }
+
+    /// Converts the function into a [`LocalDynFnOnce`] if it is synchronous, without moving it
+    /// into a new storage; the function is returned unchanged otherwise.
+    pub fn try_into_sync(self) -> Result<LocalDynFnOnce<'capture, Arg, Ret, FnStorage>, Self> {
+        match LocalDynFnOnce::try_from_async_storage(self.storage) {
+            Ok(f) => Ok(f),
+            Err(storage) => Err(Self { storage, ..self }),
+        }
+    }
+
+    /// Maps the value returned by the function.
+    ///
+    /// The function and `f` are moved together into a new storage.
+    ///
+    /// If the function is synchronous, the mapped function is synchronous too.
+    pub fn map<NewRet: ForLt + 'static>(
+        self,
+        f: impl for<'a> FnOnce(Ret::Of<'a>, PhantomData<&'a ()>) -> NewRet::Of<'a> + 'capture,
+    ) -> LocalDynAsyncFnOnce<'capture, Arg, NewRet, FnStorage, FutureStorage> {
+        if let Some(call_sync) = self.sync_call() {
+            LocalDynAsyncFnOnce::new_sync(move |arg, lt| f(self.call_sync_with(call_sync, arg), lt))
         } else {
-            self.call(arg).await
+            LocalDynAsyncFnOnce::<Arg, NewRet, _, _>::new(async move |arg, lt| {
+                f(self.call(arg).await, lt)
+            })
         }
     }
 }
 
 new_impls!(async LocalDynAsyncFnOnce, StorageMut, [for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture], for<'a> AsyncFnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture);
 
+impl_async_callable!(FnOnce LocalDynAsyncFnOnce, StorageMut);
 impl_debug!(async LocalDynAsyncFnOnce, StorageMut);
+impl_id!(async LocalDynAsyncFnOnce, StorageMut);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(async LocalDynAsyncFnOnce, StorageMut);
+impl_named!(async LocalDynAsyncFnOnce, StorageMut);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(async LocalDynAsyncFnOnce, StorageMut);
+impl_multi_args!(async FnOnce [AsyncFnOnce] LocalDynAsyncFnOnce, StorageMut, 'capture);
+impl_nullary!(async FnOnce [AsyncFnOnce] LocalDynAsyncFnOnce, StorageMut, 'capture);
+impl_with_state!(async FnOnce LocalDynAsyncFnOnce, StorageMut, 'capture);
+
+#[cfg(feature = "sync-to-async")]
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: StorageMut,
+    FutureStorage: StorageMut,
+> From<LocalDynFnOnce<'capture, Arg, Ret, FnStorage>>
+    for LocalDynAsyncFnOnce<'capture, Arg, Ret, FnStorage, FutureStorage>
+{
+    /// Converts a synchronous function, which is kept in its storage, without allocating.
+    ///
+    /// The converted function is [synchronous](Self::is_sync), so
+    /// [`call_try_sync`](Self::call_try_sync) calls it without building a future.
+    fn from(value: LocalDynFnOnce<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self {
+            storage: value.into_async_storage(),
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
+            _capture: PhantomData,
+        }
+    }
+}
+
+/// [`LocalDynAsyncFnOnce`] taking no argument.
+pub type LocalDynAsyncFnOnce0<
+    'capture,
+    R = (),
+    FnStorage = DefaultFnStorage,
+    FutureStorage = DefaultFutureStorage,
+> = LocalDynAsyncFnOnce<'capture, ForFixed<()>, ForFixed<R>, FnStorage, FutureStorage>;
 
 /// A dynamic [`AsyncFnOnce`] stored in `FnStorage`, whose returned future is stored in
 /// `FutureStorage`.
@@ -702,6 +1563,8 @@ impl_debug!(async LocalDynAsyncFnOnce, StorageMut);
 /// `DynAsyncFnOnce` can also be initialized with a synchronous function, in which case
 /// [`call_try_sync`](Self::call_try_sync) offers a lot better performance than
 /// [`call`](Self::call).
+///
+/// As for [`DynFnOnce`](crate::DynFnOnce), the function only needs to be [`Send`].
 pub struct DynAsyncFnOnce<
     'capture,
     Arg: ForLt + 'static,
@@ -710,7 +1573,7 @@ pub struct DynAsyncFnOnce<
     FutureStorage: StorageMut = DefaultFutureStorage,
 >(LocalDynAsyncFnOnce<'capture, Arg, Ret, FnStorage, FutureStorage>);
 
-unsafe_impl_send_sync!(async DynAsyncFnOnce, StorageMut);
+unsafe_impl_send_sync!(async exclusive DynAsyncFnOnce, StorageMut);
 
 impl<
     'capture,
@@ -723,21 +1586,26 @@ impl<
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
-    const unsafe fn new_impl<F: AsyncFnOnceSend<'capture, Arg, Ret>>(storage: FnStorage) -> Self {
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    const unsafe fn new_impl<F: AsyncFnOnceSend<'capture, Arg, Ret>, N: MaybeName>(
+        storage: FnStorage,
+    ) -> Self {
         let vtable = &AsyncVTable {
-            call: |func, arg, fut, _| {
+            dispatch: Dispatch::Async(|func, arg, fut, _| {
                 // SAFETY: storage comes from `DynStorage::move_storage`,
-                // so it's a valid `F`, and is never accessed after; `read`is called once
-                store_future(fut, unsafe {
-                    StorageMoved::<FnStorage, F>::new(func).read().call(arg)
-                })
-            },
-            call_sync: None,
-            drop_vtable: const { DropVTable::new::<FnStorage, F>() },
+                // so it's a valid `F`, and is never accessed after; `read` is called once
+                let future = unsafe { StorageMoved::<FnStorage, F>::new(func).read().call(arg) };
+                // SAFETY: `fut` is the future slot passed to `call`
+                unsafe { store_future::<FutureStorage, _>(fut, future) }
+            }),
+            drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+            sync_vtable: None,
         };
         Self(LocalDynAsyncFnOnce {
             // SAFETY: `drop_vtable` matches the storage
             storage: unsafe { DynStorage::new(storage, vtable) },
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
             _capture: PhantomData,
         })
     }
@@ -745,16 +1613,19 @@ impl<
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_sync_impl<
-        F: for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture,
+        F: for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
         // SAFETY: same precondition
-        Self(unsafe { LocalDynAsyncFnOnce::new_sync_impl::<F>(storage) })
+        Self(unsafe { LocalDynAsyncFnOnce::new_sync_impl::<F, N>(storage) })
     }
 
     /// Returns whether the underlying function is synchronous.
+    #[inline]
     pub fn is_sync(&self) -> bool {
         self.0.is_sync()
     }
@@ -762,11 +1633,12 @@ impl<
     /// Calls the underlying function.
     pub async fn call<'a>(self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
         // SAFETY: Future returned by `AsyncFnOnceSend` implements `Send`,
-        // and futures capturing A [`Send`] + [`Sync`] function also implements `Send`
+        // and futures capturing a `Send` function by value or `&mut` also implement `Send`
         unsafe { SendFuture::new(self.0.call(arg)).await }
     }
 
     /// Calls the underlying function if is synchronous.
+    #[inline]
     pub fn call_sync(self, arg: Arg::Of<'_>) -> Option<Ret::Of<'_>> {
         self.0.call_sync(arg)
     }
@@ -783,11 +1655,79 @@ impl<
     /// ```
     pub async fn call_try_sync<'a>(self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
         // SAFETY: Future returned by `AsyncFnOnceSend` implements `Send`,
-        // and futures capturing A [`Send`] + [`Sync`] function also implements `Send`
+        // and futures capturing a `Send` function by value or `&mut` also implement `Send`
         unsafe { SendFuture::new(self.0.call_try_sync(arg)).await }
     }
+
+    /// Converts the function into a [`DynFnOnce`] if it is synchronous, without moving it into a
+    /// new storage; the function is returned unchanged otherwise.
+    pub fn try_into_sync(self) -> Result<DynFnOnce<'capture, Arg, Ret, FnStorage>, Self> {
+        // SAFETY: synchronous functions of `DynAsyncFnOnce` are `Send`
+        match unsafe { DynFnOnce::try_from_async_storage(self.0.storage) } {
+            Ok(f) => Ok(f),
+            Err(storage) => Err(Self(LocalDynAsyncFnOnce { storage, ..self.0 })),
+        }
+    }
+
+    /// Maps the value returned by the function.
+    ///
+    /// The function and `f` are moved together into a new storage.
+    ///
+    /// If the function is synchronous, the mapped function is synchronous too.
+    pub fn map<NewRet: ForLt + 'static>(
+        self,
+        f: impl for<'a> FnOnce(Ret::Of<'a>, PhantomData<&'a ()>) -> NewRet::Of<'a> + Send + 'capture,
+    ) -> DynAsyncFnOnce<'capture, Arg, NewRet, FnStorage, FutureStorage> {
+        // The mapped future holds `f`, which implements `Send`, the future returned by the
+        // function, which implements `Send` as in `call`, and the argument, which is already
+        // held by the future returned by `call`
+        DynAsyncFnOnce(self.0.map(f))
+    }
 }
 
-new_impls!(async DynAsyncFnOnce, StorageMut + StorageSend, [for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture], AsyncFnOnceSend<'capture, Arg, Ret>);
+new_impls!(async DynAsyncFnOnce, StorageMut + StorageSend, [for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture], AsyncFnOnceSend<'capture, Arg, Ret>);
 
+impl_async_callable!(FnOnce DynAsyncFnOnce, StorageMut + StorageSend);
 impl_debug!(async DynAsyncFnOnce, StorageMut + StorageSend);
+impl_id!(async DynAsyncFnOnce, StorageMut + StorageSend);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(async DynAsyncFnOnce, StorageMut + StorageSend);
+impl_named!(async DynAsyncFnOnce, StorageMut + StorageSend);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(async DynAsyncFnOnce, StorageMut + StorageSend);
+impl_multi_args!(async FnOnce DynAsyncFnOnce, StorageMut + StorageSend, Send + 'capture);
+impl_nullary!(async FnOnce DynAsyncFnOnce, StorageMut + StorageSend, Send + 'capture);
+impl_with_state!(async FnOnce DynAsyncFnOnce, StorageMut + StorageSend, Send + 'capture);
+
+#[cfg(feature = "sync-to-async")]
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: StorageMut + StorageSend,
+    FutureStorage: StorageMut,
+> From<DynFnOnce<'capture, Arg, Ret, FnStorage>>
+    for DynAsyncFnOnce<'capture, Arg, Ret, FnStorage, FutureStorage>
+{
+    /// Converts a synchronous function, which is kept in its storage, without allocating.
+    ///
+    /// The converted function is [synchronous](Self::is_sync), so
+    /// [`call_try_sync`](Self::call_try_sync) calls it without building a future.
+    fn from(value: DynFnOnce<'capture, Arg, Ret, FnStorage>) -> Self {
+        // the function is `Send`, and synchronous, so it never returns a future
+        Self(LocalDynAsyncFnOnce {
+            storage: value.into_async_storage(),
+            future_cache: FutureStorage::EMPTY_CACHE,
+            stats: PollCounters::new(),
+            _capture: PhantomData,
+        })
+    }
+}
+
+/// [`DynAsyncFnOnce`] taking no argument.
+pub type DynAsyncFnOnce0<
+    'capture,
+    R = (),
+    FnStorage = DefaultFnStorage,
+    FutureStorage = DefaultFutureStorage,
+> = DynAsyncFnOnce<'capture, ForFixed<()>, ForFixed<R>, FnStorage, FutureStorage>;
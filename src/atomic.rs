@@ -0,0 +1,105 @@
+use core::fmt;
+
+use higher_kinded_types::ForFixed;
+
+use crate::{DynFn, ForLt, isr::SwapCell, storage::Arc};
+
+/// A cell holding a reference-counted [`DynFn`], which can be replaced while other threads are
+/// calling it.
+///
+/// [`load`](Self::load) is wait-free: it never blocks, and only clones the current function,
+/// which is a reference count increment, so the function can be called without holding the
+/// cell. [`store`](Self::store)/[`swap`](Self::swap) install a new function in place of the
+/// current one, waiting for the loads still cloning the previous function to complete.
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::Arc, thread};
+///
+/// use dyn_fn::{AtomicDynFn, DynFn, hkt::ForFixed};
+///
+/// let scale = Arc::new(AtomicDynFn::<ForFixed<u32>, ForFixed<u32>>::new());
+/// scale.store(DynFn::new(|x, _| x));
+/// let reader = thread::spawn({
+///     let scale = scale.clone();
+///     move || scale.load().unwrap().call(21)
+/// });
+/// scale.store(DynFn::new(|x, _| 2 * x));
+/// assert!(matches!(reader.join().unwrap(), 21 | 42));
+/// assert_eq!(scale.load().unwrap().call(21), 42);
+/// ```
+pub struct AtomicDynFn<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static = ForFixed<()>>(
+    SwapCell<DynFn<'capture, Arg, Ret, Arc>>,
+);
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static> AtomicDynFn<'capture, Arg, Ret> {
+    /// Constructs a new empty cell.
+    #[cfg(not(loom))]
+    pub const fn new() -> Self {
+        Self(SwapCell::new())
+    }
+
+    /// Constructs a new empty cell.
+    #[cfg(loom)]
+    pub fn new() -> Self {
+        Self(SwapCell::new())
+    }
+
+    /// Returns whether a function is installed.
+    pub fn is_set(&self) -> bool {
+        self.0.is_set()
+    }
+
+    /// Returns a clone of the installed function, if any, without ever blocking.
+    pub fn load(&self) -> Option<DynFn<'capture, Arg, Ret, Arc>> {
+        self.0.with_active(DynFn::clone)
+    }
+
+    /// Installs a function, dropping the previous one.
+    pub fn store(&self, f: DynFn<'capture, Arg, Ret, Arc>) {
+        drop(self.swap(f));
+    }
+
+    /// Installs a function, returning the previous one.
+    pub fn swap(
+        &self,
+        f: DynFn<'capture, Arg, Ret, Arc>,
+    ) -> Option<DynFn<'capture, Arg, Ret, Arc>> {
+        self.0.replace(Some(f))
+    }
+
+    /// Uninstalls the function, returning it.
+    pub fn take(&self) -> Option<DynFn<'capture, Arg, Ret, Arc>> {
+        self.0.replace(None)
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static> Default
+    for AtomicDynFn<'capture, Arg, Ret>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static> From<DynFn<'capture, Arg, Ret, Arc>>
+    for AtomicDynFn<'capture, Arg, Ret>
+{
+    fn from(value: DynFn<'capture, Arg, Ret, Arc>) -> Self {
+        let this = Self::new();
+        this.store(value);
+        this
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static> fmt::Debug
+    for AtomicDynFn<'capture, Arg, Ret>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AtomicDynFn")
+            .field("is_set", &self.is_set())
+            .finish_non_exhaustive()
+    }
+}
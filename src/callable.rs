@@ -0,0 +1,229 @@
+#[cfg(feature = "async")]
+use core::future::Future;
+use core::marker::PhantomData;
+
+use crate::ForLt;
+
+/// Something callable by value with `Arg` and returning `Ret`.
+///
+/// It is implemented by every synchronous dynamic function, whatever its storage, as well as by
+/// closures with the same signature, allowing generic code to accept any of them.
+pub trait DynCallableOnce<Arg: ForLt, Ret: ForLt> {
+    /// Calls the function by value.
+    fn call_once<'a>(self, arg: Arg::Of<'a>) -> Ret::Of<'a>;
+}
+
+/// Something callable by mutable reference with `Arg` and returning `Ret`.
+///
+/// See [`DynCallableOnce`].
+pub trait DynCallableMut<Arg: ForLt, Ret: ForLt>: DynCallableOnce<Arg, Ret> {
+    /// Calls the function by mutable reference.
+    fn call_mut<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a>;
+}
+
+/// Something callable by reference with `Arg` and returning `Ret`.
+///
+/// See [`DynCallableOnce`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use std::marker::PhantomData;
+///
+/// use dyn_fn::{DynCallable, DynFn, LocalDynFn, hkt::*, storage};
+///
+/// struct Registry<C>(Vec<C>);
+///
+/// impl<C: DynCallable<ForRef<str>, ForFixed<usize>>> Registry<C> {
+///     fn call_all(&self, s: &str) -> usize {
+///         self.0.iter().map(|c| c.call(s)).sum()
+///     }
+/// }
+///
+/// type SendCallback = DynFn<'static, ForRef<str>, ForFixed<usize>, storage::Arc>;
+/// let registry = Registry(vec![SendCallback::new(|s, _| s.len())]);
+/// assert_eq!(registry.call_all("test"), 4);
+///
+/// type LocalCallback<'a> = LocalDynFn<'a, ForRef<str>, ForFixed<usize>, storage::Raw<16>>;
+/// let prefix = "prefix";
+/// let registry = Registry(vec![
+///     LocalCallback::new(|s, _| s.len()),
+///     LocalCallback::new(|s, _| s.find(prefix).unwrap_or(0)),
+/// ]);
+/// assert_eq!(registry.call_all("a prefix"), 10);
+///
+/// // closure parameters must be annotated to be inferred as higher-ranked
+/// let registry = Registry(vec![|s: &str, _: PhantomData<&()>| s.len()]);
+/// assert_eq!(registry.call_all("test"), 4);
+/// # }
+/// ```
+pub trait DynCallable<Arg: ForLt, Ret: ForLt>: DynCallableMut<Arg, Ret> {
+    /// Calls the function by reference.
+    fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a>;
+}
+
+impl<Arg: ForLt, Ret: ForLt, F: for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a>>
+    DynCallableOnce<Arg, Ret> for F
+{
+    #[inline]
+    fn call_once<'a>(self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self(arg, PhantomData)
+    }
+}
+
+impl<Arg: ForLt, Ret: ForLt, F: for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a>>
+    DynCallableMut<Arg, Ret> for F
+{
+    #[inline]
+    fn call_mut<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self(arg, PhantomData)
+    }
+}
+
+impl<Arg: ForLt, Ret: ForLt, F: for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a>>
+    DynCallable<Arg, Ret> for F
+{
+    #[inline]
+    fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self(arg, PhantomData)
+    }
+}
+
+#[cfg(feature = "async")]
+/// Something asynchronously callable by value with `Arg` and returning `Ret`.
+///
+/// It is implemented by every asynchronous dynamic function, whatever its storages, allowing
+/// generic code to accept any of them. The returned futures are not known to be [`Send`]
+/// in generic context, even when the function is.
+pub trait DynAsyncCallableOnce<Arg: ForLt, Ret: ForLt> {
+    /// Returns whether the underlying function is synchronous.
+    fn is_sync(&self) -> bool;
+    /// Calls the function by value.
+    fn call_once<'a>(self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>>;
+    /// Calls the function by value if it is synchronous.
+    fn call_sync_once<'a>(self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>>;
+    /// Tries calling the function by value as synchronous, falling back to asynchronous call.
+    fn call_try_sync_once<'a>(self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>>;
+}
+
+#[cfg(feature = "async")]
+/// Something asynchronously callable by mutable reference with `Arg` and returning `Ret`.
+///
+/// See [`DynAsyncCallableOnce`].
+pub trait DynAsyncCallableMut<Arg: ForLt, Ret: ForLt>: DynAsyncCallableOnce<Arg, Ret> {
+    /// Calls the function by mutable reference.
+    fn call_mut<'a>(&mut self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>>;
+    /// Calls the function by mutable reference if it is synchronous.
+    fn call_sync_mut<'a>(&mut self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>>;
+    /// Tries calling the function by mutable reference as synchronous, falling back to
+    /// asynchronous call.
+    fn call_try_sync_mut<'a>(&mut self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>>;
+}
+
+#[cfg(feature = "async")]
+/// Something asynchronously callable by reference with `Arg` and returning `Ret`.
+///
+/// See [`DynAsyncCallableOnce`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use dyn_fn::{DynAsyncCallable, DynAsyncFn, LocalDynAsyncFn, hkt::*, storage};
+/// use futures_util::FutureExt;
+///
+/// async fn dispatch<C: DynAsyncCallable<ForRef<str>, ForFixed<usize>>>(
+///     cbs: &[C],
+///     s: &str,
+/// ) -> usize {
+///     let mut total = 0;
+///     for cb in cbs {
+///         total += cb.call_try_sync(s).await;
+///     }
+///     total
+/// }
+///
+/// type SendCallback = DynAsyncFn<'static, ForRef<str>, ForFixed<usize>, storage::Arc>;
+/// let cbs = [SendCallback::new_sync(|s, _| s.len())];
+/// assert_eq!(dispatch(&cbs, "test").now_or_never(), Some(4));
+///
+/// type LocalCallback<'a> = LocalDynAsyncFn<'a, ForRef<str>, ForFixed<usize>, storage::Box>;
+/// let cbs = [LocalCallback::new(async |s, _| s.len())];
+/// assert_eq!(dispatch(&cbs, "test").now_or_never(), Some(4));
+/// # }
+/// ```
+pub trait DynAsyncCallable<Arg: ForLt, Ret: ForLt>: DynAsyncCallableMut<Arg, Ret> {
+    /// Calls the function by reference.
+    fn call<'a>(&self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>>;
+    /// Calls the function by reference if it is synchronous.
+    fn call_sync<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>>;
+    /// Tries calling the function by reference as synchronous, falling back to asynchronous
+    /// call.
+    fn call_try_sync<'a>(&self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>>;
+}
+
+#[cfg(feature = "async")]
+impl<Arg: ForLt, Ret: ForLt, T: DynAsyncCallable<Arg, Ret> + ?Sized> DynAsyncCallableOnce<Arg, Ret>
+    for &T
+{
+    #[inline]
+    fn is_sync(&self) -> bool {
+        T::is_sync(self)
+    }
+
+    #[inline]
+    fn call_once<'a>(self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+        T::call(self, arg)
+    }
+
+    #[inline]
+    fn call_sync_once<'a>(self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        T::call_sync(self, arg)
+    }
+
+    #[inline]
+    fn call_try_sync_once<'a>(self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+        T::call_try_sync(self, arg)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<Arg: ForLt, Ret: ForLt, T: DynAsyncCallable<Arg, Ret> + ?Sized> DynAsyncCallableMut<Arg, Ret>
+    for &T
+{
+    #[inline]
+    fn call_mut<'a>(&mut self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+        T::call(self, arg)
+    }
+
+    #[inline]
+    fn call_sync_mut<'a>(&mut self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        T::call_sync(self, arg)
+    }
+
+    #[inline]
+    fn call_try_sync_mut<'a>(&mut self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+        T::call_try_sync(self, arg)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<Arg: ForLt, Ret: ForLt, T: DynAsyncCallable<Arg, Ret> + ?Sized> DynAsyncCallable<Arg, Ret>
+    for &T
+{
+    #[inline]
+    fn call<'a>(&self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+        T::call(self, arg)
+    }
+
+    #[inline]
+    fn call_sync<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        T::call_sync(self, arg)
+    }
+
+    #[inline]
+    fn call_try_sync<'a>(&self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+        T::call_try_sync(self, arg)
+    }
+}
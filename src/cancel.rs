@@ -0,0 +1,197 @@
+use alloc::sync::Arc;
+use core::fmt;
+
+use higher_kinded_types::ForFixed;
+
+#[cfg(feature = "async")]
+use crate::{DynAsyncFn, storage::DefaultFutureStorage, storage::StorageMut};
+use crate::{
+    DynFn, ForLt,
+    isr::SwapCell,
+    storage::{DefaultFnStorage, Storage, StorageSend},
+};
+
+/// A token cancelling a [`CancellableDynFn`] or a [`CancellableDynAsyncFn`].
+///
+/// Once cancelled, calls return `None` without calling the function, and the function is
+/// dropped, even if the cancellable handle itself is still held, e.g. by a registry which never
+/// gives it back.
+#[derive(Clone)]
+pub struct CancelToken<'capture>(Arc<dyn Cancel + Send + Sync + 'capture>);
+
+trait Cancel {
+    fn cancel(&self);
+    fn is_cancelled(&self) -> bool;
+}
+
+impl<T> Cancel for SwapCell<T> {
+    fn cancel(&self) {
+        drop(self.replace(None));
+    }
+
+    fn is_cancelled(&self) -> bool {
+        !self.is_set()
+    }
+}
+
+impl CancelToken<'_> {
+    /// Cancels the function, dropping it.
+    ///
+    /// For [`CancellableDynFn`], it waits for the calls in flight to complete, so the function
+    /// is dropped when it returns; as a consequence, it must not be called from the function
+    /// itself, as it would never complete. For [`CancellableDynAsyncFn`], the function is dropped
+    /// when the last call in flight completes.
+    pub fn cancel(&self) {
+        self.0.cancel();
+    }
+
+    /// Returns whether the function has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+impl fmt::Debug for CancelToken<'_> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancelToken")
+            .field("is_cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// A [`DynFn`] which can be cancelled with its paired [`CancelToken`].
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{CancellableDynFn, DynFn, hkt::ForFixed};
+///
+/// let (f, token) =
+///     CancellableDynFn::new(DynFn::<ForFixed<u32>, ForFixed<u32>>::new(|x, _| x + 1));
+/// // `f` is handed to an API which never gives it back
+/// assert_eq!(f.call(41), Some(42));
+/// token.cancel();
+/// assert_eq!(f.call(41), None);
+/// ```
+pub struct CancellableDynFn<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: Storage + StorageSend = DefaultFnStorage,
+>(Arc<SwapCell<DynFn<'capture, Arg, Ret, FnStorage>>>);
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    CancellableDynFn<'capture, Arg, Ret, FnStorage>
+{
+    /// Wraps a function, returning it with its cancellation token.
+    pub fn new(f: DynFn<'capture, Arg, Ret, FnStorage>) -> (Self, CancelToken<'capture>) {
+        let cell = Arc::new(SwapCell::new());
+        drop(cell.replace(Some(f)));
+        (Self(cell.clone()), CancelToken(cell))
+    }
+
+    /// Calls the function, unless it has been cancelled.
+    pub fn call<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        self.0.with_active(|f| f.call(arg))
+    }
+
+    /// Returns whether the function has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        !self.0.is_set()
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend> Clone
+    for CancellableDynFn<'capture, Arg, Ret, FnStorage>
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    fmt::Debug for CancellableDynFn<'capture, Arg, Ret, FnStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellableDynFn")
+            .field("is_cancelled", &self.is_cancelled())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`DynAsyncFn`] which can be cancelled with its paired [`CancelToken`].
+///
+/// The function is stored in [`Arc`](crate::storage::Arc) storage, so each call holds a clone of
+/// it, and cancellation doesn't have to wait for the calls in flight.
+///
+/// # Examples
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// use dyn_fn::{CancellableDynAsyncFn, DynAsyncFn, hkt::ForFixed, storage};
+///
+/// let (f, token) =
+///     CancellableDynAsyncFn::new(
+///         DynAsyncFn::<ForFixed<u32>, ForFixed<u32>, storage::Arc>::new_sync(|x, _| x + 1),
+///     );
+/// assert_eq!(f.call(41).await, Some(42));
+/// token.cancel();
+/// assert_eq!(f.call(41).await, None);
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub struct CancellableDynAsyncFn<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FutureStorage: StorageMut = DefaultFutureStorage,
+>(Arc<SwapCell<DynAsyncFn<'capture, Arg, Ret, crate::storage::Arc, FutureStorage>>>);
+
+#[cfg(feature = "async")]
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FutureStorage: StorageMut>
+    CancellableDynAsyncFn<'capture, Arg, Ret, FutureStorage>
+{
+    /// Wraps a function, returning it with its cancellation token.
+    pub fn new(
+        f: DynAsyncFn<'capture, Arg, Ret, crate::storage::Arc, FutureStorage>,
+    ) -> (Self, CancelToken<'capture>) {
+        let cell = Arc::new(SwapCell::new());
+        drop(cell.replace(Some(f)));
+        (Self(cell.clone()), CancelToken(cell))
+    }
+
+    /// Calls the function, unless it has been cancelled.
+    pub async fn call<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        let f = self.0.with_active(DynAsyncFn::clone)?;
+        Some(f.call(arg).await)
+    }
+
+    /// Returns whether the function has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        !self.0.is_set()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FutureStorage: StorageMut> Clone
+    for CancellableDynAsyncFn<'capture, Arg, Ret, FutureStorage>
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FutureStorage: StorageMut> fmt::Debug
+    for CancellableDynAsyncFn<'capture, Arg, Ret, FutureStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellableDynAsyncFn")
+            .field("is_cancelled", &self.is_cancelled())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,185 @@
+use core::{alloc::Layout, marker::PhantomData, mem};
+
+use crate::ForLt;
+
+/// Builds a closure to be passed to dynamic function constructors, appending the `PhantomData`
+/// parameter required by a [current limitation] of the compiler.
+///
+/// The closure takes a single argument, which can be omitted for `()`, with an optional type
+/// annotation; the argument pattern must be an identifier or a single token tree, e.g. a tuple
+/// pattern. `move` closures and explicit return types are supported.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{LocalDynFn, LocalDynFnMut, dyn_fn, hkt::*, storage::Raw};
+///
+/// let len = LocalDynFn::<ForRef<str>, ForFixed<usize>>::new(dyn_fn!(|s| s.len()));
+/// assert_eq!(len.call("test"), 4);
+///
+/// let prefix = String::from("pre");
+/// let strip =
+///     LocalDynFn::<ForRef<str>, ForRef<str>, Raw<32>>::new(dyn_fn!(move |s: &str| -> &str {
+///         let stripped = s.strip_prefix(prefix.as_str());
+///         stripped.unwrap_or(s)
+///     }));
+/// assert_eq!(strip.call("prefix"), "fix");
+///
+/// let mut count = 0;
+/// let mut incr = LocalDynFnMut::<ForFixed<()>, ForFixed<()>>::new(dyn_fn!(|| count += 1));
+/// incr.call(());
+/// drop(incr);
+/// assert_eq!(count, 1);
+/// ```
+///
+/// [current limitation]: https://github.com/rust-lang/rust/issues/77905
+#[macro_export]
+macro_rules! dyn_fn {
+    (move $($closure:tt)+) => {
+        $crate::dyn_fn!(@ [move] $($closure)+)
+    };
+    (@ [$($move:tt)?] || $($body:tt)+) => {
+        $($move)? |_, _: ::core::marker::PhantomData<&()>| $($body)+
+    };
+    (@ [$($move:tt)?] |mut $arg:ident $(: $ty:ty)?| $($body:tt)+) => {
+        $($move)? |mut $arg $(: $ty)?, _: ::core::marker::PhantomData<&()>| $($body)+
+    };
+    (@ [$($move:tt)?] |$arg:tt $(: $ty:ty)?| $($body:tt)+) => {
+        $($move)? |$arg $(: $ty)?, _: ::core::marker::PhantomData<&()>| $($body)+
+    };
+    ($($closure:tt)+) => {
+        $crate::dyn_fn!(@ [] $($closure)+)
+    };
+}
+
+/// [`dyn_fn!`] for async closures.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{LocalDynAsyncFn, dyn_async_fn, hkt::*};
+/// use futures_util::FutureExt;
+///
+/// let len =
+///     LocalDynAsyncFn::<ForRef<str>, ForFixed<usize>>::new(dyn_async_fn!(async |s| s.len()));
+/// assert_eq!(len.call("test").now_or_never(), Some(4));
+/// ```
+#[cfg(feature = "async")]
+#[macro_export]
+macro_rules! dyn_async_fn {
+    (async move $($closure:tt)+) => {
+        $crate::dyn_async_fn!(@ [move] $($closure)+)
+    };
+    (@ [$($move:tt)?] || $($body:tt)+) => {
+        async $($move)? |_, _: ::core::marker::PhantomData<&()>| $($body)+
+    };
+    (@ [$($move:tt)?] |mut $arg:ident $(: $ty:ty)?| $($body:tt)+) => {
+        async $($move)? |mut $arg $(: $ty)?, _: ::core::marker::PhantomData<&()>| $($body)+
+    };
+    (@ [$($move:tt)?] |$arg:tt $(: $ty:ty)?| $($body:tt)+) => {
+        async $($move)? |$arg $(: $ty)?, _: ::core::marker::PhantomData<&()>| $($body)+
+    };
+    (async $($closure:tt)+) => {
+        $crate::dyn_async_fn!(@ [] $($closure)+)
+    };
+}
+
+/// Constructs a dynamic function of the given type from a closure, using [`dyn_fn!`] or
+/// [`dyn_async_fn!`] depending on the closure.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{LocalDynFn, hkt::*, new_dyn_fn};
+///
+/// let len = new_dyn_fn!(LocalDynFn<ForRef<str>, ForFixed<usize>>, |s| s.len());
+/// assert_eq!(len.call("test"), 4);
+///
+/// # #[cfg(feature = "async")] {
+/// use dyn_fn::LocalDynAsyncFn;
+/// use futures_util::FutureExt;
+///
+/// let len = new_dyn_fn!(LocalDynAsyncFn<ForRef<str>, ForFixed<usize>>, async |s| s
+///     .len());
+/// assert_eq!(len.call("test").now_or_never(), Some(4));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! new_dyn_fn {
+    ($ty:ty, async $($closure:tt)+) => {
+        <$ty>::new($crate::dyn_async_fn!(async $($closure)+))
+    };
+    ($ty:ty, $($closure:tt)+) => {
+        <$ty>::new($crate::dyn_fn!($($closure)+))
+    };
+}
+
+/// Declares a `static` [`DynFn`](crate::DynFn) stored in place, without `alloc`.
+///
+/// The [`Raw`](crate::storage::Raw) storage is sized and aligned after the closure, so its
+/// parameters don't have to be guessed; they can still be given explicitly with
+/// `as Raw<SIZE, ALIGN>`, e.g. for several statics to share the same type. A reference to the
+/// static is a `&'static DynFn`, which can be passed around without lifetime constraint.
+///
+/// The closure must be `Send + Sync + 'static`, as the static is shared across threads and never
+/// dropped, and it must be constructible at compile-time, like any static initializer.
+///
+/// # Examples
+///
+/// ```
+/// #![no_std]
+/// # extern crate std;
+/// use core::sync::atomic::{AtomicUsize, Ordering};
+///
+/// use dyn_fn::{hkt::*, static_dyn_fn};
+///
+/// static COUNT: AtomicUsize = AtomicUsize::new(0);
+/// static_dyn_fn! {
+///     static ON_EVENT: (ForRef<str>) -> ForFixed<usize> = |s, _| {
+///         COUNT.fetch_add(s.len(), Ordering::Relaxed)
+///     };
+/// }
+///
+/// fn on_click() {
+///     ON_EVENT.call("click");
+/// }
+///
+/// fn on_key(key: &str) {
+///     ON_EVENT.call(key);
+/// }
+///
+/// # fn main() {
+/// on_click();
+/// on_key("a");
+/// assert_eq!(COUNT.load(Ordering::Relaxed), 6);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! static_dyn_fn {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: ($arg:ty) -> $ret:ty as $storage:ty = $($closure:tt)+) => {
+        $(#[$attr])*
+        $vis static $name: $crate::DynFn<'static, $arg, $ret, $storage> =
+            $crate::DynFn::<'static, $arg, $ret, $storage>::new_const($crate::static_dyn_fn!(@ closure $($closure)+));
+    };
+    ($(#[$attr:meta])* $vis:vis static $name:ident: ($arg:ty) -> $ret:ty = $($closure:tt)+) => {
+        $crate::static_dyn_fn! {
+            $(#[$attr])* $vis static $name: ($arg) -> $ret as $crate::storage::Raw<
+                { $crate::__closure_layout::<$arg, $ret, _>($crate::static_dyn_fn!(@ closure $($closure)+)).size() },
+                { $crate::__closure_layout::<$arg, $ret, _>($crate::static_dyn_fn!(@ closure $($closure)+)).align() },
+            > = $($closure)+
+        }
+    };
+    (@ closure $closure:expr;) => { $closure };
+}
+
+#[doc(hidden)]
+pub const fn __closure_layout<
+    Arg: ForLt,
+    Ret: ForLt,
+    F: for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a>,
+>(
+    f: F,
+) -> Layout {
+    mem::forget(f);
+    Layout::new::<F>()
+}
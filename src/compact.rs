@@ -0,0 +1,171 @@
+use alloc::boxed::Box;
+use core::{fmt, marker::PhantomData, ptr::NonNull};
+
+use higher_kinded_types::ForFixed;
+
+use crate::{ForLt, macros::impl_callable};
+
+#[expect(type_alias_bounds)]
+type Call<Arg: ForLt, Ret: ForLt> =
+    for<'a> fn(NonNull<()>, Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a>;
+
+struct CompactVTable<Arg: ForLt, Ret: ForLt> {
+    call: Call<Arg, Ret>,
+    /// # Safety
+    ///
+    /// The pointer must be the one of a [`LocalCompactDynFn`] allocation, which must not be
+    /// accessed after the call.
+    drop: unsafe fn(NonNull<()>),
+    #[cfg(feature = "fn_type_name")]
+    type_name: fn() -> &'static str,
+}
+
+/// The allocation of a [`LocalCompactDynFn`], prefixed by its vtable.
+#[repr(C)]
+struct Compact<VT: 'static, F> {
+    vtable: &'static VT,
+    func: F,
+}
+
+/// The vtable prefix of any [`LocalCompactDynFn`] allocation.
+type Header<Arg, Ret> = Compact<CompactVTable<Arg, Ret>, ()>;
+
+/// [`CompactDynFn`], but without the [`Send`] + [`Sync`] requirement.
+pub struct LocalCompactDynFn<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static = ForFixed<()>> {
+    /// Pointer to a `Compact<CompactVTable<Arg, Ret>, F>` allocated with [`Box`].
+    ptr: NonNull<Header<Arg, Ret>>,
+    _capture: PhantomData<&'capture ()>,
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static> LocalCompactDynFn<'capture, Arg, Ret> {
+    /// Construct a new [`LocalCompactDynFn`] from a function.
+    pub fn new(
+        f: impl for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+    ) -> Self {
+        Self::new_impl(f)
+    }
+
+    fn new_impl<F: for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture>(
+        func: F,
+    ) -> Self {
+        let vtable = &CompactVTable::<Arg, Ret> {
+            // SAFETY: ptr comes from `self.ptr`, so it's a valid `&Compact<_, F>`
+            call: |ptr, arg, _| unsafe {
+                (ptr.cast::<Compact<CompactVTable<Arg, Ret>, F>>()
+                    .as_ref()
+                    .func)(arg, PhantomData)
+            },
+            // SAFETY: ptr comes from `Box::into_raw` in `Self::new_impl`,
+            // and is not accessed after the call, as per function contract
+            drop: |ptr| unsafe {
+                drop(Box::from_raw(
+                    ptr.cast::<Compact<CompactVTable<Arg, Ret>, F>>().as_ptr(),
+                ));
+            },
+            #[cfg(feature = "fn_type_name")]
+            type_name: core::any::type_name::<F>,
+        };
+        let compact = Box::new(Compact { vtable, func });
+        Self {
+            ptr: NonNull::new(Box::into_raw(compact).cast()).unwrap(),
+            _capture: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn vtable(&self) -> &'static CompactVTable<Arg, Ret> {
+        // SAFETY: `self.ptr` points to a `repr(C)` `Compact`, whose first field is the vtable,
+        // so it starts with a valid `Header`
+        unsafe { self.ptr.as_ref() }.vtable
+    }
+
+    /// Calls the underlying function.
+    #[inline]
+    pub fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        (self.vtable().call)(self.ptr.cast(), arg, PhantomData)
+    }
+
+    /// Returns the type name of the underlying function.
+    #[cfg(feature = "fn_type_name")]
+    pub fn type_name(&self) -> &'static str {
+        (self.vtable().type_name)()
+    }
+}
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static> Drop for LocalCompactDynFn<'_, Arg, Ret> {
+    fn drop(&mut self) {
+        // SAFETY: `self.ptr` is the allocation, and it's no longer accessed after the call
+        // (because it's dropped)
+        unsafe { (self.vtable().drop)(self.ptr.cast()) }
+    }
+}
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static> fmt::Debug for LocalCompactDynFn<'_, Arg, Ret> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("LocalCompactDynFn");
+        #[cfg(feature = "debug-type-names")]
+        debug.field("type_name", &self.type_name());
+        debug.finish_non_exhaustive()
+    }
+}
+
+impl_callable!(Fn LocalCompactDynFn);
+
+/// A [`DynFn`](crate::DynFn) whose vtable pointer is stored in its allocation.
+///
+/// The handle is a single pointer, like a thin `dyn` object, halving the size of a
+/// [`DynFn`](crate::DynFn) with [`Box`](crate::storage::Box) storage; calling it reads the vtable
+/// from the allocation prefix, next to the captured data, instead of from the handle.
+/// It always allocates, even for zero-sized functions.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{CompactDynFn, hkt::*};
+///
+/// let f = CompactDynFn::<ForRef<str>, ForFixed<usize>>::new(|s, _| s.len());
+/// assert_eq!(f.call("test"), 4);
+/// assert_eq!(size_of_val(&f), size_of::<usize>());
+/// ```
+pub struct CompactDynFn<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static = ForFixed<()>>(
+    LocalCompactDynFn<'capture, Arg, Ret>,
+);
+
+// SAFETY: the object is initialized with a `Send + Sync` function
+unsafe impl<Arg: ForLt, Ret: ForLt> Send for CompactDynFn<'_, Arg, Ret> {}
+// SAFETY: the object is initialized with a `Send + Sync` function
+unsafe impl<Arg: ForLt, Ret: ForLt> Sync for CompactDynFn<'_, Arg, Ret> {}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static> CompactDynFn<'capture, Arg, Ret> {
+    /// Construct a new [`CompactDynFn`] from a function.
+    pub fn new(
+        f: impl for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture,
+    ) -> Self {
+        Self(LocalCompactDynFn::new(f))
+    }
+
+    /// Calls the underlying function.
+    #[inline]
+    pub fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.0.call(arg)
+    }
+
+    /// Returns the type name of the underlying function.
+    #[cfg(feature = "fn_type_name")]
+    pub fn type_name(&self) -> &'static str {
+        self.0.type_name()
+    }
+}
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static> fmt::Debug for CompactDynFn<'_, Arg, Ret> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("CompactDynFn");
+        #[cfg(feature = "debug-type-names")]
+        debug.field("type_name", &self.type_name());
+        debug.finish_non_exhaustive()
+    }
+}
+
+impl_callable!(Fn CompactDynFn);
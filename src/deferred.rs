@@ -0,0 +1,348 @@
+#[cfg(feature = "alloc")]
+use alloc::collections::VecDeque;
+use core::{cell::RefCell, fmt, mem::MaybeUninit};
+
+use higher_kinded_types::ForFixed;
+
+#[cfg(feature = "async")]
+use crate::{DynAsyncCallableMut, LocalDynAsyncFnMut, storage::DefaultFutureStorage};
+use crate::{
+    DynCallableMut, LocalDynFnMut,
+    storage::{DefaultFnStorage, StorageMut},
+};
+
+/// The argument queues of a [`Deferred`], either fixed-capacity or growable.
+trait Queue {
+    type Item;
+    /// Pushes an item, returning it back if the queue is full.
+    fn push(&mut self, item: Self::Item) -> Result<(), Self::Item>;
+    fn pop(&mut self) -> Option<Self::Item>;
+    fn len(&self) -> usize;
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Queue for VecDeque<T> {
+    type Item = T;
+
+    fn push(&mut self, item: Self::Item) -> Result<(), Self::Item> {
+        self.push_back(item);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<Self::Item> {
+        self.pop_front()
+    }
+
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+}
+
+/// A fixed-capacity ring buffer.
+struct ArrayQueue<T, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayQueue<T, N> {
+    const fn new() -> Self {
+        Self {
+            items: [const { MaybeUninit::uninit() }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<T, const N: usize> Queue for ArrayQueue<T, N> {
+    type Item = T;
+
+    fn push(&mut self, item: Self::Item) -> Result<(), Self::Item> {
+        if self.len == N {
+            return Err(item);
+        }
+        self.items[(self.head + self.len) % N].write(item);
+        self.len += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        // SAFETY: the `len` items following `head` are initialized,
+        // and `head` is moved past the item read
+        let item = unsafe { self.items[self.head].assume_init_read() };
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(item)
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayQueue<T, N> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+/// A callback with its queue of deferred arguments.
+struct Deferred<F, Q> {
+    f: RefCell<F>,
+    queue: RefCell<Q>,
+}
+
+impl<F, Q: Queue> Deferred<F, Q> {
+    const fn new(f: F, queue: Q) -> Self {
+        Self {
+            f: RefCell::new(f),
+            queue: RefCell::new(queue),
+        }
+    }
+
+    fn enqueue(&self, arg: Q::Item) -> Result<(), Q::Item> {
+        self.queue.borrow_mut().push(arg)
+    }
+
+    fn len(&self) -> usize {
+        self.queue.borrow().len()
+    }
+
+    fn pop(&self) -> Option<Q::Item> {
+        self.queue.borrow_mut().pop()
+    }
+
+    fn flush(&self)
+    where
+        F: DynCallableMut<ForFixed<Q::Item>, ForFixed<()>>,
+    {
+        // a nested flush is a no-op, as the outer one also flushes the arguments it would have
+        let Ok(mut f) = self.f.try_borrow_mut() else {
+            return;
+        };
+        // the queue is not borrowed during the call, so the callback can enqueue arguments
+        while let Some(arg) = self.pop() {
+            f.call_mut(arg);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    // the borrow of the function is what makes nested flushes no-ops
+    #[expect(clippy::await_holding_refcell_ref)]
+    async fn flush_async(&self)
+    where
+        F: DynAsyncCallableMut<ForFixed<Q::Item>, ForFixed<()>>,
+    {
+        let Ok(mut f) = self.f.try_borrow_mut() else {
+            return;
+        };
+        while let Some(arg) = self.pop() {
+            f.call_try_sync_mut(arg).await;
+        }
+    }
+}
+
+macro_rules! impl_deferred {
+    ($name:ident<$($param:ident),*> $(const $n:ident)?, $callback:ty, $new:expr) => {
+        impl<'capture, A: 'static, $($param: StorageMut,)* $(const $n: usize)?>
+            $name<'capture, A, $($n,)? $($param),*>
+        {
+            /// Wraps a callback, without deferred argument.
+            pub const fn new(f: $callback) -> Self {
+                Self(Deferred::new(f, $new))
+            }
+
+            /// Returns the number of deferred arguments.
+            pub fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            /// Returns whether there is no deferred argument.
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+        }
+
+        impl<'capture, A: 'static, $($param: StorageMut,)* $(const $n: usize)?> fmt::Debug
+            for $name<'capture, A, $($n,)? $($param),*>
+        {
+            #[cfg_attr(coverage_nightly, coverage(off))]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("len", &self.len())
+                    .finish_non_exhaustive()
+            }
+        }
+    };
+}
+
+/// A [`LocalDynFnMut`] whose calls are deferred: arguments are queued by
+/// [`enqueue`](Self::enqueue), then passed to the callback by [`flush`](Self::flush).
+///
+/// Up to `N` arguments can be queued, without allocation; when the queue is full, new
+/// arguments are rejected. Methods take `&self`, so arguments can be queued from a context
+/// where the callback cannot be called, e.g. while it is running: arguments enqueued during a
+/// flush are passed to the callback by the same flush. As a consequence, the type is not
+/// [`Sync`]. Arguments still queued when it is dropped are dropped without being passed.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{DeferredDynFnMut, LocalDynFnMut, storage::Raw};
+///
+/// let mut events = Vec::new();
+/// let deferred =
+///     DeferredDynFnMut::<u32, 2, Raw<8>>::new(LocalDynFnMut::new(|event, _| events.push(event)));
+/// deferred.enqueue(1).unwrap();
+/// deferred.enqueue(2).unwrap();
+/// // the queue is full
+/// assert_eq!(deferred.enqueue(3), Err(3));
+/// deferred.flush();
+/// drop(deferred);
+/// assert_eq!(events, [1, 2]);
+/// ```
+pub struct DeferredDynFnMut<
+    'capture,
+    A: 'static,
+    const N: usize,
+    FnStorage: StorageMut = DefaultFnStorage,
+>(Deferred<LocalDynFnMut<'capture, ForFixed<A>, ForFixed<()>, FnStorage>, ArrayQueue<A, N>>);
+
+impl_deferred!(
+    DeferredDynFnMut<FnStorage> const N,
+    LocalDynFnMut<'capture, ForFixed<A>, ForFixed<()>, FnStorage>,
+    ArrayQueue::new()
+);
+
+impl<'capture, A: 'static, const N: usize, FnStorage: StorageMut>
+    DeferredDynFnMut<'capture, A, N, FnStorage>
+{
+    /// Queues an argument, returning it back if the queue is full.
+    pub fn enqueue(&self, arg: A) -> Result<(), A> {
+        self.0.enqueue(arg)
+    }
+
+    /// Passes the queued arguments to the callback, in order, until the queue is empty.
+    ///
+    /// Flushing from the callback itself does nothing, as the running flush already passes
+    /// all the queued arguments.
+    pub fn flush(&self) {
+        self.0.flush();
+    }
+}
+
+/// A [`DeferredDynFnMut`] with an unbounded queue of arguments, backed by a
+/// [`VecDeque`].
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{LocalDynFnMut, UnboundedDeferredDynFnMut};
+///
+/// let mut sum = 0;
+/// let deferred = UnboundedDeferredDynFnMut::<u32>::new(LocalDynFnMut::new(|x, _| sum += x));
+/// for x in 0..100 {
+///     deferred.enqueue(x);
+/// }
+/// deferred.flush();
+/// drop(deferred);
+/// assert_eq!(sum, 4950);
+/// ```
+#[cfg(feature = "alloc")]
+pub struct UnboundedDeferredDynFnMut<'capture, A: 'static, FnStorage: StorageMut = DefaultFnStorage>(
+    Deferred<LocalDynFnMut<'capture, ForFixed<A>, ForFixed<()>, FnStorage>, VecDeque<A>>,
+);
+
+#[cfg(feature = "alloc")]
+impl_deferred!(
+    UnboundedDeferredDynFnMut<FnStorage>,
+    LocalDynFnMut<'capture, ForFixed<A>, ForFixed<()>, FnStorage>,
+    VecDeque::new()
+);
+
+#[cfg(feature = "alloc")]
+impl<'capture, A: 'static, FnStorage: StorageMut>
+    UnboundedDeferredDynFnMut<'capture, A, FnStorage>
+{
+    /// Queues an argument.
+    pub fn enqueue(&self, arg: A) {
+        self.0.queue.borrow_mut().push_back(arg);
+    }
+
+    /// Passes the queued arguments to the callback, in order, until the queue is empty.
+    ///
+    /// Flushing from the callback itself does nothing, as the running flush already passes
+    /// all the queued arguments.
+    pub fn flush(&self) {
+        self.0.flush();
+    }
+}
+
+#[cfg(feature = "async")]
+type DeferredAsync<'capture, A, FnStorage, FutureStorage, Q> =
+    Deferred<LocalDynAsyncFnMut<'capture, ForFixed<A>, ForFixed<()>, FnStorage, FutureStorage>, Q>;
+
+/// A [`DeferredDynFnMut`] whose callback is a [`LocalDynAsyncFnMut`].
+///
+/// Flushing awaits each call before passing the next argument.
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// use dyn_fn::{DeferredDynAsyncFnMut, LocalDynAsyncFnMut, hkt::ForFixed};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// type Callback<'a> = LocalDynAsyncFnMut<'a, ForFixed<u32>, ForFixed<()>>;
+/// let last = Cell::new(0);
+/// let deferred = DeferredDynAsyncFnMut::<u32, 4>::new(Callback::new(async |x, _| {
+///     tokio::task::yield_now().await;
+///     last.set(x);
+/// }));
+/// deferred.enqueue(1).unwrap();
+/// deferred.enqueue(2).unwrap();
+/// deferred.flush().await;
+/// assert_eq!(last.get(), 2);
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub struct DeferredDynAsyncFnMut<
+    'capture,
+    A: 'static,
+    const N: usize,
+    FnStorage: StorageMut = DefaultFnStorage,
+    FutureStorage: StorageMut = DefaultFutureStorage,
+>(DeferredAsync<'capture, A, FnStorage, FutureStorage, ArrayQueue<A, N>>);
+
+#[cfg(feature = "async")]
+impl_deferred!(
+    DeferredDynAsyncFnMut<FnStorage, FutureStorage> const N,
+    LocalDynAsyncFnMut<'capture, ForFixed<A>, ForFixed<()>, FnStorage, FutureStorage>,
+    ArrayQueue::new()
+);
+
+#[cfg(feature = "async")]
+impl<'capture, A: 'static, const N: usize, FnStorage: StorageMut, FutureStorage: StorageMut>
+    DeferredDynAsyncFnMut<'capture, A, N, FnStorage, FutureStorage>
+{
+    /// Queues an argument, returning it back if the queue is full.
+    pub fn enqueue(&self, arg: A) -> Result<(), A> {
+        self.0.enqueue(arg)
+    }
+
+    /// Passes the queued arguments to the callback, in order, until the queue is empty.
+    ///
+    /// Synchronous callbacks are called without polling a future. Flushing from the callback
+    /// itself does nothing, as the running flush already passes all the queued arguments. If
+    /// the flush is cancelled, the argument being passed is dropped, but the following ones
+    /// stay queued.
+    pub async fn flush(&self) {
+        self.0.flush_async().await;
+    }
+}
@@ -0,0 +1,126 @@
+use core::{alloc::Layout, fmt};
+
+/// The reason why a dynamic function could not be constructed.
+///
+/// Returned by the fallible constructors, e.g. [`DynFn::try_new`](crate::DynFn::try_new), so
+/// callers can react to the failure, for example by falling back to a
+/// [`Box`](crate::storage::Box) storage, or by logging the required layout.
+///
+/// # Examples
+///
+/// ```
+/// use core::alloc::Layout;
+///
+/// use dyn_fn::{DynFn, DynFnError, hkt::ForFixed, storage::Raw};
+///
+/// let data = [0u8; 16];
+/// let res = DynFn::<ForFixed<()>, ForFixed<u8>, Raw<8>>::try_new(move |_, _| data[0]);
+/// assert_eq!(
+///     res.unwrap_err(),
+///     DynFnError::TooLarge {
+///         required: Layout::new::<[u8; 16]>(),
+///         capacity: Layout::from_size_align(8, align_of::<usize>()).unwrap(),
+///     }
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DynFnError {
+    /// The function is larger than the storage capacity.
+    TooLarge {
+        /// The layout of the function.
+        required: Layout,
+        /// The layout of the storage capacity.
+        capacity: Layout,
+    },
+    /// The function alignment is greater than the storage one.
+    Misaligned {
+        /// The layout of the function.
+        required: Layout,
+        /// The layout of the storage capacity.
+        capacity: Layout,
+    },
+    /// The allocation of the function failed.
+    ///
+    /// Only returned by `Box`-based storages, `Rc` and `Arc` abort on allocation failure.
+    AllocFailed {
+        /// The layout of the function.
+        layout: Layout,
+    },
+}
+
+impl fmt::Display for DynFnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLarge { required, capacity } => write!(
+                f,
+                "function size {} exceeds storage capacity {}",
+                required.size(),
+                capacity.size()
+            ),
+            Self::Misaligned { required, capacity } => write!(
+                f,
+                "function alignment {} exceeds storage alignment {}",
+                required.align(),
+                capacity.align()
+            ),
+            Self::AllocFailed { layout } => write!(
+                f,
+                "failed to allocate function of size {} and alignment {}",
+                layout.size(),
+                layout.align()
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DynFnError {}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DynFnError {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Self::TooLarge { required, capacity } => defmt::write!(
+                f,
+                "function size {=usize} exceeds storage capacity {=usize}",
+                required.size(),
+                capacity.size()
+            ),
+            Self::Misaligned { required, capacity } => defmt::write!(
+                f,
+                "function alignment {=usize} exceeds storage alignment {=usize}",
+                required.align(),
+                capacity.align()
+            ),
+            Self::AllocFailed { layout } => defmt::write!(
+                f,
+                "failed to allocate function of size {=usize} and alignment {=usize}",
+                layout.size(),
+                layout.align()
+            ),
+        }
+    }
+}
+
+/// The requested signature doesn't match the one of an erased function.
+///
+/// Returned by [`AnyDynFn::call_as`](crate::AnyDynFn::call_as).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SignatureMismatch;
+
+impl fmt::Display for SignatureMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("function signature mismatch")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SignatureMismatch {}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for SignatureMismatch {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "function signature mismatch")
+    }
+}
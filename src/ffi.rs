@@ -0,0 +1,363 @@
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::ffi::c_void;
+#[cfg(feature = "alloc")]
+use core::fmt;
+
+use higher_kinded_types::ForFixed;
+
+#[cfg(feature = "alloc")]
+use crate::storage::DefaultFnStorage;
+use crate::{
+    DynFn,
+    storage::{Storage, StorageSend},
+};
+
+/// The C function pointer of an FFI callback, taking the callback context as first argument.
+pub type FfiFn<A, R> = unsafe extern "C" fn(*mut c_void, A) -> R;
+
+/// # Safety
+///
+/// `ctx` must point to a live `DynFn<'_, ForFixed<A>, ForFixed<R>, FnStorage>`.
+unsafe extern "C" fn trampoline<A: 'static, R: 'static, FnStorage: Storage + StorageSend>(
+    ctx: *mut c_void,
+    arg: A,
+) -> R {
+    // SAFETY: `ctx` points to a live function, as per function contract
+    let f = unsafe { &*ctx.cast::<DynFn<'_, ForFixed<A>, ForFixed<R>, FnStorage>>() };
+    f.call(arg)
+}
+
+impl<'capture, A: 'static, R: 'static, FnStorage: Storage + StorageSend>
+    DynFn<'capture, ForFixed<A>, ForFixed<R>, FnStorage>
+{
+    /// Returns a C function pointer calling the function, and the context pointer to pass to it.
+    ///
+    /// The context pointer is the address of the function handle, so it is only valid while the
+    /// handle is alive and not moved; [`FfiCallback`] can be used to keep it alive instead.
+    ///
+    /// As any `extern "C"` function, the returned function aborts the process if the function
+    /// panics, instead of unwinding into foreign code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::ffi::c_void;
+    ///
+    /// use dyn_fn::{DynFn, hkt::ForFixed};
+    ///
+    /// // a C library registering a callback
+    /// fn register(cb: unsafe extern "C" fn(*mut c_void, i32) -> i32, ctx: *mut c_void) -> i32 {
+    ///     unsafe { cb(ctx, 41) }
+    /// }
+    ///
+    /// let offset = 1;
+    /// let f = DynFn::<ForFixed<i32>, ForFixed<i32>>::new(move |x, _| x + offset);
+    /// let (cb, ctx) = f.as_ffi();
+    /// assert_eq!(register(cb, ctx), 42);
+    /// ```
+    pub fn as_ffi(&self) -> (FfiFn<A, R>, *mut c_void) {
+        let ctx = (self as *const Self).cast_mut().cast();
+        (trampoline::<A, R, FnStorage>, ctx)
+    }
+}
+
+#[cfg(feature = "alloc")]
+struct FfiInner<'capture, A: 'static, R: 'static, FnStorage: Storage + StorageSend> {
+    f: DynFn<'capture, ForFixed<A>, ForFixed<R>, FnStorage>,
+    #[cfg(feature = "std")]
+    on_panic: Option<R>,
+}
+
+/// # Safety
+///
+/// `ctx` must point to a live `FfiInner<'_, A, R, FnStorage>`.
+#[cfg(feature = "alloc")]
+unsafe extern "C" fn owned_trampoline<
+    A: 'static,
+    R: Copy + 'static,
+    FnStorage: Storage + StorageSend,
+>(
+    ctx: *mut c_void,
+    arg: A,
+) -> R {
+    // SAFETY: `ctx` points to a live `FfiInner`, as per function contract
+    let inner = unsafe { &*ctx.cast::<FfiInner<'_, A, R, FnStorage>>() };
+    #[cfg(feature = "std")]
+    if let Some(on_panic) = inner.on_panic {
+        return inner.f.call_catch_unwind(arg).unwrap_or(on_panic);
+    }
+    inner.f.call(arg)
+}
+
+/// An owned [`DynFn`] to be registered as a C callback, with a stable context pointer.
+///
+/// The callback is allocated, so its context pointer stays valid while it is alive, even if it
+/// is moved. [`leak`](Self::leak) keeps it alive forever, for register-once APIs, and
+/// [`from_raw`](Self::from_raw) takes it back when the foreign code releases it.
+///
+/// If the function panics, the process is aborted, unless a value to be returned on panic
+/// is given with `with_panic_return`, with the `std` feature. Return types of C functions
+/// being plain data, `R` must implement [`Copy`].
+///
+/// # Examples
+///
+/// ```
+/// use core::ffi::c_void;
+///
+/// use dyn_fn::{DynFn, FfiCallback, hkt::ForFixed};
+///
+/// // a C library keeping a registered callback
+/// static mut CALLBACK: Option<(unsafe extern "C" fn(*mut c_void, i32) -> i32, *mut c_void)> =
+///     None;
+/// fn register(cb: unsafe extern "C" fn(*mut c_void, i32) -> i32, ctx: *mut c_void) {
+///     unsafe { CALLBACK = Some((cb, ctx)) };
+/// }
+/// fn trigger(arg: i32) -> i32 {
+///     let (cb, ctx) = unsafe { CALLBACK.unwrap() };
+///     unsafe { cb(ctx, arg) }
+/// }
+///
+/// let cb = FfiCallback::new(DynFn::<ForFixed<i32>, ForFixed<i32>>::new(|x, _| x + 1));
+/// let (cb, ctx) = cb.leak();
+/// register(cb, ctx);
+/// assert_eq!(trigger(41), 42);
+/// // the callback is released by the library
+/// drop(unsafe { FfiCallback::<i32, i32>::from_raw(ctx) });
+/// ```
+#[cfg(feature = "alloc")]
+pub struct FfiCallback<
+    'capture,
+    A: 'static,
+    R: Copy + 'static,
+    FnStorage: Storage + StorageSend = DefaultFnStorage,
+>(Box<FfiInner<'capture, A, R, FnStorage>>);
+
+#[cfg(feature = "alloc")]
+impl<'capture, A: 'static, R: Copy + 'static, FnStorage: Storage + StorageSend>
+    FfiCallback<'capture, A, R, FnStorage>
+{
+    /// Constructs a new [`FfiCallback`], aborting the process if the function panics.
+    pub fn new(f: DynFn<'capture, ForFixed<A>, ForFixed<R>, FnStorage>) -> Self {
+        Self(Box::new(FfiInner {
+            f,
+            #[cfg(feature = "std")]
+            on_panic: None,
+        }))
+    }
+
+    /// Constructs a new [`FfiCallback`], returning `on_panic` if the function panics.
+    ///
+    /// The function is considered unwind safe, as in
+    /// [`DynFn::call_catch_unwind`](crate::DynFn::call_catch_unwind).
+    #[cfg(feature = "std")]
+    pub fn with_panic_return(
+        f: DynFn<'capture, ForFixed<A>, ForFixed<R>, FnStorage>,
+        on_panic: R,
+    ) -> Self {
+        Self(Box::new(FfiInner {
+            f,
+            on_panic: Some(on_panic),
+        }))
+    }
+
+    /// Returns a C function pointer calling the function, and the context pointer to pass to it.
+    ///
+    /// The context pointer is valid while the callback is alive.
+    pub fn as_ffi(&self) -> (FfiFn<A, R>, *mut c_void) {
+        let ctx = (&raw const *self.0).cast_mut().cast();
+        (owned_trampoline::<A, R, FnStorage>, ctx)
+    }
+
+    /// Returns the underlying function.
+    pub fn into_inner(self) -> DynFn<'capture, ForFixed<A>, ForFixed<R>, FnStorage> {
+        self.0.f
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: 'static, R: Copy + 'static, FnStorage: Storage + StorageSend>
+    FfiCallback<'static, A, R, FnStorage>
+{
+    /// Leaks the callback, returning a C function pointer calling the function, and the context
+    /// pointer to pass to it, valid forever unless given back to [`from_raw`](Self::from_raw).
+    pub fn leak(self) -> (FfiFn<A, R>, *mut c_void) {
+        let ctx = Box::into_raw(self.0).cast();
+        (owned_trampoline::<A, R, FnStorage>, ctx)
+    }
+
+    /// Takes back a callback leaked with [`leak`](Self::leak).
+    ///
+    /// # Safety
+    ///
+    /// `ctx` must be a context pointer returned by [`leak`](Self::leak) for the same type, which
+    /// has not been taken back already; the C function pointer must not be called after the
+    /// callback is dropped.
+    pub unsafe fn from_raw(ctx: *mut c_void) -> Self {
+        // SAFETY: `ctx` comes from `Box::into_raw`, as it's the one of a leaked callback,
+        // as per function contract
+        Self(unsafe { Box::from_raw(ctx.cast()) })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: 'static, R: Copy + 'static, FnStorage: Storage + StorageSend> fmt::Debug
+    for FfiCallback<'_, A, R, FnStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FfiCallback").field(&self.0.f).finish()
+    }
+}
+
+/// # Safety
+///
+/// `data` must come from `Box::into_raw` of a `DynFn<'static, ForFixed<A>, ForFixed<R>,
+/// FnStorage>`, not dropped yet.
+#[cfg(feature = "alloc")]
+unsafe extern "C" fn abi_drop<A: 'static, R: 'static, FnStorage: Storage + StorageSend>(
+    data: *mut c_void,
+) {
+    // SAFETY: `data` comes from `Box::into_raw`, as per function contract
+    drop(unsafe {
+        Box::from_raw(data.cast::<DynFn<'static, ForFixed<A>, ForFixed<R>, FnStorage>>())
+    });
+}
+
+/// An owned function with a stable `#[repr(C)]` layout, to be passed across a plugin ABI
+/// boundary, e.g. between a program and a dynamically loaded `cdylib`.
+///
+/// Its layout is, in order:
+/// - `version: u32`, the layout version, currently [`VERSION`](Self::VERSION); future versions
+///   will only append fields;
+/// - `data: *mut c_void`, the context pointer;
+/// - `call: unsafe extern "C" fn(*mut c_void, A) -> R`, calling the function with the context
+///   pointer;
+/// - `drop: unsafe extern "C" fn(*mut c_void)`, dropping the function with the context pointer.
+///
+/// The function is called and dropped through these pointers, so it is always dropped by the
+/// side which created it, with its own allocator. `A` and `R` must be FFI-safe for the layout to
+/// be meaningful to foreign code.
+///
+/// It is converted losslessly from and to [`DynFn`] with [`From`]; converting it back to a
+/// `DynFn` wraps it, so it is still dropped by its original side.
+///
+/// With the `abi_stable` feature, it implements `StableAbi`, so it can be a field of
+/// `#[derive(StableAbi)]` interface structs, and its layout is checked when a library is loaded.
+/// Asynchronous functions are not supported, their futures having no stable layout; only
+/// synchronous functions can cross the boundary.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{AbiDynFn, DynFn, hkt::ForFixed};
+///
+/// // exported by a plugin
+/// extern "C" fn make_callback(offset: u32) -> AbiDynFn<u32, u32> {
+///     DynFn::<ForFixed<u32>, ForFixed<u32>>::new(move |x, _| x + offset).into()
+/// }
+///
+/// let f = make_callback(1);
+/// assert_eq!(f.version(), AbiDynFn::<u32, u32>::VERSION);
+/// assert_eq!(f.call(41), 42);
+/// let f = DynFn::<ForFixed<u32>, ForFixed<u32>>::from(f);
+/// assert_eq!(f.call(41), 42);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "abi_stable", derive(abi_stable::StableAbi))]
+#[repr(C)]
+pub struct AbiDynFn<A: 'static, R: 'static> {
+    version: u32,
+    data: *mut c_void,
+    // spelled out, for the `StableAbi` derive to see a function pointer
+    call: unsafe extern "C" fn(*mut c_void, A) -> R,
+    drop: unsafe extern "C" fn(*mut c_void),
+}
+
+// SAFETY: the function is `Send + Sync`, as per `AbiDynFn::from_raw_parts` contract
+#[cfg(feature = "alloc")]
+unsafe impl<A: 'static, R: 'static> Send for AbiDynFn<A, R> {}
+// SAFETY: the function is `Send + Sync`, as per `AbiDynFn::from_raw_parts` contract
+#[cfg(feature = "alloc")]
+unsafe impl<A: 'static, R: 'static> Sync for AbiDynFn<A, R> {}
+
+#[cfg(feature = "alloc")]
+impl<A: 'static, R: 'static> AbiDynFn<A, R> {
+    /// The current layout version.
+    pub const VERSION: u32 = 1;
+
+    /// Constructs a new [`AbiDynFn`] from its context pointer, and its call and drop function
+    /// pointers.
+    ///
+    /// # Safety
+    ///
+    /// `call` and `drop` must be safe to call with `data`, `call` from any thread and
+    /// concurrently, and `drop` once, after which neither is called anymore.
+    pub const unsafe fn from_raw_parts(
+        data: *mut c_void,
+        call: FfiFn<A, R>,
+        drop: unsafe extern "C" fn(*mut c_void),
+    ) -> Self {
+        Self {
+            version: Self::VERSION,
+            data,
+            call,
+            drop,
+        }
+    }
+
+    /// Returns the layout version.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Calls the function.
+    pub fn call(&self, arg: A) -> R {
+        // SAFETY: `call` can be called with `data` while the function is alive,
+        // as per `from_raw_parts` contract
+        unsafe { (self.call)(self.data, arg) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: 'static, R: 'static> Drop for AbiDynFn<A, R> {
+    fn drop(&mut self) {
+        // SAFETY: `drop` is called once with `data`, as per `from_raw_parts` contract
+        unsafe { (self.drop)(self.data) };
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: 'static, R: 'static, FnStorage: Storage + StorageSend>
+    From<DynFn<'static, ForFixed<A>, ForFixed<R>, FnStorage>> for AbiDynFn<A, R>
+{
+    fn from(f: DynFn<'static, ForFixed<A>, ForFixed<R>, FnStorage>) -> Self {
+        let data = Box::into_raw(Box::new(f)).cast();
+        // SAFETY: `data` is a leaked `Send + Sync` function, matching the trampolines
+        unsafe {
+            Self::from_raw_parts(
+                data,
+                trampoline::<A, R, FnStorage>,
+                abi_drop::<A, R, FnStorage>,
+            )
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: 'static, R: 'static> From<AbiDynFn<A, R>> for DynFn<'static, ForFixed<A>, ForFixed<R>> {
+    fn from(f: AbiDynFn<A, R>) -> Self {
+        Self::new(move |arg, _| f.call(arg))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: 'static, R: 'static> fmt::Debug for AbiDynFn<A, R> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AbiDynFn")
+            .field("version", &self.version)
+            .field("data", &self.data)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,110 @@
+//! The [`ForLt`] trait bounding dynamic function parameters, and ready-made [`ForLt`] types
+//! complementing the ones of [`hkt`](crate::hkt).
+//!
+//! Each of them borrows for the call lifetime, so it can be used as `Arg` or `Ret` of a dynamic
+//! function without writing a [`ForLt!`](crate::ForLt!) invocation.
+
+/// A type-level function from a lifetime to a type, used for the `Arg` and `Ret` parameters of
+/// dynamic functions.
+///
+/// It is implemented for every [`higher_kinded_types::ForLt`](trait@higher_kinded_types::ForLt) type, e.g.
+/// [`ForFixed`](crate::hkt::ForFixed), [`ForRef`](crate::hkt::ForRef), [`ForLt!`](crate::ForLt!)
+/// invocations or the types of this module. The trait is sealed, so that public signatures don't
+/// depend on the version of `higher-kinded-types`; it is also the `ForLt` of [`hkt`](crate::hkt).
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{ForLt, LocalDynFn, hkt::ForRef};
+///
+/// fn call<'a, Arg: ForLt>(f: &LocalDynFn<'_, Arg, Arg>, arg: Arg::Of<'a>) -> Arg::Of<'a> {
+///     f.call(arg)
+/// }
+/// let f = LocalDynFn::<ForRef<str>, ForRef<str>>::new(|s, _| s.trim());
+/// assert_eq!(call(&f, " test "), "test");
+/// ```
+pub trait ForLt: private::Sealed {
+    /// The type for the lifetime `'a`.
+    type Of<'a>;
+}
+
+impl<T: higher_kinded_types::ForLt> ForLt for T {
+    type Of<'a> = T::Of<'a>;
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl<T: higher_kinded_types::ForLt> Sealed for T {}
+}
+
+/// `&'a mut T`
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{LocalDynFnMut, hkt::ForFixed, hkt_ext::ForRefMut};
+///
+/// let mut push = LocalDynFnMut::<ForRefMut<Vec<u8>>, ForFixed<()>>::new(|v, _| v.push(42));
+/// let mut v = Vec::new();
+/// push.call(&mut v);
+/// assert_eq!(v, [42]);
+/// ```
+pub type ForRefMut<T> = higher_kinded_types::ForLt!(<'a> = &'a mut T);
+
+/// `&'a [T]`
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{LocalDynFn, hkt::ForFixed, hkt_ext::ForSlice};
+///
+/// let sum =
+///     LocalDynFn::<ForSlice<u8>, ForFixed<u32>>::new(|s, _| s.iter().map(|&b| b as u32).sum());
+/// assert_eq!(sum.call(&[1, 2, 3]), 6);
+/// ```
+pub type ForSlice<T> = higher_kinded_types::ForLt!(<'a> = &'a [T]);
+
+/// `&'a str`
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{LocalDynFn, hkt_ext::ForStr};
+///
+/// let trim = LocalDynFn::<ForStr, ForStr>::new(|s, _| s.trim());
+/// assert_eq!(trim.call(" test "), "test");
+/// ```
+pub type ForStr = higher_kinded_types::ForLt!(<'a> = &'a str);
+
+/// `Result<T::Of<'a>, E>`
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{
+///     LocalDynFn,
+///     hkt_ext::{ForResult, ForStr},
+/// };
+///
+/// let parse =
+///     LocalDynFn::<ForStr, ForResult<ForStr, ()>>::new(|s, _| s.strip_prefix('+').ok_or(()));
+/// assert_eq!(parse.call("+test"), Ok("test"));
+/// assert_eq!(parse.call("test"), Err(()));
+/// ```
+pub type ForResult<T, E> = higher_kinded_types::ForLt!(<'a> = Result<<T as ForLt>::Of<'a>, E>);
+
+/// `Option<T::Of<'a>>`
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{
+///     LocalDynFn,
+///     hkt_ext::{ForOption, ForStr},
+/// };
+///
+/// let first_word = LocalDynFn::<ForStr, ForOption<ForStr>>::new(|s, _| s.split(' ').next());
+/// assert_eq!(first_word.call("a test"), Some("a"));
+/// ```
+pub type ForOption<T> = higher_kinded_types::ForLt!(<'a> = Option<<T as ForLt>::Of<'a>>);
@@ -0,0 +1,50 @@
+/// The identity of a dynamic function, obtained with `id` method.
+///
+/// For [`Box`], [`Rc`] and [`Arc`] storages, the identity is based on the allocation address,
+/// so clones of [`Rc`]/[`Arc`]-backed functions share the same identity.
+/// For [`Raw`] storage, it is based on the address of the function handle itself,
+/// so it is only stable while the handle isn't moved.
+///
+/// Zero-sized functions are not allocated, so [`Box`]-backed ones all have the same dangling
+/// address: as the identity also includes the vtable address, different functions still have
+/// different identities, but handles created from the same zero-sized closure share it.
+///
+/// In any case, the identity is only unique while the function is alive.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use std::collections::HashMap;
+///
+/// use dyn_fn::{CallbackId, DynFn, hkt::ForFixed, storage};
+///
+/// type Callback = DynFn<'static, ForFixed<()>, ForFixed<()>, storage::Arc>;
+/// let mut registry = HashMap::<CallbackId, Callback>::new();
+/// let cb = Callback::new(|_, _| ());
+/// let id = cb.id();
+/// registry.insert(id, cb.clone());
+/// assert!(registry.contains_key(&cb.id()));
+/// registry.remove(&id);
+/// assert!(registry.is_empty());
+/// # }
+/// ```
+///
+/// [`Box`]: crate::storage::Box
+/// [`Rc`]: crate::storage::Rc
+/// [`Arc`]: crate::storage::Arc
+/// [`Raw`]: crate::storage::Raw
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CallbackId {
+    data: usize,
+    vtable: usize,
+}
+
+impl CallbackId {
+    pub(crate) fn new<T: ?Sized, VT>(data: *const T, vtable: &'static VT) -> Self {
+        Self {
+            data: data.cast::<()>().addr(),
+            vtable: (vtable as *const VT).addr(),
+        }
+    }
+}
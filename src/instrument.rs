@@ -0,0 +1,328 @@
+use alloc::sync::Arc;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+use core::sync::atomic::AtomicU64;
+use core::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering::Relaxed},
+};
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+use std::time::Instant;
+
+use higher_kinded_types::ForFixed;
+
+#[cfg(feature = "async")]
+use crate::{DynAsyncCallable, DynAsyncCallableMut, DynAsyncCallableOnce};
+use crate::{DynCallable, DynCallableMut, DynCallableOnce, ForLt};
+
+/// Invocation statistics of an [`InstrumentedDynFn`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallStats {
+    /// Number of calls, including the ones in flight.
+    pub calls: usize,
+    /// Number of calls which returned an error, if errors are
+    /// [counted](InstrumentedDynFn::count_errors).
+    pub errors: usize,
+    /// Start of the last call, if any.
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    pub last_call: Option<Instant>,
+}
+
+/// Counters shared by an instrumented function and its clones.
+struct Counters {
+    calls: AtomicUsize,
+    errors: AtomicUsize,
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    epoch: Instant,
+    /// Nanoseconds elapsed from `epoch` to the start of the last call, plus one, or zero before
+    /// the first call.
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    last_call: AtomicU64,
+}
+
+impl Counters {
+    fn new() -> Self {
+        Self {
+            calls: AtomicUsize::new(0),
+            errors: AtomicUsize::new(0),
+            #[cfg(all(feature = "std", target_has_atomic = "64"))]
+            epoch: Instant::now(),
+            #[cfg(all(feature = "std", target_has_atomic = "64"))]
+            last_call: AtomicU64::new(0),
+        }
+    }
+
+    #[inline]
+    fn record_call(&self) {
+        self.calls.fetch_add(1, Relaxed);
+        #[cfg(all(feature = "std", target_has_atomic = "64"))]
+        {
+            let elapsed = self.epoch.elapsed().as_nanos() as u64;
+            self.last_call.fetch_max(elapsed + 1, Relaxed);
+        }
+    }
+
+    fn stats(&self) -> CallStats {
+        CallStats {
+            calls: self.calls.load(Relaxed),
+            errors: self.errors.load(Relaxed),
+            #[cfg(all(feature = "std", target_has_atomic = "64"))]
+            last_call: match self.last_call.load(Relaxed) {
+                0 => None,
+                nanos => Some(self.epoch + core::time::Duration::from_nanos(nanos - 1)),
+            },
+        }
+    }
+}
+
+#[expect(type_alias_bounds)]
+type IsError<Ret: ForLt> = for<'a> fn(&Ret::Of<'a>) -> bool;
+
+/// A dynamic function counting its invocations, returned by the `instrumented` method of every
+/// dynamic function.
+///
+/// It counts the calls, the errors returned if [enabled](Self::count_errors), and, with `std`
+/// feature, records the start of the last call. Counters are atomic and shared with the clones
+/// of the wrapper, e.g. of an instrumented [`Arc`](crate::storage::Arc)-stored function.
+/// Instrumentation is not free: each call increments shared atomic counters, and reads the
+/// monotonic clock with `std` feature, which dominates the cost of cheap functions.
+///
+/// It is called through the [`DynCallable`]/[`DynAsyncCallable`] traits implemented by the
+/// wrapped function, so it can be passed to generic code accepting them. The wrapped function is
+/// given back by [`into_inner`](Self::into_inner).
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{DynCallable, DynFn, hkt::ForFixed, storage};
+///
+/// type Callback = DynFn<'static, ForFixed<u32>, ForFixed<Result<u32, ()>>, storage::Arc>;
+/// let checked_incr = Callback::new(|x, _| x.checked_add(1).ok_or(()))
+///     .instrumented()
+///     .count_errors();
+/// let clone = checked_incr.clone();
+/// assert_eq!(checked_incr.call(41), Ok(42));
+/// assert_eq!(clone.call(u32::MAX), Err(()));
+/// let stats = checked_incr.stats();
+/// assert_eq!((stats.calls, stats.errors), (2, 1));
+/// ```
+pub struct InstrumentedDynFn<F, Ret: ForLt> {
+    f: F,
+    counters: Arc<Counters>,
+    is_error: Option<IsError<Ret>>,
+}
+
+impl<F, Ret: ForLt> InstrumentedDynFn<F, Ret> {
+    /// Wraps a function, with zeroed counters.
+    pub fn new(f: F) -> Self {
+        Self {
+            f,
+            counters: Arc::new(Counters::new()),
+            is_error: None,
+        }
+    }
+
+    /// Returns the statistics accumulated since the function has been wrapped.
+    pub fn stats(&self) -> CallStats {
+        self.counters.stats()
+    }
+
+    /// Returns a reference to the wrapped function, e.g. to pass it to APIs requiring the plain
+    /// function type.
+    ///
+    /// Calls made through the reference are not counted.
+    pub fn get_ref(&self) -> &F {
+        &self.f
+    }
+
+    /// Returns the wrapped function.
+    pub fn into_inner(self) -> F {
+        self.f
+    }
+
+    #[inline]
+    fn record<'a>(&self, ret: &Ret::Of<'a>) {
+        if self.is_error.is_some_and(|is_error| is_error(ret)) {
+            self.counters.errors.fetch_add(1, Relaxed);
+        }
+    }
+}
+
+impl<F, T, E> InstrumentedDynFn<F, ForFixed<Result<T, E>>> {
+    /// Counts the errors returned by the function.
+    pub fn count_errors(mut self) -> Self {
+        self.is_error = Some(Result::is_err);
+        self
+    }
+}
+
+impl<F: Clone, Ret: ForLt> Clone for InstrumentedDynFn<F, Ret> {
+    fn clone(&self) -> Self {
+        Self {
+            f: self.f.clone(),
+            counters: self.counters.clone(),
+            is_error: self.is_error,
+        }
+    }
+}
+
+impl<F: fmt::Debug, Ret: ForLt> fmt::Debug for InstrumentedDynFn<F, Ret> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InstrumentedDynFn")
+            .field("f", &self.f)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl<Arg: ForLt, Ret: ForLt, F: DynCallableOnce<Arg, Ret>> DynCallableOnce<Arg, Ret>
+    for InstrumentedDynFn<F, Ret>
+{
+    #[inline]
+    fn call_once<'a>(self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.counters.record_call();
+        let ret = self.f.call_once(arg);
+        // the function has been moved out, so `record` cannot be used
+        if self.is_error.is_some_and(|is_error| is_error(&ret)) {
+            self.counters.errors.fetch_add(1, Relaxed);
+        }
+        ret
+    }
+}
+
+impl<Arg: ForLt, Ret: ForLt, F: DynCallableMut<Arg, Ret>> DynCallableMut<Arg, Ret>
+    for InstrumentedDynFn<F, Ret>
+{
+    #[inline]
+    fn call_mut<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.counters.record_call();
+        let ret = self.f.call_mut(arg);
+        self.record(&ret);
+        ret
+    }
+}
+
+impl<Arg: ForLt, Ret: ForLt, F: DynCallable<Arg, Ret>> DynCallable<Arg, Ret>
+    for InstrumentedDynFn<F, Ret>
+{
+    #[inline]
+    fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.counters.record_call();
+        let ret = self.f.call(arg);
+        self.record(&ret);
+        ret
+    }
+}
+
+#[cfg(feature = "async")]
+impl<Arg: ForLt, Ret: ForLt, F: DynAsyncCallableOnce<Arg, Ret>> DynAsyncCallableOnce<Arg, Ret>
+    for InstrumentedDynFn<F, Ret>
+{
+    #[inline]
+    fn is_sync(&self) -> bool {
+        self.f.is_sync()
+    }
+
+    async fn call_once<'a>(self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        let Self {
+            f,
+            counters,
+            is_error,
+        } = self;
+        counters.record_call();
+        let ret = f.call_once(arg).await;
+        if is_error.is_some_and(|is_error| is_error(&ret)) {
+            counters.errors.fetch_add(1, Relaxed);
+        }
+        ret
+    }
+
+    fn call_sync_once<'a>(self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        if !self.f.is_sync() {
+            return None;
+        }
+        let Self {
+            f,
+            counters,
+            is_error,
+        } = self;
+        counters.record_call();
+        let ret = f.call_sync_once(arg)?;
+        if is_error.is_some_and(|is_error| is_error(&ret)) {
+            counters.errors.fetch_add(1, Relaxed);
+        }
+        Some(ret)
+    }
+
+    async fn call_try_sync_once<'a>(self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        let Self {
+            f,
+            counters,
+            is_error,
+        } = self;
+        counters.record_call();
+        let ret = f.call_try_sync_once(arg).await;
+        if is_error.is_some_and(|is_error| is_error(&ret)) {
+            counters.errors.fetch_add(1, Relaxed);
+        }
+        ret
+    }
+}
+
+#[cfg(feature = "async")]
+impl<Arg: ForLt, Ret: ForLt, F: DynAsyncCallableMut<Arg, Ret>> DynAsyncCallableMut<Arg, Ret>
+    for InstrumentedDynFn<F, Ret>
+{
+    async fn call_mut<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.counters.record_call();
+        let ret = self.f.call_mut(arg).await;
+        self.record(&ret);
+        ret
+    }
+
+    fn call_sync_mut<'a>(&mut self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        if !self.f.is_sync() {
+            return None;
+        }
+        self.counters.record_call();
+        let ret = self.f.call_sync_mut(arg)?;
+        self.record(&ret);
+        Some(ret)
+    }
+
+    async fn call_try_sync_mut<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.counters.record_call();
+        let ret = self.f.call_try_sync_mut(arg).await;
+        self.record(&ret);
+        ret
+    }
+}
+
+#[cfg(feature = "async")]
+impl<Arg: ForLt, Ret: ForLt, F: DynAsyncCallable<Arg, Ret>> DynAsyncCallable<Arg, Ret>
+    for InstrumentedDynFn<F, Ret>
+{
+    async fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.counters.record_call();
+        let ret = self.f.call(arg).await;
+        self.record(&ret);
+        ret
+    }
+
+    fn call_sync<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        if !self.f.is_sync() {
+            return None;
+        }
+        self.counters.record_call();
+        let ret = self.f.call_sync(arg)?;
+        self.record(&ret);
+        Some(ret)
+    }
+
+    async fn call_try_sync<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.counters.record_call();
+        let ret = self.f.call_try_sync(arg).await;
+        self.record(&ret);
+        ret
+    }
+}
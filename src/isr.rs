@@ -0,0 +1,228 @@
+use core::{fmt, mem::MaybeUninit, sync::atomic::Ordering};
+
+use higher_kinded_types::ForFixed;
+
+use crate::{
+    DynFn, ForLt,
+    primitives::{AtomicU32, UnsafeCell, spin_loop},
+    storage::{DefaultFnStorage, Storage, StorageSend},
+};
+
+/// Index of the active slot.
+const ACTIVE: u32 = 1;
+/// Whether each slot holds a function.
+const PRESENT: [u32; 2] = [1 << 1, 1 << 2];
+/// Whether a swap is in progress.
+const WRITER: u32 = 1 << 3;
+/// Unit of the count of accesses in flight for each slot.
+const CALL: [u32; 2] = [1 << 4, 1 << 18];
+/// Mask of the count of accesses in flight for each slot.
+const CALLS: [u32; 2] = [0x3fff << 4, 0x3fff << 18];
+
+/// A single function slot, to be installed from the main thread and called from an interrupt
+/// handler.
+///
+/// [`call_if_set`](Self::call_if_set) is wait-free: it never blocks, and never observes a
+/// function being installed or dropped. Functions are stored in place, in two alternating
+/// slots, so it doesn't allocate with [`Raw`](crate::storage::Raw) storage, and can be used as
+/// a `static`.
+///
+/// [`swap`](Self::swap) installs a new function in the inactive slot, activates it, then waits
+/// for the calls still using the previous function to complete before returning it. As a
+/// consequence, it must not be called from a context which can preempt a call, e.g. from an
+/// interrupt handler, as it would never complete.
+///
+/// # Examples
+///
+/// ```
+/// #![no_std]
+/// # extern crate std;
+/// use core::sync::atomic::{AtomicU32, Ordering};
+///
+/// use dyn_fn::{DynFn, IsrCallback, hkt::ForFixed, storage::Raw};
+///
+/// static HANDLER: IsrCallback<ForFixed<u32>, ForFixed<()>, Raw<0>> = IsrCallback::new();
+/// static TOTAL: AtomicU32 = AtomicU32::new(0);
+///
+/// fn interrupt_handler(value: u32) {
+///     HANDLER.call_if_set(value);
+/// }
+///
+/// # fn main() {
+/// interrupt_handler(1);
+/// HANDLER.swap(DynFn::new(|x, _| {
+///     TOTAL.fetch_add(x, Ordering::Relaxed);
+/// }));
+/// interrupt_handler(2);
+/// assert!(HANDLER.take().is_some());
+/// interrupt_handler(3);
+/// assert_eq!(TOTAL.load(Ordering::Relaxed), 2);
+/// # }
+/// ```
+pub struct IsrCallback<
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: Storage + StorageSend = DefaultFnStorage,
+>(SwapCell<DynFn<'static, Arg, Ret, FnStorage>>);
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    IsrCallback<Arg, Ret, FnStorage>
+{
+    /// Constructs a new empty slot.
+    #[cfg(not(loom))]
+    pub const fn new() -> Self {
+        Self(SwapCell::new())
+    }
+
+    /// Constructs a new empty slot.
+    #[cfg(loom)]
+    pub fn new() -> Self {
+        Self(SwapCell::new())
+    }
+
+    /// Returns whether a function is installed.
+    pub fn is_set(&self) -> bool {
+        self.0.is_set()
+    }
+
+    /// Calls the installed function, if any, without ever blocking.
+    pub fn call_if_set<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        self.0.with_active(|f| f.call(arg))
+    }
+
+    /// Installs a function, returning the previous one once no call uses it anymore.
+    pub fn swap(
+        &self,
+        f: DynFn<'static, Arg, Ret, FnStorage>,
+    ) -> Option<DynFn<'static, Arg, Ret, FnStorage>> {
+        self.0.replace(Some(f))
+    }
+
+    /// Uninstalls the function, returning it once no call uses it anymore.
+    pub fn take(&self) -> Option<DynFn<'static, Arg, Ret, FnStorage>> {
+        self.0.replace(None)
+    }
+}
+
+/// Two alternating slots, whose active value can be accessed without ever blocking, while
+/// replacing it waits for the accesses to the previous value to complete.
+pub(crate) struct SwapCell<T> {
+    state: AtomicU32,
+    slots: [UnsafeCell<MaybeUninit<T>>; 2],
+}
+
+// SAFETY: values are only shared through `&T`, and the slots are synchronized with the atomic
+// state
+unsafe impl<T: Send + Sync> Sync for SwapCell<T> {}
+
+/// An access in flight on a slot, decrementing its count when dropped, even on panic.
+struct InFlight<'a>(&'a AtomicU32, usize);
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(CALL[self.1], Ordering::Release);
+    }
+}
+
+impl<T> SwapCell<T> {
+    #[cfg(not(loom))]
+    pub(crate) const fn new() -> Self {
+        Self {
+            state: AtomicU32::new(0),
+            slots: [const { UnsafeCell::new(MaybeUninit::uninit()) }; 2],
+        }
+    }
+
+    #[cfg(loom)]
+    pub(crate) fn new() -> Self {
+        Self {
+            state: AtomicU32::new(0),
+            slots: [(); 2].map(|_| UnsafeCell::new(MaybeUninit::uninit())),
+        }
+    }
+
+    pub(crate) fn is_set(&self) -> bool {
+        let state = self.state.load(Ordering::Relaxed);
+        state & PRESENT[(state & ACTIVE) as usize] != 0
+    }
+
+    /// Accesses the active value, if any, without ever blocking.
+    pub(crate) fn with_active<R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        // count the access on both slots, as the active one is only known afterwards
+        let state = self.state.fetch_add(CALL[0] + CALL[1], Ordering::Acquire);
+        let active = (state & ACTIVE) as usize;
+        let _in_flight = InFlight(&self.state, active);
+        self.state.fetch_sub(CALL[1 - active], Ordering::Release);
+        if state & PRESENT[active] == 0 {
+            return None;
+        }
+        Some(self.slots[active].with(|slot| {
+            // SAFETY: the slot is initialized, as it is present, and is neither overwritten nor
+            // dropped while accesses to it are in flight
+            f(unsafe { (*slot).assume_init_ref() })
+        }))
+    }
+
+    fn wait_calls(&self, slot: usize) {
+        while self.state.load(Ordering::Acquire) & CALLS[slot] != 0 {
+            spin_loop();
+        }
+    }
+
+    /// Replaces the value, returning the previous one once no access uses it anymore.
+    pub(crate) fn replace(&self, value: Option<T>) -> Option<T> {
+        while self.state.fetch_or(WRITER, Ordering::Acquire) & WRITER != 0 {
+            spin_loop();
+        }
+        let active = (self.state.load(Ordering::Relaxed) & ACTIVE) as usize;
+        let inactive = 1 - active;
+        // accesses counted on the inactive slot are transient, they don't use it
+        self.wait_calls(inactive);
+        let present = match value {
+            Some(value) => {
+                // SAFETY: the inactive slot is empty, and not used by accesses
+                self.slots[inactive].with_mut(|slot| unsafe { (*slot).write(value) });
+                PRESENT[inactive]
+            }
+            None => 0,
+        };
+        let state = (self.state)
+            .fetch_update(Ordering::AcqRel, Ordering::Relaxed, |state| {
+                Some((state ^ ACTIVE) & !PRESENT[active] | present)
+            })
+            .unwrap();
+        self.wait_calls(active);
+        let prev = (state & PRESENT[active] != 0).then(|| {
+            // SAFETY: the previous slot is initialized if it was present, and it is not used by
+            // accesses anymore, as it is inactive and its accesses have completed
+            self.slots[active].with_mut(|slot| unsafe { (*slot).assume_init_read() })
+        });
+        self.state.fetch_and(!WRITER, Ordering::Release);
+        prev
+    }
+}
+
+impl<T> Drop for SwapCell<T> {
+    fn drop(&mut self) {
+        drop(self.replace(None));
+    }
+}
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend> Default
+    for IsrCallback<Arg, Ret, FnStorage>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend> fmt::Debug
+    for IsrCallback<Arg, Ret, FnStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IsrCallback")
+            .field("is_set", &self.is_set())
+            .finish_non_exhaustive()
+    }
+}
@@ -1,5 +1,6 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
+#![cfg_attr(feature = "nightly", feature(fn_traits, unboxed_closures))]
 #![no_std]
 #![forbid(missing_docs)]
 
@@ -14,7 +15,8 @@
 //! This crate relies on [`higher_kinded_types`], reexported as `hkt`, to support generic
 //! lifetime in function parameters and/or return type. However, because of a [current limitation]
 //! of the compiler, every closure requires a second `PhantomData` parameter to carry the lifetime
-//! of the argument for the return type. This ergonomic issue doesn't impact performance.
+//! of the argument for the return type. This ergonomic issue doesn't impact performance, and
+//! [`dyn_fn!`]/[`dyn_async_fn!`] macros hide the additional parameter.
 //!
 //! # Examples
 //!
@@ -23,14 +25,14 @@
 //! ```
 //! #![no_std]
 //! # extern crate std;
-//! use dyn_fn::{LocalDynFn, hkt, storage};
+//! use dyn_fn::{LocalDynFn, dyn_fn, hkt, storage};
 //!
 //! # fn main() {
 //! type Callback<'a> = LocalDynFn<'a, hkt::ForRef<str>, hkt::ForFixed<()>, storage::Raw<32>>;
 //! let mut callbacks = heapless::Vec::<Callback, 4>::new();
-//! callbacks.push(Callback::new(|s, _| {
+//! callbacks.push(Callback::new(dyn_fn!(|s| {
 //!     defmt::debug!("callback called with '{}'", s)
-//! }));
+//! })));
 //!
 //! let input = "input";
 //! for cb in &callbacks {
@@ -40,25 +42,63 @@
 //! # }
 //! ```
 //!
+//! ### Notification callback without argument
+//!
+//! ```
+//! #![no_std]
+//! # extern crate std;
+//! use dyn_fn::{LocalDynFn0, storage};
+//!
+//! # fn main() {
+//! type Callback<'a> = LocalDynFn0<'a, (), storage::Raw<0>>;
+//! let cb = Callback::new0(|| defmt::info!("tick"));
+//! cb.call0();
+//! # }
+//! ```
+//!
 //! ### Asynchronous dynamic callback
 //!
 //! ```
 //! use std::time::Duration;
 //!
-//! use dyn_fn::{LocalDynAsyncFn, hkt, storage};
+//! # #[cfg(feature = "async")]
+//! use dyn_fn::{LocalDynAsyncFn, dyn_async_fn, hkt, storage};
 //! use futures_util::future::join_all;
 //!
 //! # #[tokio::main(flavor = "current_thread")]
 //! # async fn main() {
+//! # #[cfg(feature = "async")] {
 //! type Callback<'a> = LocalDynAsyncFn<'a, hkt::ForFixed<Duration>, hkt::ForFixed<()>>;
 //! let mut callbacks = Vec::<Callback>::new();
-//! callbacks.push(Callback::new(async |timeout, _| {
+//! callbacks.push(Callback::new(dyn_async_fn!(async |timeout| {
 //!     tokio::time::sleep(timeout).await
-//! }));
+//! })));
 //!
 //! let timeout = Duration::from_millis(1);
 //! join_all(callbacks.iter().map(|cb| cb.call(timeout))).await;
 //! # }
+//! # }
+//! ```
+//!
+//! ### Passing dynamic functions to std APIs
+//!
+//! ```
+//! use dyn_fn::{DynFn, DynFnMut, dyn_fn, hkt};
+//!
+//! let is_even = DynFn::<hkt::ForRef<i32>, hkt::ForFixed<bool>>::new(dyn_fn!(|x| x % 2 == 0));
+//! let mut v = vec![3, 2, 1, 4];
+//! v.retain(is_even.as_fn());
+//! assert_eq!(v, [2, 4]);
+//!
+//! let mut calls = 0;
+//! let mut key = DynFnMut::<hkt::ForRef<i32>, hkt::ForFixed<i32>>::new(dyn_fn!(|x| {
+//!     calls += 1;
+//!     -x
+//! }));
+//! v.sort_by_key(key.as_fn_mut());
+//! assert_eq!(v, [4, 2]);
+//! # drop(key);
+//! # assert!(calls > 0);
 //! ```
 //!
 //! [`Box`]: storage::Box
@@ -69,15 +109,145 @@
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+mod any;
+#[cfg(all(feature = "alloc", target_has_atomic = "32"))]
+mod arc_once;
+pub mod args;
+#[cfg(feature = "async")]
 mod r#async;
+#[cfg(all(feature = "alloc", target_has_atomic = "32"))]
+mod atomic;
+mod callable;
+#[cfg(all(feature = "alloc", target_has_atomic = "32"))]
+mod cancel;
+mod closure;
+#[cfg(feature = "alloc")]
+mod compact;
+mod deferred;
+mod error;
+mod ffi;
+/// Re-export of [`higher_kinded_types`], with its `ForLt` trait replaced by [`ForLt`](trait@ForLt).
+pub mod hkt {
+    pub use higher_kinded_types::*;
+
+    pub use crate::hkt_ext::ForLt;
+}
+pub mod hkt_ext;
+mod id;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+mod instrument;
+#[cfg(target_has_atomic = "32")]
+mod isr;
 mod macros;
+mod named;
+mod padded;
+#[cfg(feature = "async")]
+mod poll_stats;
+#[cfg(target_has_atomic = "32")]
+mod primitives;
+mod priority;
+mod registry;
+#[cfg(all(feature = "std", feature = "async"))]
+mod serialized;
+#[cfg(feature = "tower")]
+mod service;
+mod signal;
+mod slab;
+mod slot;
+#[cfg(feature = "tokio")]
+mod spawn;
+#[cfg(target_has_atomic = "32")]
+mod spin;
+#[cfg(feature = "test-util")]
+mod spy;
+mod stateful;
 pub mod storage;
 mod sync;
+mod waker;
 
+#[cfg(feature = "async")]
+pub use any::AnyDynAsyncFn;
+pub use any::AnyDynFn;
+#[cfg(all(feature = "alloc", target_has_atomic = "32"))]
+pub use arc_once::ArcDynFnOnce;
+#[cfg(feature = "async")]
 pub use r#async::{
-    AsyncFnMutSend, AsyncFnOnceSend, AsyncFnSend, DynAsyncFn, DynAsyncFnMut, DynAsyncFnOnce,
-    LocalDynAsyncFn, LocalDynAsyncFnMut, LocalDynAsyncFnOnce,
+    AsyncFnMutSend, AsyncFnOnceSend, AsyncFnSend, DynAsyncFn, DynAsyncFn0, DynAsyncFnMut,
+    DynAsyncFnMut0, DynAsyncFnOnce, DynAsyncFnOnce0, LocalDynAsyncFn, LocalDynAsyncFn0,
+    LocalDynAsyncFnMut, LocalDynAsyncFnMut0, LocalDynAsyncFnOnce, LocalDynAsyncFnOnce0,
+    SendDynAsyncFn,
 };
-pub use higher_kinded_types as hkt;
-pub use sync::{DynFn, DynFnMut, DynFnOnce, LocalDynFn, LocalDynFnMut, LocalDynFnOnce};
+#[cfg(all(feature = "alloc", target_has_atomic = "32"))]
+pub use atomic::AtomicDynFn;
+#[cfg(feature = "async")]
+pub use callable::{DynAsyncCallable, DynAsyncCallableMut, DynAsyncCallableOnce};
+pub use callable::{DynCallable, DynCallableMut, DynCallableOnce};
+#[cfg(all(feature = "alloc", feature = "async", target_has_atomic = "32"))]
+pub use cancel::CancellableDynAsyncFn;
+#[cfg(all(feature = "alloc", target_has_atomic = "32"))]
+pub use cancel::{CancelToken, CancellableDynFn};
+#[doc(hidden)]
+pub use closure::__closure_layout;
+#[cfg(feature = "alloc")]
+pub use compact::{CompactDynFn, LocalCompactDynFn};
+#[cfg(feature = "async")]
+pub use deferred::DeferredDynAsyncFnMut;
+pub use deferred::DeferredDynFnMut;
+#[cfg(feature = "alloc")]
+pub use deferred::UnboundedDeferredDynFnMut;
+#[cfg(feature = "macros")]
+pub use dyn_fn_macros::callback;
+pub use error::{DynFnError, SignatureMismatch};
+pub use ffi::FfiFn;
+#[cfg(feature = "alloc")]
+pub use ffi::{AbiDynFn, FfiCallback};
+pub use hkt::ForLt;
+pub use id::CallbackId;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub use instrument::{CallStats, InstrumentedDynFn};
+#[cfg(target_has_atomic = "32")]
+pub use isr::IsrCallback;
+pub use named::FnName;
+pub use padded::{Padded, PaddedDynFn};
+#[cfg(all(feature = "async", feature = "poll-stats"))]
+pub use poll_stats::PollStats;
+pub use priority::PriorityDispatcher;
+#[cfg(feature = "alloc")]
+pub use priority::SlabPriorityDispatcher;
+#[cfg(feature = "alloc")]
+pub use registry::CallbackSlab;
+pub use registry::{CallbackHandle, CallbackKey, CallbackSet};
+#[cfg(all(feature = "std", feature = "async"))]
+pub use serialized::SerializedAsyncFnMut;
+#[cfg(feature = "tower")]
+pub use service::DynService;
+#[cfg(feature = "async")]
+pub use signal::AsyncSignal;
+#[cfg(feature = "futures")]
+pub use signal::AsyncSlabSignal;
+#[cfg(feature = "alloc")]
+pub use signal::SlabSignal;
+pub use signal::{Connection, Signal};
+#[cfg(feature = "async")]
+pub use slot::AsyncOnceSlot;
+pub use slot::{DynFnSlot, OnceSlot};
+#[cfg(target_has_atomic = "32")]
+pub use spin::SpinDynFnMut;
+#[cfg(feature = "test-util")]
+pub use spy::DynFnSpy;
+pub use stateful::{DynStatefulFn, LocalDynStatefulFn};
+#[cfg(all(feature = "std", feature = "fn_type_name"))]
+pub use sync::MetricsHook;
+pub use sync::{
+    DynFn, DynFn0, DynFnIter, DynFnMut, DynFnMut0, DynFnOnce, DynFnOnce0, FastDynFn, LocalDynFn,
+    LocalDynFn0, LocalDynFnMut, LocalDynFnMut0, LocalDynFnOnce, LocalDynFnOnce0, LocalFastDynFn,
+    SendDynFn, UnsafeDynFn,
+};
+#[cfg(feature = "alloc")]
+pub use waker::WakeFn;
+
+// declared last, so diagnostics keep using the original paths of re-exported items
+pub mod prelude;
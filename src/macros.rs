@@ -1,21 +1,68 @@
 macro_rules! new_impls {
     (sync $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($f:tt)*) => {
-        crate::macros::new_impls!(@ $name, $fn_storage $(+ $storage_send)?, {$($f)*}, new_impl, new, new_raw, new_box, new_rc, new_arc);
+        crate::macros::new_impls!(@ $name, $fn_storage $(+ $storage_send)?, {$($f)*}, new_impl, new, try_new, new_named, new_raw, new_box, new_rc, new_arc, new_const);
     };
     (async $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, [$($f_sync:tt)*], $($f:tt)*) => {
-        crate::macros::new_impls!(@ $name, $fn_storage $(+ $storage_send)?, {$($f)*}, new_impl, new, new_raw, new_box, new_rc, new_arc, FutureStorage);
-        crate::macros::new_impls!(@ $name, $fn_storage $(+ $storage_send)?, {$($f_sync)*}, new_sync_impl, new_sync, new_sync_raw, new_sync_box, new_sync_rc, new_sync_arc, FutureStorage);
+        crate::macros::new_impls!(@ $name, $fn_storage $(+ $storage_send)?, {$($f)*}, new_impl, new, try_new, new_named, new_raw, new_box, new_rc, new_arc, new_const, FutureStorage);
+        crate::macros::new_impls!(@ $name, $fn_storage $(+ $storage_send)?, {$($f_sync)*}, new_sync_impl, new_sync, try_new_sync, new_sync_named, new_sync_raw, new_sync_box, new_sync_rc, new_sync_arc, new_sync_const, FutureStorage);
     };
-    (@ $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, {$($f:tt)*}, $new_impl:ident, $new:ident, $new_box:ident, $new_raw:ident, $new_rc:ident, $new_arc:ident $(, $future_storage:ident)?) => {
+    (@ $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, {$($f:tt)*}, $new_impl:ident, $new:ident, $try_new:ident, $new_named:ident, $new_box:ident, $new_raw:ident, $new_rc:ident, $new_arc:ident, $new_const:ident $(, $future_storage:ident)?) => {
         impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?, $($future_storage: StorageMut)?>
             $name<'capture, Arg, Ret, FnStorage, $($future_storage)?>
         {
             #[doc = crate::macros::new_impls!(@ doc $name, $new_impl)]
+            #[cfg_attr(feature = "track-construction", track_caller)]
             pub fn $new<F: $($f)*>(
                 f: F,
             ) -> Self {
                 // SAFETY: storage is initialized with `F`
-                unsafe { Self::$new_impl::<F>(FnStorage::new(f)) }
+                unsafe { Self::$new_impl::<F, crate::named::Anonymous>(FnStorage::new(f)) }
+            }
+
+            #[doc = crate::macros::new_impls!(@ doc-try $name, $new_impl)]
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the function doesn't fit in the storage, or if its allocation
+            /// fails; the function is dropped in that case. Only `Box`-based storages allocate
+            /// fallibly, `Rc` and `Arc` abort on allocation failure like their `alloc` counterparts.
+            #[cfg_attr(feature = "track-construction", track_caller)]
+            pub fn $try_new<F: $($f)*>(
+                f: F,
+            ) -> Result<Self, crate::DynFnError> {
+                // SAFETY: storage is initialized with `F`
+                Ok(unsafe { Self::$new_impl::<F, crate::named::Anonymous>(FnStorage::try_new(f)?) })
+            }
+
+            #[doc = crate::macros::new_impls!(@ doc-named $name, $new_impl)]
+            ///
+            /// The name is stored in the vtable, see [`FnName`](crate::FnName).
+            #[cfg_attr(feature = "track-construction", track_caller)]
+            pub fn $new_named<N: crate::FnName, F: $($f)*>(
+                _name: N,
+                f: F,
+            ) -> Self {
+                // SAFETY: storage is initialized with `F`
+                unsafe { Self::$new_impl::<F, N>(FnStorage::new(f)) }
+            }
+        }
+
+        impl<'capture, Arg: ForLt, Ret: ForLt, const SIZE: usize, const ALIGN: usize, $($future_storage: StorageMut)?>
+            $name<'capture, Arg, Ret, crate::storage::Raw<SIZE, ALIGN>, $($future_storage)?>
+        where
+            elain::Align<ALIGN>: elain::Alignment,
+        {
+            #[doc = crate::macros::new_impls!(@ doc $name, $new_impl)]
+            ///
+            /// Contrary to [`new`](Self::new), it can be used in const contexts, e.g. to
+            /// initialize a `static`.
+            #[cfg_attr(coverage_nightly, coverage(off))] // const fn
+            #[cfg_attr(feature = "track-construction", track_caller)]
+            pub const fn $new_const<F: $($f)*>(
+                f: F,
+            ) -> Self {
+                // SAFETY: storage is initialized with `F`
+                unsafe { Self::$new_impl::<F, crate::named::Anonymous>(crate::storage::Raw::new(f)) }
             }
         }
 
@@ -28,11 +75,12 @@ macro_rules! new_impls {
             #[doc = crate::macros::new_impls!(@ doc $name, $new_impl)]
             #[cfg_attr(coverage_nightly, coverage(off))]
             #[cfg(feature = "alloc")]
+            #[cfg_attr(feature = "track-construction", track_caller)]
             pub const fn $new_raw<F: $($f)*>(
                 f: alloc::boxed::Box<F>,
             ) -> Self {
                 // SAFETY: storage is initialized with `F`
-                unsafe { Self::$new_impl::<F>(crate::storage::Raw::new(f)) }
+                unsafe { Self::$new_impl::<F, crate::named::Anonymous>(crate::storage::Raw::new(f)) }
             }
         }
 
@@ -40,11 +88,12 @@ macro_rules! new_impls {
         impl<'capture, Arg: ForLt, Ret: ForLt, $($future_storage: StorageMut)?> $name<'capture, Arg, Ret, crate::storage::Box, $($future_storage)?> {
             #[doc = crate::macros::new_impls!(@ doc $name, $new_impl "boxed")]
             #[cfg_attr(coverage_nightly, coverage(off))]
+            #[cfg_attr(feature = "track-construction", track_caller)]
             pub fn $new_box<F: $($f)*>(
                 f: alloc::boxed::Box<F>,
             ) -> Self {
                 // SAFETY: storage is initialized with `F`
-                unsafe { Self::$new_impl::<F>(crate::storage::Box::new_box(f)) }
+                unsafe { Self::$new_impl::<F, crate::named::Anonymous>(crate::storage::Box::new_box(f)) }
             }
         }
 
@@ -60,21 +109,23 @@ macro_rules! new_impls {
             #[doc = crate::macros::new_impls!(@ doc $name, $new_impl)]
             #[cfg_attr(coverage_nightly, coverage(off))]
             #[cfg(feature = "alloc")]
+            #[cfg_attr(feature = "track-construction", track_caller)]
             pub fn $new_raw<F: $($f)*>(
                 f: F,
             ) -> Self {
                 // SAFETY: storage is initialized with `F`
-                unsafe { Self::$new_impl::<F>(crate::storage::RawOrBox::new_raw(f)) }
+                unsafe { Self::$new_impl::<F, crate::named::Anonymous>(crate::storage::RawOrBox::new_raw(f)) }
             }
 
             #[doc = crate::macros::new_impls!(@ doc $name, $new_impl "boxed")]
             #[cfg_attr(coverage_nightly, coverage(off))]
             #[cfg(feature = "alloc")]
+            #[cfg_attr(feature = "track-construction", track_caller)]
             pub fn $new_box<F: $($f)*>(
                 f: alloc::boxed::Box<F>,
             ) -> Self {
                 // SAFETY: storage is initialized with `F`
-                unsafe { Self::$new_impl::<F>(crate::storage::RawOrBox::new_box(f)) }
+                unsafe { Self::$new_impl::<F, crate::named::Anonymous>(crate::storage::RawOrBox::new_box(f)) }
             }
         }
     };
@@ -83,30 +134,34 @@ macro_rules! new_impls {
         impl<'capture, Arg: ForLt, Ret: ForLt, $($future_storage: StorageMut)?> $name<'capture, Arg, Ret, crate::storage::Rc, $($future_storage)?> {
             #[doc = crate::macros::new_impls!(@ doc $name, $new_impl "reference-counted")]
             #[cfg_attr(coverage_nightly, coverage(off))]
+            #[cfg_attr(feature = "track-construction", track_caller)]
             pub fn $new_rc<F: $($f)*>(
                 f: alloc::rc::Rc<F>,
             ) -> Self {
                 // SAFETY: storage is initialized with `F`
-                unsafe { Self::$new_impl::<F>(crate::storage::Rc::new_rc(f)) }
+                unsafe { Self::$new_impl::<F, crate::named::Anonymous>(crate::storage::Rc::new_rc(f)) }
             }
         }
     };
     (@ rc $($tt:tt)*) => {};
-    (@ arc $name:ident, Storage $(+ $storage_send:ident)?, {$($f:tt)*}, $new_impl:ident, $new_arc:ident $(, $future_storage:ident)?) => {
+    (@ arc $name:ident, Storage $(+ StorageSend)?, {$($f:tt)*}, $new_impl:ident, $new_arc:ident $(, $future_storage:ident)?) => {
         #[cfg(feature = "alloc")]
         impl<'capture, Arg: ForLt, Ret: ForLt, $($future_storage: StorageMut)?> $name<'capture, Arg, Ret, crate::storage::Arc, $($future_storage)?> {
             #[doc = crate::macros::new_impls!(@ doc $name, $new_impl "reference-counted")]
             #[cfg_attr(coverage_nightly, coverage(off))]
+            #[cfg_attr(feature = "track-construction", track_caller)]
             pub fn $new_arc<F: $($f)*>(
                 f: alloc::sync::Arc<F>,
             ) -> Self {
                 // SAFETY: storage is initialized with `F`
-                unsafe { Self::$new_impl::<F>(crate::storage::Arc::new_arc(f)) }
+                unsafe { Self::$new_impl::<F, crate::named::Anonymous>(crate::storage::Arc::new_arc(f)) }
             }
         }
     };
     (@ arc $($tt:tt)*) => {};
     (@ doc $name:ident, $($suffix:tt)*) => { concat!("Construct a new [`", stringify!($name), "`]", crate::macros::new_impls!(@ doc-suffix $($suffix)*)) };
+    (@ doc-named $name:ident, $($suffix:tt)*) => { concat!("Construct a new named [`", stringify!($name), "`]", crate::macros::new_impls!(@ doc-suffix $($suffix)*)) };
+    (@ doc-try $name:ident, $($suffix:tt)*) => { concat!("Tries to construct a new [`", stringify!($name), "`]", crate::macros::new_impls!(@ doc-suffix $($suffix)*)) };
     (@ doc-suffix new_impl) => { "." };
     (@ doc-suffix new_impl $func:literal) => { concat!(" from a ", $func, " function.") };
     (@ doc-suffix new_sync_impl) => { " from as synchronous function." };
@@ -121,6 +176,49 @@ macro_rules! unsafe_impl_send_sync {
     (sync $name:ident, $fn_storage:ident) => {
         crate::macros::unsafe_impl_send_sync!(@ $name, $fn_storage);
     };
+    (sync send $name:ident, $fn_storage:ident) => {
+        // SAFETY: the object is initialized with a `Send` function, in a storage which is `Send`
+        // if the function is, and which doesn't share it; the object is not `Sync`
+        unsafe impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage + StorageSendOnly> Send
+            for $name<'capture, Arg, Ret, FnStorage>
+        {
+        }
+    };
+    (async send $name:ident, $fn_storage:ident) => {
+        // SAFETY: the object is initialized with a `Send` function, in a storage which is `Send`
+        // if the function is, and which doesn't share it, and its future cache is `Send`; the
+        // object is not `Sync`
+        unsafe impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage + StorageSendOnly, FutureStorage: StorageMut> Send
+            for $name<'capture, Arg, Ret, FnStorage, FutureStorage>
+        {
+        }
+    };
+    (sync exclusive $name:ident, $fn_storage:ident) => {
+        // SAFETY: the object is initialized with a `Send` function
+        unsafe impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage + StorageSend> Send
+            for $name<'capture, Arg, Ret, FnStorage>
+        {
+        }
+        // SAFETY: the function is only accessed through `&mut self` or `self`, shared references
+        // don't give any access to it, so it doesn't need to be `Sync`
+        unsafe impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage + StorageSend> Sync
+            for $name<'capture, Arg, Ret, FnStorage>
+        {
+        }
+    };
+    (async exclusive $name:ident, $fn_storage:ident) => {
+        // SAFETY: the object is initialized with a `Send` function, and its future cache is `Send`
+        unsafe impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage + StorageSend, FutureStorage: StorageMut> Send
+            for $name<'capture, Arg, Ret, FnStorage, FutureStorage>
+        {
+        }
+        // SAFETY: the function is only accessed through `&mut self` or `self`, so it doesn't need
+        // to be `Sync`; the future cache is already shared by `DynAsyncFn`, hence `Sync`
+        unsafe impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage + StorageSend, FutureStorage: StorageMut> Sync
+            for $name<'capture, Arg, Ret, FnStorage, FutureStorage>
+        {
+        }
+    };
     (@ $name:ident, $fn_storage:ident $(, $future_storage:ident)?) => {
         crate::macros::unsafe_impl_send_sync!(@ Send: $name, $fn_storage $(, $future_storage)?);
         crate::macros::unsafe_impl_send_sync!(@ Sync: $name, $fn_storage $(, $future_storage)?);
@@ -142,17 +240,19 @@ macro_rules! impl_clone {
     (sync $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
         crate::macros::impl_clone!(@ $name, $fn_storage $(+ $storage_send)?);
     };
-    (@ $name:ident $(.$field:tt)?, $fn_storage:ident $(+ $storage_send:ident)? $(, $future_storage:ident)?) => {
-        #[cfg(feature = "alloc")]
-        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)? + Clone, $($future_storage: StorageMut)?> Clone
-            for $name<'capture, Arg, Ret, FnStorage, $($future_storage)?>
-        {
-            crate::macros::impl_clone!(@ clone $($storage_send)?);
+    (@ clone $storage_send:ident $(, $future_storage:ident)?) => {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
         }
     };
-    (@ clone StorageSend) => {
+    (@ clone, $future_storage:ident) => {
         fn clone(&self) -> Self {
-            Self(self.0.clone())
+            Self {
+                storage: self.storage.clone(),
+                future_cache: $future_storage::EMPTY_CACHE,
+                stats: crate::poll_stats::PollCounters::new(),
+                _capture: PhantomData,
+            }
         }
     };
     (@ clone) => {
@@ -163,6 +263,14 @@ macro_rules! impl_clone {
             }
         }
     };
+    (@ $name:ident $(.$field:tt)?, $fn_storage:ident $(+ $storage_send:ident)? $(, $future_storage:ident)?) => {
+        #[cfg(feature = "alloc")]
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)? + crate::storage::StorageClone, $($future_storage: StorageMut)?> Clone
+            for $name<'capture, Arg, Ret, FnStorage, $($future_storage)?>
+        {
+            crate::macros::impl_clone!(@ clone $($storage_send)? $(, $future_storage)?);
+        }
+    };
 }
 pub(crate) use impl_clone;
 
@@ -179,10 +287,1099 @@ macro_rules! impl_debug {
         {
             #[cfg_attr(coverage_nightly, coverage(off))]
             fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-                f.debug_struct(stringify!($name)).finish()
+                let mut debug = f.debug_struct(stringify!($name));
+                crate::macros::impl_debug!(@ storage self $($storage_send)?).debug_fields(&mut debug);
+                crate::macros::impl_debug!(@ is_sync self, debug $(, $future_storage)?);
+                debug.finish()
             }
         }
 
+        #[cfg(feature = "defmt")]
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?, $($future_storage: StorageMut)?> defmt::Format
+            for $name<'capture, Arg, Ret, FnStorage, $($future_storage)?>
+        {
+            #[cfg_attr(coverage_nightly, coverage(off))]
+            fn format(&self, f: defmt::Formatter<'_>) {
+                let name = crate::macros::impl_debug!(@ storage self $($storage_send)?).name();
+                let storage = crate::macros::impl_debug!(@ storage self $($storage_send)?).defmt_kind();
+                crate::macros::impl_debug!(@ defmt self, f, stringify!($name), name, storage $(, $future_storage)?);
+            }
+        }
+    };
+    (@ storage $self:ident $storage_send:ident) => { $self.0.storage };
+    (@ storage $self:ident) => { $self.storage };
+    (@ is_sync $self:ident, $debug:ident, $future_storage:ident) => {
+        $debug.field("is_sync", &$self.is_sync());
+    };
+    (@ is_sync $self:ident, $debug:ident) => {};
+    (@ defmt $self:ident, $f:ident, $name:expr, $fn_name:ident, $storage:ident, $future_storage:ident) => {
+        defmt::write!($f, "{=str} {{ name: {}, storage: {=istr}, is_sync: {=bool} }}", $name, $fn_name, $storage, $self.is_sync())
+    };
+    (@ defmt $self:ident, $f:ident, $name:expr, $fn_name:ident, $storage:ident) => {
+        defmt::write!($f, "{=str} {{ name: {}, storage: {=istr} }}", $name, $fn_name, $storage)
     };
 }
 pub(crate) use impl_debug;
+
+#[cfg(feature = "fn_type_name")]
+macro_rules! impl_type_name {
+    (async $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_type_name!(@ $name, $fn_storage $(+ $storage_send)?, FutureStorage);
+    };
+    (sync $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_type_name!(@ $name, $fn_storage $(+ $storage_send)?);
+    };
+    (@ $name:ident, $fn_storage:ident $(+ $storage_send:ident)? $(, $future_storage:ident)?) => {
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?, $($future_storage: StorageMut)?>
+            $name<'capture, Arg, Ret, FnStorage, $($future_storage)?>
+        {
+            /// Returns the type name of the underlying function.
+            pub fn type_name(&self) -> &'static str {
+                crate::macros::impl_type_name!(@ type_name self $($storage_send)?)
+            }
+        }
+    };
+    (@ type_name $self:ident $storage_send:ident) => { $self.0.type_name() };
+    (@ type_name $self:ident) => { $self.storage.type_name() };
+}
+#[cfg(feature = "fn_type_name")]
+pub(crate) use impl_type_name;
+
+macro_rules! impl_id {
+    (async $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_id!(@ $name, $fn_storage $(+ $storage_send)?, FutureStorage);
+    };
+    (sync $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_id!(@ $name, $fn_storage $(+ $storage_send)?);
+    };
+    (@ $name:ident, $fn_storage:ident $(+ $storage_send:ident)? $(, $future_storage:ident)?) => {
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?, $($future_storage: StorageMut)?>
+            $name<'capture, Arg, Ret, FnStorage, $($future_storage)?>
+        {
+            /// Returns the identity of the underlying function.
+            ///
+            /// See [`CallbackId`](crate::CallbackId) for the stability guarantees.
+            pub fn id(&self) -> crate::CallbackId {
+                crate::macros::impl_id!(@ storage self $($storage_send)?).id()
+            }
+
+            /// Returns whether both functions dispatch to the same underlying function.
+            ///
+            /// Functions must have the same type, i.e. be created from the same closure, and,
+            /// for storages allocating the function ([`Box`], [`Rc`], [`Arc`], and
+            /// [`RawOrBox`] when boxed), share the same allocation, so clones of [`Rc`]/[`Arc`]
+            /// functions compare equal. For [`Raw`] storage, and [`RawOrBox`] when inline,
+            /// only the function type is compared.
+            ///
+            /// As function types are compared using vtable pointers, identical functions may
+            /// compare equal if the compiler merges their vtables, and functions created from the
+            /// same closure may compare unequal if instantiated in different codegen units.
+            ///
+            /// [`Box`]: crate::storage::Box
+            /// [`Rc`]: crate::storage::Rc
+            /// [`Arc`]: crate::storage::Arc
+            /// [`Raw`]: crate::storage::Raw
+            /// [`RawOrBox`]: crate::storage::RawOrBox
+            pub fn same_callee(&self, other: &Self) -> bool {
+                crate::macros::impl_id!(@ storage self $($storage_send)?)
+                    .same_callee(&crate::macros::impl_id!(@ storage other $($storage_send)?))
+            }
+
+            /// Returns where the function has been constructed, with `track-construction`
+            /// feature; it returns `None` without the feature.
+            ///
+            /// Constructors are `#[track_caller]` with the feature, so the location is the one of
+            /// the constructor call, and is kept by storage conversions.
+            pub fn constructed_at(&self) -> Option<&'static core::panic::Location<'static>> {
+                crate::macros::impl_id!(@ storage self $($storage_send)?).constructed_at()
+            }
+        }
+
+        /// Formats the data and vtable addresses, as `data@0x... vtable@0x...`.
+        ///
+        /// The data address is the allocation one for allocating storages, and the address of
+        /// the function handle itself for [`Raw`](crate::storage::Raw) storage.
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?, $($future_storage: StorageMut)?> core::fmt::Pointer
+            for $name<'capture, Arg, Ret, FnStorage, $($future_storage)?>
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                crate::macros::impl_id!(@ storage self $($storage_send)?).fmt_pointer(f)
+            }
+        }
+    };
+    (@ storage $self:ident $storage_send:ident) => { $self.0.storage };
+    (@ storage $self:ident) => { $self.storage };
+}
+pub(crate) use impl_id;
+
+#[cfg(feature = "poll-stats")]
+macro_rules! impl_poll_stats {
+    ($name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?, FutureStorage: StorageMut>
+            $name<'capture, Arg, Ret, FnStorage, FutureStorage>
+        {
+            /// Returns the polling statistics of the futures returned by the function.
+            ///
+            /// See [`PollStats`](crate::PollStats).
+            pub fn poll_stats(&self) -> crate::PollStats {
+                crate::macros::impl_poll_stats!(@ stats self $($storage_send)?).stats()
+            }
+        }
+    };
+    (@ stats $self:ident $storage_send:ident) => { $self.0.stats };
+    (@ stats $self:ident) => { $self.stats };
+}
+#[cfg(feature = "poll-stats")]
+pub(crate) use impl_poll_stats;
+
+macro_rules! impl_multi_args {
+    (sync $kind:ident $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($bounds:tt)*) => {
+        crate::macros::impl_multi_args!(@ $name, {$fn_storage $(+ $storage_send)?}, ForArgs2 [a1: A1, a2: A2], {
+            crate::macros::impl_multi_args!(@ new $kind new2 => new, [a1: A1, a2: A2], $($bounds)*);
+            crate::macros::impl_multi_args!(@ call $kind call2, [a1: A1, a2: A2]);
+        });
+        crate::macros::impl_multi_args!(@ $name, {$fn_storage $(+ $storage_send)?}, ForArgs3 [a1: A1, a2: A2, a3: A3], {
+            crate::macros::impl_multi_args!(@ new $kind new3 => new, [a1: A1, a2: A2, a3: A3], $($bounds)*);
+            crate::macros::impl_multi_args!(@ call $kind call3, [a1: A1, a2: A2, a3: A3]);
+        });
+    };
+    (async $kind:ident $([$async_kind:ident])? $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($bounds:tt)*) => {
+        crate::macros::impl_multi_args!(@ $name, {$fn_storage $(+ $storage_send)?}, ForArgs2 [a1: A1, a2: A2], {
+            crate::macros::impl_multi_args!(@ new [$($async_kind)?] new2 => new, [a1: A1, a2: A2], $($bounds)*);
+            crate::macros::impl_multi_args!(@ new $kind new_sync2 => new_sync, [a1: A1, a2: A2], $($bounds)*);
+            crate::macros::impl_multi_args!(@ call async $kind call2, call_sync2, call_try_sync2, [a1: A1, a2: A2]);
+        }, FutureStorage);
+        crate::macros::impl_multi_args!(@ $name, {$fn_storage $(+ $storage_send)?}, ForArgs3 [a1: A1, a2: A2, a3: A3], {
+            crate::macros::impl_multi_args!(@ new [$($async_kind)?] new3 => new, [a1: A1, a2: A2, a3: A3], $($bounds)*);
+            crate::macros::impl_multi_args!(@ new $kind new_sync3 => new_sync, [a1: A1, a2: A2, a3: A3], $($bounds)*);
+            crate::macros::impl_multi_args!(@ call async $kind call3, call_sync3, call_try_sync3, [a1: A1, a2: A2, a3: A3]);
+        }, FutureStorage);
+    };
+    (@ $name:ident, {$($storage:tt)*}, $for_args:ident [$($arg:ident: $A:ident),*], {$($items:tt)*} $(, $future_storage:ident)?) => {
+        impl<'capture, Arg: crate::args::$for_args, Ret: ForLt, FnStorage: $($storage)*, $($future_storage: StorageMut)?>
+            $name<'capture, Arg, Ret, FnStorage, $($future_storage)?>
+        {
+            $($items)*
+        }
+    };
+    (@ new [$kind:ident] $($tt:tt)*) => {
+        crate::macros::impl_multi_args!(@ new $kind $($tt)*);
+    };
+    (@ new [] $($tt:tt)*) => {};
+    (@ new $kind:ident $new:ident => $ctor:ident, [$($arg:ident: $A:ident),*], $($bounds:tt)*) => {
+        #[doc = crate::macros::impl_multi_args!(@ doc-new $new)]
+        pub fn $new<F: for<'a> $kind($(Arg::$A<'a>,)* PhantomData<&'a ()>) -> Ret::Of<'a> + $($bounds)*>(
+            #[allow(unused_mut)] mut f: F,
+        ) -> Self {
+            crate::macros::impl_multi_args!(@ new-body $kind $ctor, f, [$($arg),*])
+        }
+    };
+    (@ new-body AsyncFn $ctor:ident, $f:ident, [$($arg:ident),*]) => {
+        Self::$ctor(async move |args, _| {
+            let ($($arg,)*) = Arg::into_args(args);
+            $f($($arg,)* PhantomData).await
+        })
+    };
+    (@ new-body AsyncFnMut $ctor:ident, $f:ident, [$($arg:ident),*]) => {
+        crate::macros::impl_multi_args!(@ new-body AsyncFn $ctor, $f, [$($arg),*])
+    };
+    (@ new-body AsyncFnOnce $ctor:ident, $f:ident, [$($arg:ident),*]) => {
+        crate::macros::impl_multi_args!(@ new-body AsyncFn $ctor, $f, [$($arg),*])
+    };
+    (@ new-body $kind:ident $ctor:ident, $f:ident, [$($arg:ident),*]) => {
+        Self::$ctor(move |args, _| {
+            let ($($arg,)*) = Arg::into_args(args);
+            $f($($arg,)* PhantomData)
+        })
+    };
+    (@ doc-new new2) => { "Construct a new function from a function taking two arguments." };
+    (@ doc-new new3) => { "Construct a new function from a function taking three arguments." };
+    (@ doc-new new_sync2) => { "Construct a new function from a synchronous function taking two arguments." };
+    (@ doc-new new_sync3) => { "Construct a new function from a synchronous function taking three arguments." };
+    (@ call Fn $call:ident, [$($arg:ident: $A:ident),*]) => {
+        /// Calls the underlying function with multiple arguments.
+        pub fn $call<'a>(&self, $($arg: Arg::$A<'a>),*) -> Ret::Of<'a> {
+            self.call(Arg::from_args($($arg),*))
+        }
+    };
+    (@ call FnMut $call:ident, [$($arg:ident: $A:ident),*]) => {
+        /// Calls the underlying function with multiple arguments.
+        pub fn $call<'a>(&mut self, $($arg: Arg::$A<'a>),*) -> Ret::Of<'a> {
+            self.call(Arg::from_args($($arg),*))
+        }
+    };
+    (@ call FnOnce $call:ident, [$($arg:ident: $A:ident),*]) => {
+        /// Calls the underlying function with multiple arguments.
+        pub fn $call<'a>(self, $($arg: Arg::$A<'a>),*) -> Ret::Of<'a> {
+            self.call(Arg::from_args($($arg),*))
+        }
+    };
+    (@ call async Fn $call:ident, $call_sync:ident, $call_try_sync:ident, [$($arg:ident: $A:ident),*]) => {
+        /// Calls the underlying function with multiple arguments.
+        pub async fn $call<'a>(&self, $($arg: Arg::$A<'a>),*) -> Ret::Of<'a> {
+            self.call(Arg::from_args($($arg),*)).await
+        }
+
+        /// Calls the underlying function with multiple arguments if is synchronous.
+        pub fn $call_sync<'a>(&self, $($arg: Arg::$A<'a>),*) -> Option<Ret::Of<'a>> {
+            self.call_sync(Arg::from_args($($arg),*))
+        }
+
+        /// Tries calling the underlying function with multiple arguments as synchronous, falling
+        /// back to asynchronous call.
+        pub async fn $call_try_sync<'a>(&self, $($arg: Arg::$A<'a>),*) -> Ret::Of<'a> {
+            self.call_try_sync(Arg::from_args($($arg),*)).await
+        }
+    };
+    (@ call async FnMut $call:ident, $call_sync:ident, $call_try_sync:ident, [$($arg:ident: $A:ident),*]) => {
+        /// Calls the underlying function with multiple arguments.
+        pub async fn $call<'a>(&mut self, $($arg: Arg::$A<'a>),*) -> Ret::Of<'a> {
+            self.call(Arg::from_args($($arg),*)).await
+        }
+
+        /// Calls the underlying function with multiple arguments if is synchronous.
+        pub fn $call_sync<'a>(&mut self, $($arg: Arg::$A<'a>),*) -> Option<Ret::Of<'a>> {
+            self.call_sync(Arg::from_args($($arg),*))
+        }
+
+        /// Tries calling the underlying function with multiple arguments as synchronous, falling
+        /// back to asynchronous call.
+        pub async fn $call_try_sync<'a>(&mut self, $($arg: Arg::$A<'a>),*) -> Ret::Of<'a> {
+            self.call_try_sync(Arg::from_args($($arg),*)).await
+        }
+    };
+    (@ call async FnOnce $call:ident, $call_sync:ident, $call_try_sync:ident, [$($arg:ident: $A:ident),*]) => {
+        /// Calls the underlying function with multiple arguments.
+        pub async fn $call<'a>(self, $($arg: Arg::$A<'a>),*) -> Ret::Of<'a> {
+            self.call(Arg::from_args($($arg),*)).await
+        }
+
+        /// Calls the underlying function with multiple arguments if is synchronous.
+        pub fn $call_sync<'a>(self, $($arg: Arg::$A<'a>),*) -> Option<Ret::Of<'a>> {
+            self.call_sync(Arg::from_args($($arg),*))
+        }
+
+        /// Tries calling the underlying function with multiple arguments as synchronous, falling
+        /// back to asynchronous call.
+        pub async fn $call_try_sync<'a>(self, $($arg: Arg::$A<'a>),*) -> Ret::Of<'a> {
+            self.call_try_sync(Arg::from_args($($arg),*)).await
+        }
+    };
+}
+pub(crate) use impl_multi_args;
+
+macro_rules! impl_nullary {
+    (sync $kind:ident $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($bounds:tt)*) => {
+        impl<'capture, R: 'static, FnStorage: $fn_storage $(+ $storage_send)?> $name<'capture, ForFixed<()>, ForFixed<R>, FnStorage> {
+            crate::macros::impl_nullary!(@ new $kind new0 => new, $($bounds)*);
+            crate::macros::impl_nullary!(@ call $kind);
+        }
+    };
+    (async $kind:ident $([$async_kind:ident])? $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($bounds:tt)*) => {
+        impl<'capture, R: 'static, FnStorage: $fn_storage $(+ $storage_send)?, FutureStorage: StorageMut> $name<'capture, ForFixed<()>, ForFixed<R>, FnStorage, FutureStorage> {
+            crate::macros::impl_nullary!(@ new [$($async_kind)?] new0 => new, $($bounds)*);
+            crate::macros::impl_nullary!(@ new $kind new_sync0 => new_sync, $($bounds)*);
+            crate::macros::impl_nullary!(@ call async $kind);
+        }
+    };
+    (@ new [$kind:ident] $($tt:tt)*) => {
+        crate::macros::impl_nullary!(@ new $kind $($tt)*);
+    };
+    (@ new [] $($tt:tt)*) => {};
+    (@ new $kind:ident $new:ident => $ctor:ident, $($bounds:tt)*) => {
+        #[doc = crate::macros::impl_nullary!(@ doc-new $new)]
+        pub fn $new<F: $kind() -> R + $($bounds)*>(#[allow(unused_mut)] mut f: F) -> Self {
+            crate::macros::impl_nullary!(@ new-body $kind $ctor, f)
+        }
+    };
+    (@ new-body AsyncFn $ctor:ident, $f:ident) => { Self::$ctor(async move |(), _| $f().await) };
+    (@ new-body AsyncFnMut $ctor:ident, $f:ident) => { Self::$ctor(async move |(), _| $f().await) };
+    (@ new-body AsyncFnOnce $ctor:ident, $f:ident) => { Self::$ctor(async move |(), _| $f().await) };
+    (@ new-body $kind:ident $ctor:ident, $f:ident) => { Self::$ctor(move |(), _| $f()) };
+    (@ doc-new new0) => { "Construct a new function from a function taking no argument." };
+    (@ doc-new new_sync0) => { "Construct a new function from a synchronous function taking no argument." };
+    (@ call Fn) => {
+        /// Calls the underlying function without argument.
+        #[inline]
+        pub fn call0(&self) -> R {
+            self.call(())
+        }
+    };
+    (@ call FnMut) => {
+        /// Calls the underlying function without argument.
+        #[inline]
+        pub fn call0(&mut self) -> R {
+            self.call(())
+        }
+    };
+    (@ call FnOnce) => {
+        /// Calls the underlying function without argument.
+        #[inline]
+        pub fn call0(self) -> R {
+            self.call(())
+        }
+    };
+    (@ call async Fn) => {
+        /// Calls the underlying function without argument.
+        pub async fn call0(&self) -> R {
+            self.call(()).await
+        }
+
+        /// Calls the underlying function without argument if is synchronous.
+        #[inline]
+        pub fn call_sync0(&self) -> Option<R> {
+            self.call_sync(())
+        }
+
+        /// Tries calling the underlying function without argument as synchronous, falling back
+        /// to asynchronous call.
+        pub async fn call_try_sync0(&self) -> R {
+            self.call_try_sync(()).await
+        }
+    };
+    (@ call async FnMut) => {
+        /// Calls the underlying function without argument.
+        pub async fn call0(&mut self) -> R {
+            self.call(()).await
+        }
+
+        /// Calls the underlying function without argument if is synchronous.
+        #[inline]
+        pub fn call_sync0(&mut self) -> Option<R> {
+            self.call_sync(())
+        }
+
+        /// Tries calling the underlying function without argument as synchronous, falling back
+        /// to asynchronous call.
+        pub async fn call_try_sync0(&mut self) -> R {
+            self.call_try_sync(()).await
+        }
+    };
+    (@ call async FnOnce) => {
+        /// Calls the underlying function without argument.
+        pub async fn call0(self) -> R {
+            self.call(()).await
+        }
+
+        /// Calls the underlying function without argument if is synchronous.
+        #[inline]
+        pub fn call_sync0(self) -> Option<R> {
+            self.call_sync(())
+        }
+
+        /// Tries calling the underlying function without argument as synchronous, falling back
+        /// to asynchronous call.
+        pub async fn call_try_sync0(self) -> R {
+            self.call_try_sync(()).await
+        }
+    };
+}
+pub(crate) use impl_nullary;
+
+macro_rules! impl_diverging {
+    ($kind:ident $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($bounds:tt)*) => {
+        impl<'capture, Arg: ForLt, FnStorage: $fn_storage $(+ $storage_send)?> $name<'capture, Arg, ForFixed<core::convert::Infallible>, FnStorage> {
+            /// Construct a new function from a diverging function, i.e. returning `!`, e.g. a fault
+            /// handler.
+            ///
+            /// As the function never returns, its result is
+            /// [`Infallible`](core::convert::Infallible).
+            pub fn new_diverging(#[allow(unused_mut)] mut f: impl for<'a> $kind(Arg::Of<'a>) -> Never + $($bounds)*) -> Self {
+                Self::new(move |arg, _| f(arg))
+            }
+
+            crate::macros::impl_diverging!(@ call $kind);
+        }
+    };
+    (@ call Fn) => {
+        /// Calls the underlying function, which never returns.
+        #[inline]
+        pub fn call_never(&self, arg: Arg::Of<'_>) -> ! {
+            match self.call(arg) {}
+        }
+    };
+    (@ call FnMut) => {
+        /// Calls the underlying function, which never returns.
+        #[inline]
+        pub fn call_never(&mut self, arg: Arg::Of<'_>) -> ! {
+            match self.call(arg) {}
+        }
+    };
+    (@ call FnOnce) => {
+        /// Calls the underlying function, which never returns.
+        #[inline]
+        pub fn call_never(self, arg: Arg::Of<'_>) -> ! {
+            match self.call(arg) {}
+        }
+    };
+}
+pub(crate) use impl_diverging;
+
+#[cfg(feature = "std")]
+macro_rules! impl_catch_unwind {
+    ($kind:ident $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?>
+            $name<'capture, Arg, Ret, FnStorage>
+        {
+            crate::macros::impl_catch_unwind!(@ $kind);
+        }
+    };
+    (@ Fn) => {
+        /// Calls the underlying function, catching the panic if it panics.
+        ///
+        /// The function is considered unwind safe, as if wrapped in
+        /// [`AssertUnwindSafe`](core::panic::AssertUnwindSafe): state shared with
+        /// the function may be left inconsistent after a panic.
+        pub fn call_catch_unwind<'a>(
+            &self,
+            arg: Arg::Of<'a>,
+        ) -> Result<Ret::Of<'a>, std::boxed::Box<dyn core::any::Any + Send>> {
+            std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| self.call(arg)))
+        }
+    };
+    (@ FnMut) => {
+        /// Calls the underlying function, catching the panic if it panics.
+        ///
+        /// The function is considered unwind safe, as if wrapped in
+        /// [`AssertUnwindSafe`](core::panic::AssertUnwindSafe): its captured state,
+        /// as well as state shared with it, may be left inconsistent after a panic.
+        pub fn call_catch_unwind<'a>(
+            &mut self,
+            arg: Arg::Of<'a>,
+        ) -> Result<Ret::Of<'a>, std::boxed::Box<dyn core::any::Any + Send>> {
+            std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| self.call(arg)))
+        }
+    };
+    (@ FnOnce) => {
+        /// Calls the underlying function, catching the panic if it panics.
+        ///
+        /// The function is considered unwind safe, as if wrapped in
+        /// [`AssertUnwindSafe`](core::panic::AssertUnwindSafe): state shared with
+        /// the function may be left inconsistent after a panic.
+        /// The function and its storage are dropped exactly once, even if it panics.
+        pub fn call_catch_unwind<'a>(
+            self,
+            arg: Arg::Of<'a>,
+        ) -> Result<Ret::Of<'a>, std::boxed::Box<dyn core::any::Any + Send>> {
+            std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| self.call(arg)))
+        }
+    };
+}
+#[cfg(feature = "std")]
+pub(crate) use impl_catch_unwind;
+
+macro_rules! impl_with_state {
+    (sync $kind:ident $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($bounds:tt)*) => {
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?> $name<'capture, Arg, Ret, FnStorage> {
+            crate::macros::impl_with_state!(@ $kind new, $($bounds)*);
+        }
+    };
+    (async $kind:ident $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($bounds:tt)*) => {
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?, FutureStorage: StorageMut> $name<'capture, Arg, Ret, FnStorage, FutureStorage> {
+            crate::macros::impl_with_state!(@ $kind new_sync, $($bounds)*);
+        }
+    };
+    (@ Fn $ctor:ident, $($bounds:tt)*) => {
+        /// Construct a new function from a state and a function taking it by reference.
+        ///
+        /// The function must be zero-sized, i.e. a function item or a non-capturing closure,
+        /// so it is called by the vtable, and only the state is stored.
+        pub fn with_state<S: $($bounds)*, F: for<'a> Fn(&S, Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + $($bounds)*>(state: S, f: F) -> Self {
+            const { assert!(size_of::<F>() == 0, "function must be zero-sized") };
+            Self::$ctor(move |arg, lt| f(&state, arg, lt))
+        }
+    };
+    (@ FnMut $ctor:ident, $($bounds:tt)*) => {
+        /// Construct a new function from a state and a function taking it by mutable reference.
+        ///
+        /// The function must be zero-sized, i.e. a function item or a non-capturing closure,
+        /// so it is called by the vtable, and only the state is stored.
+        pub fn with_state_mut<S: $($bounds)*, F: for<'a> Fn(&mut S, Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + $($bounds)*>(mut state: S, f: F) -> Self {
+            const { assert!(size_of::<F>() == 0, "function must be zero-sized") };
+            Self::$ctor(move |arg, lt| f(&mut state, arg, lt))
+        }
+    };
+    (@ FnOnce $ctor:ident, $($bounds:tt)*) => {
+        /// Construct a new function from a state and a function taking it by value.
+        ///
+        /// The function must be zero-sized, i.e. a function item or a non-capturing closure,
+        /// so it is called by the vtable, and only the state is stored.
+        pub fn with_state_once<S: $($bounds)*, F: for<'a> Fn(S, Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + $($bounds)*>(state: S, f: F) -> Self {
+            const { assert!(size_of::<F>() == 0, "function must be zero-sized") };
+            Self::$ctor(move |arg, lt| f(state, arg, lt))
+        }
+    };
+}
+pub(crate) use impl_with_state;
+
+macro_rules! impl_result {
+    ($kind:ident $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($bounds:tt)*) => {
+        impl<'capture, Arg: ForLt, T: 'static, E: 'static, FnStorage: $fn_storage $(+ $storage_send)?>
+            $name<'capture, Arg, ForFixed<Result<T, E>>, FnStorage>
+        {
+            /// Maps the error returned by the function.
+            pub fn map_err<E2: 'static>(
+                self,
+                #[allow(unused_mut)] mut f: impl $kind(E) -> E2 + $($bounds)*,
+            ) -> $name<'capture, Arg, ForFixed<Result<T, E2>>, FnStorage> {
+                #[allow(unused_mut)]
+                let mut this = self;
+                $name::new(move |arg, _| this.call(arg).map_err(crate::macros::impl_result!(@ ref $kind f)))
+            }
+
+            /// Calls the fallback function with the same argument if the function returns an
+            /// error.
+            ///
+            /// The argument is cloned before calling the function, so it can be passed
+            /// to the fallback.
+            pub fn or_else<E2: 'static>(
+                self,
+                #[allow(unused_mut)] mut fallback: $name<'capture, Arg, ForFixed<Result<T, E2>>, FnStorage>,
+            ) -> $name<'capture, Arg, ForFixed<Result<T, E2>>, FnStorage>
+            where
+                Arg: for<'a> ForLt<Of<'a>: Clone>,
+            {
+                #[allow(unused_mut)]
+                let mut this = self;
+                $name::new(move |arg: Arg::Of<'_>, _| match this.call(arg.clone()) {
+                    Ok(ok) => Ok(ok),
+                    Err(_) => fallback.call(arg),
+                })
+            }
+
+            crate::macros::impl_result!(@ unwrap_or $kind $name, $($bounds)*);
+        }
+    };
+    (@ ref Fn $f:ident) => { &$f };
+    (@ ref FnMut $f:ident) => { &mut $f };
+    (@ ref FnOnce $f:ident) => { $f };
+    (@ unwrap_or FnOnce $name:ident, $($bounds:tt)*) => {
+        /// Returns the value returned by the function, or `default` if it returns an error.
+        pub fn unwrap_or(
+            self,
+            default: T,
+        ) -> $name<'capture, Arg, ForFixed<T>, FnStorage>
+        where
+            T: $($bounds)*,
+        {
+            $name::new(move |arg, _| self.call(arg).unwrap_or(default))
+        }
+    };
+    (@ unwrap_or $kind:ident $name:ident, $($bounds:tt)*) => {
+        /// Returns the value returned by the function, or a clone of `default` if it returns an
+        /// error.
+        pub fn unwrap_or(
+            self,
+            default: T,
+        ) -> $name<'capture, Arg, ForFixed<T>, FnStorage>
+        where
+            T: Clone + $($bounds)*,
+        {
+            #[allow(unused_mut)]
+            let mut this = self;
+            $name::new(move |arg, _| this.call(arg).unwrap_or_else(|_| default.clone()))
+        }
+    };
+}
+pub(crate) use impl_result;
+
+#[cfg(feature = "alloc")]
+macro_rules! impl_from_box {
+    ($kind:ident $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($bounds:tt)*) => {
+        /// The boxed function is moved as is into the storage, adding an indirection;
+        /// it fits without allocation in [`Raw`](crate::storage::Raw) storages of two pointers.
+        impl<'capture, A: 'static, R: 'static, FnStorage: $fn_storage $(+ $storage_send)?>
+            From<alloc::boxed::Box<dyn $kind(A) -> R + $($bounds)*>> for $name<'capture, ForFixed<A>, ForFixed<R>, FnStorage>
+        {
+            fn from(#[allow(unused_mut)] mut f: alloc::boxed::Box<dyn $kind(A) -> R + $($bounds)*>) -> Self {
+                Self::new(move |arg, _| f(arg))
+            }
+        }
+
+        impl<'capture, T: ?Sized + 'static, R: 'static, FnStorage: $fn_storage $(+ $storage_send)?>
+            $name<'capture, ForRef<T>, ForFixed<R>, FnStorage>
+        {
+            #[doc = concat!("Constructs a new [`", stringify!($name), "`] from a boxed function taking a reference.")]
+            ///
+            /// This is the `From` conversion for boxed functions taking a reference, which cannot
+            /// be a `From` implementation, as it would conflict with the one for `ForFixed`.
+            /// The boxed function is moved as is into the storage, adding an indirection.
+            pub fn from_ref_box(#[allow(unused_mut)] mut f: alloc::boxed::Box<dyn $kind(&T) -> R + $($bounds)*>) -> Self {
+                Self::new(move |arg: &T, _| f(arg))
+            }
+        }
+    };
+}
+#[cfg(feature = "alloc")]
+pub(crate) use impl_from_box;
+
+macro_rules! impl_constant {
+    (sync $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($bounds:tt)*) => {
+        impl<'capture, Arg: ForLt, T: Clone + $($bounds)*, FnStorage: $fn_storage $(+ $storage_send)?> $name<'capture, Arg, ForFixed<T>, FnStorage> {
+            /// Construct a new function ignoring its argument and returning clones of `value`.
+            pub fn constant(value: T) -> Self {
+                Self::new(move |_, _| value.clone())
+            }
+        }
+    };
+    (async $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($bounds:tt)*) => {
+        impl<'capture, Arg: ForLt, T: Clone + $($bounds)*, FnStorage: $fn_storage $(+ $storage_send)?, FutureStorage: StorageMut> $name<'capture, Arg, ForFixed<T>, FnStorage, FutureStorage> {
+            /// Construct a new synchronous function ignoring its argument and returning clones
+            /// of `value`.
+            pub fn ready(value: T) -> Self {
+                Self::new_sync(move |_, _| value.clone())
+            }
+        }
+    };
+}
+pub(crate) use impl_constant;
+
+macro_rules! impl_resize {
+    (async $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_resize!(@ $name $(+ $storage_send)?, FutureStorage);
+    };
+    (sync $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_resize!(@ $name $(+ $storage_send)?);
+    };
+    (@ $name:ident $(+ $storage_send:ident)? $(, $future_storage:ident)?) => {
+        impl<'capture, Arg: ForLt, Ret: ForLt, const SIZE: usize, const ALIGN: usize, $($future_storage: StorageMut)?>
+            $name<'capture, Arg, Ret, crate::storage::Raw<SIZE, ALIGN>, $($future_storage)?>
+        where
+            elain::Align<ALIGN>: elain::Alignment,
+        {
+            /// Moves the underlying function into a [`Raw`](crate::storage::Raw) storage of
+            /// another size.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the function size is greater than `M`.
+            #[track_caller]
+            pub fn resize<const M: usize>(
+                self,
+            ) -> $name<'capture, Arg, Ret, crate::storage::Raw<M, ALIGN>, $($future_storage)?> {
+                crate::macros::impl_resize!(@ resize $name, self $($storage_send)? $(, $future_storage)?)
+            }
+        }
+    };
+    (@ resize $name:ident, $self:ident $storage_send:ident $(, $future_storage:ident)?) => { $name($self.0.resize()) };
+    (@ resize $name:ident, $self:ident, $future_storage:ident) => {
+        $name {
+            storage: $self.storage.resize(),
+            future_cache: $self.future_cache,
+            stats: $self.stats,
+            _capture: PhantomData,
+        }
+    };
+    (@ resize $name:ident, $self:ident) => {
+        $name {
+            storage: $self.storage.resize(),
+            _capture: PhantomData,
+        }
+    };
+}
+pub(crate) use impl_resize;
+
+macro_rules! impl_into_storage {
+    (async $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_into_storage!(@ $name, $fn_storage $(+ $storage_send)?, FutureStorage);
+    };
+    (sync $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_into_storage!(@ $name, $fn_storage $(+ $storage_send)?);
+    };
+    (@ $name:ident, $fn_storage:ident $(+ $storage_send:ident)? $(, $future_storage:ident)?) => {
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?, $($future_storage: StorageMut)?>
+            $name<'capture, Arg, Ret, FnStorage, $($future_storage)?>
+        {
+            /// Moves the underlying function into another storage, converted with [`From`].
+            pub fn into_storage<S2: $fn_storage $(+ $storage_send)? + From<FnStorage>>(
+                self,
+            ) -> $name<'capture, Arg, Ret, S2, $($future_storage)?> {
+                crate::macros::impl_into_storage!(@ into_storage $name, self $($storage_send)? $(, $future_storage)?)
+            }
+        }
+    };
+    (@ into_storage $name:ident, $self:ident $storage_send:ident $(, $future_storage:ident)?) => { $name($self.0.into_storage()) };
+    (@ into_storage $name:ident, $self:ident, $future_storage:ident) => {
+        $name {
+            storage: $self.storage.into_storage(),
+            future_cache: $self.future_cache,
+            stats: $self.stats,
+            _capture: PhantomData,
+        }
+    };
+    (@ into_storage $name:ident, $self:ident) => {
+        $name {
+            storage: $self.storage.into_storage(),
+            _capture: PhantomData,
+        }
+    };
+}
+pub(crate) use impl_into_storage;
+
+#[cfg(feature = "alloc")]
+macro_rules! impl_leak {
+    (async $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_leak!(@ $name, $fn_storage $(+ $storage_send)?, FutureStorage);
+    };
+    (sync $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_leak!(@ $name, $fn_storage $(+ $storage_send)?);
+    };
+    (@ $name:ident, $fn_storage:ident $(+ $storage_send:ident)? $(, $future_storage:ident)?) => {
+        impl<Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)? + 'static, $($future_storage: StorageMut + 'static)?>
+            $name<'static, Arg, Ret, FnStorage, $($future_storage)?>
+        {
+            /// Leaks the function, returning a reference valid for the rest of the program.
+            ///
+            /// As with [`Box::leak`](alloc::boxed::Box::leak), the handle itself is moved into a
+            /// leaked allocation, so neither it nor the function is ever dropped. The returned
+            /// reference can be stored where a `&'static` one is required, e.g. in a panic hook.
+            pub fn leak(self) -> &'static mut Self {
+                alloc::boxed::Box::leak(alloc::boxed::Box::new(self))
+            }
+        }
+    };
+}
+#[cfg(feature = "alloc")]
+pub(crate) use impl_leak;
+
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+macro_rules! impl_instrumented {
+    (async $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_instrumented!(@ $name, $fn_storage $(+ $storage_send)?, FutureStorage);
+    };
+    (sync $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_instrumented!(@ $name, $fn_storage $(+ $storage_send)?);
+    };
+    (@ $name:ident, $fn_storage:ident $(+ $storage_send:ident)? $(, $future_storage:ident)?) => {
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?, $($future_storage: StorageMut)?>
+            $name<'capture, Arg, Ret, FnStorage, $($future_storage)?>
+        {
+            /// Wraps the function into an [`InstrumentedDynFn`](crate::InstrumentedDynFn),
+            /// counting its invocations.
+            pub fn instrumented(self) -> crate::InstrumentedDynFn<Self, Ret> {
+                crate::InstrumentedDynFn::new(self)
+            }
+        }
+    };
+}
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub(crate) use impl_instrumented;
+
+macro_rules! impl_named {
+    (async $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_named!(@ $name, $fn_storage $(+ $storage_send)?, FutureStorage);
+    };
+    (sync $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_named!(@ $name, $fn_storage $(+ $storage_send)?);
+    };
+    (@ $name:ident, $fn_storage:ident $(+ $storage_send:ident)? $(, $future_storage:ident)?) => {
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?, $($future_storage: StorageMut)?>
+            $name<'capture, Arg, Ret, FnStorage, $($future_storage)?>
+        {
+            /// Returns the name of the function, if it has been constructed with a
+            /// [`FnName`](crate::FnName).
+            pub fn name(&self) -> Option<&'static str> {
+                crate::macros::impl_named!(@ storage self $($storage_send)?).name()
+            }
+        }
+    };
+    (@ storage $self:ident $storage_send:ident) => { $self.0.storage };
+    (@ storage $self:ident) => { $self.storage };
+}
+pub(crate) use impl_named;
+
+macro_rules! impl_iter {
+    ($name:ident, $fn_storage:ident $(+ $storage_send:ident)?, $($bounds:tt)*) => {
+        impl<'capture, T: 'static, FnStorage: $fn_storage $(+ $storage_send)?> $name<'capture, ForFixed<()>, ForFixed<Option<T>>, FnStorage> {
+            #[doc = concat!("Construct a new [`", stringify!($name), "`] from an iterator, returning its items.")]
+            pub fn from_iter_fn(mut iter: impl Iterator<Item = T> + $($bounds)*) -> Self {
+                Self::new(move |(), _| iter.next())
+            }
+
+            /// Returns an iterator calling the underlying function until it returns `None`.
+            pub fn iter_mut(&mut self) -> DynFnIter<&mut Self> {
+                DynFnIter(Some(self))
+            }
+        }
+
+        impl<'capture, T: 'static, FnStorage: $fn_storage $(+ $storage_send)?> IntoIterator for $name<'capture, ForFixed<()>, ForFixed<Option<T>>, FnStorage> {
+            type Item = T;
+            type IntoIter = DynFnIter<Self>;
+            fn into_iter(self) -> Self::IntoIter {
+                DynFnIter(Some(self))
+            }
+        }
+
+        crate::macros::impl_iter!(@ $name, $fn_storage $(+ $storage_send)?, [], $name<'capture, ForFixed<()>, ForFixed<Option<T>>, FnStorage>);
+        crate::macros::impl_iter!(@ $name, $fn_storage $(+ $storage_send)?, ['a,], &'a mut $name<'capture, ForFixed<()>, ForFixed<Option<T>>, FnStorage>);
+    };
+    (@ $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, [$($lt:lifetime,)?], $func:ty) => {
+        impl<$($lt,)? 'capture, T: 'static, FnStorage: $fn_storage $(+ $storage_send)?> Iterator for DynFnIter<$func> {
+            type Item = T;
+            fn next(&mut self) -> Option<Self::Item> {
+                let item = self.0.as_mut()?.call(());
+                if item.is_none() {
+                    self.0 = None;
+                }
+                item
+            }
+        }
+
+        impl<$($lt,)? 'capture, T: 'static, FnStorage: $fn_storage $(+ $storage_send)?> core::iter::FusedIterator for DynFnIter<$func> {}
+    };
+}
+pub(crate) use impl_iter;
+
+macro_rules! impl_as_fn {
+    (Fn $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        impl<'capture, Arg: ForLt, R: 'static, FnStorage: $fn_storage $(+ $storage_send)?> $name<'capture, Arg, ForFixed<R>, FnStorage> {
+            /// Returns a closure calling the underlying function, to be passed to APIs expecting
+            /// an [`Fn`].
+            pub fn as_fn(&self) -> impl for<'a> Fn(Arg::Of<'a>) -> R + '_ {
+                |arg| self.call(arg)
+            }
+        }
+    };
+    (FnMut $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        impl<'capture, Arg: ForLt, R: 'static, FnStorage: $fn_storage $(+ $storage_send)?> $name<'capture, Arg, ForFixed<R>, FnStorage> {
+            /// Returns a closure calling the underlying function, to be passed to APIs expecting
+            /// an [`FnMut`].
+            pub fn as_fn_mut(&mut self) -> impl for<'a> FnMut(Arg::Of<'a>) -> R + '_ {
+                |arg| self.call(arg)
+            }
+        }
+    };
+}
+pub(crate) use impl_as_fn;
+
+macro_rules! impl_callable {
+    (Fn $name:ident $(, $storage:ident: $bound:ident $(+ $storage_send:ident)?)?) => {
+        crate::macros::impl_callable!(@ DynCallableOnce $name $(, $storage: $bound $(+ $storage_send)?)? {
+            fn call_once<'a>(self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+                $name::call(&self, arg)
+            }
+        });
+        crate::macros::impl_callable!(@ DynCallableMut $name $(, $storage: $bound $(+ $storage_send)?)? {
+            fn call_mut<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+                $name::call(self, arg)
+            }
+        });
+        crate::macros::impl_callable!(@ DynCallable $name $(, $storage: $bound $(+ $storage_send)?)? {
+            fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+                $name::call(self, arg)
+            }
+        });
+    };
+    (FnMut $name:ident $(, $storage:ident: $bound:ident $(+ $storage_send:ident)?)?) => {
+        crate::macros::impl_callable!(@ DynCallableOnce $name $(, $storage: $bound $(+ $storage_send)?)? {
+            fn call_once<'a>(mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+                $name::call(&mut self, arg)
+            }
+        });
+        crate::macros::impl_callable!(@ DynCallableMut $name $(, $storage: $bound $(+ $storage_send)?)? {
+            fn call_mut<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+                $name::call(self, arg)
+            }
+        });
+    };
+    (FnOnce $name:ident $(, $storage:ident: $bound:ident $(+ $storage_send:ident)?)?) => {
+        crate::macros::impl_callable!(@ DynCallableOnce $name $(, $storage: $bound $(+ $storage_send)?)? {
+            fn call_once<'a>(self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+                $name::call(self, arg)
+            }
+        });
+    };
+    (@ $trait:ident $name:ident $(, $storage:ident: $bound:ident $(+ $storage_send:ident)?)? {$($method:tt)*}) => {
+        impl<'capture, Arg: ForLt, Ret: ForLt $(, $storage: $bound $(+ $storage_send)?)?>
+            crate::$trait<Arg, Ret> for $name<'capture, Arg, Ret $(, $storage)?>
+        {
+            #[inline]
+            $($method)*
+        }
+    };
+}
+pub(crate) use impl_callable;
+
+#[cfg(feature = "async")]
+macro_rules! impl_async_callable {
+    (Fn $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_async_callable!(@ DynAsyncCallableOnce $name, $fn_storage $(+ $storage_send)?, {
+            #[inline]
+            fn is_sync(&self) -> bool {
+                $name::is_sync(self)
+            }
+            #[inline]
+            fn call_once<'a>(self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+                async move { $name::call(&self, arg).await }
+            }
+            #[inline]
+            fn call_sync_once<'a>(self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+                $name::call_sync(&self, arg)
+            }
+            #[inline]
+            fn call_try_sync_once<'a>(self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+                async move { $name::call_try_sync(&self, arg).await }
+            }
+        });
+        crate::macros::impl_async_callable!(@ DynAsyncCallableMut $name, $fn_storage $(+ $storage_send)?, {
+            #[inline]
+            fn call_mut<'a>(&mut self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+                $name::call(self, arg)
+            }
+            #[inline]
+            fn call_sync_mut<'a>(&mut self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+                $name::call_sync(self, arg)
+            }
+            #[inline]
+            fn call_try_sync_mut<'a>(&mut self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+                $name::call_try_sync(self, arg)
+            }
+        });
+        crate::macros::impl_async_callable!(@ DynAsyncCallable $name, $fn_storage $(+ $storage_send)?, {
+            #[inline]
+            fn call<'a>(&self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+                $name::call(self, arg)
+            }
+            #[inline]
+            fn call_sync<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+                $name::call_sync(self, arg)
+            }
+            #[inline]
+            fn call_try_sync<'a>(&self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+                $name::call_try_sync(self, arg)
+            }
+        });
+    };
+    (FnMut $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_async_callable!(@ DynAsyncCallableOnce $name, $fn_storage $(+ $storage_send)?, {
+            #[inline]
+            fn is_sync(&self) -> bool {
+                $name::is_sync(self)
+            }
+            #[inline]
+            fn call_once<'a>(mut self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+                async move { $name::call(&mut self, arg).await }
+            }
+            #[inline]
+            fn call_sync_once<'a>(mut self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+                $name::call_sync(&mut self, arg)
+            }
+            #[inline]
+            fn call_try_sync_once<'a>(mut self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+                async move { $name::call_try_sync(&mut self, arg).await }
+            }
+        });
+        crate::macros::impl_async_callable!(@ DynAsyncCallableMut $name, $fn_storage $(+ $storage_send)?, {
+            #[inline]
+            fn call_mut<'a>(&mut self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+                $name::call(self, arg)
+            }
+            #[inline]
+            fn call_sync_mut<'a>(&mut self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+                $name::call_sync(self, arg)
+            }
+            #[inline]
+            fn call_try_sync_mut<'a>(&mut self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+                $name::call_try_sync(self, arg)
+            }
+        });
+    };
+    (FnOnce $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_async_callable!(@ DynAsyncCallableOnce $name, $fn_storage $(+ $storage_send)?, {
+            #[inline]
+            fn is_sync(&self) -> bool {
+                $name::is_sync(self)
+            }
+            #[inline]
+            fn call_once<'a>(self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+                $name::call(self, arg)
+            }
+            #[inline]
+            fn call_sync_once<'a>(self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+                $name::call_sync(self, arg)
+            }
+            #[inline]
+            fn call_try_sync_once<'a>(self, arg: Arg::Of<'a>) -> impl Future<Output = Ret::Of<'a>> {
+                $name::call_try_sync(self, arg)
+            }
+        });
+    };
+    (@ $trait:ident $name:ident, $fn_storage:ident $(+ $storage_send:ident)?, {$($method:tt)*}) => {
+        impl<'capture, Arg: ForLt, Ret: ForLt, FnStorage: $fn_storage $(+ $storage_send)?, FutureStorage: StorageMut>
+            crate::$trait<Arg, Ret> for $name<'capture, Arg, Ret, FnStorage, FutureStorage>
+        {
+            $($method)*
+        }
+    };
+}
+#[cfg(feature = "async")]
+pub(crate) use impl_async_callable;
+
+#[cfg(feature = "nightly")]
+macro_rules! impl_fn_traits {
+    ($kind:ident $name:ident, $fn_storage:ident $(+ $storage_send:ident)?) => {
+        crate::macros::impl_fn_traits!(@ $kind $name, {$fn_storage $(+ $storage_send)?}, [A: 'static], ForFixed<A>, A);
+        // `ForFixed<&'a T>` and `ForRef<T>` only differ by higher-ranked lifetime,
+        // which coherence check ignores for now, see https://github.com/rust-lang/rust/issues/56105
+        crate::macros::impl_fn_traits!(@ $kind $name, {$fn_storage $(+ $storage_send)?}, ['a, T: ?Sized + 'static], ForRef<T>, &'a T, #[allow(coherence_leak_check)]);
+    };
+    (@ Fn $name:ident, {$($bounds:tt)*}, [$($generics:tt)*], $arg_hkt:ty, $arg:ty $(, #[$attr:meta])?) => {
+        $(#[$attr])?
+        impl<'capture, $($generics)*, R: 'static, FnStorage: $($bounds)*> FnOnce<($arg,)>
+            for $name<'capture, $arg_hkt, ForFixed<R>, FnStorage>
+        {
+            type Output = R;
+            extern "rust-call" fn call_once(self, (arg,): ($arg,)) -> R {
+                $name::call(&self, arg)
+            }
+        }
+        $(#[$attr])?
+        impl<'capture, $($generics)*, R: 'static, FnStorage: $($bounds)*> FnMut<($arg,)>
+            for $name<'capture, $arg_hkt, ForFixed<R>, FnStorage>
+        {
+            extern "rust-call" fn call_mut(&mut self, (arg,): ($arg,)) -> R {
+                $name::call(self, arg)
+            }
+        }
+        $(#[$attr])?
+        impl<'capture, $($generics)*, R: 'static, FnStorage: $($bounds)*> Fn<($arg,)>
+            for $name<'capture, $arg_hkt, ForFixed<R>, FnStorage>
+        {
+            extern "rust-call" fn call(&self, (arg,): ($arg,)) -> R {
+                $name::call(self, arg)
+            }
+        }
+    };
+    (@ FnMut $name:ident, {$($bounds:tt)*}, [$($generics:tt)*], $arg_hkt:ty, $arg:ty $(, #[$attr:meta])?) => {
+        $(#[$attr])?
+        impl<'capture, $($generics)*, R: 'static, FnStorage: $($bounds)*> FnOnce<($arg,)>
+            for $name<'capture, $arg_hkt, ForFixed<R>, FnStorage>
+        {
+            type Output = R;
+            extern "rust-call" fn call_once(mut self, (arg,): ($arg,)) -> R {
+                $name::call(&mut self, arg)
+            }
+        }
+        $(#[$attr])?
+        impl<'capture, $($generics)*, R: 'static, FnStorage: $($bounds)*> FnMut<($arg,)>
+            for $name<'capture, $arg_hkt, ForFixed<R>, FnStorage>
+        {
+            extern "rust-call" fn call_mut(&mut self, (arg,): ($arg,)) -> R {
+                $name::call(self, arg)
+            }
+        }
+    };
+    (@ FnOnce $name:ident, {$($bounds:tt)*}, [$($generics:tt)*], $arg_hkt:ty, $arg:ty $(, #[$attr:meta])?) => {
+        $(#[$attr])?
+        impl<'capture, $($generics)*, R: 'static, FnStorage: $($bounds)*> FnOnce<($arg,)>
+            for $name<'capture, $arg_hkt, ForFixed<R>, FnStorage>
+        {
+            type Output = R;
+            extern "rust-call" fn call_once(self, (arg,): ($arg,)) -> R {
+                $name::call(self, arg)
+            }
+        }
+    };
+}
+#[cfg(feature = "nightly")]
+pub(crate) use impl_fn_traits;
@@ -0,0 +1,68 @@
+/// A function name, stored in the vtable of functions constructed with the `new_named` method
+/// of every dynamic function.
+///
+/// Erased functions are anonymous, which makes a registry of callbacks hard to debug; the name
+/// is a `&'static str` shared by all functions constructed at the same site, so it costs no
+/// space in the function itself. It is returned by the `name` method, included in the [`Debug`]
+/// output, in the [`defmt::Format`] one with `defmt` feature, and in invariant violation panic
+/// messages. Names are usually declared with [`fn_name!`](crate::fn_name).
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{DynFn, fn_name, hkt::ForFixed};
+///
+/// fn_name!(Incr = "incr");
+///
+/// let callbacks: [DynFn<'static, ForFixed<u32>, ForFixed<u32>>; 2] = [
+///     DynFn::new_named(Incr, |x, _| x + 1),
+///     DynFn::new(|x, _| x * 2),
+/// ];
+/// assert_eq!(callbacks[0].name(), Some("incr"));
+/// assert_eq!(callbacks[1].name(), None);
+/// assert_eq!(callbacks[0].call(41), 42);
+/// assert!(format!("{:?}", callbacks[0]).contains(r#"name: Some("incr")"#));
+/// ```
+pub trait FnName {
+    /// The name of the function.
+    const NAME: &'static str;
+}
+
+/// Declares a unit struct implementing [`FnName`](crate::FnName).
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{FnName, fn_name};
+///
+/// fn_name!(pub Incr = "incr");
+/// assert_eq!(Incr::NAME, "incr");
+/// ```
+#[macro_export]
+macro_rules! fn_name {
+    ($(#[$attr:meta])* $vis:vis $name:ident = $value:expr $(;)?) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Default)]
+        $vis struct $name;
+
+        impl $crate::FnName for $name {
+            const NAME: &'static str = $value;
+        }
+    };
+}
+
+/// The absence of name, used by every constructor but `new_named`.
+pub(crate) struct Anonymous;
+
+/// An optional function name, resolved at compile time to be stored in the vtable.
+pub(crate) trait MaybeName {
+    const NAME: Option<&'static str>;
+}
+
+impl MaybeName for Anonymous {
+    const NAME: Option<&'static str> = None;
+}
+
+impl<N: FnName> MaybeName for N {
+    const NAME: Option<&'static str> = Some(<N as FnName>::NAME);
+}
@@ -0,0 +1,106 @@
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use elain::{Align, Alignment};
+
+use crate::DynFn;
+
+/// A value padded and aligned to `ALIGN` bytes, typically a cache line.
+///
+/// Dynamic function handles are only a few words long, so the adjacent entries of an array share
+/// the same cache line; when each entry is written by a different core, e.g. replaced or called
+/// with a mutable state, the line ping-pongs between the cores. Padding the entries to a cache
+/// line removes this false sharing. Cache lines are 64 bytes on most targets, but 128 bytes may
+/// be needed to defeat the adjacent line prefetcher of some x86_64 and aarch64 CPUs.
+///
+/// `Padded` dereferences to the wrapped value, so its methods can be called directly.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{DynFn, Padded, PaddedDynFn, hkt::ForFixed};
+///
+/// type Callback = PaddedDynFn<'static, ForFixed<u32>, ForFixed<u32>>;
+/// assert_eq!(size_of::<Callback>(), 64);
+/// assert_eq!(align_of::<Padded<(), 128>>(), 128);
+///
+/// let callbacks: [Callback; 4] = [0, 1, 2, 3].map(|i| Padded::new(DynFn::new(move |x, _| x + i)));
+/// assert_eq!(callbacks[2].call(40), 42);
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct Padded<T, const ALIGN: usize = 64>
+where
+    Align<ALIGN>: Alignment,
+{
+    value: T,
+    _align: Align<ALIGN>,
+}
+
+impl<T, const ALIGN: usize> Padded<T, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    /// Pads a value.
+    pub const fn new(value: T) -> Self {
+        Self {
+            value,
+            _align: Align::NEW,
+        }
+    }
+
+    /// Returns the padded value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, const ALIGN: usize> Deref for Padded<T, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, const ALIGN: usize> DerefMut for Padded<T, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T, const ALIGN: usize> From<T> for Padded<T, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: fmt::Debug, const ALIGN: usize> fmt::Debug for Padded<T, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+/// [`DynFn`] padded to a cache line.
+pub type PaddedDynFn<
+    'capture,
+    Arg,
+    Ret = higher_kinded_types::ForFixed<()>,
+    FnStorage = crate::storage::DefaultFnStorage,
+    const ALIGN: usize = 64,
+> = Padded<DynFn<'capture, Arg, Ret, FnStorage>, ALIGN>;
@@ -0,0 +1,145 @@
+//! Polling instrumentation of the futures returned by dynamic asynchronous functions.
+//!
+//! Without `poll-stats` feature, counters and recorders are zero-sized and their methods are
+//! no-ops, so the instrumentation is compiled out entirely.
+
+#[cfg(feature = "poll-stats")]
+use core::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+use core::{marker::PhantomData, task::Poll};
+
+/// Polling statistics of the futures returned by a dynamic asynchronous function.
+///
+/// They are accumulated over all the calls of the function, and reset when it is cloned.
+/// Futures of synchronous functions are counted as well, but not
+/// [`call_sync`](crate::DynAsyncFn::call_sync) and the synchronous path of
+/// [`call_try_sync`](crate::DynAsyncFn::call_try_sync), which don't return a future.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use dyn_fn::{AsyncFnSend, DynAsyncFn, ForLt, hkt::ForFixed};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// type Callback = DynAsyncFn<'static, ForFixed<u64>, ForFixed<u64>>;
+/// let mut registry = HashMap::<&str, Callback>::new();
+/// registry.insert("double", Callback::new_sync(|x, _| 2 * x));
+/// registry.insert("yield", Callback::new(Yield));
+/// for callback in registry.values() {
+///     callback.call(21).await;
+/// }
+/// for (name, callback) in &registry {
+///     let stats = callback.poll_stats();
+///     println!(
+///         "{name}: {} polls for {} calls",
+///         stats.polls, stats.completions
+///     );
+/// }
+/// assert_eq!(registry["yield"].poll_stats().pendings, 1);
+///
+/// struct Yield;
+/// impl AsyncFnSend<'static, ForFixed<u64>, ForFixed<u64>> for Yield {
+///     async fn call<'a>(&self, x: <ForFixed<u64> as ForLt>::Of<'a>) -> u64 {
+///         tokio::task::yield_now().await;
+///         x
+///     }
+/// }
+/// # }
+/// ```
+#[cfg(feature = "poll-stats")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PollStats {
+    /// Number of polls of the returned futures.
+    pub polls: usize,
+    /// Number of polls which returned [`Poll::Pending`], i.e. which didn't complete the future,
+    /// whether the future has been woken or not.
+    pub pendings: usize,
+    /// Number of returned futures polled to completion.
+    pub completions: usize,
+    /// Maximal number of polls needed by a returned future to complete.
+    pub max_polls_to_complete: usize,
+}
+
+/// Wait-free counters of a function, shared by all its returned futures.
+pub(crate) struct PollCounters {
+    #[cfg(feature = "poll-stats")]
+    polls: AtomicUsize,
+    #[cfg(feature = "poll-stats")]
+    pendings: AtomicUsize,
+    #[cfg(feature = "poll-stats")]
+    completions: AtomicUsize,
+    #[cfg(feature = "poll-stats")]
+    max_polls_to_complete: AtomicUsize,
+}
+
+impl PollCounters {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub(crate) const fn new() -> Self {
+        Self {
+            #[cfg(feature = "poll-stats")]
+            polls: AtomicUsize::new(0),
+            #[cfg(feature = "poll-stats")]
+            pendings: AtomicUsize::new(0),
+            #[cfg(feature = "poll-stats")]
+            completions: AtomicUsize::new(0),
+            #[cfg(feature = "poll-stats")]
+            max_polls_to_complete: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns a recorder for a returned future.
+    #[inline(always)]
+    pub(crate) fn recorder(&self) -> PollRecorder<'_> {
+        PollRecorder {
+            #[cfg(feature = "poll-stats")]
+            counters: self,
+            #[cfg(feature = "poll-stats")]
+            polls: 0,
+            _counters: PhantomData,
+        }
+    }
+
+    #[cfg(feature = "poll-stats")]
+    pub(crate) fn stats(&self) -> PollStats {
+        PollStats {
+            polls: self.polls.load(Relaxed),
+            pendings: self.pendings.load(Relaxed),
+            completions: self.completions.load(Relaxed),
+            max_polls_to_complete: self.max_polls_to_complete.load(Relaxed),
+        }
+    }
+}
+
+/// Records the polls of a returned future into the counters of its function.
+pub(crate) struct PollRecorder<'c> {
+    #[cfg(feature = "poll-stats")]
+    counters: &'c PollCounters,
+    /// Number of polls of the future.
+    #[cfg(feature = "poll-stats")]
+    polls: usize,
+    _counters: PhantomData<&'c PollCounters>,
+}
+
+impl PollRecorder<'_> {
+    #[cfg(feature = "poll-stats")]
+    #[inline]
+    pub(crate) fn record<T>(&mut self, poll: &Poll<T>) {
+        let counters = self.counters;
+        self.polls = self.polls.wrapping_add(1);
+        counters.polls.fetch_add(1, Relaxed);
+        if poll.is_pending() {
+            counters.pendings.fetch_add(1, Relaxed);
+        } else {
+            counters.completions.fetch_add(1, Relaxed);
+            counters
+                .max_polls_to_complete
+                .fetch_max(self.polls, Relaxed);
+        }
+    }
+
+    #[cfg(not(feature = "poll-stats"))]
+    #[inline(always)]
+    pub(crate) fn record<T>(&mut self, _: &Poll<T>) {}
+}
@@ -0,0 +1,66 @@
+//! Common imports, to be glob-imported with `use dyn_fn::prelude::*`.
+//!
+//! It contains the dynamic function types, the `AsyncFn*Send` traits, the [`dyn_fn!`],
+//! `dyn_async_fn!` and [`static_dyn_fn!`] macros, the [`ForLt`](trait@ForLt) trait and macro, the most used
+//! [`hkt`](crate::hkt) types, and the [`storage`] module; asynchronous items require the `async`
+//! feature.
+//!
+//! [`Box`](storage::Box), [`Rc`](storage::Rc) and [`Arc`](storage::Arc) storages are not
+//! re-exported directly, as they would shadow their standard counterparts when glob-imported;
+//! they are meant to be used qualified, e.g. `storage::Box`. Only [`Raw`] and [`RawOrBox`], which
+//! don't collide, are re-exported.
+//!
+//! # Examples
+//!
+//! ```
+//! #![no_std]
+//! # extern crate std;
+//! use dyn_fn::prelude::*;
+//!
+//! # fn main() {
+//! type Callback<'a> = LocalDynFn<'a, ForRef<str>, ForFixed<()>, Raw<32>>;
+//! let mut callbacks = heapless::Vec::<Callback, 4>::new();
+//! callbacks.push(Callback::new(dyn_fn!(|s| {
+//!     defmt::debug!("callback called with '{}'", s)
+//! })));
+//!
+//! let input = "input";
+//! for cb in &callbacks {
+//!     cb.call(input);
+//! }
+//! # }
+//! ```
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use dyn_fn::prelude::*;
+//! use futures_util::future::join_all;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! # #[cfg(all(feature = "alloc", feature = "async"))] {
+//! type Callback<'a> = LocalDynAsyncFn<'a, ForFixed<Duration>, ForFixed<()>, storage::Arc>;
+//! let mut callbacks = Vec::<Callback>::new();
+//! callbacks.push(Callback::new(dyn_async_fn!(async |timeout| {
+//!     tokio::time::sleep(timeout).await
+//! })));
+//!
+//! let timeout = Duration::from_millis(1);
+//! join_all(callbacks.iter().map(|cb| cb.call(timeout))).await;
+//! # }
+//! # }
+//! ```
+
+#[cfg(feature = "async")]
+pub use crate::{
+    AsyncFnMutSend, AsyncFnOnceSend, AsyncFnSend, DynAsyncFn, DynAsyncFnMut, DynAsyncFnOnce,
+    LocalDynAsyncFn, LocalDynAsyncFnMut, LocalDynAsyncFnOnce, SendDynAsyncFn, dyn_async_fn,
+};
+pub use crate::{
+    DynFn, DynFnMut, DynFnOnce, ForLt, LocalDynFn, LocalDynFnMut, LocalDynFnOnce, SendDynFn,
+    UnsafeDynFn, dyn_fn,
+    hkt::{ForFixed, ForRef},
+    static_dyn_fn,
+    storage::{self, Raw, RawOrBox},
+};
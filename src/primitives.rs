@@ -0,0 +1,38 @@
+//! Synchronization primitives, swapped for [`loom`](https://docs.rs/loom) ones when testing with
+//! `--cfg loom`, so concurrent state machines can be model-checked.
+
+#[cfg(all(not(loom), feature = "alloc"))]
+pub(crate) use alloc::sync::Arc;
+#[cfg(not(loom))]
+pub(crate) use core::{hint::spin_loop, sync::atomic::AtomicU32};
+
+#[cfg(all(loom, feature = "alloc"))]
+pub(crate) use loom::sync::Arc;
+#[cfg(loom)]
+pub(crate) use loom::{cell::UnsafeCell, hint::spin_loop, sync::atomic::AtomicU32};
+
+/// [`UnsafeCell`](core::cell::UnsafeCell) with the closure-based API of the `loom` one, which
+/// tracks the accesses.
+#[cfg(not(loom))]
+pub(crate) struct UnsafeCell<T>(core::cell::UnsafeCell<T>);
+
+#[cfg(not(loom))]
+impl<T> UnsafeCell<T> {
+    pub(crate) const fn new(data: T) -> Self {
+        Self(core::cell::UnsafeCell::new(data))
+    }
+
+    #[inline]
+    pub(crate) fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+        f(self.0.get())
+    }
+
+    #[inline]
+    pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+        f(self.0.get())
+    }
+
+    pub(crate) fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
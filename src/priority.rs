@@ -0,0 +1,236 @@
+use core::{fmt, ops::ControlFlow};
+
+use higher_kinded_types::ForFixed;
+
+#[cfg(feature = "async")]
+use crate::DynAsyncCallable;
+#[cfg(feature = "alloc")]
+use crate::slab::VecSlab;
+use crate::{
+    CallbackKey, DynCallable, ForLt,
+    slab::{ArraySlab, Buffer, EntryBuffer, Slab, SlabSlot},
+};
+
+/// Calls the callbacks in order until one of them breaks.
+fn dispatch<'a, Arg: ForLt, B: 'static, C: DynCallable<Arg, ForFixed<ControlFlow<B>>> + 'a>(
+    callbacks: impl Iterator<Item = &'a (i8, C)>,
+    arg: Arg::Of<'_>,
+) -> ControlFlow<B>
+where
+    for<'b> Arg::Of<'b>: Clone,
+{
+    for (_, f) in callbacks {
+        f.call(arg.clone())?;
+    }
+    ControlFlow::Continue(())
+}
+
+/// Calls the callbacks in order until one of them breaks, awaiting each one in turn.
+#[cfg(feature = "async")]
+async fn dispatch_async<
+    'a,
+    Arg: ForLt,
+    B: 'static,
+    C: DynAsyncCallable<Arg, ForFixed<ControlFlow<B>>> + 'a,
+>(
+    callbacks: impl Iterator<Item = &'a (i8, C)>,
+    arg: Arg::Of<'_>,
+) -> ControlFlow<B>
+where
+    for<'b> Arg::Of<'b>: Clone,
+{
+    for (_, f) in callbacks {
+        f.call_try_sync(arg.clone()).await?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn fmt_slab<C: fmt::Debug, E: EntryBuffer<Value = (i8, C)>, S: Buffer<Item = SlabSlot>>(
+    slab: &Slab<E, S>,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    f.debug_list().entries(slab.values()).finish()
+}
+
+/// A dispatcher calling up to `N` callbacks by decreasing priority, until one of them breaks.
+///
+/// Callbacks can be any [`DynCallable`] returning a [`ControlFlow`], dispatched with
+/// [`dispatch`](Self::dispatch), or any [`DynAsyncCallable`](crate::DynAsyncCallable),
+/// dispatched with [`dispatch_async`](Self::dispatch_async). Callbacks with the same priority
+/// are called in registration order. A callback returning [`ControlFlow::Break`] marks the
+/// event as handled, so later callbacks are not called, and the dispatch returns the break
+/// value.
+///
+/// # Examples
+///
+/// ```
+/// use core::ops::ControlFlow;
+///
+/// use dyn_fn::{DynFn, PriorityDispatcher, hkt::*, storage::Raw};
+///
+/// type Handler = DynFn<'static, ForRef<str>, ForFixed<ControlFlow<&'static str>>, Raw<0>>;
+/// let mut dispatcher = PriorityDispatcher::<Handler, 4>::new();
+/// dispatcher
+///     .register(0, Handler::new(|_, _| ControlFlow::Break("fallback")))
+///     .unwrap();
+/// dispatcher
+///     .register(
+///         10,
+///         Handler::new(|key, _| match key {
+///             "q" => ControlFlow::Break("quit"),
+///             _ => ControlFlow::Continue(()),
+///         }),
+///     )
+///     .unwrap();
+/// assert_eq!(dispatcher.dispatch("q"), ControlFlow::Break("quit"));
+/// assert_eq!(dispatcher.dispatch("a"), ControlFlow::Break("fallback"));
+/// ```
+pub struct PriorityDispatcher<C, const N: usize>(ArraySlab<(i8, C), N>);
+
+impl<C, const N: usize> PriorityDispatcher<C, N> {
+    /// Constructs a new empty dispatcher.
+    pub const fn new() -> Self {
+        Self(Slab::new())
+    }
+
+    /// Returns the number of registered callbacks.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether no callback is registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns whether the callback of the key is still registered.
+    pub fn contains(&self, key: CallbackKey) -> bool {
+        self.0.contains(key)
+    }
+
+    /// Registers a callback with the given priority, returning its key.
+    ///
+    /// Returns the callback back if the dispatcher is full.
+    pub fn register(&mut self, priority: i8, f: C) -> Result<CallbackKey, C> {
+        self.0
+            .insert_before((priority, f), |&(p, _)| p < priority)
+            .map_err(|(_, f)| f)
+    }
+
+    /// Unregisters the callback of the key, returning it.
+    ///
+    /// Returns `None` if the key is stale.
+    pub fn unregister(&mut self, key: CallbackKey) -> Option<C> {
+        Some(self.0.remove(key)??.1)
+    }
+
+    /// Calls the callbacks by decreasing priority with a clone of `arg`, until one of them
+    /// breaks, returning the break value.
+    pub fn dispatch<Arg: ForLt, B: 'static>(&self, arg: Arg::Of<'_>) -> ControlFlow<B>
+    where
+        C: DynCallable<Arg, ForFixed<ControlFlow<B>>>,
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        dispatch(self.0.values(), arg)
+    }
+
+    /// Calls the callbacks by decreasing priority with a clone of `arg`, awaiting each one in
+    /// turn, until one of them breaks, returning the break value.
+    #[cfg(feature = "async")]
+    pub async fn dispatch_async<Arg: ForLt, B: 'static>(&self, arg: Arg::Of<'_>) -> ControlFlow<B>
+    where
+        C: DynAsyncCallable<Arg, ForFixed<ControlFlow<B>>>,
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        dispatch_async(self.0.values(), arg).await
+    }
+}
+
+impl<C, const N: usize> Default for PriorityDispatcher<C, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: fmt::Debug, const N: usize> fmt::Debug for PriorityDispatcher<C, N> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_slab(&self.0, f)
+    }
+}
+
+/// A [`PriorityDispatcher`] with growable capacity.
+#[cfg(feature = "alloc")]
+pub struct SlabPriorityDispatcher<C>(VecSlab<(i8, C)>);
+
+#[cfg(feature = "alloc")]
+impl<C> SlabPriorityDispatcher<C> {
+    /// Constructs a new empty dispatcher.
+    pub const fn new() -> Self {
+        Self(Slab::new())
+    }
+
+    /// Returns the number of registered callbacks.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether no callback is registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns whether the callback of the key is still registered.
+    pub fn contains(&self, key: CallbackKey) -> bool {
+        self.0.contains(key)
+    }
+
+    /// Registers a callback with the given priority, returning its key.
+    pub fn register(&mut self, priority: i8, f: C) -> CallbackKey {
+        let Ok(key) = self.0.insert_before((priority, f), |&(p, _)| p < priority);
+        key
+    }
+
+    /// Unregisters the callback of the key, returning it.
+    ///
+    /// Returns `None` if the key is stale.
+    pub fn unregister(&mut self, key: CallbackKey) -> Option<C> {
+        Some(self.0.remove(key)??.1)
+    }
+
+    /// Calls the callbacks by decreasing priority with a clone of `arg`, until one of them
+    /// breaks, returning the break value.
+    pub fn dispatch<Arg: ForLt, B: 'static>(&self, arg: Arg::Of<'_>) -> ControlFlow<B>
+    where
+        C: DynCallable<Arg, ForFixed<ControlFlow<B>>>,
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        dispatch(self.0.values(), arg)
+    }
+
+    /// Calls the callbacks by decreasing priority with a clone of `arg`, awaiting each one in
+    /// turn, until one of them breaks, returning the break value.
+    #[cfg(feature = "async")]
+    pub async fn dispatch_async<Arg: ForLt, B: 'static>(&self, arg: Arg::Of<'_>) -> ControlFlow<B>
+    where
+        C: DynAsyncCallable<Arg, ForFixed<ControlFlow<B>>>,
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        dispatch_async(self.0.values(), arg).await
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C> Default for SlabPriorityDispatcher<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C: fmt::Debug> fmt::Debug for SlabPriorityDispatcher<C> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_slab(&self.0, f)
+    }
+}
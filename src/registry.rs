@@ -0,0 +1,323 @@
+#[cfg(feature = "alloc")]
+use core::cell::RefCell;
+use core::fmt;
+
+#[cfg(all(feature = "alloc", feature = "async"))]
+use crate::DynAsyncCallable;
+#[cfg(feature = "alloc")]
+use crate::{
+    DynCallable,
+    slab::{Dispatch, Slab, VecSlab},
+};
+use crate::{
+    DynFn, ForLt,
+    storage::{Storage, StorageSend},
+};
+
+/// A handle to a callback registered in a [`CallbackSet`], used to unregister it.
+///
+/// Handles are generational: once the callback is unregistered, its handle is rejected, even if
+/// its slot is reused by another callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallbackHandle {
+    index: usize,
+    generation: u32,
+}
+
+/// A registered callback, with the index of its slot.
+type Entry<'capture, Arg, Ret, FnStorage> = (usize, DynFn<'capture, Arg, Ret, FnStorage>);
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    generation: u32,
+    /// The position of the callback in registration order, if the slot is used.
+    position: Option<usize>,
+}
+
+/// A fixed-capacity set of up to `N` [`DynFn`], with handles to unregister them.
+///
+/// It doesn't allocate, so with [`Raw`](crate::storage::Raw) storage, it can be used in
+/// `no_std` environments. Callbacks are called in registration order.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{CallbackSet, DynFn, hkt::*, storage::Raw};
+///
+/// type Callback = DynFn<'static, ForRef<str>, ForFixed<()>, Raw<0>>;
+/// let mut set = CallbackSet::<ForRef<str>, ForFixed<()>, Raw<0>, 4>::new();
+/// let handle = set.register(Callback::new(|s, _| println!("{s}"))).unwrap();
+/// set.call_all("hello");
+/// assert!(set.unregister(handle).is_some());
+/// // the handle is stale now
+/// assert!(set.unregister(handle).is_none());
+/// ```
+pub struct CallbackSet<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: Storage + StorageSend,
+    const N: usize,
+> {
+    /// Callbacks in registration order, with their slot index.
+    callbacks: [Option<Entry<'capture, Arg, Ret, FnStorage>>; N],
+    len: usize,
+    slots: [Slot; N],
+}
+
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: Storage + StorageSend,
+    const N: usize,
+> CallbackSet<'capture, Arg, Ret, FnStorage, N>
+{
+    /// Constructs a new empty set.
+    pub const fn new() -> Self {
+        Self {
+            callbacks: [const { None }; N],
+            len: 0,
+            slots: [Slot {
+                generation: 0,
+                position: None,
+            }; N],
+        }
+    }
+
+    /// Returns the number of registered callbacks.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether no callback is registered.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns whether the callback of the handle is still registered.
+    pub fn contains(&self, handle: CallbackHandle) -> bool {
+        self.slots
+            .get(handle.index)
+            .is_some_and(|slot| slot.generation == handle.generation && slot.position.is_some())
+    }
+
+    /// Registers a callback, returning its handle.
+    ///
+    /// Returns the callback back if the set is full.
+    pub fn register(
+        &mut self,
+        f: DynFn<'capture, Arg, Ret, FnStorage>,
+    ) -> Result<CallbackHandle, DynFn<'capture, Arg, Ret, FnStorage>> {
+        let Some(index) = self.slots.iter().position(|slot| slot.position.is_none()) else {
+            return Err(f);
+        };
+        self.slots[index].position = Some(self.len);
+        self.callbacks[self.len] = Some((index, f));
+        self.len += 1;
+        Ok(CallbackHandle {
+            index,
+            generation: self.slots[index].generation,
+        })
+    }
+
+    /// Unregisters the callback of the handle, returning it.
+    ///
+    /// Returns `None` if the handle is stale, i.e. its callback has already been unregistered.
+    pub fn unregister(
+        &mut self,
+        handle: CallbackHandle,
+    ) -> Option<DynFn<'capture, Arg, Ret, FnStorage>> {
+        let slot = self.slots.get_mut(handle.index)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        let position = slot.position.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        let (_, f) = self.callbacks[position].take()?;
+        // keep the registration order of the following callbacks
+        self.callbacks[position..self.len].rotate_left(1);
+        self.len -= 1;
+        for (i, (index, _)) in self.callbacks[position..self.len]
+            .iter()
+            .flatten()
+            .enumerate()
+        {
+            self.slots[*index].position = Some(position + i);
+        }
+        Some(f)
+    }
+
+    /// Calls every registered callback in registration order, with a clone of `arg`.
+    ///
+    /// For [`ForRef`](crate::hkt::ForRef) arguments, the same reference is passed to each
+    /// callback.
+    pub fn call_all(&self, arg: Arg::Of<'_>)
+    where
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        for (_, f) in self.callbacks[..self.len].iter().flatten() {
+            f.call(arg.clone());
+        }
+    }
+}
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend, const N: usize>
+    Default for CallbackSet<'_, Arg, Ret, FnStorage, N>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend, const N: usize>
+    fmt::Debug for CallbackSet<'_, Arg, Ret, FnStorage, N>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.callbacks[..self.len].iter().flatten().map(|(_, f)| f))
+            .finish()
+    }
+}
+
+/// A key to a callback registered in a [`CallbackSlab`] or a [`Signal`](crate::Signal), used to
+/// unregister it.
+///
+/// Keys are generational, see [`CallbackHandle`]; they stay valid when the slab is compacted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallbackKey {
+    pub(crate) index: usize,
+    pub(crate) generation: u32,
+}
+
+/// A growable set of callbacks, with keys to unregister them.
+///
+/// Callbacks can be any [`DynCallable`], e.g. [`DynFn`] or [`LocalDynFn`](crate::LocalDynFn)
+/// whatever their storage, dispatched with [`call_all`](Self::call_all), or any
+/// [`DynAsyncCallable`](crate::DynAsyncCallable), dispatched with
+/// [`call_all_async`](Self::call_all_async).
+///
+/// Registration and unregistration are amortized O(1), and callbacks are called in
+/// registration order. Methods take `&self`, so callbacks can unregister themselves, or
+/// register others, while being dispatched: unregistered callbacks are not called anymore,
+/// while those registered during a dispatch are only called by the next ones. As a consequence,
+/// the slab is not [`Sync`].
+///
+/// # Examples
+///
+/// ```
+/// use std::{cell::Cell, rc::Rc};
+///
+/// use dyn_fn::{CallbackKey, CallbackSlab, LocalDynFn, hkt::*};
+///
+/// type Callback<'a> = LocalDynFn<'a, ForRef<str>, ForFixed<()>>;
+/// let slab = Rc::new(CallbackSlab::<Callback>::new());
+/// // a callback unregistering itself on its first call
+/// let key = Rc::new(Cell::new(None::<CallbackKey>));
+/// let (slab2, key2) = (slab.clone(), key.clone());
+/// key.set(Some(slab.register(Callback::new(move |s, _| {
+///     println!("{s}");
+///     slab2.unregister(key2.get().unwrap());
+/// }))));
+/// slab.call_all("hello");
+/// assert!(slab.is_empty());
+/// ```
+#[cfg(feature = "alloc")]
+pub struct CallbackSlab<C>(pub(crate) RefCell<VecSlab<C>>);
+
+#[cfg(feature = "alloc")]
+impl<C> CallbackSlab<C> {
+    /// Constructs a new empty slab.
+    pub const fn new() -> Self {
+        Self(RefCell::new(Slab::new()))
+    }
+
+    /// Returns the number of registered callbacks.
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Returns whether no callback is registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns whether the callback of the key is still registered.
+    pub fn contains(&self, key: CallbackKey) -> bool {
+        self.0.borrow().contains(key)
+    }
+
+    /// Registers a callback, returning its key.
+    ///
+    /// If called during a dispatch, the callback is only called by the next ones.
+    pub fn register(&self, f: C) -> CallbackKey {
+        let Ok(key) = self.0.borrow_mut().insert(f);
+        key
+    }
+
+    /// Unregisters the callback of the key, dropping it.
+    ///
+    /// If the callback is being called, e.g. when it unregisters itself, it is dropped once its
+    /// call completes. Returns `false` if the key is stale.
+    pub fn unregister(&self, key: CallbackKey) -> bool {
+        let removed = self.0.borrow_mut().remove(key);
+        removed.is_some()
+    }
+
+    /// Unregisters the callback of the key, returning it.
+    ///
+    /// Returns `None` if the key is stale.
+    pub fn remove(&mut self, key: CallbackKey) -> Option<C> {
+        self.0.get_mut().remove(key).flatten()
+    }
+
+    /// Calls every registered callback in registration order, with a clone of `arg`.
+    ///
+    /// For [`ForRef`](crate::hkt::ForRef) arguments, the same reference is passed to each
+    /// callback. A callback is not called again by a re-entrant dispatch, i.e. a dispatch
+    /// started by a callback.
+    pub fn call_all<Arg: ForLt, Ret: ForLt>(&self, arg: Arg::Of<'_>)
+    where
+        C: DynCallable<Arg, Ret>,
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        Dispatch::run(&self.0, |f| {
+            f.call(arg.clone());
+        });
+    }
+
+    /// Calls every registered callback in registration order, with a clone of `arg`, awaiting
+    /// each one in turn.
+    ///
+    /// Synchronous callbacks are called without creating a future. See
+    /// [`call_all`](Self::call_all).
+    #[cfg(feature = "async")]
+    pub async fn call_all_async<Arg: ForLt, Ret: ForLt>(&self, arg: Arg::Of<'_>)
+    where
+        C: DynAsyncCallable<Arg, Ret>,
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        let (dispatch, len) = Dispatch::start(&self.0);
+        for position in 0..len {
+            if let Some(f) = dispatch.take(position) {
+                f.call_try_sync(arg.clone()).await;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C> Default for CallbackSlab<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C: fmt::Debug> fmt::Debug for CallbackSlab<C> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.borrow().values()).finish()
+    }
+}
@@ -0,0 +1,249 @@
+use alloc::collections::VecDeque;
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use higher_kinded_types::ForFixed;
+
+use crate::{
+    DynAsyncFnMut, ForLt,
+    storage::{DefaultFnStorage, DefaultFutureStorage, StorageMut, StorageSend},
+};
+
+#[derive(Default)]
+struct LockState {
+    locked: bool,
+    /// Waiter the lock has been handed over to, which hasn't polled it yet.
+    handed_over: Option<u64>,
+    waiters: VecDeque<(u64, Waker)>,
+    next_id: u64,
+}
+
+/// An asynchronous lock, granted in FIFO order.
+///
+/// When released, the lock is handed over to the first waiter, without being unlocked in
+/// between, so new callers cannot barge in front of the queued ones.
+#[derive(Default)]
+struct FifoLock(Mutex<LockState>);
+
+impl FifoLock {
+    fn state(&self) -> MutexGuard<'_, LockState> {
+        // the state is never left inconsistent, as the lock doesn't call user code
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn lock(&self) -> Acquire<'_> {
+        Acquire {
+            lock: self,
+            id: None,
+        }
+    }
+
+    fn unlock(&self) {
+        let mut state = self.state();
+        match state.waiters.pop_front() {
+            Some((id, waker)) => {
+                state.handed_over = Some(id);
+                drop(state);
+                waker.wake();
+            }
+            None => state.locked = false,
+        }
+    }
+}
+
+/// Future acquiring a [`FifoLock`].
+///
+/// Dropping it while queued removes it from the queue, or hands the lock over to the next waiter
+/// if it has already been handed over to it.
+struct Acquire<'l> {
+    lock: &'l FifoLock,
+    /// Queue identifier, once queued.
+    id: Option<u64>,
+}
+
+impl<'l> Future for Acquire<'l> {
+    type Output = LockGuard<'l>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let lock = self.lock;
+        let mut state = lock.state();
+        match self.id {
+            None if !state.locked => state.locked = true,
+            None => {
+                let id = state.next_id;
+                state.next_id += 1;
+                state.waiters.push_back((id, cx.waker().clone()));
+                drop(state);
+                self.id = Some(id);
+                return Poll::Pending;
+            }
+            Some(id) if state.handed_over == Some(id) => {
+                state.handed_over = None;
+                drop(state);
+                self.id = None;
+            }
+            Some(id) => {
+                if let Some((_, waker)) = state.waiters.iter_mut().find(|(i, _)| *i == id) {
+                    waker.clone_from(cx.waker());
+                }
+                return Poll::Pending;
+            }
+        }
+        Poll::Ready(LockGuard(lock))
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        let Some(id) = self.id else { return };
+        let mut state = self.lock.state();
+        if state.handed_over == Some(id) {
+            state.handed_over = None;
+            drop(state);
+            self.lock.unlock();
+        } else {
+            state.waiters.retain(|(i, _)| *i != id);
+        }
+    }
+}
+
+/// Releases a [`FifoLock`] when dropped.
+struct LockGuard<'l>(&'l FifoLock);
+
+impl Drop for LockGuard<'_> {
+    fn drop(&mut self) {
+        self.0.unlock();
+    }
+}
+
+/// A [`DynAsyncFnMut`] callable through a shared reference, with calls serialized.
+///
+/// Calls are queued in FIFO order, and run one at a time: a call only starts once the
+/// future of the previous one has completed, or has been dropped. Cancelling a queued call,
+/// i.e. dropping its future, removes it from the queue without affecting the other calls.
+///
+/// It requires the `std` feature, as waiters are queued behind a [`std::sync::Mutex`].
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use dyn_fn::{DynAsyncFnMut, SerializedAsyncFnMut, hkt::ForFixed};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let mut total = 0;
+/// let add = Arc::new(SerializedAsyncFnMut::new(DynAsyncFnMut::<
+///     'static,
+///     ForFixed<u64>,
+///     ForFixed<u64>,
+/// >::new_sync(move |x, _| {
+///     total += x;
+///     total
+/// })));
+/// let tasks: Vec<_> = (1..=10)
+///     .map(|x| {
+///         tokio::spawn({
+///             let add = add.clone();
+///             async move { add.call(x).await }
+///         })
+///     })
+///     .collect();
+/// let mut totals = Vec::new();
+/// for task in tasks {
+///     totals.push(task.await.unwrap());
+/// }
+/// // every call has seen the state left by the previous one
+/// totals.sort();
+/// assert_eq!(totals.last(), Some(&55));
+/// # }
+/// ```
+pub struct SerializedAsyncFnMut<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: StorageMut + StorageSend = DefaultFnStorage,
+    FutureStorage: StorageMut = DefaultFutureStorage,
+> {
+    lock: FifoLock,
+    f: UnsafeCell<DynAsyncFnMut<'capture, Arg, Ret, FnStorage, FutureStorage>>,
+}
+
+// SAFETY: the function is only accessed while holding the lock,
+// and `DynAsyncFnMut` is `Send`, so it can be accessed from any thread
+unsafe impl<Arg: ForLt, Ret: ForLt, FnStorage: StorageMut + StorageSend, FutureStorage: StorageMut>
+    Sync for SerializedAsyncFnMut<'_, Arg, Ret, FnStorage, FutureStorage>
+{
+}
+
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: StorageMut + StorageSend,
+    FutureStorage: StorageMut,
+> SerializedAsyncFnMut<'capture, Arg, Ret, FnStorage, FutureStorage>
+{
+    /// Wraps a function.
+    pub fn new(f: DynAsyncFnMut<'capture, Arg, Ret, FnStorage, FutureStorage>) -> Self {
+        Self {
+            lock: FifoLock::default(),
+            f: UnsafeCell::new(f),
+        }
+    }
+
+    /// Calls the function once the previously queued calls have completed.
+    ///
+    /// The function is held exclusively until the returned future completes, or is dropped.
+    pub async fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        let _guard = self.lock.lock().await;
+        // SAFETY: the lock is held until the call completes, so the function is accessed
+        // exclusively
+        let f = unsafe { &mut *self.f.get() };
+        f.call_try_sync(arg).await
+    }
+
+    /// Returns the wrapped function.
+    pub fn into_inner(self) -> DynAsyncFnMut<'capture, Arg, Ret, FnStorage, FutureStorage> {
+        self.f.into_inner()
+    }
+}
+
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: StorageMut + StorageSend,
+    FutureStorage: StorageMut,
+> From<DynAsyncFnMut<'capture, Arg, Ret, FnStorage, FutureStorage>>
+    for SerializedAsyncFnMut<'capture, Arg, Ret, FnStorage, FutureStorage>
+{
+    fn from(value: DynAsyncFnMut<'capture, Arg, Ret, FnStorage, FutureStorage>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: StorageMut + StorageSend,
+    FutureStorage: StorageMut,
+> fmt::Debug for SerializedAsyncFnMut<'capture, Arg, Ret, FnStorage, FutureStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock.state();
+        f.debug_struct("SerializedAsyncFnMut")
+            .field("locked", &state.locked)
+            .field("waiters", &state.waiters.len())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,135 @@
+use alloc::{boxed::Box, sync::Arc};
+use core::{
+    fmt,
+    future::{Future, poll_fn},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use higher_kinded_types::ForFixed;
+use tower_service::Service;
+
+use crate::{
+    AsyncFnSend, DynAsyncFn,
+    storage::{DefaultFnStorage, DefaultFutureStorage, Storage, StorageMut, StorageSend},
+};
+
+type ServiceDynFn<Req, Resp, E, FnStorage, FutureStorage> =
+    DynAsyncFn<'static, ForFixed<Req>, ForFixed<Result<Resp, E>>, FnStorage, FutureStorage>;
+
+/// A [`DynAsyncFn`] returning a `Result`, as a [`Service`], obtained with
+/// [`DynAsyncFn::into_service`].
+///
+/// The service is always ready, and its handles can be cloned, sharing the same function.
+pub struct DynService<
+    Req: 'static,
+    Resp: 'static,
+    E: 'static,
+    FnStorage: Storage + StorageSend = DefaultFnStorage,
+    FutureStorage: StorageMut = DefaultFutureStorage,
+>(Arc<ServiceDynFn<Req, Resp, E, FnStorage, FutureStorage>>);
+
+impl<
+    Req: Send + 'static,
+    Resp: 'static,
+    E: 'static,
+    FnStorage: Storage + StorageSend + 'static,
+    FutureStorage: StorageMut + 'static,
+> Service<Req> for DynService<Req, Resp, E, FnStorage, FutureStorage>
+{
+    type Response = Resp;
+    type Error = E;
+    type Future = Pin<Box<dyn Future<Output = Result<Resp, E>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), E>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        let f = self.0.clone();
+        Box::pin(async move { f.call(req).await })
+    }
+}
+
+impl<Req, Resp, E, FnStorage: Storage + StorageSend, FutureStorage: StorageMut> Clone
+    for DynService<Req, Resp, E, FnStorage, FutureStorage>
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<Req, Resp, E, FnStorage: Storage + StorageSend, FutureStorage: StorageMut> fmt::Debug
+    for DynService<Req, Resp, E, FnStorage, FutureStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DynService").field(&self.0).finish()
+    }
+}
+
+/// A [`Service`] as an [`AsyncFnSend`], each call using a fresh clone of the service.
+struct ServiceFn<S>(S);
+
+impl<Req: Send + 'static, S>
+    AsyncFnSend<'static, ForFixed<Req>, ForFixed<Result<S::Response, S::Error>>> for ServiceFn<S>
+where
+    S: Service<Req, Response: 'static, Error: 'static, Future: Send>
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    async fn call<'a>(&self, req: Req) -> Result<S::Response, S::Error> {
+        let mut service = self.0.clone();
+        poll_fn(|cx| service.poll_ready(cx)).await?;
+        service.call(req).await
+    }
+}
+
+impl<
+    Req: Send + 'static,
+    Resp: 'static,
+    E: 'static,
+    FnStorage: Storage + StorageSend + 'static,
+    FutureStorage: StorageMut + 'static,
+> DynAsyncFn<'static, ForFixed<Req>, ForFixed<Result<Resp, E>>, FnStorage, FutureStorage>
+{
+    /// Converts the function into a [`Service`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dyn_fn::{DynAsyncFn, hkt::ForFixed};
+    /// use futures_util::FutureExt;
+    /// use tower::ServiceExt;
+    ///
+    /// let f = DynAsyncFn::<ForFixed<u64>, ForFixed<Result<u64, ()>>>::new_sync(|x, _| Ok(x + 1));
+    /// let res = f.into_service().oneshot(41).now_or_never();
+    /// assert_eq!(res, Some(Ok(42)));
+    /// ```
+    pub fn into_service(self) -> DynService<Req, Resp, E, FnStorage, FutureStorage> {
+        DynService(Arc::new(self))
+    }
+
+    /// Constructs a new [`DynAsyncFn`] from a [`Service`].
+    ///
+    /// Each call waits for a fresh clone of the service to be ready, then calls it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dyn_fn::{DynAsyncFn, hkt::ForFixed};
+    /// use futures_util::FutureExt;
+    ///
+    /// let service = tower::service_fn(async |x: u64| Ok::<_, ()>(x + 1));
+    /// let f = DynAsyncFn::<ForFixed<u64>, ForFixed<Result<u64, ()>>>::from_service(service);
+    /// assert_eq!(f.call(41).now_or_never(), Some(Ok(42)));
+    /// ```
+    pub fn from_service<S>(service: S) -> Self
+    where
+        S: Service<Req, Response = Resp, Error = E, Future: Send> + Clone + Send + Sync + 'static,
+    {
+        Self::new(ServiceFn(service))
+    }
+}
@@ -0,0 +1,488 @@
+#[cfg(feature = "futures")]
+use alloc::vec::Vec;
+#[cfg(feature = "async")]
+use core::{
+    array,
+    future::{Future, poll_fn},
+    pin::{Pin, pin},
+    task::Poll,
+};
+use core::{cell::RefCell, fmt, mem};
+
+#[cfg(feature = "futures")]
+use futures_util::{StreamExt, stream::FuturesUnordered};
+use higher_kinded_types::ForFixed;
+
+#[cfg(feature = "alloc")]
+use crate::CallbackSlab;
+#[cfg(feature = "async")]
+use crate::DynAsyncCallable;
+use crate::{
+    CallbackKey, DynCallable, ForLt,
+    slab::{ArraySlab, Dispatch, Slab},
+};
+
+/// A signal, type-erased for its connections.
+trait Disconnect {
+    fn is_connected(&self, key: CallbackKey) -> bool;
+    fn disconnect(&self, key: CallbackKey) -> bool;
+}
+
+/// The connection of a slot to a signal, disconnecting it when dropped.
+#[must_use = "the slot is disconnected when the connection is dropped"]
+pub struct Connection<'s> {
+    signal: &'s dyn Disconnect,
+    key: CallbackKey,
+}
+
+impl Connection<'_> {
+    /// Returns the key of the slot, which can be used to disconnect it from the signal.
+    pub fn key(&self) -> CallbackKey {
+        self.key
+    }
+
+    /// Returns whether the slot is still connected.
+    pub fn is_connected(&self) -> bool {
+        self.signal.is_connected(self.key)
+    }
+
+    /// Disconnects the slot, which is equivalent to dropping the connection.
+    pub fn disconnect(self) {}
+
+    /// Releases the connection without disconnecting the slot, returning its key.
+    ///
+    /// The slot stays connected until it is disconnected with its key, or the signal dropped.
+    pub fn detach(self) -> CallbackKey {
+        let key = self.key;
+        mem::forget(self);
+        key
+    }
+}
+
+impl Drop for Connection<'_> {
+    fn drop(&mut self) {
+        self.signal.disconnect(self.key);
+    }
+}
+
+impl fmt::Debug for Connection<'_> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Connection")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A signal broadcasting its argument to up to `N` connected slots, without allocation.
+///
+/// Slots can be any [`DynCallable`] returning `()`, e.g. [`DynFn`](crate::DynFn) or
+/// [`LocalDynFn`](crate::LocalDynFn) with [`Raw`](crate::storage::Raw) storage. They are
+/// called in connection order, and are disconnected when their [`Connection`] is dropped.
+///
+/// Methods take `&self`, so slots can connect or disconnect slots while the signal is emitted:
+/// disconnected slots are not called anymore, and dropped after their call if they are being
+/// called, while slots connected during emission are only called by the next ones. As a
+/// consequence, the signal is not [`Sync`].
+///
+/// # Examples
+///
+/// ```
+/// use core::cell::Cell;
+///
+/// use dyn_fn::{LocalDynFn, Signal, hkt::*, storage::Raw};
+///
+/// type Slot<'a> = LocalDynFn<'a, ForRef<str>, ForFixed<()>, Raw<8>>;
+/// let count = Cell::new(0);
+/// let signal = Signal::<Slot, 4>::new();
+/// let connection = signal
+///     .connect(Slot::new(|s, _| count.set(count.get() + s.len())))
+///     .unwrap();
+/// signal.emit("hello");
+/// assert_eq!(count.get(), 5);
+/// drop(connection);
+/// signal.emit("hello");
+/// assert_eq!(count.get(), 5);
+/// ```
+pub struct Signal<C, const N: usize>(RefCell<ArraySlab<C, N>>);
+
+impl<C, const N: usize> Signal<C, N> {
+    /// Constructs a new signal without slot.
+    pub const fn new() -> Self {
+        Self(RefCell::new(Slab::new()))
+    }
+
+    /// Returns the number of connected slots.
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Returns whether no slot is connected.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Connects a slot, returning its connection.
+    ///
+    /// Returns the slot back if the signal is full. Slots disconnected during emission only
+    /// free their place once it completes.
+    pub fn connect(&self, slot: C) -> Result<Connection<'_>, C> {
+        let key = self.0.borrow_mut().insert(slot)?;
+        Ok(Connection { signal: self, key })
+    }
+
+    /// Disconnects the slot of the key, returning `false` if it is not connected.
+    pub fn disconnect(&self, key: CallbackKey) -> bool {
+        let removed = self.0.borrow_mut().remove(key);
+        removed.is_some()
+    }
+
+    /// Calls every connected slot in connection order, with a clone of `arg`.
+    ///
+    /// For [`ForRef`](crate::hkt::ForRef) arguments, the same reference is passed to each
+    /// slot.
+    pub fn emit<Arg: ForLt>(&self, arg: Arg::Of<'_>)
+    where
+        C: DynCallable<Arg, ForFixed<()>>,
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        Dispatch::run(&self.0, |slot| slot.call(arg.clone()));
+    }
+}
+
+impl<C, const N: usize> Disconnect for Signal<C, N> {
+    fn is_connected(&self, key: CallbackKey) -> bool {
+        self.0.borrow().contains(key)
+    }
+
+    fn disconnect(&self, key: CallbackKey) -> bool {
+        Signal::disconnect(self, key)
+    }
+}
+
+impl<C, const N: usize> Default for Signal<C, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: fmt::Debug, const N: usize> fmt::Debug for Signal<C, N> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.borrow().values()).finish()
+    }
+}
+
+/// A [`Signal`] with growable capacity, backed by a [`CallbackSlab`].
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// use dyn_fn::{LocalDynFn, SlabSignal, hkt::*};
+///
+/// type Slot<'a> = LocalDynFn<'a, ForFixed<u32>, ForFixed<()>>;
+/// let sum = &Cell::new(0);
+/// let signal = SlabSignal::<Slot>::new();
+/// let connections: Vec<_> = (1..=3)
+///     .map(|i| signal.connect(Slot::new(move |x, _| sum.set(sum.get() + i * x))))
+///     .collect();
+/// signal.emit(2);
+/// assert_eq!(sum.get(), 12);
+/// ```
+#[cfg(feature = "alloc")]
+pub struct SlabSignal<C>(CallbackSlab<C>);
+
+#[cfg(feature = "alloc")]
+impl<C> SlabSignal<C> {
+    /// Constructs a new signal without slot.
+    pub const fn new() -> Self {
+        Self(CallbackSlab::new())
+    }
+
+    /// Returns the number of connected slots.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether no slot is connected.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Connects a slot, returning its connection.
+    pub fn connect(&self, slot: C) -> Connection<'_> {
+        let key = self.0.register(slot);
+        Connection { signal: self, key }
+    }
+
+    /// Disconnects the slot of the key, returning `false` if it is not connected.
+    pub fn disconnect(&self, key: CallbackKey) -> bool {
+        self.0.unregister(key)
+    }
+
+    /// Calls every connected slot in connection order, with a clone of `arg`.
+    ///
+    /// See [`Signal::emit`].
+    pub fn emit<Arg: ForLt>(&self, arg: Arg::Of<'_>)
+    where
+        C: DynCallable<Arg, ForFixed<()>>,
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        self.0.call_all(arg);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C> Disconnect for SlabSignal<C> {
+    fn is_connected(&self, key: CallbackKey) -> bool {
+        self.0.contains(key)
+    }
+
+    fn disconnect(&self, key: CallbackKey) -> bool {
+        self.0.unregister(key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C> Default for SlabSignal<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C: fmt::Debug> fmt::Debug for SlabSignal<C> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Calls a slot, returning its future unless it is synchronous.
+#[cfg(feature = "async")]
+fn start<'a, Arg: ForLt, C: DynAsyncCallable<Arg, ForFixed<()>>>(
+    slot: &C,
+    arg: Arg::Of<'a>,
+) -> Option<impl Future<Output = ()>> {
+    if slot.is_sync() {
+        slot.call_sync(arg);
+        return None;
+    }
+    Some(slot.call(arg))
+}
+
+/// Polls the futures concurrently until they all complete, without allocation.
+#[cfg(feature = "async")]
+async fn join_all<F: Future<Output = ()>>(mut futures: Pin<&mut [Option<F>]>) {
+    poll_fn(|cx| {
+        // SAFETY: futures are not moved out of the slice, only dropped in place
+        let futures = unsafe { futures.as_mut().get_unchecked_mut() };
+        let mut ready = true;
+        for future in futures {
+            if let Some(fut) = future {
+                // SAFETY: the slice is pinned
+                if unsafe { Pin::new_unchecked(fut) }.poll(cx).is_ready() {
+                    *future = None;
+                } else {
+                    ready = false;
+                }
+            }
+        }
+        if ready {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await;
+}
+
+/// A [`Signal`] whose slots are asynchronous.
+///
+/// Slots can be any [`DynAsyncCallable`] returning `()`, e.g.
+/// [`DynAsyncFn`](crate::DynAsyncFn). [`emit`](Self::emit) awaits them concurrently, without
+/// allocation, while [`emit_serial`](Self::emit_serial) awaits them in connection order.
+/// Synchronous slots are called without creating a future.
+///
+/// Dropping an emission future drops the futures of the slots still running.
+///
+/// # Examples
+///
+/// ```
+/// use core::cell::Cell;
+///
+/// use dyn_fn::{AsyncSignal, LocalDynAsyncFn, hkt::*, storage::Raw};
+/// use futures_util::FutureExt;
+///
+/// type Slot<'a> = LocalDynAsyncFn<'a, ForFixed<u32>, ForFixed<()>, Raw<8>>;
+/// let sum = &Cell::new(0);
+/// let signal = AsyncSignal::<Slot, 4>::new();
+/// let _sync = signal.connect(Slot::new_sync(|x, _| sum.set(sum.get() + x)));
+/// let _async = signal.connect(Slot::new(async move |x, _| sum.set(sum.get() + 10 * x)));
+/// assert_eq!(signal.emit(2).now_or_never(), Some(()));
+/// assert_eq!(sum.get(), 22);
+/// ```
+#[cfg(feature = "async")]
+pub struct AsyncSignal<C, const N: usize>(Signal<C, N>);
+
+#[cfg(feature = "async")]
+impl<C, const N: usize> AsyncSignal<C, N> {
+    /// Constructs a new signal without slot.
+    pub const fn new() -> Self {
+        Self(Signal::new())
+    }
+
+    /// Returns the number of connected slots.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether no slot is connected.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Connects a slot, returning its connection.
+    ///
+    /// See [`Signal::connect`].
+    pub fn connect(&self, slot: C) -> Result<Connection<'_>, C> {
+        self.0.connect(slot)
+    }
+
+    /// Disconnects the slot of the key, returning `false` if it is not connected.
+    pub fn disconnect(&self, key: CallbackKey) -> bool {
+        self.0.disconnect(key)
+    }
+
+    /// Calls every connected slot with a clone of `arg`, awaiting them concurrently.
+    pub async fn emit<Arg: ForLt>(&self, arg: Arg::Of<'_>)
+    where
+        C: DynAsyncCallable<Arg, ForFixed<()>>,
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        let (dispatch, len) = Dispatch::start(&self.0.0);
+        let slots: [_; N] = array::from_fn(|pos| if pos < len { dispatch.take(pos) } else { None });
+        let futures: [_; N] = array::from_fn(|i| start(&**slots[i].as_ref()?, arg.clone()));
+        join_all(pin!(futures)).await;
+    }
+
+    /// Calls every connected slot with a clone of `arg`, awaiting them in connection order.
+    pub async fn emit_serial<Arg: ForLt>(&self, arg: Arg::Of<'_>)
+    where
+        C: DynAsyncCallable<Arg, ForFixed<()>>,
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        let (dispatch, len) = Dispatch::start(&self.0.0);
+        for position in 0..len {
+            if let Some(slot) = dispatch.take(position) {
+                slot.call_try_sync(arg.clone()).await;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<C, const N: usize> Default for AsyncSignal<C, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<C: fmt::Debug, const N: usize> fmt::Debug for AsyncSignal<C, N> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// An [`AsyncSignal`] with growable capacity, driving its slots with a
+/// [`FuturesUnordered`].
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// use dyn_fn::{AsyncSlabSignal, LocalDynAsyncFn, hkt::*};
+/// use futures_util::FutureExt;
+///
+/// type Slot<'a> = LocalDynAsyncFn<'a, ForFixed<u32>, ForFixed<()>>;
+/// let sum = &Cell::new(0);
+/// let signal = AsyncSlabSignal::<Slot>::new();
+/// let _sync = signal.connect(Slot::new_sync(|x, _| sum.set(sum.get() + x)));
+/// let _async = signal.connect(Slot::new(async move |x, _| sum.set(sum.get() + 10 * x)));
+/// assert_eq!(signal.emit(2).now_or_never(), Some(()));
+/// assert_eq!(sum.get(), 22);
+/// ```
+#[cfg(feature = "futures")]
+pub struct AsyncSlabSignal<C>(SlabSignal<C>);
+
+#[cfg(feature = "futures")]
+impl<C> AsyncSlabSignal<C> {
+    /// Constructs a new signal without slot.
+    pub const fn new() -> Self {
+        Self(SlabSignal::new())
+    }
+
+    /// Returns the number of connected slots.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether no slot is connected.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Connects a slot, returning its connection.
+    pub fn connect(&self, slot: C) -> Connection<'_> {
+        self.0.connect(slot)
+    }
+
+    /// Disconnects the slot of the key, returning `false` if it is not connected.
+    pub fn disconnect(&self, key: CallbackKey) -> bool {
+        self.0.disconnect(key)
+    }
+
+    /// Calls every connected slot with a clone of `arg`, awaiting them concurrently.
+    pub async fn emit<Arg: ForLt>(&self, arg: Arg::Of<'_>)
+    where
+        C: DynAsyncCallable<Arg, ForFixed<()>>,
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        let (dispatch, len) = Dispatch::start(&self.0.0.0);
+        let slots: Vec<_> = (0..len).filter_map(|pos| dispatch.take(pos)).collect();
+        let mut futures: FuturesUnordered<_> = slots
+            .iter()
+            .filter_map(|slot| start(&**slot, arg.clone()))
+            .collect();
+        while futures.next().await.is_some() {}
+    }
+
+    /// Calls every connected slot with a clone of `arg`, awaiting them in connection order.
+    pub async fn emit_serial<Arg: ForLt>(&self, arg: Arg::Of<'_>)
+    where
+        C: DynAsyncCallable<Arg, ForFixed<()>>,
+        for<'a> Arg::Of<'a>: Clone,
+    {
+        self.0.0.call_all_async(arg).await;
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<C> Default for AsyncSlabSignal<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<C: fmt::Debug> fmt::Debug for AsyncSlabSignal<C> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
@@ -0,0 +1,373 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::convert::Infallible;
+use core::{
+    cell::RefCell,
+    ops::{Deref, DerefMut},
+};
+
+use crate::CallbackKey;
+
+/// The buffers of a [`Slab`], either growable or fixed-capacity.
+pub(crate) trait Buffer: DerefMut<Target = [Self::Item]> {
+    type Item;
+    /// The item given back when the buffer is full, [`Infallible`] if it grows instead.
+    type Full<T>;
+    const EMPTY: Self;
+    /// Pushes an item, returning it back if the buffer is full.
+    fn push(&mut self, item: Self::Item) -> Result<(), Self::Full<Self::Item>>;
+    fn truncate(&mut self, len: usize);
+    fn into_item<T>(full: Self::Full<T>) -> T;
+    fn map_full<T, U>(full: Self::Full<T>, f: impl FnOnce(T) -> U) -> Self::Full<U>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Buffer for Vec<T> {
+    type Item = T;
+    type Full<U> = Infallible;
+    const EMPTY: Self = Vec::new();
+
+    fn push(&mut self, item: Self::Item) -> Result<(), Infallible> {
+        Vec::push(self, item);
+        Ok(())
+    }
+
+    fn truncate(&mut self, len: usize) {
+        Vec::truncate(self, len);
+    }
+
+    fn into_item<U>(full: Infallible) -> U {
+        match full {}
+    }
+
+    fn map_full<U, V>(full: Infallible, _f: impl FnOnce(U) -> V) -> Infallible {
+        full
+    }
+}
+
+/// An item of an [`ArrayBuffer`], with an empty value filling unused capacity.
+pub(crate) trait Empty {
+    const EMPTY: Self;
+}
+
+/// A fixed-capacity [`Buffer`].
+pub(crate) struct ArrayBuffer<T, const N: usize> {
+    items: [T; N],
+    len: usize,
+}
+
+impl<T: Empty, const N: usize> Buffer for ArrayBuffer<T, N> {
+    type Item = T;
+    type Full<U> = U;
+    const EMPTY: Self = Self {
+        items: [const { T::EMPTY }; N],
+        len: 0,
+    };
+
+    fn push(&mut self, item: Self::Item) -> Result<(), Self::Item> {
+        let Some(slot) = self.items.get_mut(self.len) else {
+            return Err(item);
+        };
+        *slot = item;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            self.len -= 1;
+            self.items[self.len] = T::EMPTY;
+        }
+    }
+
+    fn into_item<U>(full: U) -> U {
+        full
+    }
+
+    fn map_full<U, V>(full: U, f: impl FnOnce(U) -> V) -> V {
+        f(full)
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayBuffer<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.items[..self.len]
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayBuffer<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.items[..self.len]
+    }
+}
+
+/// A registered callback of a [`Slab`], in insertion order.
+pub(crate) struct SlabEntry<T> {
+    /// The slot index, `None` once the callback is removed.
+    slot: Option<usize>,
+    /// The callback, `None` once removed or while being called.
+    value: Option<T>,
+}
+
+impl<T> Empty for SlabEntry<T> {
+    const EMPTY: Self = Self {
+        slot: None,
+        value: None,
+    };
+}
+
+/// A slot of a [`Slab`], referenced by keys.
+#[derive(Clone, Copy)]
+pub(crate) struct SlabSlot {
+    generation: u32,
+    state: SlotState,
+}
+
+#[derive(Clone, Copy)]
+enum SlotState {
+    /// The position of the entry of the slot.
+    Used(usize),
+    /// The next free slot.
+    Free(Option<usize>),
+}
+
+impl Empty for SlabSlot {
+    const EMPTY: Self = Self {
+        generation: 0,
+        state: SlotState::Free(None),
+    };
+}
+
+/// A [`Buffer`] of [`SlabEntry`].
+pub(crate) trait EntryBuffer: Buffer<Item = SlabEntry<Self::Value>> {
+    type Value;
+}
+
+impl<T, E: Buffer<Item = SlabEntry<T>>> EntryBuffer for E {
+    type Value = T;
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) type VecSlab<T> = Slab<Vec<SlabEntry<T>>, Vec<SlabSlot>>;
+pub(crate) type ArraySlab<T, const N: usize> =
+    Slab<ArrayBuffer<SlabEntry<T>, N>, ArrayBuffer<SlabSlot, N>>;
+
+/// Callbacks in insertion order, with generational keys, and deferred removal while
+/// dispatching.
+///
+/// Removed entries are left as tombstones, so positions are stable while dispatching, and
+/// compacted once they are the majority, or when the buffer is full, and no dispatch is
+/// running.
+pub(crate) struct Slab<E: EntryBuffer, S: Buffer<Item = SlabSlot>> {
+    entries: E,
+    slots: S,
+    free: Option<usize>,
+    removed: usize,
+    dispatching: usize,
+}
+
+impl<E: EntryBuffer, S: Buffer<Item = SlabSlot>> Slab<E, S> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            entries: E::EMPTY,
+            slots: S::EMPTY,
+            free: None,
+            removed: 0,
+            dispatching: 0,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len() - self.removed
+    }
+
+    pub(crate) fn contains(&self, key: CallbackKey) -> bool {
+        self.slots.get(key.index).is_some_and(|slot| {
+            slot.generation == key.generation && matches!(slot.state, SlotState::Used(_))
+        })
+    }
+
+    /// Inserts a value, returning it back if the slab is full.
+    pub(crate) fn insert(&mut self, value: E::Value) -> Result<CallbackKey, E::Full<E::Value>> {
+        let entry = SlabEntry {
+            slot: None,
+            value: Some(value),
+        };
+        let mut pushed = self.entries.push(entry);
+        if let Err(full) = pushed {
+            // make room by compacting removed entries
+            self.compact(true);
+            pushed = self.entries.push(E::into_item(full));
+        }
+        if let Err(full) = pushed {
+            return Err(E::map_full(full, |entry| entry.value.unwrap()));
+        }
+        let position = self.entries.len() - 1;
+        let index = match self.free {
+            Some(index) => index,
+            None => {
+                // all slots are used by the other entries, so there is room for a new one
+                let _ = self.slots.push(SlabSlot::EMPTY);
+                self.slots.len() - 1
+            }
+        };
+        let slot = &mut self.slots[index];
+        if let SlotState::Free(next) = slot.state {
+            self.free = next;
+        }
+        slot.state = SlotState::Used(position);
+        self.entries[position].slot = Some(index);
+        Ok(CallbackKey {
+            index,
+            generation: slot.generation,
+        })
+    }
+
+    /// Inserts a value before the first entry matching `before`, returning it back if the slab
+    /// is full.
+    ///
+    /// It must not be called while dispatching, as it moves entries.
+    pub(crate) fn insert_before(
+        &mut self,
+        value: E::Value,
+        before: impl Fn(&E::Value) -> bool,
+    ) -> Result<CallbackKey, E::Full<E::Value>> {
+        let key = self.insert(value)?;
+        let last = self.entries.len() - 1;
+        let position = self.entries[..last]
+            .iter()
+            .position(|entry| entry.value.as_ref().is_some_and(&before))
+            .unwrap_or(last);
+        self.entries[position..].rotate_right(1);
+        for position in position..=last {
+            if let Some(index) = self.entries[position].slot {
+                self.slots[index].state = SlotState::Used(position);
+            }
+        }
+        Ok(key)
+    }
+
+    /// Removes the entry of the key, returning its value, which is `None` if it is being called.
+    ///
+    /// Returns `None` if the key is stale.
+    pub(crate) fn remove(&mut self, key: CallbackKey) -> Option<Option<E::Value>> {
+        let slot = self.slots.get_mut(key.index)?;
+        let SlotState::Used(position) = slot.state else {
+            return None;
+        };
+        if slot.generation != key.generation {
+            return None;
+        }
+        slot.generation = slot.generation.wrapping_add(1);
+        slot.state = SlotState::Free(self.free.replace(key.index));
+        self.removed += 1;
+        let entry = &mut self.entries[position];
+        entry.slot = None;
+        let value = entry.value.take();
+        self.compact(false);
+        Some(value)
+    }
+
+    /// Compacts removed entries if they are the majority, or if forced, unless dispatching.
+    fn compact(&mut self, force: bool) {
+        if self.dispatching > 0 || !(force || self.removed * 2 > self.entries.len()) {
+            return;
+        }
+        let mut len = 0;
+        for position in 0..self.entries.len() {
+            if let Some(index) = self.entries[position].slot {
+                self.entries.swap(len, position);
+                self.slots[index].state = SlotState::Used(len);
+                len += 1;
+            }
+        }
+        self.entries.truncate(len);
+        self.removed = 0;
+    }
+
+    /// Returns the callbacks not being called, in insertion order.
+    pub(crate) fn values(&self) -> impl Iterator<Item = &E::Value> {
+        self.entries.iter().filter_map(|entry| entry.value.as_ref())
+    }
+}
+
+/// A running dispatch over a [`Slab`], preventing its compaction until dropped.
+pub(crate) struct Dispatch<'a, E: EntryBuffer, S: Buffer<Item = SlabSlot>>(&'a RefCell<Slab<E, S>>);
+
+impl<'a, E: EntryBuffer, S: Buffer<Item = SlabSlot>> Dispatch<'a, E, S> {
+    /// Starts a dispatch, returning the number of entries to dispatch to.
+    ///
+    /// Callbacks inserted during the dispatch are not part of it.
+    pub(crate) fn start(slab: &'a RefCell<Slab<E, S>>) -> (Self, usize) {
+        let mut inner = slab.borrow_mut();
+        inner.dispatching += 1;
+        (Self(slab), inner.entries.len())
+    }
+
+    /// Calls `f` with every callback, in insertion order.
+    pub(crate) fn run(slab: &'a RefCell<Slab<E, S>>, mut f: impl FnMut(&E::Value)) {
+        let (dispatch, len) = Self::start(slab);
+        for position in 0..len {
+            if let Some(value) = dispatch.take(position) {
+                f(&value);
+            }
+        }
+    }
+
+    /// Takes the callback at the given position out of the slab while it is called.
+    ///
+    /// Returns `None` if it has been removed, or if it is already being called by a re-entrant
+    /// dispatch.
+    pub(crate) fn take(&self, position: usize) -> Option<Called<'a, E, S>> {
+        let value = self.0.borrow_mut().entries[position].value.take()?;
+        Some(Called {
+            slab: self.0,
+            position,
+            value: Some(value),
+        })
+    }
+}
+
+impl<E: EntryBuffer, S: Buffer<Item = SlabSlot>> Drop for Dispatch<'_, E, S> {
+    fn drop(&mut self) {
+        let mut inner = self.0.borrow_mut();
+        inner.dispatching -= 1;
+        inner.compact(false);
+    }
+}
+
+/// A callback taken out of a [`Slab`] while it is called, put back when dropped, or dropped
+/// itself if it has been removed in the meantime.
+pub(crate) struct Called<'a, E: EntryBuffer, S: Buffer<Item = SlabSlot>> {
+    slab: &'a RefCell<Slab<E, S>>,
+    position: usize,
+    value: Option<E::Value>,
+}
+
+impl<E: EntryBuffer, S: Buffer<Item = SlabSlot>> Deref for Called<'_, E, S> {
+    type Target = E::Value;
+
+    fn deref(&self) -> &Self::Target {
+        self.value.as_ref().unwrap()
+    }
+}
+
+impl<E: EntryBuffer, S: Buffer<Item = SlabSlot>> Drop for Called<'_, E, S> {
+    fn drop(&mut self) {
+        let Some(value) = self.value.take() else {
+            return;
+        };
+        let mut inner = self.slab.borrow_mut();
+        let entry = &mut inner.entries[self.position];
+        if entry.slot.is_some() {
+            entry.value = Some(value);
+        } else {
+            // the callback may unregister others when dropped
+            drop(inner);
+            drop(value);
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use core::fmt;
+
+use higher_kinded_types::ForFixed;
+
+#[cfg(feature = "async")]
+use crate::{DynAsyncFnOnce, storage::DefaultFutureStorage};
+use crate::{
+    DynFn, DynFnOnce, ForLt,
+    storage::{DefaultFnStorage, Storage, StorageMut, StorageSend},
+};
+
+/// A slot holding an optional [`DynFn`], which can be set, called, and cleared.
+///
+/// The slot has the same size as the function it holds, whatever the storage, as the empty
+/// state is encoded in the niche of the function vtable pointer; the same holds for any `Option`
+/// of dynamic function.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{DynFn, DynFnSlot, hkt::ForFixed, storage};
+///
+/// type Slot = DynFnSlot<'static, ForFixed<i32>, ForFixed<i32>, storage::Raw<32>>;
+/// assert_eq!(
+///     size_of::<Slot>(),
+///     size_of::<DynFn<ForFixed<i32>, ForFixed<i32>, storage::Raw<32>>>()
+/// );
+/// let mut slot = Slot::new();
+/// assert_eq!(slot.call_if_set(41), None);
+/// slot.set(DynFn::new(|x, _| x + 1));
+/// assert_eq!(slot.call_if_set(41), Some(42));
+/// slot.clear();
+/// assert!(!slot.is_set());
+/// ```
+pub struct DynFnSlot<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: Storage + StorageSend = DefaultFnStorage,
+>(Option<DynFn<'capture, Arg, Ret, FnStorage>>);
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    DynFnSlot<'capture, Arg, Ret, FnStorage>
+{
+    /// Construct a new empty slot.
+    pub const fn new() -> Self {
+        Self(None)
+    }
+
+    /// Returns whether the slot holds a function.
+    pub fn is_set(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Sets the function of the slot, returning the previous one if any.
+    pub fn set(
+        &mut self,
+        f: DynFn<'capture, Arg, Ret, FnStorage>,
+    ) -> Option<DynFn<'capture, Arg, Ret, FnStorage>> {
+        self.0.replace(f)
+    }
+
+    /// Takes the function out of the slot, leaving it empty.
+    pub fn clear(&mut self) -> Option<DynFn<'capture, Arg, Ret, FnStorage>> {
+        self.0.take()
+    }
+
+    /// Calls the function of the slot.
+    ///
+    /// Returns `None` if the slot is empty.
+    pub fn call_if_set<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        Some(self.0.as_ref()?.call(arg))
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend> Default
+    for DynFnSlot<'capture, Arg, Ret, FnStorage>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    fmt::Debug for DynFnSlot<'capture, Arg, Ret, FnStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DynFnSlot").field(&self.0).finish()
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    From<DynFn<'capture, Arg, Ret, FnStorage>> for DynFnSlot<'capture, Arg, Ret, FnStorage>
+{
+    fn from(value: DynFn<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self(Some(value))
+    }
+}
+
+/// A slot holding an optional [`DynFnOnce`], which can be armed, fired, and re-armed.
+///
+/// With [`Raw`](crate::storage::Raw) storage, the slot doesn't allocate, and has the same size
+/// as the function it holds.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{DynFnOnce, OnceSlot, hkt::ForFixed, storage};
+///
+/// let mut slot = OnceSlot::<ForFixed<i32>, ForFixed<i32>, storage::Raw<0>>::new();
+/// slot.arm(DynFnOnce::new(|x, _| x + 1));
+/// assert_eq!(slot.fire(41), Some(42));
+/// assert_eq!(slot.fire(41), None);
+/// ```
+pub struct OnceSlot<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: StorageMut + StorageSend = DefaultFnStorage,
+>(Option<DynFnOnce<'capture, Arg, Ret, FnStorage>>);
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
+    OnceSlot<'capture, Arg, Ret, FnStorage>
+{
+    /// Construct a new empty slot.
+    pub const fn new() -> Self {
+        Self(None)
+    }
+
+    /// Returns whether the slot holds a function.
+    pub fn is_armed(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Arms the slot with a function, dropping the previously armed one if any.
+    pub fn arm(&mut self, f: DynFnOnce<'capture, Arg, Ret, FnStorage>) {
+        self.0 = Some(f);
+    }
+
+    /// Takes the armed function out of the slot and calls it.
+    ///
+    /// Returns `None` if the slot is empty.
+    pub fn fire<'a>(&mut self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        Some(self.0.take()?.call(arg))
+    }
+
+    /// Takes the armed function out of the slot without calling it.
+    pub fn disarm(&mut self) -> Option<DynFnOnce<'capture, Arg, Ret, FnStorage>> {
+        self.0.take()
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
+    Default for OnceSlot<'capture, Arg, Ret, FnStorage>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
+    fmt::Debug for OnceSlot<'capture, Arg, Ret, FnStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OnceSlot").field(&self.0).finish()
+    }
+}
+
+/// A slot holding an optional [`DynAsyncFnOnce`], which can be armed, fired, and re-armed.
+///
+/// See [`OnceSlot`].
+#[cfg(feature = "async")]
+pub struct AsyncOnceSlot<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: StorageMut + StorageSend = DefaultFnStorage,
+    FutureStorage: StorageMut = DefaultFutureStorage,
+>(Option<DynAsyncFnOnce<'capture, Arg, Ret, FnStorage, FutureStorage>>);
+
+#[cfg(feature = "async")]
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: StorageMut + StorageSend,
+    FutureStorage: StorageMut,
+> AsyncOnceSlot<'capture, Arg, Ret, FnStorage, FutureStorage>
+{
+    /// Construct a new empty slot.
+    pub const fn new() -> Self {
+        Self(None)
+    }
+
+    /// Returns whether the slot holds a function.
+    pub fn is_armed(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Arms the slot with a function, dropping the previously armed one if any.
+    pub fn arm(&mut self, f: DynAsyncFnOnce<'capture, Arg, Ret, FnStorage, FutureStorage>) {
+        self.0 = Some(f);
+    }
+
+    /// Takes the armed function out of the slot and calls it.
+    ///
+    /// Returns `None` if the slot is empty.
+    pub async fn fire<'a>(&mut self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        Some(self.0.take()?.call_try_sync(arg).await)
+    }
+
+    /// Takes the armed function out of the slot without calling it.
+    pub fn disarm(
+        &mut self,
+    ) -> Option<DynAsyncFnOnce<'capture, Arg, Ret, FnStorage, FutureStorage>> {
+        self.0.take()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: StorageMut + StorageSend,
+    FutureStorage: StorageMut,
+> Default for AsyncOnceSlot<'capture, Arg, Ret, FnStorage, FutureStorage>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: StorageMut + StorageSend,
+    FutureStorage: StorageMut,
+> fmt::Debug for AsyncOnceSlot<'capture, Arg, Ret, FnStorage, FutureStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AsyncOnceSlot").field(&self.0).finish()
+    }
+}
@@ -0,0 +1,92 @@
+use higher_kinded_types::ForFixed;
+use tokio::task::JoinHandle;
+
+use crate::{
+    DynAsyncFn, DynAsyncFnOnce,
+    storage::{StorageClone, StorageMut, StorageSend},
+};
+
+impl<
+    A: Send + 'static,
+    R: Send + 'static,
+    FnStorage: StorageMut + StorageSend + 'static,
+    FutureStorage: StorageMut + 'static,
+> DynAsyncFnOnce<'static, ForFixed<A>, ForFixed<R>, FnStorage, FutureStorage>
+{
+    /// Spawns the call of the function as a new [`tokio`] task.
+    pub fn spawn(self, arg: A) -> JoinHandle<R> {
+        tokio::spawn(self.call(arg))
+    }
+
+    /// Spawns the call of the function, with [`tokio::task::spawn_blocking`] if the function is
+    /// synchronous, or as a new [`tokio`] task otherwise.
+    ///
+    /// Synchronous functions may block, so they are not run on the asynchronous workers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dyn_fn::{DynAsyncFnOnce, hkt::ForFixed};
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let f = DynAsyncFnOnce::<ForFixed<u64>, ForFixed<u64>>::new_sync(|x, _| {
+    ///     std::thread::sleep(std::time::Duration::from_millis(x));
+    ///     x
+    /// });
+    /// // the blocking function doesn't block the runtime
+    /// assert_eq!(f.spawn_auto(10).await.unwrap(), 10);
+    /// # }
+    /// ```
+    pub fn spawn_auto(self, arg: A) -> JoinHandle<R> {
+        match self.try_into_sync() {
+            Ok(f) => tokio::task::spawn_blocking(move || f.call(arg)),
+            Err(this) => this.spawn(arg),
+        }
+    }
+}
+
+impl<
+    A: Send + 'static,
+    R: Send + 'static,
+    FnStorage: StorageClone + StorageSend + 'static,
+    FutureStorage: StorageMut + 'static,
+> DynAsyncFn<'static, ForFixed<A>, ForFixed<R>, FnStorage, FutureStorage>
+{
+    /// Spawns the call of a clone of the function as a new [`tokio`] task.
+    ///
+    /// The function storage must be shared, e.g. [`Arc`](crate::storage::Arc), so the
+    /// function is not cloned itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dyn_fn::{DynAsyncFn, hkt::ForFixed, storage};
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// type Callback = DynAsyncFn<'static, ForFixed<u64>, ForFixed<u64>, storage::Arc>;
+    /// let f = Callback::new_sync(|x, _| x + 1);
+    /// let tasks = [f.spawn_call(1), f.spawn_call(41)];
+    /// for (task, res) in tasks.into_iter().zip([2, 42]) {
+    ///     assert_eq!(task.await.unwrap(), res);
+    /// }
+    /// # }
+    /// ```
+    pub fn spawn_call(&self, arg: A) -> JoinHandle<R> {
+        let this = self.clone();
+        tokio::spawn(async move { this.call(arg).await })
+    }
+
+    /// Spawns the call of a clone of the function, with [`tokio::task::spawn_blocking`] if the
+    /// function is synchronous, or as a new [`tokio`] task otherwise.
+    ///
+    /// See [`spawn_call`](Self::spawn_call) and
+    /// [`DynAsyncFnOnce::spawn_auto`](crate::DynAsyncFnOnce::spawn_auto).
+    pub fn spawn_call_auto(&self, arg: A) -> JoinHandle<R> {
+        match self.clone().try_into_blocking() {
+            Ok(f) => tokio::task::spawn_blocking(move || f(arg)),
+            Err(this) => tokio::spawn(async move { this.call(arg).await }),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use core::{fmt, sync::atomic::Ordering};
+
+use higher_kinded_types::ForFixed;
+
+use crate::{
+    DynFnMut, ForLt,
+    primitives::{AtomicU32, UnsafeCell, spin_loop},
+    storage::{DefaultFnStorage, StorageMut, StorageSend},
+};
+
+/// Releases the lock when dropped, including when the function panics.
+struct Unlock<'a>(&'a AtomicU32);
+
+impl Drop for Unlock<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.store(0, Ordering::Release);
+    }
+}
+
+/// A [`DynFnMut`] callable through a shared reference, with calls serialized by a spinlock.
+///
+/// It shares a stateful callback between threads, or between the cores of a multicore
+/// microcontroller, without `std` nor allocation: with [`Raw`](crate::storage::Raw) storage, it
+/// can be used as a `static`. [`call`](Self::call) spins until the lock is free, then calls the
+/// function, so the captured state is never accessed concurrently. The lock is released even if
+/// the function panics, leaving the state as the function left it.
+///
+/// # Deadlocks
+///
+/// A spinlock only makes progress if its holder runs: if an interrupt handler calls
+/// [`call`](Self::call) while the code it interrupted on the same core holds the lock, the
+/// handler spins forever. Interrupt handlers must use [`try_call`](Self::try_call) instead, or
+/// rely on [`IsrCallback`](crate::IsrCallback), whose calls never block. For the same reason,
+/// the function must not call itself.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+///
+/// use dyn_fn::{DynFnMut, SpinDynFnMut, hkt::ForFixed, storage::Raw};
+///
+/// static TOTAL: SpinDynFnMut<ForFixed<u32>, ForFixed<u32>, Raw<4>> = SpinDynFnMut::new({
+///     let mut total = 0;
+///     DynFnMut::new_const(move |x, _| {
+///         total += x;
+///         total
+///     })
+/// });
+///
+/// let threads: Vec<_> = (0..4)
+///     .map(|_| {
+///         thread::spawn(|| {
+///             for _ in 0..100 {
+///                 TOTAL.call(1);
+///             }
+///         })
+///     })
+///     .collect();
+/// for thread in threads {
+///     thread.join().unwrap();
+/// }
+/// assert_eq!(TOTAL.call(0), 400);
+/// ```
+pub struct SpinDynFnMut<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: StorageMut + StorageSend = DefaultFnStorage,
+> {
+    locked: AtomicU32,
+    f: UnsafeCell<DynFnMut<'capture, Arg, Ret, FnStorage>>,
+}
+
+// SAFETY: the function is only accessed while holding the lock,
+// and `DynFnMut` is `Send`, so it can be accessed from any thread
+unsafe impl<Arg: ForLt, Ret: ForLt, FnStorage: StorageMut + StorageSend> Sync
+    for SpinDynFnMut<'_, Arg, Ret, FnStorage>
+{
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
+    SpinDynFnMut<'capture, Arg, Ret, FnStorage>
+{
+    /// Wraps a function.
+    #[cfg(not(loom))]
+    pub const fn new(f: DynFnMut<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self {
+            locked: AtomicU32::new(0),
+            f: UnsafeCell::new(f),
+        }
+    }
+
+    /// Wraps a function.
+    #[cfg(loom)]
+    pub fn new(f: DynFnMut<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self {
+            locked: AtomicU32::new(0),
+            f: UnsafeCell::new(f),
+        }
+    }
+
+    /// Calls the function, spinning until the calls in progress have completed.
+    ///
+    /// See the [deadlock](Self#deadlocks) caveat.
+    pub fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        while !self.try_lock() {
+            // spin on a load, so waiters don't contend on the cache line of the lock
+            while self.is_locked() {
+                spin_loop();
+            }
+        }
+        self.call_locked(arg)
+    }
+
+    /// Calls the function, unless a call is in progress.
+    ///
+    /// It never blocks, so it can be used from an interrupt handler.
+    pub fn try_call<'a>(&self, arg: Arg::Of<'a>) -> Option<Ret::Of<'a>> {
+        self.try_lock().then(|| self.call_locked(arg))
+    }
+
+    /// Returns whether a call is in progress.
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed) != 0
+    }
+
+    /// Returns the wrapped function.
+    pub fn into_inner(self) -> DynFnMut<'capture, Arg, Ret, FnStorage> {
+        self.f.into_inner()
+    }
+
+    fn try_lock(&self) -> bool {
+        (self.locked)
+            .compare_exchange(0, 1, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    /// Calls the function, the lock being held.
+    fn call_locked<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        let _unlock = Unlock(&self.locked);
+        // SAFETY: the lock is held until the call completes, so the function is accessed
+        // exclusively
+        self.f.with_mut(|f| unsafe { (*f).call(arg) })
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
+    From<DynFnMut<'capture, Arg, Ret, FnStorage>> for SpinDynFnMut<'capture, Arg, Ret, FnStorage>
+{
+    fn from(value: DynFnMut<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
+    fmt::Debug for SpinDynFnMut<'capture, Arg, Ret, FnStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpinDynFnMut")
+            .field("locked", &self.is_locked())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,145 @@
+use alloc::{sync::Arc, vec::Vec};
+use core::fmt;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use higher_kinded_types::ForFixed;
+
+use crate::{DynFn, DynFnMut, ForLt};
+
+struct SpyInner<Arg: ForLt + 'static, Ret: ForLt + 'static, T> {
+    project: fn(&Arg::Of<'_>) -> T,
+    f: DynFn<'static, Arg, Ret>,
+    calls: Mutex<Vec<T>>,
+}
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static, T> SpyInner<Arg, Ret, T> {
+    fn calls(&self) -> MutexGuard<'_, Vec<T>> {
+        // a panicking predicate must not prevent later assertions
+        self.calls.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        let recorded = (self.project)(&arg);
+        self.calls().push(recorded);
+        self.f.call(arg)
+    }
+}
+
+/// A test double recording the calls of the functions it provides, returning canned values.
+///
+/// Functions obtained with [`as_dyn_fn`](Self::as_dyn_fn)/[`as_dyn_fn_mut`](Self::as_dyn_fn_mut)
+/// record their arguments, and share their records with the spy. [`ForFixed`] arguments are
+/// recorded by cloning them, while other arguments, e.g. [`ForRef`](crate::hkt::ForRef), are
+/// recorded with an owned projection given at construction.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{DynFn, DynFnSpy, hkt::*};
+///
+/// fn notify_all(users: &[&str], notify: DynFn<'_, ForRef<str>, ForFixed<bool>>) -> usize {
+///     users.iter().filter(|user| notify.call(user)).count()
+/// }
+///
+/// let spy = DynFnSpy::<ForRef<str>, _, _>::return_const_projected(|s| s.len(), true);
+/// assert_eq!(notify_all(&["alice", "bob"], spy.as_dyn_fn()), 2);
+/// assert_eq!(spy.times_called(), 2);
+/// assert_eq!(spy.calls(), [5, 3]);
+/// assert!(spy.was_called_with(|&len| len == 3));
+/// ```
+pub struct DynFnSpy<Arg: ForLt + 'static, Ret: ForLt + 'static, T>(Arc<SpyInner<Arg, Ret, T>>);
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static, T: Send + 'static> DynFnSpy<Arg, Ret, T> {
+    /// Constructs a new spy recording the projection of the arguments, and calling `f`.
+    pub fn with_projection(project: fn(&Arg::Of<'_>) -> T, f: DynFn<'static, Arg, Ret>) -> Self {
+        Self(Arc::new(SpyInner {
+            project,
+            f,
+            calls: Mutex::new(Vec::new()),
+        }))
+    }
+
+    /// Returns a [`DynFn`] recording its calls in the spy.
+    pub fn as_dyn_fn(&self) -> DynFn<'static, Arg, Ret> {
+        let inner = self.0.clone();
+        DynFn::new(move |arg, _| inner.call(arg))
+    }
+
+    /// Returns a [`DynFnMut`] recording its calls in the spy.
+    pub fn as_dyn_fn_mut(&self) -> DynFnMut<'static, Arg, Ret> {
+        let inner = self.0.clone();
+        DynFnMut::new(move |arg, _| inner.call(arg))
+    }
+
+    /// Returns the number of calls.
+    pub fn times_called(&self) -> usize {
+        self.0.calls().len()
+    }
+
+    /// Returns the recorded arguments of the calls, in call order.
+    pub fn calls(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.0.calls().clone()
+    }
+
+    /// Returns whether a call has been made with a recorded argument matching `pred`.
+    pub fn was_called_with(&self, pred: impl Fn(&T) -> bool) -> bool {
+        self.0.calls().iter().any(pred)
+    }
+}
+
+impl<Arg: ForLt + 'static, R: Send + Sync + 'static, T: Send + 'static>
+    DynFnSpy<Arg, ForFixed<R>, T>
+{
+    /// Constructs a new spy recording the projection of the arguments, and returning the
+    /// result of `f`.
+    pub fn returning_projected(
+        project: fn(&Arg::Of<'_>) -> T,
+        f: impl for<'a> Fn(Arg::Of<'a>) -> R + Send + Sync + 'static,
+    ) -> Self {
+        Self::with_projection(project, DynFn::new(move |arg, _| f(arg)))
+    }
+
+    /// Constructs a new spy recording the projection of the arguments, and returning a clone
+    /// of `value`.
+    pub fn return_const_projected(project: fn(&Arg::Of<'_>) -> T, value: R) -> Self
+    where
+        R: Clone,
+    {
+        Self::returning_projected(project, move |_| value.clone())
+    }
+}
+
+impl<A: Clone + Send + 'static, R: Send + Sync + 'static> DynFnSpy<ForFixed<A>, ForFixed<R>, A> {
+    /// Constructs a new spy recording the arguments, and returning the result of `f`.
+    pub fn returning(f: impl Fn(A) -> R + Send + Sync + 'static) -> Self {
+        Self::returning_projected(A::clone, f)
+    }
+
+    /// Constructs a new spy recording the arguments, and returning a clone of `value`.
+    pub fn return_const(value: R) -> Self
+    where
+        R: Clone,
+    {
+        Self::return_const_projected(A::clone, value)
+    }
+}
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static, T> Clone for DynFnSpy<Arg, Ret, T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static, T: fmt::Debug> fmt::Debug
+    for DynFnSpy<Arg, Ret, T>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynFnSpy")
+            .field("calls", &*self.0.calls())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,149 @@
+use core::{marker::PhantomData, mem, ptr::NonNull};
+
+use higher_kinded_types::ForFixed;
+
+#[cfg(feature = "fn_type_name")]
+use crate::macros::impl_type_name;
+use crate::{
+    ForLt,
+    macros::{impl_clone, impl_debug, impl_id, unsafe_impl_send_sync},
+    storage::{DefaultFnStorage, DropVTable, DynStorage, Storage, StorageSend, VTable},
+};
+
+#[expect(type_alias_bounds)]
+type Call<Arg: ForLt, Ret: ForLt, T> =
+    for<'s, 'a> fn(NonNull<T>, Arg::Of<'a>, PhantomData<&'s ()>) -> Ret::Of<'s>;
+
+struct StatefulVTable<Arg: ForLt, Ret: ForLt, T = ()> {
+    call: Call<Arg, Ret, T>,
+    drop_vtable: DropVTable,
+}
+
+impl<Arg: ForLt + 'static, Ret: ForLt + 'static, T: 'static> VTable
+    for StatefulVTable<Arg, Ret, T>
+{
+    fn drop_vtable(&self) -> &DropVTable {
+        &self.drop_vtable
+    }
+}
+
+/// [`DynStatefulFn`], but without the [`Send`] + [`Sync`] requirement.
+pub struct LocalDynStatefulFn<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: Storage = DefaultFnStorage,
+> {
+    storage: DynStorage<FnStorage, StatefulVTable<Arg, Ret>>,
+    _capture: PhantomData<&'capture ()>,
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage>
+    LocalDynStatefulFn<'capture, Arg, Ret, FnStorage>
+{
+    /// # Safety
+    ///
+    /// `storage` must have been initialized with `(T, F)`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    const unsafe fn new_impl<
+        T: 'capture,
+        F: for<'s, 'a> Fn(&'s T, Arg::Of<'a>) -> Ret::Of<'s> + 'capture,
+    >(
+        storage: FnStorage,
+    ) -> Self {
+        let vtable = &StatefulVTable {
+            call: |func, arg, _| {
+                // SAFETY: func comes from `self.storage.ptr()`, so it's a valid `&(T, F)`
+                let (state, f) = unsafe { func.cast::<(T, F)>().as_ref() };
+                // SAFETY: the state is borrowed for the lifetime passed by `call`,
+                // which is the lifetime of `&self`, so it can be transmuted
+                unsafe { mem::transmute::<Ret::Of<'_>, Ret::Of<'_>>(f(state, arg)) }
+            },
+            drop_vtable: const { DropVTable::new::<FnStorage, (T, F)>() },
+        };
+        Self {
+            // SAFETY: `drop_vtable` matches the storage
+            storage: unsafe { DynStorage::new(storage, vtable) },
+            _capture: PhantomData,
+        }
+    }
+
+    /// Construct a new [`LocalDynStatefulFn`] from a state and a function taking it by
+    /// reference.
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    pub fn new<T: 'capture, F: for<'s, 'a> Fn(&'s T, Arg::Of<'a>) -> Ret::Of<'s> + 'capture>(
+        state: T,
+        f: F,
+    ) -> Self {
+        // SAFETY: storage is initialized with `(T, F)`
+        unsafe { Self::new_impl::<T, F>(FnStorage::new((state, f))) }
+    }
+
+    /// Calls the underlying function.
+    ///
+    /// The returned value may borrow from the function state.
+    pub fn call<'s>(&'s self, arg: Arg::Of<'_>) -> Ret::Of<'s> {
+        (self.storage.vtable().call)(self.storage.ptr(), arg, PhantomData)
+    }
+}
+
+impl_clone!(sync LocalDynStatefulFn, Storage);
+impl_debug!(sync LocalDynStatefulFn, Storage);
+impl_id!(sync LocalDynStatefulFn, Storage);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(sync LocalDynStatefulFn, Storage);
+
+/// A dynamic [`Fn`] with a state, stored in `FnStorage`.
+///
+/// Contrary to [`DynFn`](crate::DynFn), whose returned value can only borrow from the argument,
+/// the returned value can borrow from the state of the function.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{DynStatefulFn, hkt::*, storage};
+///
+/// type Greet = DynStatefulFn<'static, ForRef<str>, ForRef<str>, storage::Raw<32>>;
+/// let greet = Greet::new(String::from("Hello"), |greeting, name| {
+///     assert!(!name.is_empty());
+///     greeting.as_str()
+/// });
+/// assert_eq!(greet.call("World"), "Hello");
+/// ```
+pub struct DynStatefulFn<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: Storage + StorageSend = DefaultFnStorage,
+>(LocalDynStatefulFn<'capture, Arg, Ret, FnStorage>);
+
+unsafe_impl_send_sync!(sync DynStatefulFn, Storage);
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    DynStatefulFn<'capture, Arg, Ret, FnStorage>
+{
+    /// Construct a new [`DynStatefulFn`] from a state and a function taking it by reference.
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    pub fn new<
+        T: Send + Sync + 'capture,
+        F: for<'s, 'a> Fn(&'s T, Arg::Of<'a>) -> Ret::Of<'s> + Send + Sync + 'capture,
+    >(
+        state: T,
+        f: F,
+    ) -> Self {
+        Self(LocalDynStatefulFn::new(state, f))
+    }
+
+    /// Calls the underlying function.
+    ///
+    /// The returned value may borrow from the function state.
+    pub fn call<'s>(&'s self, arg: Arg::Of<'_>) -> Ret::Of<'s> {
+        self.0.call(arg)
+    }
+}
+
+impl_clone!(sync DynStatefulFn, Storage + StorageSend);
+impl_debug!(sync DynStatefulFn, Storage + StorageSend);
+impl_id!(sync DynStatefulFn, Storage + StorageSend);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(sync DynStatefulFn, Storage + StorageSend);
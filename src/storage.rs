@@ -4,20 +4,27 @@
 use alloc::{boxed::Box as StdBox, rc::Rc as StdRc, sync::Arc as StdArc};
 use core::{
     alloc::Layout,
+    cell::UnsafeCell,
+    fmt,
     marker::{PhantomData, PhantomPinned},
     mem,
     mem::{ManuallyDrop, MaybeUninit},
+    panic::{Location, RefUnwindSafe},
+    ptr,
     ptr::NonNull,
 };
 
 use elain::{Align, Alignment};
 
+use crate::{CallbackId, DynFnError, named::MaybeName};
+
 #[cfg(not(feature = "alloc"))]
 /// Default function storage.
 pub type DefaultFnStorage = Raw<{ size_of::<usize>() }>;
 #[cfg(feature = "alloc")]
 /// Default function storage.
 pub type DefaultFnStorage = Box;
+#[cfg(feature = "async")]
 /// Default future storage.
 pub type DefaultFutureStorage = RawOrBox<{ 16 * size_of::<usize>() }>;
 
@@ -27,13 +34,31 @@ pub trait Storage: private::Storage {}
 pub trait StorageMut: Storage {}
 /// A storage implementing [`Send`] + [`Sync`] if the stored object implements [`Send`] + [`Sync`].
 pub trait StorageSend: private::StorageSend {}
+/// A storage implementing [`Send`] if the stored object implements [`Send`], even if it doesn't
+/// implement [`Sync`].
+///
+/// Contrary to [`StorageSend`], it excludes shared storages like [`Arc`], whose clones would
+/// allow sharing a non-[`Sync`] object between threads.
+pub trait StorageSendOnly: private::StorageSendOnly {}
+/// A shared [`Storage`], e.g. [`Rc`] or [`Arc`], whose functions are cloned without cloning
+/// the stored object.
+pub trait StorageClone: Storage + private::StorageClone {}
 
 pub(crate) struct DropVTable {
     /// # Safety
     ///
     /// See [`private::Storage::drop_inner`].
     drop_inner: Option<unsafe fn(NonNull<()>)>,
+    /// # Safety
+    ///
+    /// See [`private::Storage::clone_inner`].
+    #[cfg(feature = "alloc")]
+    clone_inner: unsafe fn(NonNull<()>),
     layout: Layout,
+    #[cfg(feature = "fn_type_name")]
+    type_name: fn() -> &'static str,
+    /// See [`FnName`](crate::FnName).
+    name: Option<&'static str>,
 }
 
 impl DropVTable {
@@ -47,49 +72,178 @@ impl DropVTable {
                     None
                 }
             },
+            #[cfg(feature = "alloc")]
+            clone_inner: S::clone_inner::<T>,
             layout: const { Layout::new::<T>() },
+            #[cfg(feature = "fn_type_name")]
+            type_name: core::any::type_name::<T>,
+            name: None,
+        }
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))] // const fn
+    pub(crate) const fn with_name<N: MaybeName>(self) -> Self {
+        Self {
+            name: N::NAME,
+            ..self
         }
     }
 
+    pub(crate) fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    #[cfg(feature = "fn_type_name")]
+    pub(crate) fn type_name(&self) -> &'static str {
+        (self.type_name)()
+    }
+
+    /// Returns the suffix of panic messages about the stored data, with the function name if
+    /// any, and its type with `fn_type_name` feature.
+    pub(crate) fn panic_context(&self) -> PanicContext<'_> {
+        PanicContext(self)
+    }
+
     /// # Safety
     ///
     /// The vtable must match the data stored in the storage,
     /// and the storage must be accessed after the call.
     #[cfg_attr(coverage_nightly, coverage(off))]
-    pub(crate) unsafe fn drop_storage(&self, storage: &mut impl Storage) {
+    pub(crate) unsafe fn drop_storage<S: Storage>(&self, storage: &mut S) {
+        /// Releases the storage memory, even if the data drop unwinds.
+        struct DropInPlace<'a, S: Storage>(&'a mut S, Layout);
+        impl<S: Storage> Drop for DropInPlace<'_, S> {
+            fn drop(&mut self) {
+                // SAFETY: the storage data is no longer accessed after the call,
+                // and is matched by the vtable as per `drop_storage` contract.
+                unsafe { self.0.drop_in_place(self.1) };
+            }
+        }
+        let storage = DropInPlace(storage, self.layout);
         if let Some(drop_inner) = self.drop_inner {
             // SAFETY: the storage data is no longer accessed after the call,
             // and is matched by the vtable as per function contract.
-            unsafe { drop_inner(storage.ptr_mut()) };
+            unsafe { drop_inner(storage.0.ptr_mut()) };
         }
-        // SAFETY: the storage data is no longer accessed after the call,
-        // and is matched by the vtable as per function contract.
-        unsafe { storage.drop_in_place(self.layout) };
     }
 }
 
+/// See [`DropVTable::panic_context`].
+pub(crate) struct PanicContext<'a>(&'a DropVTable);
+
+impl fmt::Display for PanicContext<'_> {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = self.0.name() {
+            write!(f, " (function `{name}`)")?;
+        }
+        #[cfg(feature = "fn_type_name")]
+        write!(f, " (stored type: `{}`)", self.0.type_name())?;
+        Ok(())
+    }
+}
+
+/// The cache of a storage, see [`private::Storage::Cache`].
+#[cfg(feature = "async")]
+pub(crate) type StorageCache<S> = <S as private::Storage>::Cache;
+
 pub(crate) trait VTable: 'static {
     fn drop_vtable(&self) -> &DropVTable;
 }
 
+// `repr(C)`, so that the layout doesn't depend on the vtable signature, see `AnyDynFn`
 #[derive(Debug)]
+#[repr(C)]
 pub(crate) struct DynStorage<S: Storage, VT: VTable> {
     storage: S,
     vtable: &'static VT,
+    /// Where the function has been constructed.
+    #[cfg(feature = "track-construction")]
+    location: &'static Location<'static>,
 }
 
 impl<S: Storage, VT: VTable> DynStorage<S, VT> {
     /// # Safety
     ///
     /// `vtable.drop_vtable()` must match the data stored in `storage`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     pub(crate) const unsafe fn new(storage: S, vtable: &'static VT) -> Self {
-        Self { storage, vtable }
+        Self {
+            storage,
+            vtable,
+            #[cfg(feature = "track-construction")]
+            location: Location::caller(),
+        }
+    }
+
+    /// Rebuilds the storage with another storage or vtable, keeping its construction site.
+    ///
+    /// # Safety
+    ///
+    /// `vtable.drop_vtable()` must match the data stored in `storage`.
+    unsafe fn rebuild<S2: Storage, VT2: VTable>(
+        &self,
+        storage: S2,
+        vtable: &'static VT2,
+    ) -> DynStorage<S2, VT2> {
+        DynStorage {
+            storage,
+            vtable,
+            #[cfg(feature = "track-construction")]
+            location: self.location,
+        }
     }
 
+    #[inline]
     pub(crate) fn vtable(&self) -> &'static VT {
         self.vtable
     }
 
+    #[cfg(feature = "fn_type_name")]
+    pub(crate) fn type_name(&self) -> &'static str {
+        self.vtable.drop_vtable().type_name()
+    }
+
+    pub(crate) fn name(&self) -> Option<&'static str> {
+        self.vtable.drop_vtable().name()
+    }
+
+    #[cfg(feature = "track-construction")]
+    pub(crate) fn constructed_at(&self) -> Option<&'static Location<'static>> {
+        Some(self.location)
+    }
+
+    #[cfg(not(feature = "track-construction"))]
+    pub(crate) fn constructed_at(&self) -> Option<&'static Location<'static>> {
+        None
+    }
+
+    pub(crate) fn debug_fields(&self, debug: &mut fmt::DebugStruct<'_, '_>) {
+        debug.field("name", &self.name());
+        #[cfg(feature = "debug-type-names")]
+        debug.field("type_name", &self.type_name());
+        debug.field("storage", &self.storage.kind());
+        #[cfg(feature = "track-construction")]
+        debug.field("constructed_at", &format_args!("{}", self.location));
+    }
+
+    #[cfg(feature = "defmt")]
+    pub(crate) fn defmt_kind(&self) -> defmt::Str {
+        self.storage.defmt_kind()
+    }
+
+    pub(crate) fn id(&self) -> CallbackId {
+        CallbackId::new(self.storage.ptr().as_ptr(), self.vtable)
+    }
+
+    pub(crate) fn fmt_pointer(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "data@{:p} vtable@{:p}", self.storage.ptr(), self.vtable)
+    }
+
+    pub(crate) fn same_callee(&self, other: &Self) -> bool {
+        ptr::eq(self.vtable, other.vtable) && self.storage.heap_ptr() == other.storage.heap_ptr()
+    }
+
     /// # Safety
     ///
     /// The returned storage must be used only to instantiate `StorageMoved`.
@@ -97,13 +251,69 @@ impl<S: Storage, VT: VTable> DynStorage<S, VT> {
         (&mut this.storage).into()
     }
 
+    #[inline]
     pub(crate) fn ptr<T>(&self) -> NonNull<T> {
         self.storage.ptr().cast()
     }
 
+    #[inline]
     pub(crate) fn ptr_mut<T>(&mut self) -> NonNull<T> {
         self.storage.ptr_mut().cast()
     }
+
+    /// Replaces the vtable of the stored data.
+    ///
+    /// # Safety
+    ///
+    /// `vtable.drop_vtable()` must match the data stored in the storage.
+    #[cfg(feature = "async")]
+    pub(crate) unsafe fn with_vtable<VT2: VTable>(
+        self,
+        vtable: &'static VT2,
+    ) -> DynStorage<S, VT2> {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is not dropped, so the storage is read only once
+        let storage = unsafe { ptr::read(&this.storage) };
+        // SAFETY: same precondition
+        unsafe { this.rebuild(storage, vtable) }
+    }
+
+    pub(crate) fn into_storage<S2: Storage + From<S>>(self) -> DynStorage<S2, VT> {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is not dropped, so the storage is read only once
+        let storage = S2::from(unsafe { ptr::read(&this.storage) });
+        // SAFETY: `From` conversions between storages preserve the stored data,
+        // and the drop vtable is compatible, as per `Storage` contract
+        unsafe { this.rebuild(storage, this.vtable) }
+    }
+}
+
+impl<const SIZE: usize, const ALIGN: usize, VT: VTable> DynStorage<Raw<SIZE, ALIGN>, VT>
+where
+    Align<ALIGN>: Alignment,
+{
+    /// Moves the stored data into a [`Raw`] storage of another size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stored data doesn't fit in the new storage.
+    #[track_caller]
+    pub(crate) fn resize<const M: usize>(self) -> DynStorage<Raw<M, ALIGN>, VT> {
+        let this = ManuallyDrop::new(self);
+        let drop_vtable = this.vtable.drop_vtable();
+        let size = drop_vtable.layout.size();
+        assert!(
+            size <= M,
+            "stored data size ({size}) exceeds storage size ({M}){}",
+            drop_vtable.panic_context()
+        );
+        // SAFETY: the data is stored in `this.storage`, so its size is lesser or equal to `SIZE`,
+        // and the assertion above ensures it's lesser or equal to `M`
+        let storage = unsafe { this.storage.resize::<M>(size) };
+        // SAFETY: the data has been copied into the new storage, and `Raw` drop vtable doesn't
+        // depend on the storage size; the old storage is not dropped, as it's a `ManuallyDrop`
+        unsafe { this.rebuild(storage, this.vtable) }
+    }
 }
 
 impl<S: Storage, VT: VTable> Drop for DynStorage<S, VT> {
@@ -115,15 +325,24 @@ impl<S: Storage, VT: VTable> Drop for DynStorage<S, VT> {
 }
 
 #[cfg(feature = "alloc")]
-impl<S: Storage + Clone, VT: VTable> Clone for DynStorage<S, VT> {
+impl<S: StorageClone, VT: VTable> Clone for DynStorage<S, VT> {
     fn clone(&self) -> Self {
+        // SAFETY: `Self::new` ensures the vtable matches the data stored
+        unsafe { (self.vtable.drop_vtable().clone_inner)(self.storage.ptr()) };
         Self {
-            storage: self.storage.clone(),
+            storage: self.storage.clone_ref(),
             vtable: self.vtable,
+            #[cfg(feature = "track-construction")]
+            location: self.location,
         }
     }
 }
 
+/// Guard releasing the storage memory of a moved-out `T`.
+///
+/// [`read`](Self::read) takes `&self`, so the guard is still dropped after the data has been
+/// read, including when the function called with it unwinds; the memory is then released
+/// exactly once, while `T` itself is dropped by its new owner.
 pub(crate) struct StorageMoved<S: StorageMut, T> {
     storage: NonNull<S>,
     _phantom: PhantomData<T>,
@@ -170,7 +389,9 @@ pub struct Raw<const SIZE: usize, const ALIGN: usize = { align_of::<usize>() }>
 where
     Align<ALIGN>: Alignment,
 {
-    data: MaybeUninit<[u8; SIZE]>,
+    // `UnsafeCell` allows mutating data through the pointer returned by `Storage::ptr`,
+    // e.g. when calling a `Fn` closure capturing a `Cell` by value
+    data: UnsafeCell<MaybeUninit<[u8; SIZE]>>,
     _align: Align<ALIGN>,
     _not_send_sync: PhantomData<*mut ()>,
     _pinned: PhantomPinned,
@@ -185,14 +406,36 @@ where
     /// `data` must have size and alignment lesser or equal to the generic parameters.
     const unsafe fn new_unchecked<T>(data: T) -> Self {
         let mut raw = Self {
-            data: MaybeUninit::uninit(),
+            data: UnsafeCell::new(MaybeUninit::uninit()),
             _align: Align::NEW,
             _not_send_sync: PhantomData,
             _pinned: PhantomPinned,
         };
         // SAFETY: function contract guarantees that `raw.data` size and alignment
         // matches `data` ones; alignment is obtained through `_align` field and `repr(C)`
-        unsafe { raw.data.as_mut_ptr().cast::<T>().write(data) };
+        unsafe { raw.data.get_mut().as_mut_ptr().cast::<T>().write(data) };
+        raw
+    }
+
+    /// # Safety
+    ///
+    /// `size` must be lesser or equal to both `SIZE` and `M`.
+    unsafe fn resize<const M: usize>(&self, size: usize) -> Raw<M, ALIGN> {
+        let mut raw = Raw {
+            data: UnsafeCell::new(MaybeUninit::uninit()),
+            _align: Align::NEW,
+            _not_send_sync: PhantomData,
+            _pinned: PhantomPinned,
+        };
+        // SAFETY: function contract guarantees that `size` bytes can be read from `self.data`
+        // and written to `raw.data`
+        unsafe {
+            ptr::copy_nonoverlapping(
+                self.data.get().cast_const().cast::<u8>(),
+                raw.data.get_mut().as_mut_ptr().cast::<u8>(),
+                size,
+            );
+        }
         raw
     }
 
@@ -202,8 +445,27 @@ where
         // SAFETY: assertion above ensures function contract
         unsafe { Self::new_unchecked::<T>(data) }
     }
+
+    pub(crate) fn try_new<T>(data: T) -> Result<Self, DynFnError> {
+        let required = Layout::new::<T>();
+        let capacity = Layout::from_size_align(SIZE, ALIGN).unwrap();
+        if required.size() > capacity.size() {
+            return Err(DynFnError::TooLarge { required, capacity });
+        }
+        if required.align() > capacity.align() {
+            return Err(DynFnError::Misaligned { required, capacity });
+        }
+        // SAFETY: size and alignment are checked above
+        Ok(unsafe { Self::new_unchecked::<T>(data) })
+    }
 }
 
+// `UnsafeCell` is an implementation detail, it must not make `Raw` less unwind safe
+// than pointer-based storages.
+impl<const SIZE: usize, const ALIGN: usize> RefUnwindSafe for Raw<SIZE, ALIGN> where
+    Align<ALIGN>: Alignment
+{
+}
 impl<const SIZE: usize, const ALIGN: usize> Storage for Raw<SIZE, ALIGN> where
     Align<ALIGN>: Alignment
 {
@@ -216,6 +478,10 @@ impl<const SIZE: usize, const ALIGN: usize> StorageSend for Raw<SIZE, ALIGN> whe
     Align<ALIGN>: Alignment
 {
 }
+impl<const SIZE: usize, const ALIGN: usize> StorageSendOnly for Raw<SIZE, ALIGN> where
+    Align<ALIGN>: Alignment
+{
+}
 
 /// A type-erased [`Box`](StdBox).
 #[cfg(feature = "alloc")]
@@ -233,6 +499,8 @@ impl Storage for Box {}
 impl StorageMut for Box {}
 #[cfg(feature = "alloc")]
 impl StorageSend for Box {}
+#[cfg(feature = "alloc")]
+impl StorageSendOnly for Box {}
 
 /// A type-erased [`Rc`](StdRc).
 #[cfg(feature = "alloc")]
@@ -245,17 +513,9 @@ impl Rc {
     }
 }
 #[cfg(feature = "alloc")]
-impl Clone for Rc {
-    fn clone(&self) -> Self {
-        // SAFETY: The pointer has been obtained through `Rc::into_raw`,
-        // and the `Rc` instance is still valid because strong
-        // count is only decremented in drop.
-        unsafe { StdRc::increment_strong_count(self.0.as_ptr()) };
-        Self(self.0)
-    }
-}
-#[cfg(feature = "alloc")]
 impl Storage for Rc {}
+#[cfg(feature = "alloc")]
+impl StorageClone for Rc {}
 
 /// A type-erased [`Arc`](StdArc).
 #[cfg(feature = "alloc")]
@@ -268,20 +528,54 @@ impl Arc {
     }
 }
 #[cfg(feature = "alloc")]
-impl Clone for Arc {
-    fn clone(&self) -> Self {
-        // SAFETY: The pointer has been obtained through `Arc::into_raw`,
-        // and the `Arc` instance is still valid because strong
-        // count is only decremented in drop.
-        unsafe { StdArc::increment_strong_count(self.0.as_ptr()) };
-        Self(self.0)
-    }
-}
-#[cfg(feature = "alloc")]
 impl Storage for Arc {}
 #[cfg(feature = "alloc")]
+impl StorageClone for Arc {}
+#[cfg(feature = "alloc")]
 impl StorageSend for Arc {}
 
+/// A [`Box`] future storage reusing the allocation of the previous future.
+///
+/// When a future stored in it is dropped, its allocation is kept in the function handle, and
+/// reused by the next call if the layout matches, so a function called in a loop doesn't allocate
+/// in steady state. Only one allocation is cached: concurrent calls of a [`DynAsyncFn`] fall back
+/// to a fresh allocation. The cached allocation is freed with the function.
+///
+/// Used as function storage, it behaves like [`Box`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "async")] {
+/// use dyn_fn::{LocalDynAsyncFn, hkt::ForFixed, storage};
+/// use futures_util::FutureExt;
+///
+/// type Callback<'a> =
+///     LocalDynAsyncFn<'a, ForFixed<u64>, ForFixed<u64>, storage::Box, storage::BoxCached>;
+/// let f = Callback::new(async |x, _| x + 1);
+/// for _ in 0..3 {
+///     // the future allocation is reused after the first call
+///     assert_eq!(f.call(41).now_or_never(), Some(42));
+/// }
+/// # }
+/// ```
+///
+/// [`DynAsyncFn`]: crate::DynAsyncFn
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct BoxCached {
+    ptr: NonNull<()>,
+    cache: Option<NonNull<private::BoxCache>>,
+}
+#[cfg(feature = "alloc")]
+impl Storage for BoxCached {}
+#[cfg(feature = "alloc")]
+impl StorageMut for BoxCached {}
+#[cfg(feature = "alloc")]
+impl StorageSend for BoxCached {}
+#[cfg(feature = "alloc")]
+impl StorageSendOnly for BoxCached {}
+
 #[derive(Debug)]
 enum RawOrBoxInner<const SIZE: usize, const ALIGN: usize = { align_of::<usize>() }>
 where
@@ -316,6 +610,24 @@ where
     }
 }
 
+impl<const SIZE: usize, const ALIGN: usize> From<Raw<SIZE, ALIGN>> for RawOrBox<SIZE, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    fn from(value: Raw<SIZE, ALIGN>) -> Self {
+        Self(RawOrBoxInner::Raw(value))
+    }
+}
+#[cfg(feature = "alloc")]
+impl<const SIZE: usize, const ALIGN: usize> From<Box> for RawOrBox<SIZE, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    fn from(value: Box) -> Self {
+        Self(RawOrBoxInner::Box(value))
+    }
+}
+
 impl<const SIZE: usize, const ALIGN: usize> Storage for RawOrBox<SIZE, ALIGN> where
     Align<ALIGN>: Alignment
 {
@@ -328,22 +640,60 @@ impl<const SIZE: usize, const ALIGN: usize> StorageSend for RawOrBox<SIZE, ALIGN
     Align<ALIGN>: Alignment
 {
 }
+impl<const SIZE: usize, const ALIGN: usize> StorageSendOnly for RawOrBox<SIZE, ALIGN> where
+    Align<ALIGN>: Alignment
+{
+}
 
 pub(crate) mod private {
     #[cfg(feature = "alloc")]
     use alloc::{boxed::Box, rc::Rc, sync::Arc};
     use core::{alloc::Layout, ptr::NonNull};
+    #[cfg(feature = "alloc")]
+    use core::{
+        ptr,
+        sync::atomic::{AtomicPtr, Ordering},
+    };
 
     use elain::{Align, Alignment};
 
+    use crate::DynFnError;
+
     /// # Safety
     ///
     /// `ptr`/`ptr_mut` must return a pointer to the data stored in the storage.
+    /// `From` conversions between storages must preserve the stored data, and the
+    /// [`DropVTable`](super::DropVTable) of the source storage must be valid for the target one.
     pub unsafe trait Storage: Sized + 'static {
         const NEEDS_DROP_INNER: bool = false;
+        /// Whether the storage pointer comes from `Arc::into_raw`, so the reference count can be
+        /// shared, e.g. by wakers.
+        const IS_ARC: bool = false;
+        /// Cache kept in the function handle, and passed when storing its returned futures.
+        type Cache;
+        /// The initial value of the cache, a constant for functions to be built in const context.
+        const EMPTY_CACHE: Self::Cache;
         fn new<T>(data: T) -> Self;
+        fn try_new<T>(data: T) -> Result<Self, DynFnError> {
+            Ok(Self::new(data))
+        }
+        /// # Safety
+        ///
+        /// The storage must be dropped before `cache`.
+        unsafe fn new_cached<T>(data: T, _cache: &Self::Cache) -> Self {
+            Self::new(data)
+        }
         fn ptr(&self) -> NonNull<()>;
         fn ptr_mut(&mut self) -> NonNull<()>;
+        /// Returns the storage kind, used in `Debug` output.
+        fn kind(&self) -> &'static str;
+        /// [`kind`](Self::kind), interned for `defmt::Format` output.
+        #[cfg(feature = "defmt")]
+        fn defmt_kind(&self) -> defmt::Str;
+        /// Returns the pointer to the data if it's allocated outside the storage.
+        fn heap_ptr(&self) -> Option<NonNull<()>> {
+            Some(self.ptr())
+        }
         /// # Safety
         ///
         /// `ptr_mut` must have been obtained from `Storage::ptr_mut`.
@@ -354,6 +704,15 @@ pub(crate) mod private {
             // and is no longer accessed after the call.
             unsafe { ptr_mut.cast::<T>().drop_in_place() }
         }
+        /// Called when a `StorageClone` storage is cloned,
+        /// e.g. to increment the reference count of a shared storage.
+        ///
+        /// # Safety
+        ///
+        /// `ptr` must have been obtained from `Storage::ptr`.
+        /// Storage must have been instantiated with a data of type `T`.
+        #[cfg_attr(coverage_nightly, coverage(off))]
+        unsafe fn clone_inner<T>(_ptr: NonNull<()>) {}
         /// # Safety
         ///
         /// `drop_in_place` must be called once, and the storage must not be used
@@ -368,20 +727,55 @@ pub(crate) mod private {
     /// implements `Send` + `Sync`.
     pub unsafe trait StorageSend {}
 
+    /// # Safety
+    ///
+    /// The underlying storage must implement `Send` if the stored data implements `Send`, and
+    /// must not share the stored data between its clones.
+    pub unsafe trait StorageSendOnly {}
+
+    /// # Safety
+    ///
+    /// `clone_ref` must return a storage pointing to the same data, which must be kept alive
+    /// by `clone_inner` until all the storages are dropped.
+    pub unsafe trait StorageClone: Storage {
+        /// Returns a storage pointing to the same data.
+        ///
+        /// The data is not cloned, and the reference count of a shared storage can only be
+        /// incremented with the stored type, so it is done by `clone_inner`.
+        fn clone_ref(&self) -> Self;
+    }
+
     // SAFETY: `ptr`/`ptr_mut` return a pointer to the stored data.
     unsafe impl<const SIZE: usize, const ALIGN: usize> Storage for super::Raw<SIZE, ALIGN>
     where
         Align<ALIGN>: Alignment,
     {
+        type Cache = ();
+        const EMPTY_CACHE: Self::Cache = ();
         fn new<T>(data: T) -> Self {
             Self::new(data)
         }
+        fn try_new<T>(data: T) -> Result<Self, DynFnError> {
+            Self::try_new(data)
+        }
+        #[inline]
         fn ptr(&self) -> NonNull<()> {
             NonNull::from(&self.data).cast()
         }
+        #[inline]
         fn ptr_mut(&mut self) -> NonNull<()> {
             NonNull::from(&mut self.data).cast()
         }
+        fn kind(&self) -> &'static str {
+            "Raw"
+        }
+        #[cfg(feature = "defmt")]
+        fn defmt_kind(&self) -> defmt::Str {
+            defmt::intern!("Raw")
+        }
+        fn heap_ptr(&self) -> Option<NonNull<()>> {
+            None
+        }
         unsafe fn drop_in_place(&mut self, _layout: Layout) {}
     }
 
@@ -390,23 +784,56 @@ pub(crate) mod private {
         Align<ALIGN>: Alignment
     {
     }
+    // SAFETY: Raw storage has the same guarantee the data it stores, and clones the data with it.
+    unsafe impl<const SIZE: usize, const ALIGN: usize> StorageSendOnly for super::Raw<SIZE, ALIGN> where
+        Align<ALIGN>: Alignment
+    {
+    }
 
     // SAFETY: `ptr`/`ptr_mut` return a pointer to the stored data.
     #[cfg(feature = "alloc")]
     unsafe impl Storage for super::Box {
+        type Cache = ();
+        const EMPTY_CACHE: Self::Cache = ();
         fn new<T>(data: T) -> Self {
             Self::new_box(Box::new(data))
         }
+        fn try_new<T>(data: T) -> Result<Self, DynFnError> {
+            let layout = Layout::new::<T>();
+            if layout.size() == 0 {
+                return Ok(Self::new(data));
+            }
+            // SAFETY: layout has a non-zero size
+            let ptr = unsafe { alloc::alloc::alloc(layout) }.cast::<T>();
+            let ptr = NonNull::new(ptr).ok_or(DynFnError::AllocFailed { layout })?;
+            // SAFETY: `ptr` has been allocated with the layout of `T`
+            unsafe { ptr.write(data) };
+            // The allocation is the same as the one of a `Box<T>`, as documented in `Box` memory
+            // layout section, so it can be freed the same way
+            Ok(Self(ptr.cast()))
+        }
+        #[inline]
         fn ptr(&self) -> NonNull<()> {
             self.0
         }
+        #[inline]
         fn ptr_mut(&mut self) -> NonNull<()> {
             self.0
         }
+        fn kind(&self) -> &'static str {
+            "Box"
+        }
+        #[cfg(feature = "defmt")]
+        fn defmt_kind(&self) -> defmt::Str {
+            defmt::intern!("Box")
+        }
         unsafe fn drop_in_place(&mut self, layout: Layout) {
             if layout.size() != 0 {
-                // SAFETY: storage has been initialized with `Box<T>`,
-                // and `layout` must be `Layout::new::<T>()` as per function contract
+                // SAFETY: storage has been initialized with `Box<T>`, and `layout` must be
+                // `Layout::new::<T>()` as per function contract; non zero-sized boxes are
+                // allocated with the global allocator and this layout, as documented in
+                // `Box` memory layout section, and `self.0` is the pointer returned by
+                // `Box::into_raw`, with its provenance
                 unsafe { alloc::alloc::dealloc(self.0.as_ptr().cast(), layout) };
             }
         }
@@ -414,50 +841,251 @@ pub(crate) mod private {
     // SAFETY: Box has the same guarantee the data it stores.
     #[cfg(feature = "alloc")]
     unsafe impl StorageSend for super::Box {}
+    // SAFETY: Box has the same guarantee the data it stores, and clones the data with it.
+    #[cfg(feature = "alloc")]
+    unsafe impl StorageSendOnly for super::Box {}
 
     // SAFETY: `ptr`/`ptr_mut` return a pointer to the stored data.
     #[cfg(feature = "alloc")]
     unsafe impl Storage for super::Rc {
         const NEEDS_DROP_INNER: bool = true;
+        type Cache = ();
+        const EMPTY_CACHE: Self::Cache = ();
         fn new<T>(data: T) -> Self {
             Self::new_rc(Rc::new(data))
         }
+        #[inline]
         fn ptr(&self) -> NonNull<()> {
             self.0
         }
+        #[inline]
         fn ptr_mut(&mut self) -> NonNull<()> {
             self.0
         }
+        fn kind(&self) -> &'static str {
+            "Rc"
+        }
+        #[cfg(feature = "defmt")]
+        fn defmt_kind(&self) -> defmt::Str {
+            defmt::intern!("Rc")
+        }
         unsafe fn drop_inner<T>(ptr_mut: NonNull<()>) {
             // SAFETY: storage has been initialized with `Rc<T>`
             drop(unsafe { Rc::<T>::from_raw(ptr_mut.cast().as_ptr()) });
         }
+        unsafe fn clone_inner<T>(ptr: NonNull<()>) {
+            // SAFETY: storage has been initialized with `Rc<T>`, and the `Rc` instance
+            // is still valid because strong count is only decremented in drop
+            unsafe { Rc::<T>::increment_strong_count(ptr.cast().as_ptr()) };
+        }
         unsafe fn drop_in_place(&mut self, _layout: Layout) {}
     }
 
+    // SAFETY: clones point to the same `Rc` allocation, whose strong count is incremented by
+    // `clone_inner`.
+    #[cfg(feature = "alloc")]
+    unsafe impl StorageClone for super::Rc {
+        fn clone_ref(&self) -> Self {
+            Self(self.0)
+        }
+    }
+
     // SAFETY: `ptr`/`ptr_mut` return a pointer to the stored data.
     #[cfg(feature = "alloc")]
     unsafe impl Storage for super::Arc {
         const NEEDS_DROP_INNER: bool = true;
+        const IS_ARC: bool = true;
+        type Cache = ();
+        const EMPTY_CACHE: Self::Cache = ();
         fn new<T>(data: T) -> Self {
             Self::new_arc(Arc::new(data))
         }
+        #[inline]
         fn ptr(&self) -> NonNull<()> {
             self.0
         }
+        #[inline]
         fn ptr_mut(&mut self) -> NonNull<()> {
             self.0
         }
+        fn kind(&self) -> &'static str {
+            "Arc"
+        }
+        #[cfg(feature = "defmt")]
+        fn defmt_kind(&self) -> defmt::Str {
+            defmt::intern!("Arc")
+        }
         unsafe fn drop_inner<T>(ptr_mut: NonNull<()>) {
             // SAFETY: storage has been initialized with `Arc<T>`
             drop(unsafe { Arc::<T>::from_raw(ptr_mut.cast().as_ptr()) });
         }
+        unsafe fn clone_inner<T>(ptr: NonNull<()>) {
+            // SAFETY: storage has been initialized with `Arc<T>`, and the `Arc` instance
+            // is still valid because strong count is only decremented in drop
+            unsafe { Arc::<T>::increment_strong_count(ptr.cast().as_ptr()) };
+        }
         unsafe fn drop_in_place(&mut self, _layout: Layout) {}
     }
 
     // SAFETY: `Arc` implements `Send` + `Sync` when data implements `Send` + `Sync`
     #[cfg(feature = "alloc")]
     unsafe impl StorageSend for super::Arc {}
+    // SAFETY: clones point to the same `Arc` allocation, whose strong count is incremented by
+    // `clone_inner`.
+    #[cfg(feature = "alloc")]
+    unsafe impl StorageClone for super::Arc {
+        fn clone_ref(&self) -> Self {
+            Self(self.0)
+        }
+    }
+
+    /// The allocation cache of [`BoxCached`](super::BoxCached).
+    ///
+    /// A released allocation stores its own layout, so it can be checked by the next call, or freed
+    /// with the cache.
+    #[cfg(feature = "alloc")]
+    #[derive(Debug)]
+    pub struct BoxCache(AtomicPtr<u8>);
+
+    #[cfg(feature = "alloc")]
+    impl BoxCache {
+        /// Takes the cached allocation if it matches `layout`, freeing it otherwise.
+        #[inline]
+        fn take(&self, layout: Layout) -> Option<NonNull<u8>> {
+            let block = NonNull::new(self.0.swap(ptr::null_mut(), Ordering::Acquire))?;
+            // SAFETY: cached allocations store their layout, see `Self::release`
+            let cached = unsafe { block.cast::<Layout>().read() };
+            if cached == layout {
+                return Some(block);
+            }
+            // SAFETY: the allocation has been allocated with `cached` layout
+            unsafe { alloc::alloc::dealloc(block.as_ptr(), cached) };
+            None
+        }
+
+        /// Caches the allocation, or frees it if it cannot store its layout, or if the cache is
+        /// already full.
+        ///
+        /// # Safety
+        ///
+        /// `block` must have been allocated by the global allocator with `layout`,
+        /// and must not be used after the call.
+        #[inline]
+        unsafe fn release(&self, block: NonNull<u8>, layout: Layout) {
+            if layout.size() >= size_of::<Layout>() && layout.align() >= align_of::<Layout>() {
+                // SAFETY: the allocation is not used anymore, and can store an aligned `Layout`
+                unsafe { block.cast::<Layout>().write(layout) };
+                let cached = self.0.compare_exchange(
+                    ptr::null_mut(),
+                    block.as_ptr(),
+                    Ordering::Release,
+                    Ordering::Relaxed,
+                );
+                if cached.is_ok() {
+                    return;
+                }
+            }
+            // SAFETY: the allocation has been allocated with `layout`, as per function contract
+            unsafe { alloc::alloc::dealloc(block.as_ptr(), layout) };
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl Drop for BoxCache {
+        fn drop(&mut self) {
+            if let Some(block) = NonNull::new(*self.0.get_mut()) {
+                // SAFETY: cached allocations store their layout, see `Self::release`
+                let layout = unsafe { block.cast::<Layout>().read() };
+                // SAFETY: the allocation has been allocated with its stored layout
+                unsafe { alloc::alloc::dealloc(block.as_ptr(), layout) };
+            }
+        }
+    }
+
+    // SAFETY: `ptr`/`ptr_mut` return a pointer to the stored data.
+    #[cfg(feature = "alloc")]
+    unsafe impl Storage for super::BoxCached {
+        type Cache = BoxCache;
+        #[expect(clippy::declare_interior_mutable_const)]
+        const EMPTY_CACHE: Self::Cache = BoxCache(AtomicPtr::new(ptr::null_mut()));
+        fn new<T>(data: T) -> Self {
+            Self {
+                ptr: super::Box::new(data).0,
+                cache: None,
+            }
+        }
+        fn try_new<T>(data: T) -> Result<Self, DynFnError> {
+            Ok(Self {
+                ptr: super::Box::try_new(data)?.0,
+                cache: None,
+            })
+        }
+        unsafe fn new_cached<T>(data: T, cache: &Self::Cache) -> Self {
+            let ptr = match cache.take(Layout::new::<T>()) {
+                Some(block) => {
+                    // SAFETY: the allocation has been allocated with `T` layout
+                    unsafe { block.cast::<T>().write(data) };
+                    block.cast()
+                }
+                None => super::Box::new(data).0,
+            };
+            Self {
+                ptr,
+                cache: Some(cache.into()),
+            }
+        }
+        #[inline]
+        fn ptr(&self) -> NonNull<()> {
+            self.ptr
+        }
+        #[inline]
+        fn ptr_mut(&mut self) -> NonNull<()> {
+            self.ptr
+        }
+        fn kind(&self) -> &'static str {
+            "BoxCached"
+        }
+        #[cfg(feature = "defmt")]
+        fn defmt_kind(&self) -> defmt::Str {
+            defmt::intern!("BoxCached")
+        }
+        unsafe fn drop_in_place(&mut self, layout: Layout) {
+            match self.cache {
+                Some(cache) if layout.size() != 0 => {
+                    // SAFETY: the cache outlives the storage, as per `new_cached` contract;
+                    // the allocation has been allocated like `Box<T>` with `layout`, as per
+                    // function contract, and it's not used anymore
+                    unsafe { cache.as_ref().release(self.ptr.cast(), layout) };
+                }
+                // SAFETY: same precondition, the allocation being the one of a `Box`
+                _ => unsafe { super::Box(self.ptr).drop_in_place(layout) },
+            }
+        }
+    }
+    // SAFETY: BoxCached has the same guarantee the data it stores, its cache being `Sync`.
+    #[cfg(feature = "alloc")]
+    unsafe impl StorageSend for super::BoxCached {}
+    // SAFETY: BoxCached has the same guarantee the data it stores, its cache holding no data.
+    #[cfg(feature = "alloc")]
+    unsafe impl StorageSendOnly for super::BoxCached {}
+
+    /// Marks the spilled path of [`RawOrBox`](super::RawOrBox) as unlikely, the inline one being
+    /// expected to be the common case.
+    #[cfg(feature = "alloc")]
+    #[cold]
+    #[inline(always)]
+    fn cold() {}
+
+    /// # Safety
+    ///
+    /// See [`Storage::drop_in_place`].
+    #[cfg(feature = "alloc")]
+    #[cold]
+    #[inline(never)]
+    unsafe fn drop_box(storage: &mut super::Box, layout: Layout) {
+        // SAFETY: same precondition
+        unsafe { storage.drop_in_place(layout) }
+    }
 
     // SAFETY: Both `Raw` and `Box` implements `Storage`
     // This enum is generic and the variant is chosen according constant predicate,
@@ -468,6 +1096,8 @@ pub(crate) mod private {
     where
         Align<ALIGN>: Alignment,
     {
+        type Cache = ();
+        const EMPTY_CACHE: Self::Cache = ();
         fn new<T>(data: T) -> Self {
             #[cfg(feature = "alloc")]
             if size_of::<T>() <= SIZE && align_of::<T>() <= ALIGN {
@@ -483,18 +1113,63 @@ pub(crate) mod private {
                 Self(super::RawOrBoxInner::Raw(super::Raw::new(data)))
             }
         }
+        fn try_new<T>(data: T) -> Result<Self, DynFnError> {
+            #[cfg(feature = "alloc")]
+            if size_of::<T>() <= SIZE && align_of::<T>() <= ALIGN {
+                // SAFETY: size and alignment are checked above
+                Ok(Self(super::RawOrBoxInner::Raw(unsafe {
+                    super::Raw::new_unchecked(data)
+                })))
+            } else {
+                Ok(Self(super::RawOrBoxInner::Box(super::Box::try_new(data)?)))
+            }
+            #[cfg(not(feature = "alloc"))]
+            {
+                Ok(Self(super::RawOrBoxInner::Raw(super::Raw::try_new(data)?)))
+            }
+        }
+        #[inline]
         fn ptr(&self) -> NonNull<()> {
             match &self.0 {
                 super::RawOrBoxInner::Raw(s) => s.ptr(),
                 #[cfg(feature = "alloc")]
-                super::RawOrBoxInner::Box(s) => s.ptr(),
+                super::RawOrBoxInner::Box(s) => {
+                    cold();
+                    s.ptr()
+                }
             }
         }
+        #[inline]
         fn ptr_mut(&mut self) -> NonNull<()> {
             match &mut self.0 {
                 super::RawOrBoxInner::Raw(s) => s.ptr_mut(),
                 #[cfg(feature = "alloc")]
-                super::RawOrBoxInner::Box(s) => s.ptr_mut(),
+                super::RawOrBoxInner::Box(s) => {
+                    cold();
+                    s.ptr_mut()
+                }
+            }
+        }
+        fn kind(&self) -> &'static str {
+            match &self.0 {
+                super::RawOrBoxInner::Raw(s) => s.kind(),
+                #[cfg(feature = "alloc")]
+                super::RawOrBoxInner::Box(s) => s.kind(),
+            }
+        }
+        #[cfg(feature = "defmt")]
+        fn defmt_kind(&self) -> defmt::Str {
+            match &self.0 {
+                super::RawOrBoxInner::Raw(s) => s.defmt_kind(),
+                #[cfg(feature = "alloc")]
+                super::RawOrBoxInner::Box(s) => s.defmt_kind(),
+            }
+        }
+        fn heap_ptr(&self) -> Option<NonNull<()>> {
+            match &self.0 {
+                super::RawOrBoxInner::Raw(s) => s.heap_ptr(),
+                #[cfg(feature = "alloc")]
+                super::RawOrBoxInner::Box(s) => s.heap_ptr(),
             }
         }
         unsafe fn drop_in_place(&mut self, layout: Layout) {
@@ -503,7 +1178,7 @@ pub(crate) mod private {
                 super::RawOrBoxInner::Raw(s) => unsafe { s.drop_in_place(layout) },
                 #[cfg(feature = "alloc")]
                 // SAFETY: same precondition
-                super::RawOrBoxInner::Box(s) => unsafe { s.drop_in_place(layout) },
+                super::RawOrBoxInner::Box(s) => unsafe { drop_box(s, layout) },
             }
         }
     }
@@ -513,13 +1188,19 @@ pub(crate) mod private {
         Align<ALIGN>: Alignment
     {
     }
+
+    // SAFETY: Both `Raw` and `Box` implements `StorageSendOnly`
+    unsafe impl<const SIZE: usize, const ALIGN: usize> StorageSendOnly for super::RawOrBox<SIZE, ALIGN> where
+        Align<ALIGN>: Alignment
+    {
+    }
 }
 
 #[cfg_attr(coverage_nightly, coverage(off))]
 #[cfg(test)]
 #[allow(clippy::undocumented_unsafe_blocks)]
 mod tests {
-    use core::{mem, mem::ManuallyDrop};
+    use core::{cell::Cell, mem, mem::ManuallyDrop};
 
     use elain::{Align, Alignment};
 
@@ -563,6 +1244,14 @@ mod tests {
         check_alignment::<1024>();
     }
 
+    #[test]
+    fn raw_interior_mutability() {
+        let storage = TestStorage::<super::Raw<{ size_of::<usize>() }>>::new_test(Cell::new(0));
+        let cell = unsafe { storage.ptr::<Cell<usize>>().as_ref() };
+        cell.set(42);
+        assert_eq!(cell.get(), 42);
+    }
+
     #[cfg(feature = "alloc")]
     #[test]
     fn raw_or_box() {
@@ -606,6 +1295,8 @@ mod tests {
         check_drop::<super::RawOrBox<{ size_of::<SetDropped>() }>>();
         #[cfg(feature = "alloc")]
         check_drop::<super::RawOrBox<0>>();
+        #[cfg(feature = "alloc")]
+        check_drop::<super::BoxCached>();
     }
 
     #[test]
@@ -627,6 +1318,8 @@ mod tests {
         check_drop_moved::<super::RawOrBox<{ size_of::<SetDropped>() }>>();
         #[cfg(feature = "alloc")]
         check_drop_moved::<super::RawOrBox<0>>();
+        #[cfg(feature = "alloc")]
+        check_drop_moved::<super::BoxCached>();
     }
 
     #[test]
@@ -645,12 +1338,57 @@ mod tests {
         check_dst::<super::RawOrBox<{ size_of::<SetDropped>() }>>();
         #[cfg(feature = "alloc")]
         check_dst::<super::RawOrBox<0>>();
+        #[cfg(feature = "alloc")]
+        check_dst::<super::BoxCached>();
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn box_cached() {
+        use super::{
+            BoxCached,
+            private::{BoxCache, Storage as _},
+        };
+        fn new_cached<T>(data: T, cache: &BoxCache) -> TestStorage<BoxCached> {
+            TestStorage {
+                storage: unsafe { BoxCached::new_cached(data, cache) },
+                vtable: &const { DropVTable::new::<BoxCached, T>() },
+            }
+        }
+        let cache = BoxCached::EMPTY_CACHE;
+        // the released allocation is reused for the same layout
+        let storage = new_cached([0u64; 4], &cache);
+        let ptr = storage.ptr::<()>();
+        drop(storage);
+        let storage = new_cached([1u64; 4], &cache);
+        assert_eq!(storage.ptr::<()>(), ptr);
+        assert_eq!(unsafe { storage.ptr::<[u64; 4]>().read() }, [1; 4]);
+        // concurrent storages miss the cache, only one allocation is cached
+        let storage2 = new_cached([2u64; 4], &cache);
+        assert_ne!(storage2.ptr::<()>(), ptr);
+        drop(storage);
+        drop(storage2);
+        // a different layout frees the cached allocation, and is cached in turn
+        let storage = new_cached([0u64; 8], &cache);
+        let ptr = storage.ptr::<()>();
+        drop(storage);
+        let storage = new_cached([1u64; 8], &cache);
+        assert_eq!(storage.ptr::<()>(), ptr);
+        drop(storage);
+        // allocations which cannot store their layout are freed instead of being cached
+        drop(new_cached(0u8, &cache));
+        drop(new_cached([0u8; 64], &cache));
+        drop(new_cached([0u64; 8], &cache));
+        // zero-sized data is not allocated
+        drop(new_cached((), &cache));
+        // the cached allocation is freed with the cache
+        drop(cache);
     }
 
     #[cfg(feature = "alloc")]
     #[test]
     fn clone() {
-        fn check_clone<S: Storage + Clone>() {
+        fn check_clone<S: StorageClone>() {
             use core::sync::atomic::{AtomicBool, Ordering::Relaxed};
             // cannot use `&mut bool` because first `assert!(!dropped)` would invalid the tag
             struct SetDropped<'a>(&'a AtomicBool);
@@ -659,6 +1397,9 @@ mod tests {
                     assert!(!self.0.swap(true, Relaxed));
                 }
             }
+            // over-aligned data is stored at a different offset in `Rc`/`Arc` allocation
+            #[repr(align(64))]
+            struct OverAligned<T>(T);
             let mut dropped = AtomicBool::new(false);
             let storage = TestStorage::<S>::new_test(SetDropped(&dropped));
             let storage2 = storage.clone();
@@ -666,6 +1407,13 @@ mod tests {
             assert!(!dropped.load(Relaxed));
             drop(storage2);
             assert!(*dropped.get_mut());
+            let mut dropped = AtomicBool::new(false);
+            let storage = TestStorage::<S>::new_test(OverAligned(SetDropped(&dropped)));
+            let storage2 = storage.clone();
+            drop(storage);
+            assert!(!dropped.load(Relaxed));
+            drop(storage2);
+            assert!(*dropped.get_mut());
         }
         check_clone::<super::Rc>();
         check_clone::<super::Arc>();
@@ -1,22 +1,92 @@
-use core::{marker::PhantomData, mem::ManuallyDrop, ptr::NonNull};
+#[cfg(all(feature = "std", feature = "fn_type_name"))]
+use core::mem;
+#[cfg(feature = "arc-waker")]
+use core::task::{RawWaker, RawWakerVTable};
+#[cfg(any(all(feature = "std", feature = "fn_type_name"), feature = "tokio"))]
+use core::time::Duration;
+use core::{
+    marker::PhantomData,
+    mem::{ManuallyDrop, MaybeUninit},
+    ptr,
+    ptr::NonNull,
+};
 
-use higher_kinded_types::{ForFixed, ForLt};
+use higher_kinded_types::ForFixed;
+#[cfg(any(feature = "alloc", feature = "nightly"))]
+use higher_kinded_types::ForRef;
 
+#[cfg(feature = "async")]
+use crate::r#async::{AsyncVTable, Dispatch};
+#[cfg(feature = "std")]
+use crate::macros::impl_catch_unwind;
+#[cfg(feature = "nightly")]
+use crate::macros::impl_fn_traits;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+use crate::macros::impl_instrumented;
+#[cfg(feature = "fn_type_name")]
+use crate::macros::impl_type_name;
+#[cfg(feature = "arc-waker")]
+use crate::waker::ArcWaker;
 use crate::{
-    macros::{impl_clone, impl_debug, new_impls, unsafe_impl_send_sync},
+    ForLt,
+    args::ForTuple2,
+    macros::{
+        impl_as_fn, impl_callable, impl_clone, impl_constant, impl_debug, impl_diverging, impl_id,
+        impl_into_storage, impl_iter, impl_multi_args, impl_named, impl_nullary, impl_resize,
+        impl_result, impl_with_state, new_impls, unsafe_impl_send_sync,
+    },
+    named::{Anonymous, MaybeName},
     storage::{
         DefaultFnStorage, DropVTable, DynStorage, Storage, StorageMoved, StorageMut, StorageSend,
-        VTable,
+        StorageSendOnly, VTable,
     },
 };
+#[cfg(feature = "alloc")]
+use crate::{
+    hkt_ext::ForResult,
+    macros::{impl_from_box, impl_leak},
+    storage::StorageClone,
+};
 
 #[expect(type_alias_bounds)]
 type Call<Arg: ForLt, Ret: ForLt, T> =
     for<'a, 'b> fn(NonNull<T>, Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a>;
+#[expect(type_alias_bounds)]
+type CallInto<Arg: ForLt, Ret: ForLt, T> =
+    for<'a> fn(NonNull<T>, Arg::Of<'a>, &mut MaybeUninit<Ret::Of<'a>>);
+
+#[expect(type_alias_bounds)]
+type FnPtr<Arg: ForLt, Ret: ForLt> = for<'a> fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a>;
+
+mod never {
+    pub trait FnOutput {
+        type Output;
+    }
+    impl<T> FnOutput for fn() -> T {
+        type Output = T;
+    }
+}
+/// The never type `!`, which can only be named on stable as the output of a function pointer.
+type Never = <fn() -> ! as never::FnOutput>::Output;
 
-struct SyncVTable<Arg: ForLt, Ret: ForLt, T = ()> {
+pub(crate) struct SyncVTable<Arg: ForLt + 'static, Ret: ForLt + 'static, T: 'static = ()> {
     call: Call<Arg, Ret, T>,
+    call_into: CallInto<Arg, Ret, T>,
     drop_vtable: DropVTable,
+    /// Whether the function is a [`FnPtr`] stored by `new_fn_ptr`.
+    fn_ptr: bool,
+    /// The vtable of the function as a synchronous async function, so it can be converted
+    /// without being moved into a new storage.
+    ///
+    /// It is only built with `sync-to-async` feature, as it grows every vtable.
+    #[cfg(feature = "sync-to-async")]
+    async_vtable: &'static AsyncVTable<Arg, Ret, T>,
+    /// The waker vtable of an [`Fn`] stored in an [`Arc`](crate::storage::Arc), so it can be
+    /// converted into a waker sharing the storage reference count.
+    ///
+    /// It is only built with `arc-waker` feature, as it grows every vtable.
+    #[cfg(feature = "arc-waker")]
+    arc_waker: Option<&'static RawWakerVTable>,
 }
 
 impl<Arg: ForLt + 'static, Ret: ForLt + 'static, T: 'static> VTable for SyncVTable<Arg, Ret, T> {
@@ -25,7 +95,22 @@ impl<Arg: ForLt + 'static, Ret: ForLt + 'static, T: 'static> VTable for SyncVTab
     }
 }
 
+/// Hook called around each call of a function wrapped with [`LocalDynFn::metrics_hook`].
+///
+/// It allows integrating with any metrics system.
+#[cfg(all(feature = "std", feature = "fn_type_name"))]
+pub trait MetricsHook: Send + Sync {
+    /// Called before the function call.
+    fn on_call_start(&self, fn_name: &str);
+    /// Called after the function call, with its duration.
+    fn on_call_end(&self, fn_name: &str, duration: Duration);
+    /// Called if the function call panics.
+    fn on_call_error(&self, fn_name: &str);
+}
+
 /// [`DynFn`], but without the [`Send`] + [`Sync`] requirement.
+// `repr(C)`, so that the layout doesn't depend on the signature, see `AnyDynFn`
+#[repr(C)]
 pub struct LocalDynFn<
     'capture,
     Arg: ForLt + 'static,
@@ -42,15 +127,54 @@ impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage>
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_impl<
         F: for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
+    >(
+        storage: FnStorage,
+    ) -> Self {
+        // SAFETY: same precondition, and `F` is only flagged as `FnPtr` by `new_fn_ptr`
+        unsafe { Self::new_impl_with::<F, N, false>(storage) }
+    }
+
+    /// # Safety
+    ///
+    /// `storage` must have been initialized with `F`, and `F` must be [`FnPtr`] if `FN_PTR`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    const unsafe fn new_impl_with<
+        F: for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
+        const FN_PTR: bool,
     >(
         storage: FnStorage,
     ) -> Self {
         let vtable = &SyncVTable {
             // SAFETY: func comes from `self.storage.ptr()`, so it's a valid `&F`
             call: |func, arg, _| unsafe { func.cast::<F>().as_ref()(arg, PhantomData) },
-            drop_vtable: const { DropVTable::new::<FnStorage, F>() },
+            call_into: |func, arg, out| {
+                // SAFETY: func comes from `self.storage.ptr()`, so it's a valid `&F`
+                out.write(unsafe { func.cast::<F>().as_ref()(arg, PhantomData) });
+            },
+            drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+            fn_ptr: FN_PTR,
+            #[cfg(feature = "sync-to-async")]
+            async_vtable: &AsyncVTable {
+                // SAFETY: func comes from `self.storage.ptr()`, so it's a valid `&F`
+                dispatch: Dispatch::Sync(|func, arg, _| unsafe {
+                    func.cast::<F>().as_ref()(arg, PhantomData)
+                }),
+                drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+                sync_vtable: None,
+            },
+            #[cfg(feature = "arc-waker")]
+            arc_waker: const {
+                if FnStorage::IS_ARC {
+                    Some(&ArcWaker::<Arg, Ret, F>::VTABLE)
+                } else {
+                    None
+                }
+            },
         };
         Self {
             // SAFETY: `drop_vtable` matches the storage
@@ -59,18 +183,218 @@ impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage>
         }
     }
 
+    /// Construct a new [`LocalDynFn`] from a function pointer, which can then be compared with
+    /// [`eq_fn_ptr`](Self::eq_fn_ptr).
+    ///
+    /// Like closures, the function takes a second `PhantomData` parameter carrying the lifetime
+    /// of the argument.
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    pub fn new_fn_ptr(f: FnPtr<Arg, Ret>) -> Self {
+        // SAFETY: storage is initialized with a `FnPtr`
+        unsafe { Self::new_impl_with::<FnPtr<Arg, Ret>, Anonymous, true>(FnStorage::new(f)) }
+    }
+
+    /// Returns whether the function has been constructed with
+    /// [`new_fn_ptr`](Self::new_fn_ptr).
+    pub fn is_fn_ptr(&self) -> bool {
+        self.storage.vtable().fn_ptr
+    }
+
+    /// Returns whether the function has been constructed with [`new_fn_ptr`](Self::new_fn_ptr)
+    /// from the given function pointer; it always returns `false` for closures.
+    ///
+    /// Function pointers are compared with [`ptr::fn_addr_eq`](core::ptr::fn_addr_eq), so the same
+    /// function may compare unequal if instantiated in different codegen units, and different
+    /// functions may compare equal if the compiler merges their code.
+    pub fn eq_fn_ptr(&self, f: FnPtr<Arg, Ret>) -> bool {
+        if !self.is_fn_ptr() {
+            return false;
+        }
+        // SAFETY: the vtable is only flagged by `new_fn_ptr`, whose storage holds a `FnPtr`
+        let stored = unsafe { *self.storage.ptr::<FnPtr<Arg, Ret>>().as_ref() };
+        ptr::fn_addr_eq(stored, f)
+    }
+
     /// Calls the underlying function.
+    ///
+    /// The call itself never panics, only the underlying function may.
+    #[inline]
     pub fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
         (self.storage.vtable().call)(self.storage.ptr(), arg, PhantomData)
     }
+
+    /// Calls the underlying function, writing the result into `out`.
+    ///
+    /// The result is written through an out-pointer instead of being returned through the
+    /// dynamic call; the function itself still returns it by value.
+    #[inline]
+    pub fn call_into<'a, 'o>(
+        &self,
+        arg: Arg::Of<'a>,
+        out: &'o mut MaybeUninit<Ret::Of<'a>>,
+    ) -> &'o mut Ret::Of<'a> {
+        (self.storage.vtable().call_into)(self.storage.ptr(), arg, out);
+        // SAFETY: `call_into` initializes `out`
+        unsafe { out.assume_init_mut() }
+    }
+
+    /// Combines the function with another one taking the same argument, returning both results.
+    ///
+    /// Functions are called sequentially, the argument being cloned for the first one.
+    pub fn fanout<Ret2: ForLt + 'static>(
+        self,
+        other: LocalDynFn<'capture, Arg, Ret2, FnStorage>,
+    ) -> LocalDynFn<'capture, Arg, ForTuple2<Ret, Ret2>, FnStorage>
+    where
+        Arg: for<'a> ForLt<Of<'a>: Clone>,
+    {
+        LocalDynFn::new(move |arg: Arg::Of<'_>, _| (self.call(arg.clone()), other.call(arg)))
+    }
+
+    /// Wraps the function with an observer called with the argument before each call.
+    ///
+    /// The returned function has the same signature and storage, so it can replace the original
+    /// one transparently; the wrapper must fit in the storage, like any function passed to
+    /// [`new`](Self::new).
+    pub fn inspect(self, observer: impl Fn(&Arg::Of<'_>) + 'capture) -> Self {
+        Self::new(move |arg, _| {
+            observer(&arg);
+            self.call(arg)
+        })
+    }
+
+    /// Wraps the function with an observer called with the result after each call.
+    ///
+    /// The returned function has the same signature and storage, so it can replace the original
+    /// one transparently; the wrapper must fit in the storage, like any function passed to
+    /// [`new`](Self::new).
+    pub fn inspect_output(self, observer: impl Fn(&Ret::Of<'_>) + 'capture) -> Self {
+        Self::new(move |arg, _| {
+            let ret = self.call(arg);
+            observer(&ret);
+            ret
+        })
+    }
+
+    /// Wraps the function with an argument validation.
+    ///
+    /// The function is only called if `validator` returns `Ok`, and its result is then wrapped in
+    /// `Ok`; otherwise, the validation error is returned.
+    #[cfg(feature = "alloc")]
+    pub fn with_arg_validation<E: 'static>(
+        self,
+        validator: impl Fn(&Arg::Of<'_>) -> Result<(), E> + 'capture,
+    ) -> LocalDynFn<'capture, Arg, ForResult<Ret, E>, crate::storage::Box> {
+        LocalDynFn::new(move |arg, _| {
+            validator(&arg)?;
+            Ok(self.call(arg))
+        })
+    }
+
+    /// Wraps the function with a post-condition check.
+    ///
+    /// The result of the function is returned wrapped in `Ok` if `check` returns `Ok`;
+    /// otherwise, the check error is returned.
+    #[cfg(feature = "alloc")]
+    pub fn assert_invariant<E: 'static>(
+        self,
+        check: impl Fn(&Ret::Of<'_>) -> Result<(), E> + 'capture,
+    ) -> LocalDynFn<'capture, Arg, ForResult<Ret, E>, crate::storage::Box> {
+        LocalDynFn::new(move |arg, _| {
+            let ret = self.call(arg);
+            check(&ret)?;
+            Ok(ret)
+        })
+    }
+
+    /// Wraps the function with a post-condition check, only enabled in debug builds.
+    ///
+    /// Like [`debug_assert!`], it panics if `check` returns an error; the check is not
+    /// executed in release builds. The panic message gives the location of the
+    /// `assert_invariant_debug` call, the name of the function if any, and its type with
+    /// `fn_type_name` feature.
+    #[cfg(feature = "alloc")]
+    #[track_caller]
+    pub fn assert_invariant_debug<E: core::fmt::Debug>(
+        self,
+        check: impl Fn(&Ret::Of<'_>) -> Result<(), E> + 'capture,
+    ) -> LocalDynFn<'capture, Arg, Ret, crate::storage::Box> {
+        let location = core::panic::Location::caller();
+        LocalDynFn::new(move |arg, _| {
+            let ret = self.call(arg);
+            #[cfg(debug_assertions)]
+            if let Err(err) = check(&ret) {
+                let context = self.storage.vtable().drop_vtable().panic_context();
+                panic!("invariant asserted at {location} violated: {err:?}{context}");
+            }
+            #[cfg(not(debug_assertions))]
+            let _ = (&check, location);
+            ret
+        })
+    }
+
+    /// Wraps the function with a [`MetricsHook`] called around each call.
+    ///
+    /// The hook receives the [type name](Self::type_name) of the underlying function.
+    #[cfg(all(feature = "std", feature = "fn_type_name"))]
+    pub fn metrics_hook(
+        self,
+        hook: alloc::sync::Arc<dyn MetricsHook>,
+    ) -> LocalDynFn<'capture, Arg, Ret, crate::storage::Box> {
+        struct ErrorGuard<'a>(&'a dyn MetricsHook, &'a str);
+        impl Drop for ErrorGuard<'_> {
+            fn drop(&mut self) {
+                self.0.on_call_error(self.1);
+            }
+        }
+        LocalDynFn::new(move |arg, _| {
+            let fn_name = self.type_name();
+            hook.on_call_start(fn_name);
+            let guard = ErrorGuard(&*hook, fn_name);
+            let start = std::time::Instant::now();
+            let ret = self.call(arg);
+            mem::forget(guard);
+            hook.on_call_end(fn_name, start.elapsed());
+            ret
+        })
+    }
 }
 
 new_impls!(sync LocalDynFn, Storage, for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture);
 
 impl_clone!(sync LocalDynFn, Storage);
 impl_debug!(sync LocalDynFn, Storage);
+impl_id!(sync LocalDynFn, Storage);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(sync LocalDynFn, Storage);
+impl_named!(sync LocalDynFn, Storage);
+impl_resize!(sync LocalDynFn, Storage);
+impl_into_storage!(sync LocalDynFn, Storage);
+#[cfg(feature = "alloc")]
+impl_leak!(sync LocalDynFn, Storage);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(sync LocalDynFn, Storage);
+impl_multi_args!(sync Fn LocalDynFn, Storage, 'capture);
+#[cfg(feature = "std")]
+impl_catch_unwind!(Fn LocalDynFn, Storage);
+impl_nullary!(sync Fn LocalDynFn, Storage, 'capture);
+impl_diverging!(Fn LocalDynFn, Storage, 'capture);
+impl_with_state!(sync Fn LocalDynFn, Storage, 'capture);
+impl_constant!(sync LocalDynFn, Storage, 'capture);
+impl_result!(Fn LocalDynFn, Storage, 'capture);
+#[cfg(feature = "alloc")]
+impl_from_box!(Fn LocalDynFn, Storage, 'capture);
+
+/// [`LocalDynFn`] taking no argument.
+pub type LocalDynFn0<'capture, R = (), FnStorage = DefaultFnStorage> =
+    LocalDynFn<'capture, ForFixed<()>, ForFixed<R>, FnStorage>;
+impl_as_fn!(Fn LocalDynFn, Storage);
+impl_callable!(Fn LocalDynFn, FnStorage: Storage);
+#[cfg(feature = "nightly")]
+impl_fn_traits!(Fn LocalDynFn, Storage);
 
 /// A dynamic [`Fn`] stored in `FnStorage`.
+#[repr(transparent)]
 pub struct DynFn<
     'capture,
     Arg: ForLt + 'static,
@@ -86,27 +410,538 @@ impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage +
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_impl<
         F: for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
         // SAFETY: same precondition
-        Self(unsafe { LocalDynFn::new_impl::<F>(storage) })
+        Self(unsafe { LocalDynFn::new_impl::<F, N>(storage) })
     }
 
     /// Calls the underlying function.
+    ///
+    /// The call itself never panics, only the underlying function may.
+    #[inline]
     pub fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
         self.0.call(arg)
     }
+
+    /// Calls the underlying function, writing the result into `out`.
+    ///
+    /// The result is written through an out-pointer instead of being returned through the
+    /// dynamic call; the function itself still returns it by value.
+    #[inline]
+    pub fn call_into<'a, 'o>(
+        &self,
+        arg: Arg::Of<'a>,
+        out: &'o mut MaybeUninit<Ret::Of<'a>>,
+    ) -> &'o mut Ret::Of<'a> {
+        self.0.call_into(arg, out)
+    }
+
+    /// Construct a new [`DynFn`] from a function pointer, which can then be compared with
+    /// [`eq_fn_ptr`](Self::eq_fn_ptr).
+    ///
+    /// See [`LocalDynFn::new_fn_ptr`].
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    pub fn new_fn_ptr(f: FnPtr<Arg, Ret>) -> Self {
+        Self(LocalDynFn::new_fn_ptr(f))
+    }
+
+    /// Returns whether the function has been constructed with
+    /// [`new_fn_ptr`](Self::new_fn_ptr).
+    pub fn is_fn_ptr(&self) -> bool {
+        self.0.is_fn_ptr()
+    }
+
+    /// Returns whether the function has been constructed with [`new_fn_ptr`](Self::new_fn_ptr)
+    /// from the given function pointer; it always returns `false` for closures.
+    ///
+    /// See [`LocalDynFn::eq_fn_ptr`] for the caveats of function pointer comparison.
+    pub fn eq_fn_ptr(&self, f: FnPtr<Arg, Ret>) -> bool {
+        self.0.eq_fn_ptr(f)
+    }
+
+    /// Combines the function with another one taking the same argument, returning both results.
+    ///
+    /// Functions are called sequentially, the argument being cloned for the first one.
+    pub fn fanout<Ret2: ForLt + 'static>(
+        self,
+        other: DynFn<'capture, Arg, Ret2, FnStorage>,
+    ) -> DynFn<'capture, Arg, ForTuple2<Ret, Ret2>, FnStorage>
+    where
+        Arg: for<'a> ForLt<Of<'a>: Clone>,
+    {
+        DynFn::new(move |arg: Arg::Of<'_>, _| (self.call(arg.clone()), other.call(arg)))
+    }
+
+    /// Wraps the function with an observer called with the argument before each call.
+    ///
+    /// The returned function has the same signature and storage, so it can replace the original
+    /// one transparently; the wrapper must fit in the storage, like any function passed to
+    /// [`new`](Self::new).
+    pub fn inspect(self, observer: impl Fn(&Arg::Of<'_>) + Send + Sync + 'capture) -> Self {
+        Self::new(move |arg, _| {
+            observer(&arg);
+            self.call(arg)
+        })
+    }
+
+    /// Wraps the function with an observer called with the result after each call.
+    ///
+    /// The returned function has the same signature and storage, so it can replace the original
+    /// one transparently; the wrapper must fit in the storage, like any function passed to
+    /// [`new`](Self::new).
+    pub fn inspect_output(self, observer: impl Fn(&Ret::Of<'_>) + Send + Sync + 'capture) -> Self {
+        Self::new(move |arg, _| {
+            let ret = self.call(arg);
+            observer(&ret);
+            ret
+        })
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<A: Send + 'static, R: Send + 'static, FnStorage: Storage + StorageSend + 'static>
+    DynFn<'static, ForFixed<A>, ForFixed<R>, FnStorage>
+{
+    /// Wraps the function into an asynchronous function with a timeout.
+    ///
+    /// The function is executed with [`tokio::task::spawn_blocking`], and its result is returned
+    /// wrapped in `Some` if it completes within `timeout`; otherwise, `None` is returned, but the
+    /// blocking task keeps running until completion.
+    ///
+    /// It is not available on [`LocalDynFn`]: the function is shared with tokio's blocking
+    /// threads, and may outlive the returned function if a call times out, so it must be
+    /// [`Send`] + [`Sync`] and `'static`, as well as its argument and return value.
+    pub fn time_out_if_sync(
+        self,
+        timeout: Duration,
+    ) -> crate::LocalDynAsyncFn<'static, ForFixed<A>, ForFixed<Option<R>>, crate::storage::Box>
+    {
+        let this = alloc::sync::Arc::new(self);
+        crate::LocalDynAsyncFn::<ForFixed<A>, ForFixed<Option<R>>, _, _>::new(
+            async move |arg, _| {
+                let this = this.clone();
+                let task = tokio::task::spawn_blocking(move || this.call(arg));
+                match tokio::time::timeout(timeout, task).await.ok()? {
+                    Ok(res) => Some(res),
+                    Err(err) => std::panic::resume_unwind(err.into_panic()),
+                }
+            },
+        )
+    }
 }
 
 new_impls!(sync DynFn, Storage + StorageSend, for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture);
 
+#[cfg(feature = "arc-waker")]
+impl<FnStorage: Storage + StorageSend> DynFn<'static, ForFixed<()>, ForFixed<()>, FnStorage> {
+    /// Converts the function into a [`RawWaker`] sharing its storage reference count, if it is
+    /// stored in an [`Arc`](crate::storage::Arc).
+    pub(crate) fn into_arc_raw_waker(self) -> Result<RawWaker, Self> {
+        match self.0.storage.vtable().arc_waker {
+            Some(vtable) if FnStorage::IS_ARC => {
+                let this = ManuallyDrop::new(self);
+                // The strong reference of the storage is transferred to the waker
+                Ok(RawWaker::new(this.0.storage.ptr::<()>().as_ptr(), vtable))
+            }
+            _ => Err(self),
+        }
+    }
+}
+
 impl_clone!(sync DynFn, Storage + StorageSend);
 impl_debug!(sync DynFn, Storage + StorageSend);
+impl_id!(sync DynFn, Storage + StorageSend);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(sync DynFn, Storage + StorageSend);
+impl_named!(sync DynFn, Storage + StorageSend);
+impl_resize!(sync DynFn, Storage + StorageSend);
+impl_into_storage!(sync DynFn, Storage + StorageSend);
+#[cfg(feature = "alloc")]
+impl_leak!(sync DynFn, Storage + StorageSend);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(sync DynFn, Storage + StorageSend);
+impl_multi_args!(sync Fn DynFn, Storage + StorageSend, Send + Sync + 'capture);
+#[cfg(feature = "std")]
+impl_catch_unwind!(Fn DynFn, Storage + StorageSend);
+impl_nullary!(sync Fn DynFn, Storage + StorageSend, Send + Sync + 'capture);
+impl_diverging!(Fn DynFn, Storage + StorageSend, Send + Sync + 'capture);
+impl_with_state!(sync Fn DynFn, Storage + StorageSend, Send + Sync + 'capture);
+impl_constant!(sync DynFn, Storage + StorageSend, Send + Sync + 'capture);
+impl_result!(Fn DynFn, Storage + StorageSend, Send + Sync + 'capture);
+#[cfg(feature = "alloc")]
+impl_from_box!(Fn DynFn, Storage + StorageSend, Send + Sync + 'capture);
+
+/// [`DynFn`] taking no argument.
+pub type DynFn0<'capture, R = (), FnStorage = DefaultFnStorage> =
+    DynFn<'capture, ForFixed<()>, ForFixed<R>, FnStorage>;
+impl_as_fn!(Fn DynFn, Storage + StorageSend);
+impl_callable!(Fn DynFn, FnStorage: Storage + StorageSend);
+#[cfg(feature = "nightly")]
+impl_fn_traits!(Fn DynFn, Storage + StorageSend);
+
+/// [`DynFn`], but only requiring the function to be [`Send`].
+///
+/// It can be moved to another thread and called there, e.g. handed to the worker thread of an
+/// actor, but it cannot be shared between threads, so it doesn't implement [`Sync`].
+///
+/// Shared storages like [`Arc`](crate::storage::Arc) are not supported, as their clones would
+/// share the function.
+///
+/// # Examples
+///
+/// ```
+/// use std::{cell::Cell, sync::mpsc, thread};
+///
+/// use dyn_fn::{SendDynFn, hkt::ForFixed, storage::Raw};
+///
+/// let (tx, rx) = mpsc::channel();
+/// let count = Cell::new(0);
+/// let report = SendDynFn::<ForFixed<&str>, ForFixed<()>, Raw<32>>::new(move |msg, _| {
+///     count.set(count.get() + 1);
+///     tx.send(format!("{}: {msg}", count.get())).unwrap();
+/// });
+/// thread::spawn(move || {
+///     report.call("hello");
+///     report.call("world");
+/// });
+/// assert_eq!(rx.iter().collect::<Vec<_>>(), ["1: hello", "2: world"]);
+/// ```
+pub struct SendDynFn<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: Storage + StorageSendOnly = DefaultFnStorage,
+>(LocalDynFn<'capture, Arg, Ret, FnStorage>);
+
+unsafe_impl_send_sync!(sync send SendDynFn, Storage);
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSendOnly>
+    SendDynFn<'capture, Arg, Ret, FnStorage>
+{
+    /// # Safety
+    ///
+    /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    const unsafe fn new_impl<
+        F: for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture,
+        N: MaybeName,
+    >(
+        storage: FnStorage,
+    ) -> Self {
+        // SAFETY: same precondition
+        Self(unsafe { LocalDynFn::new_impl::<F, N>(storage) })
+    }
+
+    /// Calls the underlying function.
+    #[inline]
+    pub fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.0.call(arg)
+    }
+
+    /// Calls the underlying function, writing the result into `out`.
+    ///
+    /// The result is written through an out-pointer instead of being returned through the
+    /// dynamic call; the function itself still returns it by value.
+    #[inline]
+    pub fn call_into<'a, 'o>(
+        &self,
+        arg: Arg::Of<'a>,
+        out: &'o mut MaybeUninit<Ret::Of<'a>>,
+    ) -> &'o mut Ret::Of<'a> {
+        self.0.call_into(arg, out)
+    }
+}
+
+impl<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static,
+    FnStorage: Storage + StorageSend + StorageSendOnly,
+> From<DynFn<'capture, Arg, Ret, FnStorage>> for SendDynFn<'capture, Arg, Ret, FnStorage>
+{
+    fn from(value: DynFn<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self(value.0)
+    }
+}
+
+new_impls!(sync SendDynFn, Storage + StorageSendOnly, for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture);
+
+impl_clone!(sync SendDynFn, Storage + StorageSendOnly);
+impl_debug!(sync SendDynFn, Storage + StorageSendOnly);
+impl_id!(sync SendDynFn, Storage + StorageSendOnly);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(sync SendDynFn, Storage + StorageSendOnly);
+impl_named!(sync SendDynFn, Storage + StorageSendOnly);
+impl_resize!(sync SendDynFn, Storage + StorageSendOnly);
+impl_into_storage!(sync SendDynFn, Storage + StorageSendOnly);
+#[cfg(feature = "alloc")]
+impl_leak!(sync SendDynFn, Storage + StorageSendOnly);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(sync SendDynFn, Storage + StorageSendOnly);
+impl_multi_args!(sync Fn SendDynFn, Storage + StorageSendOnly, Send + 'capture);
+#[cfg(feature = "std")]
+impl_catch_unwind!(Fn SendDynFn, Storage + StorageSendOnly);
+impl_nullary!(sync Fn SendDynFn, Storage + StorageSendOnly, Send + 'capture);
+impl_diverging!(Fn SendDynFn, Storage + StorageSendOnly, Send + 'capture);
+impl_with_state!(sync Fn SendDynFn, Storage + StorageSendOnly, Send + 'capture);
+impl_constant!(sync SendDynFn, Storage + StorageSendOnly, Send + 'capture);
+impl_result!(Fn SendDynFn, Storage + StorageSendOnly, Send + 'capture);
+#[cfg(feature = "alloc")]
+impl_from_box!(Fn SendDynFn, Storage + StorageSendOnly, Send + 'capture);
+
+impl_as_fn!(Fn SendDynFn, Storage + StorageSendOnly);
+impl_callable!(Fn SendDynFn, FnStorage: Storage + StorageSendOnly);
+#[cfg(feature = "nightly")]
+impl_fn_traits!(Fn SendDynFn, Storage + StorageSendOnly);
+
+/// [`DynFn`] whose call is unsafe.
+///
+/// It erases functions with an unsafe calling contract, e.g. requiring the caller to uphold a
+/// hardware or aliasing invariant, without laundering the unsafety: the only way to call it is
+/// the unsafe [`call_unchecked`](Self::call_unchecked). Functions can be `unsafe fn` pointers,
+/// using [`new_unsafe`](Self::new_unsafe), or closures calling unsafe code, whose contract is
+/// then upheld by the caller of `call_unchecked`.
+///
+/// # Examples
+///
+/// ```
+/// use std::marker::PhantomData;
+///
+/// use dyn_fn::{UnsafeDynFn, hkt::ForFixed};
+///
+/// /// # Safety
+/// ///
+/// /// `ptr` must be valid for reads.
+/// unsafe fn read(ptr: *const u32, _: PhantomData<&()>) -> u32 {
+///     // SAFETY: same precondition
+///     unsafe { ptr.read() }
+/// }
+///
+/// let read = UnsafeDynFn::<ForFixed<*const u32>, ForFixed<u32>>::new_unsafe(read);
+/// let read_twice = UnsafeDynFn::<ForFixed<*const u32>, ForFixed<u32>>::new(|ptr, _| {
+///     // SAFETY: the caller of `call_unchecked` passes a pointer valid for reads
+///     2 * unsafe { ptr.read() }
+/// });
+/// let x = 21;
+/// // SAFETY: `&x` is valid for reads
+/// assert_eq!(unsafe { read.call_unchecked(&x) }, 21);
+/// // SAFETY: `&x` is valid for reads
+/// assert_eq!(unsafe { read_twice.call_unchecked(&x) }, 42);
+/// ```
+pub struct UnsafeDynFn<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: Storage + StorageSend = DefaultFnStorage,
+>(LocalDynFn<'capture, Arg, Ret, FnStorage>);
+
+unsafe_impl_send_sync!(sync UnsafeDynFn, Storage);
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    UnsafeDynFn<'capture, Arg, Ret, FnStorage>
+{
+    /// # Safety
+    ///
+    /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    const unsafe fn new_impl<
+        F: for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture,
+        N: MaybeName,
+    >(
+        storage: FnStorage,
+    ) -> Self {
+        // SAFETY: same precondition
+        Self(unsafe { LocalDynFn::new_impl::<F, N>(storage) })
+    }
+
+    /// Construct a new [`UnsafeDynFn`] from an `unsafe fn` pointer.
+    ///
+    /// Like closures, the function takes a second `PhantomData` parameter carrying the lifetime
+    /// of the argument.
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    pub fn new_unsafe(
+        f: for<'a> unsafe fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a>,
+    ) -> Self {
+        // SAFETY: the function is only called by `call_unchecked`, whose caller upholds its
+        // contract
+        Self::new(move |arg, _| unsafe { f(arg, PhantomData) })
+    }
+
+    /// Calls the underlying function.
+    ///
+    /// # Safety
+    ///
+    /// The caller must uphold the contract of the underlying function.
+    #[inline]
+    pub unsafe fn call_unchecked<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.0.call(arg)
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    From<DynFn<'capture, Arg, Ret, FnStorage>> for UnsafeDynFn<'capture, Arg, Ret, FnStorage>
+{
+    fn from(value: DynFn<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self(value.0)
+    }
+}
+
+new_impls!(sync UnsafeDynFn, Storage + StorageSend, for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture);
+
+impl_clone!(sync UnsafeDynFn, Storage + StorageSend);
+impl_debug!(sync UnsafeDynFn, Storage + StorageSend);
+impl_id!(sync UnsafeDynFn, Storage + StorageSend);
+impl_resize!(sync UnsafeDynFn, Storage + StorageSend);
+impl_into_storage!(sync UnsafeDynFn, Storage + StorageSend);
+#[cfg(feature = "alloc")]
+impl_leak!(sync UnsafeDynFn, Storage + StorageSend);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(sync UnsafeDynFn, Storage + StorageSend);
+
+/// [`FastDynFn`], but without the [`Send`] + [`Sync`] requirement.
+pub struct LocalFastDynFn<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: Storage = DefaultFnStorage,
+> {
+    call: Call<Arg, Ret, ()>,
+    inner: LocalDynFn<'capture, Arg, Ret, FnStorage>,
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage>
+    LocalFastDynFn<'capture, Arg, Ret, FnStorage>
+{
+    /// Construct a new [`LocalFastDynFn`] from a function.
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    pub fn new(
+        f: impl for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+    ) -> Self {
+        LocalDynFn::new(f).into()
+    }
+
+    /// Calls the underlying function.
+    #[inline]
+    pub fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        (self.call)(self.inner.storage.ptr(), arg, PhantomData)
+    }
+
+    /// Returns the underlying [`LocalDynFn`].
+    pub fn into_inner(self) -> LocalDynFn<'capture, Arg, Ret, FnStorage> {
+        self.inner
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage>
+    From<LocalDynFn<'capture, Arg, Ret, FnStorage>>
+    for LocalFastDynFn<'capture, Arg, Ret, FnStorage>
+{
+    fn from(inner: LocalDynFn<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self {
+            call: inner.storage.vtable().call,
+            inner,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageClone> Clone
+    for LocalFastDynFn<'capture, Arg, Ret, FnStorage>
+{
+    fn clone(&self) -> Self {
+        Self {
+            call: self.call,
+            inner: self.inner.clone(),
+        }
+    }
+}
 
-/// [`DynFnMut`], but without the [`Send`] + [`Sync`] requirement.
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage> core::fmt::Debug
+    for LocalFastDynFn<'capture, Arg, Ret, FnStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("LocalFastDynFn").field(&self.inner).finish()
+    }
+}
+
+impl_callable!(Fn LocalFastDynFn, FnStorage: Storage);
+
+/// A [`DynFn`] with the call function pointer stored in the handle.
+///
+/// Calling it requires one less indirection than [`DynFn`], which loads the function pointer
+/// from its vtable, at the cost of one more word in the handle.
+///
+/// # Examples
+///
+/// ```
+/// use dyn_fn::{DynFn, FastDynFn, hkt::*};
+///
+/// let f = FastDynFn::<ForRef<str>, ForFixed<usize>>::new(|s, _| s.len());
+/// assert_eq!(f.call("test"), 4);
+/// let f: FastDynFn<_, _> = DynFn::<ForRef<str>, ForFixed<usize>>::new(|s, _| s.len()).into();
+/// assert_eq!(f.call("test"), 4);
+/// ```
+pub struct FastDynFn<
+    'capture,
+    Arg: ForLt + 'static,
+    Ret: ForLt + 'static = ForFixed<()>,
+    FnStorage: Storage + StorageSend = DefaultFnStorage,
+>(LocalFastDynFn<'capture, Arg, Ret, FnStorage>);
+
+unsafe_impl_send_sync!(sync FastDynFn, Storage);
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    FastDynFn<'capture, Arg, Ret, FnStorage>
+{
+    /// Construct a new [`FastDynFn`] from a function.
+    #[cfg_attr(feature = "track-construction", track_caller)]
+    pub fn new(
+        f: impl for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture,
+    ) -> Self {
+        DynFn::new(f).into()
+    }
+
+    /// Calls the underlying function.
+    #[inline]
+    pub fn call<'a>(&self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+        self.0.call(arg)
+    }
+
+    /// Returns the underlying [`DynFn`].
+    pub fn into_inner(self) -> DynFn<'capture, Arg, Ret, FnStorage> {
+        DynFn(self.0.into_inner())
+    }
+}
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    From<DynFn<'capture, Arg, Ret, FnStorage>> for FastDynFn<'capture, Arg, Ret, FnStorage>
+{
+    fn from(inner: DynFn<'capture, Arg, Ret, FnStorage>) -> Self {
+        Self(inner.0.into())
+    }
+}
+
+impl_clone!(sync FastDynFn, Storage + StorageSend);
+
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: Storage + StorageSend>
+    core::fmt::Debug for FastDynFn<'capture, Arg, Ret, FnStorage>
+{
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("FastDynFn").field(&self.0.inner).finish()
+    }
+}
+
+impl_callable!(Fn FastDynFn, FnStorage: Storage + StorageSend);
+
+/// [`DynFnMut`], but without the [`Send`] requirement.
 pub struct LocalDynFnMut<
     'capture,
     Arg: ForLt + 'static,
@@ -123,34 +958,169 @@ impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_impl<
         F: for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
-        let vtable = &SyncVTable {
-            // SAFETY: func comes from `self.storage.ptr_mut()`, so it's a valid `&mut F`
-            call: |func, arg, _| unsafe { func.cast::<F>().as_mut()(arg, PhantomData) },
-            drop_vtable: const { DropVTable::new::<FnStorage, F>() },
-        };
         Self {
             // SAFETY: `drop_vtable` matches the storage
-            storage: unsafe { DynStorage::new(storage, vtable) },
+            storage: unsafe { DynStorage::new(storage, Self::vtable::<F, N>()) },
             _capture: PhantomData,
         }
     }
 
+    /// Returns the vtable of `F`.
+    ///
+    /// It is a function, so the async vtable can refer back to it.
+    const fn vtable<
+        F: for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
+    >() -> &'static SyncVTable<Arg, Ret> {
+        &SyncVTable {
+            // SAFETY: func comes from `self.storage.ptr_mut()`, so it's a valid `&mut F`
+            call: |func, arg, _| unsafe { func.cast::<F>().as_mut()(arg, PhantomData) },
+            call_into: |func, arg, out| {
+                // SAFETY: func comes from `self.storage.ptr_mut()`, so it's a valid `&mut F`
+                out.write(unsafe { func.cast::<F>().as_mut()(arg, PhantomData) });
+            },
+            drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+            fn_ptr: false,
+            #[cfg(feature = "sync-to-async")]
+            async_vtable: const { Self::async_vtable::<F, N>() },
+            #[cfg(feature = "arc-waker")]
+            arc_waker: None,
+        }
+    }
+
     /// Calls the underlying function.
+    #[inline]
     pub fn call<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
         (self.storage.vtable().call)(self.storage.ptr_mut(), arg, PhantomData)
     }
+
+    /// Calls the underlying function, writing the result into `out`.
+    ///
+    /// The result is written through an out-pointer instead of being returned through the
+    /// dynamic call; the function itself still returns it by value.
+    #[inline]
+    pub fn call_into<'a, 'o>(
+        &mut self,
+        arg: Arg::Of<'a>,
+        out: &'o mut MaybeUninit<Ret::Of<'a>>,
+    ) -> &'o mut Ret::Of<'a> {
+        (self.storage.vtable().call_into)(self.storage.ptr_mut(), arg, out);
+        // SAFETY: `call_into` initializes `out`
+        unsafe { out.assume_init_mut() }
+    }
+
+    /// Wraps the function with an observer called with the argument before each call.
+    ///
+    /// The returned function has the same signature and storage, so it can replace the original
+    /// one transparently; the wrapper must fit in the storage, like any function passed to
+    /// [`new`](Self::new).
+    pub fn inspect(mut self, mut observer: impl FnMut(&Arg::Of<'_>) + 'capture) -> Self {
+        Self::new(move |arg, _| {
+            observer(&arg);
+            self.call(arg)
+        })
+    }
+
+    /// Wraps the function with an observer called with the result after each call.
+    ///
+    /// The returned function has the same signature and storage, so it can replace the original
+    /// one transparently; the wrapper must fit in the storage, like any function passed to
+    /// [`new`](Self::new).
+    pub fn inspect_output(mut self, mut observer: impl FnMut(&Ret::Of<'_>) + 'capture) -> Self {
+        Self::new(move |arg, _| {
+            let ret = self.call(arg);
+            observer(&ret);
+            ret
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut>
+    LocalDynFnMut<'capture, Arg, Ret, FnStorage>
+{
+    /// Returns the vtable of `F` as a synchronous async function.
+    pub(crate) const fn async_vtable<
+        F: for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
+    >() -> &'static AsyncVTable<Arg, Ret> {
+        &AsyncVTable {
+            // SAFETY: func comes from `self.storage.ptr_mut()`, so it's a valid `&mut F`
+            dispatch: Dispatch::Sync(|func, arg, _| unsafe {
+                func.cast::<F>().as_mut()(arg, PhantomData)
+            }),
+            drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+            sync_vtable: Some(Self::vtable::<F, N>),
+        }
+    }
+
+    /// Returns the storage of the function, with its vtable as a synchronous async function.
+    #[cfg(feature = "sync-to-async")]
+    pub(crate) fn into_async_storage(self) -> DynStorage<FnStorage, AsyncVTable<Arg, Ret>> {
+        let vtable = self.storage.vtable().async_vtable;
+        // SAFETY: `async_vtable` is built with the same drop vtable as the function vtable
+        unsafe { self.storage.with_vtable(vtable) }
+    }
+
+    /// Converts back the storage of a synchronous async function, if it has a synchronous vtable;
+    /// the storage is returned otherwise.
+    pub(crate) fn try_from_async_storage(
+        storage: DynStorage<FnStorage, AsyncVTable<Arg, Ret>>,
+    ) -> Result<Self, DynStorage<FnStorage, AsyncVTable<Arg, Ret>>> {
+        let Some(vtable) = storage.vtable().sync_vtable else {
+            return Err(storage);
+        };
+        Ok(Self {
+            // SAFETY: `sync_vtable` is built with the same drop vtable as the function vtable
+            storage: unsafe { storage.with_vtable(vtable()) },
+            _capture: PhantomData,
+        })
+    }
 }
 
 new_impls!(sync LocalDynFnMut, StorageMut, for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture);
 
 impl_debug!(sync LocalDynFnMut, StorageMut);
+impl_id!(sync LocalDynFnMut, StorageMut);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(sync LocalDynFnMut, StorageMut);
+impl_named!(sync LocalDynFnMut, StorageMut);
+impl_resize!(sync LocalDynFnMut, StorageMut);
+impl_into_storage!(sync LocalDynFnMut, StorageMut);
+#[cfg(feature = "alloc")]
+impl_leak!(sync LocalDynFnMut, StorageMut);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(sync LocalDynFnMut, StorageMut);
+impl_multi_args!(sync FnMut LocalDynFnMut, StorageMut, 'capture);
+#[cfg(feature = "std")]
+impl_catch_unwind!(FnMut LocalDynFnMut, StorageMut);
+impl_nullary!(sync FnMut LocalDynFnMut, StorageMut, 'capture);
+impl_diverging!(FnMut LocalDynFnMut, StorageMut, 'capture);
+impl_with_state!(sync FnMut LocalDynFnMut, StorageMut, 'capture);
+impl_result!(FnMut LocalDynFnMut, StorageMut, 'capture);
+#[cfg(feature = "alloc")]
+impl_from_box!(FnMut LocalDynFnMut, StorageMut, 'capture);
+
+/// [`LocalDynFnMut`] taking no argument.
+pub type LocalDynFnMut0<'capture, R = (), FnStorage = DefaultFnStorage> =
+    LocalDynFnMut<'capture, ForFixed<()>, ForFixed<R>, FnStorage>;
+impl_as_fn!(FnMut LocalDynFnMut, StorageMut);
+impl_callable!(FnMut LocalDynFnMut, FnStorage: StorageMut);
+impl_iter!(LocalDynFnMut, StorageMut, 'capture);
+#[cfg(feature = "nightly")]
+impl_fn_traits!(FnMut LocalDynFnMut, StorageMut);
 
 /// A dynamic [`FnMut`] stored in `FnStorage`.
+///
+/// The function only needs to be [`Send`]: it is never accessed through a shared reference, so
+/// the object is still [`Sync`].
 pub struct DynFnMut<
     'capture,
     Arg: ForLt + 'static,
@@ -158,7 +1128,7 @@ pub struct DynFnMut<
     FnStorage: StorageMut + StorageSend = DefaultFnStorage,
 >(LocalDynFnMut<'capture, Arg, Ret, FnStorage>);
 
-unsafe_impl_send_sync!(sync DynFnMut, StorageMut);
+unsafe_impl_send_sync!(sync exclusive DynFnMut, StorageMut);
 
 impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
     DynFnMut<'capture, Arg, Ret, FnStorage>
@@ -166,26 +1136,127 @@ impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_impl<
-        F: for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture,
+        F: for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
         // SAFETY: same precondition
-        Self(unsafe { LocalDynFnMut::new_impl::<F>(storage) })
+        Self(unsafe { LocalDynFnMut::new_impl::<F, N>(storage) })
     }
 
     /// Calls the underlying function.
+    #[inline]
     pub fn call<'a>(&mut self, arg: Arg::Of<'a>) -> Ret::Of<'a> {
         self.0.call(arg)
     }
+
+    /// Calls the underlying function, writing the result into `out`.
+    ///
+    /// The result is written through an out-pointer instead of being returned through the
+    /// dynamic call; the function itself still returns it by value.
+    #[inline]
+    pub fn call_into<'a, 'o>(
+        &mut self,
+        arg: Arg::Of<'a>,
+        out: &'o mut MaybeUninit<Ret::Of<'a>>,
+    ) -> &'o mut Ret::Of<'a> {
+        self.0.call_into(arg, out)
+    }
+
+    /// Wraps the function with an observer called with the argument before each call.
+    ///
+    /// The returned function has the same signature and storage, so it can replace the original
+    /// one transparently; the wrapper must fit in the storage, like any function passed to
+    /// [`new`](Self::new).
+    pub fn inspect(mut self, mut observer: impl FnMut(&Arg::Of<'_>) + Send + 'capture) -> Self {
+        Self::new(move |arg, _| {
+            observer(&arg);
+            self.call(arg)
+        })
+    }
+
+    /// Wraps the function with an observer called with the result after each call.
+    ///
+    /// The returned function has the same signature and storage, so it can replace the original
+    /// one transparently; the wrapper must fit in the storage, like any function passed to
+    /// [`new`](Self::new).
+    pub fn inspect_output(
+        mut self,
+        mut observer: impl FnMut(&Ret::Of<'_>) + Send + 'capture,
+    ) -> Self {
+        Self::new(move |arg, _| {
+            let ret = self.call(arg);
+            observer(&ret);
+            ret
+        })
+    }
 }
 
-new_impls!(sync DynFnMut, StorageMut + StorageSend, for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture);
+#[cfg(feature = "async")]
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
+    DynFnMut<'capture, Arg, Ret, FnStorage>
+{
+    /// Returns the storage of the function, with its vtable as a synchronous async function.
+    #[cfg(feature = "sync-to-async")]
+    pub(crate) fn into_async_storage(self) -> DynStorage<FnStorage, AsyncVTable<Arg, Ret>> {
+        self.0.into_async_storage()
+    }
+
+    /// Converts back the storage of a synchronous async function, if it has a synchronous vtable;
+    /// the storage is returned otherwise.
+    ///
+    /// # Safety
+    ///
+    /// The stored function must be [`Send`].
+    pub(crate) unsafe fn try_from_async_storage(
+        storage: DynStorage<FnStorage, AsyncVTable<Arg, Ret>>,
+    ) -> Result<Self, DynStorage<FnStorage, AsyncVTable<Arg, Ret>>> {
+        LocalDynFnMut::try_from_async_storage(storage).map(Self)
+    }
+}
+
+new_impls!(sync DynFnMut, StorageMut + StorageSend, for<'a> FnMut(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture);
 
 impl_debug!(sync DynFnMut, StorageMut + StorageSend);
+impl_id!(sync DynFnMut, StorageMut + StorageSend);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(sync DynFnMut, StorageMut + StorageSend);
+impl_named!(sync DynFnMut, StorageMut + StorageSend);
+impl_resize!(sync DynFnMut, StorageMut + StorageSend);
+impl_into_storage!(sync DynFnMut, StorageMut + StorageSend);
+#[cfg(feature = "alloc")]
+impl_leak!(sync DynFnMut, StorageMut + StorageSend);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(sync DynFnMut, StorageMut + StorageSend);
+impl_multi_args!(sync FnMut DynFnMut, StorageMut + StorageSend, Send + 'capture);
+#[cfg(feature = "std")]
+impl_catch_unwind!(FnMut DynFnMut, StorageMut + StorageSend);
+impl_nullary!(sync FnMut DynFnMut, StorageMut + StorageSend, Send + 'capture);
+impl_diverging!(FnMut DynFnMut, StorageMut + StorageSend, Send + 'capture);
+impl_with_state!(sync FnMut DynFnMut, StorageMut + StorageSend, Send + 'capture);
+impl_result!(FnMut DynFnMut, StorageMut + StorageSend, Send + 'capture);
+#[cfg(feature = "alloc")]
+impl_from_box!(FnMut DynFnMut, StorageMut + StorageSend, Send + 'capture);
 
-/// [`DynFnOnce`], but without the [`Send`] + [`Sync`] requirement.
+/// [`DynFnMut`] taking no argument.
+pub type DynFnMut0<'capture, R = (), FnStorage = DefaultFnStorage> =
+    DynFnMut<'capture, ForFixed<()>, ForFixed<R>, FnStorage>;
+impl_as_fn!(FnMut DynFnMut, StorageMut + StorageSend);
+impl_callable!(FnMut DynFnMut, FnStorage: StorageMut + StorageSend);
+impl_iter!(DynFnMut, StorageMut + StorageSend, Send + 'capture);
+#[cfg(feature = "nightly")]
+impl_fn_traits!(FnMut DynFnMut, StorageMut + StorageSend);
+
+/// An iterator calling a dynamic function until it returns `None`.
+///
+/// It is returned by `into_iter`/`iter_mut` methods of [`LocalDynFnMut`]/[`DynFnMut`].
+#[derive(Debug)]
+pub struct DynFnIter<F>(Option<F>);
+
+/// [`DynFnOnce`], but without the [`Send`] requirement.
 pub struct LocalDynFnOnce<
     'capture,
     Arg: ForLt + 'static,
@@ -202,40 +1273,162 @@ impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_impl<
         F: for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
-        let vtable = &SyncVTable {
+        Self {
+            // SAFETY: `drop_vtable` matches the storage
+            storage: unsafe { DynStorage::new(storage, Self::vtable::<F, N>()) },
+            _capture: PhantomData,
+        }
+    }
+
+    /// Returns the vtable of `F`.
+    ///
+    /// It is a function, so the async vtable can refer back to it.
+    const fn vtable<
+        F: for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
+    >() -> &'static SyncVTable<Arg, Ret, FnStorage> {
+        &SyncVTable {
             // SAFETY: storage comes from `DynStorage::move_storage`,
-            // so it's a valid `F`, and is never accessed after; `read`is called once
+            // so it's a valid `F`, and is never accessed after; `read` is called once
             call: |storage, arg, _| unsafe {
                 StorageMoved::<FnStorage, F>::new(storage).read()(arg, PhantomData)
             },
-            drop_vtable: const { DropVTable::new::<FnStorage, F>() },
-        };
-        Self {
-            // SAFETY: `drop_vtable` matches the storage
-            storage: unsafe { DynStorage::new(storage, vtable) },
-            _capture: PhantomData,
+            call_into: |storage, arg, out| {
+                // SAFETY: storage comes from `DynStorage::move_storage`,
+                // so it's a valid `F`, and is never accessed after; `read` is called once
+                let f = unsafe { StorageMoved::<FnStorage, F>::new(storage).read() };
+                out.write(f(arg, PhantomData));
+            },
+            drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+            fn_ptr: false,
+            #[cfg(feature = "sync-to-async")]
+            async_vtable: const { Self::async_vtable::<F, N>() },
+            #[cfg(feature = "arc-waker")]
+            arc_waker: None,
         }
     }
 
     /// Calls the underlying function.
+    #[inline]
     pub fn call(self, arg: Arg::Of<'_>) -> Ret::Of<'_> {
         let mut storage = ManuallyDrop::new(self.storage);
         // SAFETY: `moved_storage` is passed to `StorageMoved` in `call`
         let moved_storage = unsafe { DynStorage::move_storage(&mut storage) };
         (storage.vtable().call)(moved_storage, arg, PhantomData)
     }
+
+    /// Calls the underlying function, writing the result into `out`.
+    ///
+    /// The result is written through an out-pointer instead of being returned through the
+    /// dynamic call; the function itself still returns it by value.
+    #[inline]
+    pub fn call_into<'a, 'o>(
+        self,
+        arg: Arg::Of<'a>,
+        out: &'o mut MaybeUninit<Ret::Of<'a>>,
+    ) -> &'o mut Ret::Of<'a> {
+        let mut storage = ManuallyDrop::new(self.storage);
+        // SAFETY: `moved_storage` is passed to `StorageMoved` in `call_into`
+        let moved_storage = unsafe { DynStorage::move_storage(&mut storage) };
+        (storage.vtable().call_into)(moved_storage, arg, out);
+        // SAFETY: `call_into` initializes `out`
+        unsafe { out.assume_init_mut() }
+    }
+
+    /// Maps the value returned by the function.
+    ///
+    /// The function and `f` are moved together into a new storage.
+    pub fn map<NewRet: ForLt + 'static>(
+        self,
+        f: impl for<'a> FnOnce(Ret::Of<'a>, PhantomData<&'a ()>) -> NewRet::Of<'a> + 'capture,
+    ) -> LocalDynFnOnce<'capture, Arg, NewRet, FnStorage> {
+        LocalDynFnOnce::new(move |arg, lt| f(self.call(arg), lt))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut>
+    LocalDynFnOnce<'capture, Arg, Ret, FnStorage>
+{
+    /// Returns the vtable of `F` as a synchronous async function.
+    pub(crate) const fn async_vtable<
+        F: for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture,
+        N: MaybeName,
+    >() -> &'static AsyncVTable<Arg, Ret, FnStorage> {
+        &AsyncVTable {
+            // SAFETY: storage comes from `DynStorage::move_storage`,
+            // so it's a valid `F`, and is never accessed after; `read` is called once
+            dispatch: Dispatch::Sync(|storage, arg, _| unsafe {
+                StorageMoved::<FnStorage, F>::new(storage).read()(arg, PhantomData)
+            }),
+            drop_vtable: const { DropVTable::new::<FnStorage, F>().with_name::<N>() },
+            sync_vtable: Some(Self::vtable::<F, N>),
+        }
+    }
+
+    /// Returns the storage of the function, with its vtable as a synchronous async function.
+    #[cfg(feature = "sync-to-async")]
+    pub(crate) fn into_async_storage(
+        self,
+    ) -> DynStorage<FnStorage, AsyncVTable<Arg, Ret, FnStorage>> {
+        let vtable = self.storage.vtable().async_vtable;
+        // SAFETY: `async_vtable` is built with the same drop vtable as the function vtable
+        unsafe { self.storage.with_vtable(vtable) }
+    }
+
+    /// Converts back the storage of a synchronous async function, if it has a synchronous vtable;
+    /// the storage is returned otherwise.
+    pub(crate) fn try_from_async_storage(
+        storage: DynStorage<FnStorage, AsyncVTable<Arg, Ret, FnStorage>>,
+    ) -> Result<Self, DynStorage<FnStorage, AsyncVTable<Arg, Ret, FnStorage>>> {
+        let Some(vtable) = storage.vtable().sync_vtable else {
+            return Err(storage);
+        };
+        Ok(Self {
+            // SAFETY: `sync_vtable` is built with the same drop vtable as the function vtable
+            storage: unsafe { storage.with_vtable(vtable()) },
+            _capture: PhantomData,
+        })
+    }
 }
 
 new_impls!(sync LocalDynFnOnce, StorageMut, for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + 'capture);
 
 impl_debug!(sync LocalDynFnOnce, StorageMut);
+impl_id!(sync LocalDynFnOnce, StorageMut);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(sync LocalDynFnOnce, StorageMut);
+impl_named!(sync LocalDynFnOnce, StorageMut);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(sync LocalDynFnOnce, StorageMut);
+impl_multi_args!(sync FnOnce LocalDynFnOnce, StorageMut, 'capture);
+#[cfg(feature = "std")]
+impl_catch_unwind!(FnOnce LocalDynFnOnce, StorageMut);
+impl_nullary!(sync FnOnce LocalDynFnOnce, StorageMut, 'capture);
+impl_diverging!(FnOnce LocalDynFnOnce, StorageMut, 'capture);
+impl_with_state!(sync FnOnce LocalDynFnOnce, StorageMut, 'capture);
+impl_result!(FnOnce LocalDynFnOnce, StorageMut, 'capture);
+#[cfg(feature = "alloc")]
+impl_from_box!(FnOnce LocalDynFnOnce, StorageMut, 'capture);
+impl_callable!(FnOnce LocalDynFnOnce, FnStorage: StorageMut);
+
+/// [`LocalDynFnOnce`] taking no argument.
+pub type LocalDynFnOnce0<'capture, R = (), FnStorage = DefaultFnStorage> =
+    LocalDynFnOnce<'capture, ForFixed<()>, ForFixed<R>, FnStorage>;
+#[cfg(feature = "nightly")]
+impl_fn_traits!(FnOnce LocalDynFnOnce, StorageMut);
 
 /// A dynamic [`FnOnce`] stored in `FnStorage`.
+///
+/// The function only needs to be [`Send`]: it is never accessed through a shared reference, so
+/// the object is still [`Sync`].
 pub struct DynFnOnce<
     'capture,
     Arg: ForLt + 'static,
@@ -243,7 +1436,7 @@ pub struct DynFnOnce<
     FnStorage: StorageMut + StorageSend = DefaultFnStorage,
 >(LocalDynFnOnce<'capture, Arg, Ret, FnStorage>);
 
-unsafe_impl_send_sync!(sync DynFnOnce, StorageMut);
+unsafe_impl_send_sync!(sync exclusive DynFnOnce, StorageMut);
 
 impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
     DynFnOnce<'capture, Arg, Ret, FnStorage>
@@ -251,21 +1444,94 @@ impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut
     /// # Safety
     ///
     /// `storage` must have been initialized with `F`.
+    #[cfg_attr(feature = "track-construction", track_caller)]
     const unsafe fn new_impl<
-        F: for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture,
+        F: for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture,
+        N: MaybeName,
     >(
         storage: FnStorage,
     ) -> Self {
         // SAFETY: same precondition
-        Self(unsafe { LocalDynFnOnce::new_impl::<F>(storage) })
+        Self(unsafe { LocalDynFnOnce::new_impl::<F, N>(storage) })
     }
 
     /// Calls the underlying function.
+    #[inline]
     pub fn call(self, arg: Arg::Of<'_>) -> Ret::Of<'_> {
         self.0.call(arg)
     }
+
+    /// Calls the underlying function, writing the result into `out`.
+    ///
+    /// The result is written through an out-pointer instead of being returned through the
+    /// dynamic call; the function itself still returns it by value.
+    #[inline]
+    pub fn call_into<'a, 'o>(
+        self,
+        arg: Arg::Of<'a>,
+        out: &'o mut MaybeUninit<Ret::Of<'a>>,
+    ) -> &'o mut Ret::Of<'a> {
+        self.0.call_into(arg, out)
+    }
+
+    /// Maps the value returned by the function.
+    ///
+    /// The function and `f` are moved together into a new storage.
+    pub fn map<NewRet: ForLt + 'static>(
+        self,
+        f: impl for<'a> FnOnce(Ret::Of<'a>, PhantomData<&'a ()>) -> NewRet::Of<'a> + Send + 'capture,
+    ) -> DynFnOnce<'capture, Arg, NewRet, FnStorage> {
+        DynFnOnce::new(move |arg, lt| f(self.call(arg), lt))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'capture, Arg: ForLt + 'static, Ret: ForLt + 'static, FnStorage: StorageMut + StorageSend>
+    DynFnOnce<'capture, Arg, Ret, FnStorage>
+{
+    /// Returns the storage of the function, with its vtable as a synchronous async function.
+    #[cfg(feature = "sync-to-async")]
+    pub(crate) fn into_async_storage(
+        self,
+    ) -> DynStorage<FnStorage, AsyncVTable<Arg, Ret, FnStorage>> {
+        self.0.into_async_storage()
+    }
+
+    /// Converts back the storage of a synchronous async function, if it has a synchronous vtable;
+    /// the storage is returned otherwise.
+    ///
+    /// # Safety
+    ///
+    /// The stored function must be [`Send`].
+    pub(crate) unsafe fn try_from_async_storage(
+        storage: DynStorage<FnStorage, AsyncVTable<Arg, Ret, FnStorage>>,
+    ) -> Result<Self, DynStorage<FnStorage, AsyncVTable<Arg, Ret, FnStorage>>> {
+        LocalDynFnOnce::try_from_async_storage(storage).map(Self)
+    }
 }
 
-new_impls!(sync DynFnOnce, StorageMut + StorageSend, for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + Sync + 'capture);
+new_impls!(sync DynFnOnce, StorageMut + StorageSend, for<'a> FnOnce(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a> + Send + 'capture);
 
 impl_debug!(sync DynFnOnce, StorageMut + StorageSend);
+impl_id!(sync DynFnOnce, StorageMut + StorageSend);
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl_instrumented!(sync DynFnOnce, StorageMut + StorageSend);
+impl_named!(sync DynFnOnce, StorageMut + StorageSend);
+#[cfg(feature = "fn_type_name")]
+impl_type_name!(sync DynFnOnce, StorageMut + StorageSend);
+impl_multi_args!(sync FnOnce DynFnOnce, StorageMut + StorageSend, Send + 'capture);
+#[cfg(feature = "std")]
+impl_catch_unwind!(FnOnce DynFnOnce, StorageMut + StorageSend);
+impl_nullary!(sync FnOnce DynFnOnce, StorageMut + StorageSend, Send + 'capture);
+impl_diverging!(FnOnce DynFnOnce, StorageMut + StorageSend, Send + 'capture);
+impl_with_state!(sync FnOnce DynFnOnce, StorageMut + StorageSend, Send + 'capture);
+impl_result!(FnOnce DynFnOnce, StorageMut + StorageSend, Send + 'capture);
+#[cfg(feature = "alloc")]
+impl_from_box!(FnOnce DynFnOnce, StorageMut + StorageSend, Send + 'capture);
+impl_callable!(FnOnce DynFnOnce, FnStorage: StorageMut + StorageSend);
+
+/// [`DynFnOnce`] taking no argument.
+pub type DynFnOnce0<'capture, R = (), FnStorage = DefaultFnStorage> =
+    DynFnOnce<'capture, ForFixed<()>, ForFixed<R>, FnStorage>;
+#[cfg(feature = "nightly")]
+impl_fn_traits!(FnOnce DynFnOnce, StorageMut + StorageSend);
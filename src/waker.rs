@@ -0,0 +1,204 @@
+#[cfg(feature = "alloc")]
+use alloc::{sync::Arc, task::Wake};
+use core::task::{RawWaker, RawWakerVTable, Waker};
+#[cfg(feature = "arc-waker")]
+use core::{marker::PhantomData, ptr::NonNull};
+
+use higher_kinded_types::ForFixed;
+#[cfg(feature = "arc-waker")]
+use higher_kinded_types::ForLt;
+
+use crate::{
+    DynFn,
+    storage::{Storage, StorageSend},
+};
+
+/// A function called when woken, to be converted into a [`Waker`] through
+/// [`Waker::from`]`(Arc<WakeFn<F>>)`.
+///
+/// This is the safe counterpart of [`DynFn::into_waker`], without type erasure.
+///
+/// # Examples
+///
+/// ```
+/// use std::{
+///     sync::{
+///         Arc,
+///         atomic::{AtomicBool, Ordering},
+///     },
+///     task::Waker,
+/// };
+///
+/// use dyn_fn::WakeFn;
+///
+/// let woken = Arc::new(AtomicBool::new(false));
+/// let woken2 = woken.clone();
+/// let waker = Waker::from(Arc::new(WakeFn(move || {
+///     woken2.store(true, Ordering::Relaxed)
+/// })));
+/// waker.wake();
+/// assert!(woken.load(Ordering::Relaxed));
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct WakeFn<F>(pub F);
+
+#[cfg(feature = "alloc")]
+impl<F: Fn() + Send + Sync + 'static> Wake for WakeFn<F> {
+    fn wake(self: Arc<Self>) {
+        (self.0)();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        (self.0)();
+    }
+}
+
+/// The [`RawWakerVTable`] of a function `F` stored in an [`Arc`](crate::storage::Arc), whose
+/// waker data is the storage pointer.
+///
+/// With `arc-waker` feature, it is built for every [`Fn`] stored in an `Arc`, but only used by
+/// [`DynFn::into_waker`].
+#[cfg(feature = "arc-waker")]
+pub(crate) struct ArcWaker<Arg, Ret, F>(PhantomData<(Arg, Ret, F)>);
+
+#[cfg(feature = "arc-waker")]
+impl<Arg: ForLt, Ret: ForLt, F: for<'a> Fn(Arg::Of<'a>, PhantomData<&'a ()>) -> Ret::Of<'a>>
+    ArcWaker<Arg, Ret, F>
+{
+    pub(crate) const VTABLE: RawWakerVTable = RawWakerVTable::new(
+        // SAFETY: the waker data comes from the `Arc` storage in `into_waker`
+        |ptr| unsafe {
+            Arc::increment_strong_count(ptr.cast::<F>());
+            RawWaker::new(ptr, &Self::VTABLE)
+        },
+        // SAFETY: same as above, the waker reference being consumed
+        |ptr| Self::wake(&unsafe { Arc::from_raw(ptr.cast::<F>()) }),
+        // SAFETY: same as above
+        |ptr| Self::wake(unsafe { &*ptr.cast::<F>() }),
+        // SAFETY: same as above, the waker reference being dropped
+        |ptr| drop(unsafe { Arc::from_raw(ptr.cast::<F>()) }),
+    );
+
+    fn wake(f: &F) {
+        // SAFETY: the vtable is only used by `into_waker`, so the argument is `()`,
+        // which can be read from a dangling pointer as it's zero-sized
+        let arg = unsafe { NonNull::<Arg::Of<'_>>::dangling().read() };
+        f(arg, PhantomData);
+    }
+}
+
+impl<FnStorage: Storage + StorageSend + 'static>
+    DynFn<'static, ForFixed<()>, ForFixed<()>, FnStorage>
+{
+    #[cfg(feature = "alloc")]
+    const ARC_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(
+        // SAFETY: the waker data comes from `Arc::into_raw` in `into_waker`
+        |ptr| unsafe {
+            Arc::increment_strong_count(ptr.cast::<Self>());
+            RawWaker::new(ptr, &Self::ARC_WAKER_VTABLE)
+        },
+        // SAFETY: same as above, the waker reference being consumed
+        |ptr| unsafe { Arc::from_raw(ptr.cast::<Self>()) }.call(()),
+        // SAFETY: same as above
+        |ptr| unsafe { &*ptr.cast::<Self>() }.call(()),
+        // SAFETY: same as above, the waker reference being dropped
+        |ptr| drop(unsafe { Arc::from_raw(ptr.cast::<Self>()) }),
+    );
+
+    const STATIC_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |ptr| RawWaker::new(ptr, &Self::STATIC_WAKER_VTABLE),
+        // SAFETY: the waker data comes from a `&'static Self` in `static_waker`
+        |ptr| unsafe { &*ptr.cast::<Self>() }.call(()),
+        // SAFETY: same as above
+        |ptr| unsafe { &*ptr.cast::<Self>() }.call(()),
+        |_| {},
+    );
+
+    /// Converts the function into a [`Waker`] calling it when woken.
+    ///
+    /// The function is shared by the waker and its clones, and dropped with the last of them;
+    /// [`Waker::wake_by_ref`] calls it without consuming the waker. With `arc-waker` feature, a
+    /// function stored in an [`Arc`](crate::storage::Arc) is converted without allocation, the
+    /// waker sharing its reference count; otherwise, it is moved into a new
+    /// [`Arc`](alloc::sync::Arc).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{
+    ///     Arc,
+    ///     atomic::{AtomicUsize, Ordering},
+    /// };
+    ///
+    /// use dyn_fn::{DynFn0, storage};
+    ///
+    /// let wakes = Arc::new(AtomicUsize::new(0));
+    /// let wakes2 = wakes.clone();
+    /// let waker = DynFn0::<(), storage::Arc>::new0(move || {
+    ///     wakes2.fetch_add(1, Ordering::Relaxed);
+    /// })
+    /// .into_waker();
+    /// waker.wake_by_ref();
+    /// waker.wake();
+    /// assert_eq!(wakes.load(Ordering::Relaxed), 2);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn into_waker(self) -> Waker {
+        #[cfg(feature = "arc-waker")]
+        let raw_waker = self
+            .into_arc_raw_waker()
+            .unwrap_or_else(Self::into_new_arc_raw_waker);
+        #[cfg(not(feature = "arc-waker"))]
+        let raw_waker = self.into_new_arc_raw_waker();
+        // SAFETY: the vtable matches the data, which is `Send + Sync`
+        unsafe { Waker::from_raw(raw_waker) }
+    }
+
+    /// Moves the function into a new [`Arc`](alloc::sync::Arc), and converts it into a
+    /// [`RawWaker`].
+    #[cfg(feature = "alloc")]
+    fn into_new_arc_raw_waker(self) -> RawWaker {
+        let ptr = Arc::into_raw(Arc::new(self));
+        RawWaker::new(ptr.cast(), &Self::ARC_WAKER_VTABLE)
+    }
+
+    /// Converts the function into a [`Waker`] calling it when woken, using [`WakeFn`].
+    ///
+    /// It behaves like [`into_waker`](Self::into_waker), but goes through the safe
+    /// [`Wake`](alloc::task::Wake) implementation of [`WakeFn`].
+    #[cfg(feature = "alloc")]
+    pub fn into_arc_waker(self) -> Waker {
+        Waker::from(Arc::new(WakeFn(move || self.call(()))))
+    }
+
+    /// Returns a [`Waker`] calling the function when woken, without allocation.
+    ///
+    /// The function being `'static`, e.g. declared with [`static_dyn_fn!`](crate::static_dyn_fn),
+    /// the waker and its clones only hold a reference to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![no_std]
+    /// # extern crate std;
+    /// use core::sync::atomic::{AtomicBool, Ordering};
+    ///
+    /// use dyn_fn::{hkt::ForFixed, static_dyn_fn};
+    ///
+    /// static WOKEN: AtomicBool = AtomicBool::new(false);
+    /// static_dyn_fn! {
+    ///     static WAKE: (ForFixed<()>) -> ForFixed<()> = |(), _| WOKEN.store(true, Ordering::Relaxed);
+    /// }
+    ///
+    /// # fn main() {
+    /// WAKE.static_waker().wake();
+    /// assert!(WOKEN.load(Ordering::Relaxed));
+    /// # }
+    /// ```
+    pub fn static_waker(&'static self) -> Waker {
+        let ptr = (self as *const Self).cast();
+        // SAFETY: the vtable matches the data, which is `Send + Sync` and never dropped
+        unsafe { Waker::from_raw(RawWaker::new(ptr, &Self::STATIC_WAKER_VTABLE)) }
+    }
+}
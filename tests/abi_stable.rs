@@ -0,0 +1,31 @@
+#![cfg(feature = "abi_stable")]
+
+use abi_stable::{StableAbi, abi_stability::abi_checking::check_layout_compatibility};
+use dyn_fn::{AbiDynFn, DynFn, hkt::ForFixed};
+
+/// A plugin interface, whose layout is checked by `abi_stable` when the plugin is loaded.
+#[repr(C)]
+#[derive(StableAbi)]
+struct Plugin {
+    make_callback: extern "C" fn(u32) -> AbiDynFn<u32, u32>,
+}
+
+extern "C" fn make_callback(offset: u32) -> AbiDynFn<u32, u32> {
+    DynFn::<ForFixed<u32>, ForFixed<u32>>::new(move |x, _| x + offset).into()
+}
+
+#[test]
+fn stable_abi() {
+    let layout = <AbiDynFn<u32, u32> as StableAbi>::LAYOUT;
+    assert!(check_layout_compatibility(layout, layout).is_ok());
+    // the signature is part of the layout
+    let other = <AbiDynFn<u32, u64> as StableAbi>::LAYOUT;
+    assert!(check_layout_compatibility(layout, other).is_err());
+    assert!(check_layout_compatibility(Plugin::LAYOUT, Plugin::LAYOUT).is_ok());
+
+    let plugin = Plugin { make_callback };
+    let f = (plugin.make_callback)(1);
+    assert_eq!(f.call(41), 42);
+    let f = DynFn::<ForFixed<u32>, ForFixed<u32>>::from(f);
+    assert_eq!(f.call(41), 42);
+}
@@ -1,7 +1,34 @@
-#[cfg(all(not(miri), not(coverage_nightly), feature = "alloc"))]
+// stderr output is compiler-dependent, so it is only checked on stable
+#[cfg(all(
+    not(miri),
+    not(coverage_nightly),
+    not(feature = "nightly"),
+    feature = "alloc",
+    feature = "async"
+))]
 #[test]
 fn compilation() {
     let t = trybuild::TestCases::new();
     t.pass("tests/compilation/not-local.rs");
     t.compile_fail("tests/compilation/local.rs");
+    t.compile_fail("tests/compilation/stateful.rs");
+    t.pass("tests/compilation/covariant.rs");
+    t.pass("tests/compilation/hkt-compat.rs");
+    t.compile_fail("tests/compilation/extend-capture.rs");
+    t.compile_fail("tests/compilation/capture.rs");
+    t.compile_fail("tests/compilation/arg-escape.rs");
+    t.compile_fail("tests/compilation/once-twice.rs");
+    t.compile_fail("tests/compilation/raw-size.rs");
+    t.compile_fail("tests/compilation/raw-align.rs");
+    t.compile_fail("tests/compilation/static-size.rs");
+    t.compile_fail("tests/compilation/static-capture.rs");
+    t.compile_fail("tests/compilation/static-local.rs");
+    t.compile_fail("tests/compilation/mut-not-send.rs");
+    t.compile_fail("tests/compilation/async-mut-not-send.rs");
+    t.compile_fail("tests/compilation/send-not-sync.rs");
+    t.compile_fail("tests/compilation/unsafe-fn.rs");
+    t.compile_fail("tests/compilation/leak-capture.rs");
+    t.compile_fail("tests/compilation/method-escape.rs");
+    #[cfg(feature = "macros")]
+    t.compile_fail("tests/compilation/callback.rs");
 }
@@ -0,0 +1,10 @@
+use dyn_fn::{hkt::*, *};
+
+fn main() {
+    let f = LocalDynFn::<ForRef<str>, ForRef<str>>::new(|s, _| s);
+    let res = {
+        let s = String::from("test");
+        f.call(&s)
+    };
+    println!("{res}");
+}
@@ -0,0 +1,17 @@
+use std::{cell::Cell, rc::Rc};
+
+use dyn_fn::{hkt::*, *};
+
+fn main() {
+    // `DynAsyncFn` is still shared, so its function must be `Sync`
+    let cell = Cell::new(0);
+    DynAsyncFn::<ForFixed<u64>, ForFixed<u64>>::new_sync(move |x, _| cell.get() + x);
+    // `DynAsyncFnMut`/`DynAsyncFnOnce` functions must still be `Send`
+    let rc = Rc::new(0);
+    DynAsyncFnMut::<ForFixed<u64>, ForFixed<u64>>::new_sync(move |x, _| *rc + x);
+    let rc = Rc::new(());
+    DynAsyncFnOnce::<ForFixed<u64>, ForFixed<u64>>::new_sync(move |x, _| {
+        drop(rc);
+        x
+    });
+}
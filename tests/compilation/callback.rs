@@ -0,0 +1,32 @@
+use dyn_fn::callback;
+
+#[callback]
+fn generic<T>(x: T) -> T {
+    x
+}
+
+#[callback]
+fn ambiguous<'a, 'b>(a: &'a str, b: &'b str) -> &'a str {
+    a
+}
+
+#[callback]
+fn too_many_args(a: u8, b: u8, c: u8, d: u8) -> u8 {
+    a + b + c + d
+}
+
+#[callback]
+fn impl_trait(x: impl Into<u8>) -> u8 {
+    x.into()
+}
+
+struct Handler;
+
+impl Handler {
+    #[callback]
+    fn receiver(&self, x: u8) -> u8 {
+        x
+    }
+}
+
+fn main() {}
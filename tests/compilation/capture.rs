@@ -0,0 +1,9 @@
+use dyn_fn::{hkt::*, *};
+
+fn main() {
+    let f = {
+        let s = String::from("test");
+        LocalDynFn::<'static, ForFixed<()>, ForFixed<usize>>::new(|_, _| s.len())
+    };
+    println!("{}", f.call(()));
+}
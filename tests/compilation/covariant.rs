@@ -0,0 +1,75 @@
+use dyn_fn::{hkt::*, *};
+
+fn shrink_local_dyn_fn<'long: 'short, 'short>(
+    f: LocalDynFn<'long, ForRef<str>, ForRef<str>>,
+) -> LocalDynFn<'short, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn shrink_dyn_fn<'long: 'short, 'short>(
+    f: DynFn<'long, ForRef<str>, ForRef<str>>,
+) -> DynFn<'short, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn shrink_local_dyn_fn_mut<'long: 'short, 'short>(
+    f: LocalDynFnMut<'long, ForRef<str>, ForRef<str>>,
+) -> LocalDynFnMut<'short, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn shrink_dyn_fn_mut<'long: 'short, 'short>(
+    f: DynFnMut<'long, ForRef<str>, ForRef<str>>,
+) -> DynFnMut<'short, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn shrink_local_dyn_fn_once<'long: 'short, 'short>(
+    f: LocalDynFnOnce<'long, ForRef<str>, ForRef<str>>,
+) -> LocalDynFnOnce<'short, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn shrink_dyn_fn_once<'long: 'short, 'short>(
+    f: DynFnOnce<'long, ForRef<str>, ForRef<str>>,
+) -> DynFnOnce<'short, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn shrink_local_dyn_async_fn<'long: 'short, 'short>(
+    f: LocalDynAsyncFn<'long, ForRef<str>, ForRef<str>>,
+) -> LocalDynAsyncFn<'short, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn shrink_dyn_async_fn<'long: 'short, 'short>(
+    f: DynAsyncFn<'long, ForRef<str>, ForRef<str>>,
+) -> DynAsyncFn<'short, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn shrink_local_dyn_async_fn_mut<'long: 'short, 'short>(
+    f: LocalDynAsyncFnMut<'long, ForRef<str>, ForRef<str>>,
+) -> LocalDynAsyncFnMut<'short, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn shrink_dyn_async_fn_mut<'long: 'short, 'short>(
+    f: DynAsyncFnMut<'long, ForRef<str>, ForRef<str>>,
+) -> DynAsyncFnMut<'short, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn shrink_local_dyn_async_fn_once<'long: 'short, 'short>(
+    f: LocalDynAsyncFnOnce<'long, ForRef<str>, ForRef<str>>,
+) -> LocalDynAsyncFnOnce<'short, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn shrink_dyn_async_fn_once<'long: 'short, 'short>(
+    f: DynAsyncFnOnce<'long, ForRef<str>, ForRef<str>>,
+) -> DynAsyncFnOnce<'short, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn main() {}
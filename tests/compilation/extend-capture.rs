@@ -0,0 +1,75 @@
+use dyn_fn::{hkt::*, *};
+
+fn extend_local_dyn_fn<'long: 'short, 'short>(
+    f: LocalDynFn<'short, ForRef<str>, ForRef<str>>,
+) -> LocalDynFn<'long, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn extend_dyn_fn<'long: 'short, 'short>(
+    f: DynFn<'short, ForRef<str>, ForRef<str>>,
+) -> DynFn<'long, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn extend_local_dyn_fn_mut<'long: 'short, 'short>(
+    f: LocalDynFnMut<'short, ForRef<str>, ForRef<str>>,
+) -> LocalDynFnMut<'long, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn extend_dyn_fn_mut<'long: 'short, 'short>(
+    f: DynFnMut<'short, ForRef<str>, ForRef<str>>,
+) -> DynFnMut<'long, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn extend_local_dyn_fn_once<'long: 'short, 'short>(
+    f: LocalDynFnOnce<'short, ForRef<str>, ForRef<str>>,
+) -> LocalDynFnOnce<'long, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn extend_dyn_fn_once<'long: 'short, 'short>(
+    f: DynFnOnce<'short, ForRef<str>, ForRef<str>>,
+) -> DynFnOnce<'long, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn extend_local_dyn_async_fn<'long: 'short, 'short>(
+    f: LocalDynAsyncFn<'short, ForRef<str>, ForRef<str>>,
+) -> LocalDynAsyncFn<'long, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn extend_dyn_async_fn<'long: 'short, 'short>(
+    f: DynAsyncFn<'short, ForRef<str>, ForRef<str>>,
+) -> DynAsyncFn<'long, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn extend_local_dyn_async_fn_mut<'long: 'short, 'short>(
+    f: LocalDynAsyncFnMut<'short, ForRef<str>, ForRef<str>>,
+) -> LocalDynAsyncFnMut<'long, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn extend_dyn_async_fn_mut<'long: 'short, 'short>(
+    f: DynAsyncFnMut<'short, ForRef<str>, ForRef<str>>,
+) -> DynAsyncFnMut<'long, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn extend_local_dyn_async_fn_once<'long: 'short, 'short>(
+    f: LocalDynAsyncFnOnce<'short, ForRef<str>, ForRef<str>>,
+) -> LocalDynAsyncFnOnce<'long, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn extend_dyn_async_fn_once<'long: 'short, 'short>(
+    f: DynAsyncFnOnce<'short, ForRef<str>, ForRef<str>>,
+) -> DynAsyncFnOnce<'long, ForRef<str>, ForRef<str>> {
+    f
+}
+
+fn main() {}
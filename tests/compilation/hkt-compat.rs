@@ -0,0 +1,29 @@
+// code written against the re-exported `higher_kinded_types` keeps compiling
+use dyn_fn::{
+    DynAsyncFn, DynFn, LocalDynFnMut,
+    hkt::{ForFixed, ForLt, ForRef},
+};
+
+fn call<'a, Arg: ForLt, Ret: ForLt>(f: &DynFn<'_, Arg, Ret>, arg: Arg::Of<'a>) -> Ret::Of<'a> {
+    f.call(arg)
+}
+
+async fn call_async<'a, Arg: ForLt, Ret: ForLt>(
+    f: &DynAsyncFn<'_, Arg, Ret>,
+    arg: Arg::Of<'a>,
+) -> Ret::Of<'a> {
+    f.call(arg).await
+}
+
+fn local<'a, Arg: dyn_fn::ForLt>(f: &mut LocalDynFnMut<'_, Arg, ForFixed<()>>, arg: Arg::Of<'a>) {
+    f.call(arg);
+}
+
+fn main() {
+    let trim = DynFn::<ForRef<str>, ForRef<str>>::new(|s, _| s.trim());
+    assert_eq!(call(&trim, " test "), "test");
+    let len = DynAsyncFn::<ForLt!(<'a> = &'a [u8]), ForFixed<usize>>::new_sync(|s, _| s.len());
+    let _ = call_async(&len, &[42]);
+    let mut f = LocalDynFnMut::<ForLt!(<'a> = &'a mut u8), ForFixed<()>>::new(|x, _| *x += 1);
+    local(&mut f, &mut 0);
+}
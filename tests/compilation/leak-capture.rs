@@ -0,0 +1,7 @@
+use dyn_fn::{DynFn, hkt::ForFixed};
+
+fn main() {
+    let offset = 1;
+    let f = DynFn::<ForFixed<u32>, ForFixed<u32>>::new(|x, _| x + offset).leak();
+    assert_eq!(f.call(41), 42);
+}
@@ -0,0 +1,19 @@
+use std::sync::{Arc, Mutex};
+
+use dyn_fn::{
+    DynAsyncFn,
+    hkt::{ForFixed, ForLt},
+};
+
+struct Foo;
+
+fn main() {
+    // the object cannot be lent to the argument, as the function may drop it before
+    let _ = DynAsyncFn::<ForLt!(<'a> = &'a Mutex<Option<&'a Foo>>), ForFixed<()>>::from_dyn_method(
+        Arc::new(Foo),
+        |obj, slot| {
+            *slot.lock().unwrap() = Some(&*obj);
+            Box::pin(async {})
+        },
+    );
+}
@@ -0,0 +1,17 @@
+use std::{cell::Cell, rc::Rc};
+
+use dyn_fn::{hkt::*, *};
+
+fn main() {
+    // `DynFn` is still shared, so its function must be `Sync`
+    let cell = Cell::new(0);
+    DynFn::<ForFixed<u64>, ForFixed<u64>>::new(move |x, _| cell.get() + x);
+    // `DynFnMut`/`DynFnOnce` functions must still be `Send`
+    let rc = Rc::new(0);
+    DynFnMut::<ForFixed<u64>, ForFixed<u64>>::new(move |x, _| *rc + x);
+    let rc = Rc::new(());
+    DynFnOnce::<ForFixed<u64>, ForFixed<u64>>::new(move |x, _| {
+        drop(rc);
+        x
+    });
+}
@@ -36,4 +36,12 @@ fn check_async_fn_once(f: DynAsyncFnOnce<ForRef<str>, ForRef<str>>) {
     assert_send(&f.call("test"));
 }
 
+fn check_send_fn(f: SendDynFn<ForRef<str>, ForRef<str>>) {
+    assert_send(&f);
+}
+
+fn check_send_async_fn(f: SendDynAsyncFn<ForRef<str>, ForRef<str>>) {
+    assert_send(&f);
+}
+
 fn main() {}
@@ -0,0 +1,7 @@
+use dyn_fn::{hkt::*, *};
+
+fn main() {
+    let f = LocalDynFnOnce::<ForFixed<()>, ForFixed<()>>::new(|_, _| ());
+    f.call(());
+    f.call(());
+}
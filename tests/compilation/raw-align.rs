@@ -0,0 +1,7 @@
+use dyn_fn::{hkt::*, *};
+
+fn main() {
+    let data = 0u128;
+    let f = LocalDynFn::<ForFixed<()>, ForFixed<u128>, storage::Raw<16, 1>>::new(move |_, _| data);
+    println!("{}", f.call(()));
+}
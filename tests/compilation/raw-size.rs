@@ -0,0 +1,7 @@
+use dyn_fn::{hkt::*, *};
+
+fn main() {
+    let data = [0u8; 16];
+    let f = LocalDynFn::<ForFixed<()>, ForFixed<u8>, storage::Raw<8>>::new(move |_, _| data[0]);
+    println!("{}", f.call(()));
+}
@@ -0,0 +1,21 @@
+use std::rc::Rc;
+
+use dyn_fn::{hkt::*, *};
+
+fn assert_sync<T: Sync>(_: &T) {}
+
+fn check_send_fn(f: SendDynFn<ForRef<str>, ForRef<str>>) {
+    assert_sync(&f);
+}
+
+fn check_send_async_fn(f: SendDynAsyncFn<ForRef<str>, ForRef<str>>) {
+    assert_sync(&f);
+}
+
+// clones would share the function between threads
+fn check_shared_storage(_: SendDynFn<ForRef<str>, ForRef<str>, storage::Arc>) {}
+
+fn main() {
+    let rc = Rc::new(0);
+    SendDynFn::<ForFixed<u64>, ForFixed<u64>>::new(move |x, _| *rc + x);
+}
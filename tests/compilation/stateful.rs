@@ -0,0 +1,11 @@
+use dyn_fn::{hkt::*, *};
+
+fn main() {
+    let res = {
+        let f = LocalDynStatefulFn::<ForFixed<()>, ForRef<str>>::new(String::from("test"), |s, _| {
+            s.as_str()
+        });
+        f.call(())
+    };
+    println!("{res}");
+}
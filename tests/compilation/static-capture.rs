@@ -0,0 +1,14 @@
+use std::cell::Cell;
+
+use dyn_fn::{hkt::*, static_dyn_fn};
+
+static_dyn_fn! {
+    static INCR: (ForFixed<()>) -> ForFixed<usize> = {
+        let count = Cell::new(0);
+        move |_, _| count.replace(count.get() + 1)
+    };
+}
+
+fn main() {
+    INCR.call(());
+}
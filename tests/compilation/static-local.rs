@@ -0,0 +1,9 @@
+use dyn_fn::{hkt::*, static_dyn_fn};
+
+fn main() {
+    let prefix = String::from("prefix");
+    static_dyn_fn! {
+        static HAS_PREFIX: (ForRef<str>) -> ForFixed<bool> = |s, _| s.starts_with(&prefix);
+    }
+    HAS_PREFIX.call("prefixed");
+}
@@ -0,0 +1,12 @@
+use dyn_fn::{hkt::*, static_dyn_fn, storage::Raw};
+
+static_dyn_fn! {
+    static FIRST: (ForFixed<()>) -> ForFixed<u8> as Raw<8> = {
+        let data = [0u8; 16];
+        move |_, _| data[0]
+    };
+}
+
+fn main() {
+    println!("{}", FIRST.call(()));
+}
@@ -0,0 +1,14 @@
+use std::marker::PhantomData;
+
+use dyn_fn::{hkt::*, *};
+
+unsafe fn read(ptr: *const u64, _: PhantomData<&()>) -> u64 {
+    unsafe { ptr.read() }
+}
+
+fn main() {
+    DynFn::<ForFixed<*const u64>, ForFixed<u64>>::new(read);
+    LocalDynFnMut::<ForFixed<*const u64>, ForFixed<u64>>::new(read);
+    let f = UnsafeDynFn::<ForFixed<*const u64>, ForFixed<u64>>::new_unsafe(read);
+    f.call(&0);
+}
@@ -0,0 +1,105 @@
+//! Embassy-style usage: callbacks in statics, `StaticCell` and `'static` task arguments, driven by
+//! a single-threaded executor, without allocation.
+#![cfg(feature = "async")]
+#![no_std]
+extern crate std;
+
+use core::cell::Cell;
+use std::alloc::{GlobalAlloc, Layout, System};
+
+use dyn_fn::{hkt::*, storage::Raw, *};
+use embassy_futures::{block_on, yield_now};
+use static_cell::StaticCell;
+
+struct CountingAlloc;
+
+std::thread_local! {
+    static ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+// SAFETY: forwards to `System`
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCS.try_with(|n| n.set(n.get() + 1));
+        // SAFETY: same precondition
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: same precondition
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// Runs `f`, and checks it doesn't allocate.
+fn assert_no_alloc(f: impl FnOnce()) {
+    let allocs = ALLOCS.with(Cell::get);
+    f();
+    assert_eq!(ALLOCS.with(Cell::get), allocs, "allocation");
+}
+
+type FutureStorage = Raw<64>;
+
+struct Double;
+impl AsyncFnSend<'static, ForFixed<u32>, ForFixed<u32>> for Double {
+    async fn call<'a>(&self, arg: <ForFixed<u32> as ForLt>::Of<'a>) -> u32 {
+        yield_now().await;
+        arg * 2
+    }
+}
+
+struct Accumulate(u32);
+impl AsyncFnMutSend<'static, ForFixed<u32>, ForFixed<u32>> for Accumulate {
+    async fn call<'a>(&mut self, arg: <ForFixed<u32> as ForLt>::Of<'a>) -> u32 {
+        yield_now().await;
+        self.0 += arg;
+        self.0
+    }
+}
+
+static DOUBLE: DynAsyncFn<'static, ForFixed<u32>, ForFixed<u32>, Raw<0>, FutureStorage> =
+    DynAsyncFn::new_const(Double);
+
+type Handler =
+    DynAsyncFnMut<'static, ForFixed<u32>, ForFixed<u32>, Raw<{ size_of::<u32>() }>, FutureStorage>;
+static HANDLER: StaticCell<Handler> = StaticCell::new();
+
+/// An embassy task, whose arguments must be `'static`.
+async fn task(handler: &'static mut Handler, inputs: &'static [u32]) -> u32 {
+    let mut total = 0;
+    for &input in inputs {
+        total = handler.call(DOUBLE.call(input).await).await;
+    }
+    total
+}
+
+#[test]
+fn static_cell_task() {
+    assert_no_alloc(|| {
+        let handler = HANDLER.init(Handler::new(Accumulate(0)));
+        assert_eq!(block_on(task(handler, &[1, 2, 3])), 12);
+    });
+}
+
+#[test]
+fn local_not_send() {
+    assert_no_alloc(|| {
+        // `&Cell` is not `Send`, neither are the futures holding it
+        let total = Cell::new(0);
+        let add = LocalDynAsyncFn::<ForFixed<u32>, ForFixed<()>, Raw<8>, FutureStorage>::new(
+            async |x, _| {
+                let total = &total;
+                yield_now().await;
+                total.set(total.get() + x);
+            },
+        );
+        block_on(async {
+            add.call(1).await;
+            add.call(2).await;
+        });
+        assert_eq!(total.get(), 3);
+    });
+}
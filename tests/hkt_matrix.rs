@@ -0,0 +1,176 @@
+//! Matrix of `Arg`/`Ret` shapes × callback kinds × storages, checking with random payloads that
+//! arguments and returned values round-trip through every function, that captures are dropped
+//! exactly once, and that `call_sync` agrees with `call` for `new_sync`-built async functions.
+#![cfg(feature = "alloc")]
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering::Relaxed},
+};
+
+use dyn_fn::{hkt::*, hkt_ext::ForRefMut, *};
+#[cfg(feature = "async")]
+use futures_util::FutureExt;
+use proptest::prelude::*;
+
+/// Captures of the tested functions, counting their drops.
+struct Captures {
+    drops: Arc<AtomicUsize>,
+    salt: u64,
+}
+
+impl Captures {
+    fn new(drops: &Arc<AtomicUsize>, salt: u64) -> Self {
+        Self {
+            drops: drops.clone(),
+            salt,
+        }
+    }
+
+    fn salt(&self) -> u64 {
+        self.salt
+    }
+}
+
+impl Drop for Captures {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Relaxed);
+    }
+}
+
+macro_rules! matrix {
+    ($(
+        $shape:ident: $arg:ty => $ret:ty, $owned:ty = $input:expr,
+        |$v:ident| $to_arg:expr, |$x:ident, $salt:ident| $plain:expr;
+    )*) => {$(
+        mod $shape {
+            use super::*;
+
+            type Arg = $arg;
+            type Ret = $ret;
+
+            fn input() -> impl Strategy<Value = $owned> {
+                $input
+            }
+
+            // the input is mutable for `ForRefMut` shapes
+            #[allow(clippy::ptr_arg)]
+            fn arg<'a>($v: &'a mut $owned) -> <Arg as ForLt>::Of<'a> {
+                $to_arg
+            }
+
+            fn plain<'a>($x: <Arg as ForLt>::Of<'a>, $salt: u64) -> <Ret as ForLt>::Of<'a> {
+                $plain
+            }
+
+            kinds!();
+        }
+    )*};
+}
+
+macro_rules! kinds {
+    () => {
+        kinds!(new fn:
+            local_dyn_fn LocalDynFn, dyn_fn DynFn, send_dyn_fn SendDynFn,
+            local_dyn_fn_mut LocalDynFnMut, dyn_fn_mut DynFnMut);
+        kinds!(new once: local_dyn_fn_once LocalDynFnOnce, dyn_fn_once DynFnOnce);
+        #[cfg(feature = "async")]
+        kinds!(new_sync fn:
+            local_dyn_async_fn LocalDynAsyncFn, dyn_async_fn DynAsyncFn,
+            send_dyn_async_fn SendDynAsyncFn, local_dyn_async_fn_mut LocalDynAsyncFnMut,
+            dyn_async_fn_mut DynAsyncFnMut);
+        #[cfg(feature = "async")]
+        kinds!(new_sync once:
+            local_dyn_async_fn_once LocalDynAsyncFnOnce, dyn_async_fn_once DynAsyncFnOnce);
+    };
+    ($new:ident $call:tt: $($kind:ident $fn:ident),*) => {$(
+        mod $kind {
+            use super::*;
+
+            case!(raw, storage::Raw<32>, $new $call $fn);
+            case!(boxed, storage::Box, $new $call $fn);
+            case!(raw_or_box_inline, storage::RawOrBox<32>, $new $call $fn);
+            case!(raw_or_box_spilled, storage::RawOrBox<8>, $new $call $fn);
+        }
+    )*};
+}
+
+/// Checks a call against the plain function, each with its own copy of the input.
+macro_rules! check {
+    ($input:ident, $salt:ident, $f:ident.$method:ident $($res:tt)*) => {{
+        let (mut a, mut b) = ($input.clone(), $input.clone());
+        prop_assert_eq!($f.$method(arg(&mut a)) $($res)*, plain(arg(&mut b), $salt));
+        prop_assert_eq!(a, b);
+    }};
+}
+
+macro_rules! case {
+    ($name:ident, $storage:ty, $new:ident $call:tt $fn:ident) => {
+        proptest! {
+            #[test]
+            fn $name(input in input(), salt in any::<u64>()) {
+                let drops = Arc::new(AtomicUsize::new(0));
+                let new = |captures: Captures| {
+                    $fn::<Arg, Ret, $storage>::$new(move |x, _| plain(x, captures.salt()))
+                };
+                case!(@ $new $call, new, input, salt, drops);
+            }
+        }
+    };
+    (@ new fn, $new:ident, $input:ident, $salt:ident, $drops:ident) => {
+        #[allow(unused_mut)]
+        let mut f = $new(Captures::new(&$drops, $salt));
+        check!($input, $salt, f.call);
+        check!($input, $salt, f.call);
+        prop_assert_eq!($drops.load(Relaxed), 0);
+        drop(f);
+        prop_assert_eq!($drops.load(Relaxed), 1);
+    };
+    (@ new once, $new:ident, $input:ident, $salt:ident, $drops:ident) => {
+        let f = $new(Captures::new(&$drops, $salt));
+        check!($input, $salt, f.call);
+        prop_assert_eq!($drops.load(Relaxed), 1);
+        drop($new(Captures::new(&$drops, $salt)));
+        prop_assert_eq!($drops.load(Relaxed), 2);
+    };
+    (@ new_sync fn, $new:ident, $input:ident, $salt:ident, $drops:ident) => {
+        #[allow(unused_mut)]
+        let mut f = $new(Captures::new(&$drops, $salt));
+        prop_assert!(f.is_sync());
+        check!($input, $salt, f.call_sync.unwrap());
+        check!($input, $salt, f.call.now_or_never().unwrap());
+        check!($input, $salt, f.call_try_sync.now_or_never().unwrap());
+        prop_assert_eq!($drops.load(Relaxed), 0);
+        drop(f);
+        prop_assert_eq!($drops.load(Relaxed), 1);
+    };
+    (@ new_sync once, $new:ident, $input:ident, $salt:ident, $drops:ident) => {
+        let f = $new(Captures::new(&$drops, $salt));
+        prop_assert!(f.is_sync());
+        check!($input, $salt, f.call_sync.unwrap());
+        let f = $new(Captures::new(&$drops, $salt));
+        check!($input, $salt, f.call.now_or_never().unwrap());
+        let f = $new(Captures::new(&$drops, $salt));
+        check!($input, $salt, f.call_try_sync.now_or_never().unwrap());
+        prop_assert_eq!($drops.load(Relaxed), 3);
+        drop($new(Captures::new(&$drops, $salt)));
+        prop_assert_eq!($drops.load(Relaxed), 4);
+    };
+}
+
+matrix! {
+    fixed: ForFixed<u64> => ForFixed<u64>, u64 = any::<u64>(),
+        |v| *v, |x, salt| x.wrapping_mul(salt | 1);
+    ref_str: ForRef<str> => ForRef<str>, String = ".{0,32}",
+        |v| v.as_str(), |x, salt| x.trim_start_matches(char::from(b'a' + (salt % 26) as u8));
+    ref_slice: ForRef<[u8]> => ForRef<[u8]>, Vec<u8> = prop::collection::vec(any::<u8>(), 0..64),
+        |v| v.as_slice(), |x, salt| &x[salt as usize % (x.len() + 1)..];
+    ref_mut: ForRefMut<Vec<u8>> => ForFixed<usize>,
+        Vec<u8> = prop::collection::vec(any::<u8>(), 0..64),
+        |v| v, |x, salt| { x.push(salt as u8); x.len() };
+    ref_string: ForRef<String> => ForRef<str>, String = ".{0,32}",
+        |v| &*v, |x, salt| x.get(..salt as usize % (x.len() + 1)).unwrap_or(x);
+    fixed_option: ForFixed<Option<&'static str>> => ForFixed<Option<&'static str>>,
+        Option<&'static str> = prop::option::of(prop::sample::select(&[" a ", "b", "", " \t"][..])),
+        |v| *v, |x, salt| x.filter(|_| salt % 3 != 0).map(str::trim);
+}
@@ -0,0 +1,699 @@
+#![cfg(feature = "alloc")]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    marker::PhantomData,
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::{
+        Once,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+#[cfg(feature = "async")]
+use std::{
+    future::Future,
+    pin::{Pin, pin},
+    task::{Context, Poll, Waker},
+};
+
+use dyn_fn::{hkt::*, *};
+#[cfg(feature = "async")]
+use futures_util::FutureExt;
+
+struct CountingAlloc;
+
+thread_local! {
+    static LIVE_ALLOCS: Cell<isize> = const { Cell::new(0) };
+    static LIVE_OBJECTS: Cell<isize> = const { Cell::new(0) };
+    static FAIL_ALLOCS: Cell<bool> = const { Cell::new(false) };
+}
+
+// SAFETY: forwards to `System`
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if FAIL_ALLOCS.try_with(Cell::get).unwrap_or(false) {
+            return std::ptr::null_mut();
+        }
+        let _ = LIVE_ALLOCS.try_with(|n| n.set(n.get() + 1));
+        // SAFETY: same precondition
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let _ = LIVE_ALLOCS.try_with(|n| n.set(n.get() - 1));
+        // SAFETY: same precondition
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// Live object counter, captured by the tested functions.
+struct Counted;
+impl Counted {
+    fn new() -> Self {
+        LIVE_OBJECTS.with(|n| n.set(n.get() + 1));
+        Self
+    }
+}
+impl Drop for Counted {
+    fn drop(&mut self) {
+        LIVE_OBJECTS.with(|n| n.set(n.get() - 1));
+    }
+}
+
+struct Guard<'a>(&'a AtomicUsize);
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Runs `f`, and checks it doesn't leak any allocation or [`Counted`] object.
+fn assert_no_leak(f: impl FnOnce()) {
+    static SILENT_HOOK: Once = Once::new();
+    SILENT_HOOK.call_once(|| std::panic::set_hook(Box::new(|_| {})));
+    // warm up the panic machinery, which may lazily allocate thread-local state
+    let _ = catch_unwind(|| panic!("warm up"));
+    let allocs = LIVE_ALLOCS.with(Cell::get);
+    let objects = LIVE_OBJECTS.with(Cell::get);
+    f();
+    assert_eq!(LIVE_ALLOCS.with(Cell::get), allocs, "leaked allocation");
+    assert_eq!(LIVE_OBJECTS.with(Cell::get), objects, "leaked object");
+}
+
+/// Runs a scenario, and checks that both allocations and [`Counted`] objects are back to their
+/// baseline after it.
+macro_rules! assert_balanced {
+    ($($scenario:tt)*) => {
+        assert_no_leak(|| { $($scenario)* })
+    };
+}
+
+/// Runs `f`, which must panic, and checks it doesn't leak any allocation.
+fn assert_unwind_no_leak(f: impl FnOnce()) {
+    assert_no_leak(|| assert!(catch_unwind(AssertUnwindSafe(f)).is_err()));
+}
+
+fn once_call_unwind<S: storage::StorageMut + storage::StorageSend>(padding: usize) {
+    let dropped = AtomicUsize::new(0);
+    assert_unwind_no_leak(|| {
+        let guard = Guard(&dropped);
+        let padding = vec![0u8; padding];
+        let f = LocalDynFnOnce::<ForFixed<bool>, ForFixed<usize>, S>::new(move |fail, _| {
+            let _guard = &guard;
+            assert!(!fail);
+            padding.len()
+        });
+        f.call(true);
+    });
+    assert_eq!(dropped.load(Ordering::Relaxed), 1);
+    let dropped = AtomicUsize::new(0);
+    assert_unwind_no_leak(|| {
+        let guard = Guard(&dropped);
+        let f = DynFnOnce::<ForFixed<bool>, ForFixed<()>, S>::new(move |fail, _| {
+            let _guard = &guard;
+            assert!(!fail);
+        });
+        f.call_into(true, &mut core::mem::MaybeUninit::uninit());
+    });
+    assert_eq!(dropped.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn once_call_unwind_box() {
+    once_call_unwind::<storage::Box>(0);
+}
+
+#[test]
+fn once_call_unwind_raw() {
+    once_call_unwind::<storage::Raw<{ 4 * size_of::<usize>() }>>(0);
+}
+
+#[test]
+fn once_call_unwind_raw_or_box() {
+    // stored inline
+    once_call_unwind::<storage::RawOrBox<{ 4 * size_of::<usize>() }>>(0);
+    // stored in a box
+    once_call_unwind::<storage::RawOrBox<0>>(8);
+}
+
+#[cfg(feature = "async")]
+struct PanicOnCall<'a> {
+    _guard: Guard<'a>,
+}
+#[cfg(feature = "async")]
+impl<'capture> AsyncFnOnceSend<'capture, ForFixed<()>, ForFixed<()>> for PanicOnCall<'capture> {
+    fn call(
+        self,
+        _: <ForFixed<()> as ForLt>::Of<'_>,
+    ) -> impl Future<Output = <ForFixed<()> as ForLt>::Of<'_>> + Send {
+        if std::hint::black_box(true) {
+            panic!("future construction");
+        }
+        async move { drop(self) }
+    }
+}
+
+#[cfg(feature = "async")]
+fn async_once_construction_unwind<S: storage::StorageMut + storage::StorageSend>() {
+    let dropped = AtomicUsize::new(0);
+    assert_unwind_no_leak(|| {
+        let guard = Guard(&dropped);
+        let f = LocalDynAsyncFnOnce::<ForFixed<bool>, ForFixed<()>, S>::new(
+            move |fail: bool, _: PhantomData<&()>| {
+                let _guard = &guard;
+                assert!(!fail);
+                async {}
+            },
+        );
+        let _ = f.call(true).now_or_never();
+    });
+    assert_eq!(dropped.load(Ordering::Relaxed), 1);
+    let dropped = AtomicUsize::new(0);
+    assert_unwind_no_leak(|| {
+        let f = DynAsyncFnOnce::<ForFixed<()>, ForFixed<()>, S>::new(PanicOnCall {
+            _guard: Guard(&dropped),
+        });
+        let _ = f.call(()).now_or_never();
+    });
+    assert_eq!(dropped.load(Ordering::Relaxed), 1);
+    let dropped = AtomicUsize::new(0);
+    assert_unwind_no_leak(|| {
+        let guard = Guard(&dropped);
+        let f = DynAsyncFnOnce::<ForFixed<bool>, ForFixed<()>, S>::new_sync(move |fail, _| {
+            let _guard = &guard;
+            assert!(!fail);
+        });
+        f.call_sync(true);
+    });
+    assert_eq!(dropped.load(Ordering::Relaxed), 1);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_once_construction_unwind_box() {
+    async_once_construction_unwind::<storage::Box>();
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_once_construction_unwind_raw() {
+    async_once_construction_unwind::<storage::Raw<{ 4 * size_of::<usize>() }>>();
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_once_construction_unwind_raw_or_box() {
+    async_once_construction_unwind::<storage::RawOrBox<{ 4 * size_of::<usize>() }>>();
+    async_once_construction_unwind::<storage::RawOrBox<0>>();
+}
+
+#[cfg(feature = "async")]
+struct YieldOnce(bool);
+#[cfg(feature = "async")]
+impl Future for YieldOnce {
+    type Output = ();
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if std::mem::replace(&mut self.0, true) {
+            return Poll::Ready(());
+        }
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+#[cfg(feature = "async")]
+/// Polls the future returned by `call` until it panics, then drops it after catching the panic.
+fn poll_until_panic<Fut: Future>(call: impl FnOnce() -> Fut) {
+    assert_no_leak(|| {
+        let mut fut = pin!(call());
+        let mut cx = Context::from_waker(Waker::noop());
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            while fut.as_mut().poll(&mut cx).is_pending() {}
+        }));
+        assert!(res.is_err());
+    });
+}
+
+#[cfg(feature = "async")]
+fn poll_unwind<S: storage::StorageMut>() {
+    for pending in [false, true] {
+        let dropped = AtomicUsize::new(0);
+        let f =
+            LocalDynAsyncFn::<ForFixed<()>, ForFixed<()>, storage::Box, S>::new(async |_, _| {
+                let _guard = Guard(&dropped);
+                if pending {
+                    YieldOnce(false).await;
+                }
+                panic!("poll");
+            });
+        poll_until_panic(|| f.call(()));
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+        poll_until_panic(|| {
+            LocalDynAsyncFnOnce::<ForFixed<()>, ForFixed<()>, storage::Box, S>::new(async |_, _| {
+                let _guard = Guard(&dropped);
+                if pending {
+                    YieldOnce(false).await;
+                }
+                panic!("poll");
+            })
+            .call(())
+        });
+        assert_eq!(dropped.load(Ordering::Relaxed), 2);
+    }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poll_unwind_box() {
+    poll_unwind::<storage::Box>();
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poll_unwind_raw() {
+    poll_unwind::<storage::Raw<{ 16 * size_of::<usize>() }>>();
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poll_unwind_raw_or_box() {
+    poll_unwind::<storage::RawOrBox<{ 16 * size_of::<usize>() }>>();
+    poll_unwind::<storage::RawOrBox<0>>();
+}
+
+#[cfg(feature = "async")]
+/// Polls `fut` to completion with a noop waker.
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(res) = fut.as_mut().poll(&mut cx) {
+            return res;
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+/// Polls `fut` once with a noop waker.
+fn poll_once<F: Future>(fut: Pin<&mut F>) -> Poll<F::Output> {
+    fut.poll(&mut Context::from_waker(Waker::noop()))
+}
+
+/// Constructs a sync closure capturing a [`Counted`], an allocation, and an interior mutable
+/// call counter.
+fn sync_closure() -> impl Fn(bool, PhantomData<&()>) -> usize + Send + Sync {
+    let counted = Counted::new();
+    let padding = Vec::from([0u8; 8]);
+    let calls = AtomicUsize::new(0);
+    move |fail, _| {
+        let _counted = &counted;
+        calls.fetch_add(1, Ordering::Relaxed);
+        assert!(!fail);
+        padding.len()
+    }
+}
+
+#[cfg(feature = "async")]
+/// Constructs an async closure capturing a [`Counted`] and an allocation,
+/// whose future is pending once before completing.
+fn async_closure() -> impl AsyncFn(bool, PhantomData<&()>) -> usize {
+    let counted = Counted::new();
+    let padding = Vec::from([0u8; 8]);
+    async move |fail, _| {
+        let _counted = &counted;
+        YieldOnce(false).await;
+        assert!(!fail);
+        padding.len()
+    }
+}
+
+#[cfg(feature = "async")]
+/// [`async_closure`], as an [`AsyncFnSend`] implementation.
+struct AsyncCallback {
+    _counted: Counted,
+    padding: Vec<u8>,
+}
+#[cfg(feature = "async")]
+impl AsyncCallback {
+    fn new() -> Self {
+        Self {
+            _counted: Counted::new(),
+            padding: vec![0u8; 8],
+        }
+    }
+}
+#[cfg(feature = "async")]
+impl<'capture> AsyncFnSend<'capture, ForFixed<bool>, ForFixed<usize>> for AsyncCallback {
+    async fn call<'a>(
+        &self,
+        fail: <ForFixed<bool> as ForLt>::Of<'a>,
+    ) -> <ForFixed<usize> as ForLt>::Of<'a> {
+        YieldOnce(false).await;
+        assert!(!fail);
+        self.padding.len()
+    }
+}
+#[cfg(feature = "async")]
+impl<'capture> AsyncFnMutSend<'capture, ForFixed<bool>, ForFixed<usize>> for AsyncCallback {
+    async fn call<'a>(
+        &mut self,
+        fail: <ForFixed<bool> as ForLt>::Of<'a>,
+    ) -> <ForFixed<usize> as ForLt>::Of<'a> {
+        <Self as AsyncFnSend<_, _>>::call(self, fail).await
+    }
+}
+#[cfg(feature = "async")]
+impl<'capture> AsyncFnOnceSend<'capture, ForFixed<bool>, ForFixed<usize>> for AsyncCallback {
+    async fn call<'a>(
+        self,
+        fail: <ForFixed<bool> as ForLt>::Of<'a>,
+    ) -> <ForFixed<usize> as ForLt>::Of<'a> {
+        <Self as AsyncFnSend<_, _>>::call(&self, fail).await
+    }
+}
+
+type Raw = storage::Raw<{ 4 * size_of::<usize>() }>;
+type RawOrBoxInline = storage::RawOrBox<{ 4 * size_of::<usize>() }>;
+type RawOrBoxBoxed = storage::RawOrBox<0>;
+
+/// Runs every sync scenario for a function type constructed with `$new`, for each storage `S`.
+macro_rules! sync_matrix {
+    ($test:ident, $new:expr, [$($storage:ty),+]) => {
+        #[test]
+        #[allow(unused_mut)]
+        fn $test() {$({
+            type S = $storage;
+            // call to completion
+            assert_balanced!(let mut f = $new; assert_eq!(f.call(false), 8););
+            // drop without call
+            assert_balanced!(drop($new));
+            // panic
+            assert_balanced! {
+                let mut f = $new;
+                assert!(catch_unwind(AssertUnwindSafe(move || f.call(true))).is_err());
+            }
+        })+}
+    };
+}
+
+sync_matrix!(
+    local_dyn_fn_matrix,
+    LocalDynFn::<ForFixed<bool>, ForFixed<usize>, S>::new(sync_closure()),
+    [
+        storage::Box,
+        storage::Rc,
+        storage::Arc,
+        Raw,
+        RawOrBoxInline,
+        RawOrBoxBoxed
+    ]
+);
+sync_matrix!(
+    dyn_fn_matrix,
+    DynFn::<ForFixed<bool>, ForFixed<usize>, S>::new(sync_closure()),
+    [
+        storage::Box,
+        storage::Arc,
+        Raw,
+        RawOrBoxInline,
+        RawOrBoxBoxed
+    ]
+);
+sync_matrix!(
+    local_dyn_fn_mut_matrix,
+    LocalDynFnMut::<ForFixed<bool>, ForFixed<usize>, S>::new(sync_closure()),
+    [storage::Box, Raw, RawOrBoxInline, RawOrBoxBoxed]
+);
+sync_matrix!(
+    dyn_fn_mut_matrix,
+    DynFnMut::<ForFixed<bool>, ForFixed<usize>, S>::new(sync_closure()),
+    [storage::Box, Raw, RawOrBoxInline, RawOrBoxBoxed]
+);
+sync_matrix!(
+    local_dyn_fn_once_matrix,
+    LocalDynFnOnce::<ForFixed<bool>, ForFixed<usize>, S>::new(sync_closure()),
+    [storage::Box, Raw, RawOrBoxInline, RawOrBoxBoxed]
+);
+sync_matrix!(
+    dyn_fn_once_matrix,
+    DynFnOnce::<ForFixed<bool>, ForFixed<usize>, S>::new(sync_closure()),
+    [storage::Box, Raw, RawOrBoxInline, RawOrBoxBoxed]
+);
+
+#[test]
+fn compact_dyn_fn_matrix() {
+    // call to completion
+    assert_balanced! {
+        let f = LocalCompactDynFn::<ForFixed<bool>, ForFixed<usize>>::new(sync_closure());
+        assert_eq!(f.call(false), 8);
+    };
+    assert_balanced! {
+        let f = CompactDynFn::<ForFixed<bool>, ForFixed<usize>>::new(sync_closure());
+        assert_eq!(f.call(false), 8);
+    };
+    // drop without call
+    assert_balanced!(drop(CompactDynFn::<ForFixed<bool>, ForFixed<usize>>::new(
+        sync_closure()
+    )));
+    // zero-sized function
+    assert_balanced!(drop(CompactDynFn::<ForFixed<bool>, ForFixed<usize>>::new(
+        |_, _| 0
+    )));
+    // panic
+    assert_balanced! {
+        let f = CompactDynFn::<ForFixed<bool>, ForFixed<usize>>::new(sync_closure());
+        assert!(catch_unwind(AssertUnwindSafe(move || f.call(true))).is_err());
+    }
+}
+
+/// Runs every async scenario for a function type constructed with `$new`, for each storage `S`.
+macro_rules! async_matrix {
+    ($test:ident, $new:expr, [$($storage:ty),+]) => {
+        #[cfg(feature = "async")]
+        #[test]
+        #[allow(unused_mut)]
+        fn $test() {$({
+            type S = $storage;
+            // call to completion
+            assert_balanced!(let mut f = $new; assert_eq!(block_on(f.call(false)), 8););
+            // call to completion, trying sync call first
+            assert_balanced!(let mut f = $new; assert_eq!(block_on(f.call_try_sync(false)), 8););
+            // drop without call
+            assert_balanced!(drop($new));
+            // drop the future without polling it
+            assert_balanced!(let mut f = $new; drop(f.call(false)););
+            // drop the future mid-poll (sync functions complete on first poll)
+            assert_balanced! {
+                let mut f = $new;
+                let mut fut = pin!(f.call(false));
+                let _ = poll_once(fut.as_mut());
+            }
+            // panic, dropping the future after catching it
+            assert_balanced! {
+                let mut f = $new;
+                let mut fut = pin!(f.call(true));
+                assert!(catch_unwind(AssertUnwindSafe(|| block_on(fut.as_mut()))).is_err());
+            }
+        })+}
+    };
+}
+
+async_matrix!(
+    local_dyn_async_fn_matrix,
+    LocalDynAsyncFn::<ForFixed<bool>, ForFixed<usize>, S>::new(async_closure()),
+    [
+        storage::Box,
+        storage::Rc,
+        storage::Arc,
+        Raw,
+        RawOrBoxInline,
+        RawOrBoxBoxed
+    ]
+);
+async_matrix!(
+    local_dyn_async_fn_sync_matrix,
+    LocalDynAsyncFn::<ForFixed<bool>, ForFixed<usize>, S>::new_sync(sync_closure()),
+    [
+        storage::Box,
+        storage::Rc,
+        storage::Arc,
+        Raw,
+        RawOrBoxInline,
+        RawOrBoxBoxed
+    ]
+);
+async_matrix!(
+    dyn_async_fn_matrix,
+    DynAsyncFn::<ForFixed<bool>, ForFixed<usize>, S>::new(AsyncCallback::new()),
+    [
+        storage::Box,
+        storage::Arc,
+        Raw,
+        RawOrBoxInline,
+        RawOrBoxBoxed
+    ]
+);
+async_matrix!(
+    dyn_async_fn_sync_matrix,
+    DynAsyncFn::<ForFixed<bool>, ForFixed<usize>, S>::new_sync(sync_closure()),
+    [
+        storage::Box,
+        storage::Arc,
+        Raw,
+        RawOrBoxInline,
+        RawOrBoxBoxed
+    ]
+);
+async_matrix!(
+    local_dyn_async_fn_mut_matrix,
+    LocalDynAsyncFnMut::<ForFixed<bool>, ForFixed<usize>, S>::new(async_closure()),
+    [storage::Box, Raw, RawOrBoxInline, RawOrBoxBoxed]
+);
+async_matrix!(
+    local_dyn_async_fn_mut_sync_matrix,
+    LocalDynAsyncFnMut::<ForFixed<bool>, ForFixed<usize>, S>::new_sync(sync_closure()),
+    [storage::Box, Raw, RawOrBoxInline, RawOrBoxBoxed]
+);
+async_matrix!(
+    dyn_async_fn_mut_matrix,
+    DynAsyncFnMut::<ForFixed<bool>, ForFixed<usize>, S>::new(AsyncCallback::new()),
+    [storage::Box, Raw, RawOrBoxInline, RawOrBoxBoxed]
+);
+async_matrix!(
+    dyn_async_fn_mut_sync_matrix,
+    DynAsyncFnMut::<ForFixed<bool>, ForFixed<usize>, S>::new_sync(sync_closure()),
+    [storage::Box, Raw, RawOrBoxInline, RawOrBoxBoxed]
+);
+async_matrix!(
+    local_dyn_async_fn_once_matrix,
+    LocalDynAsyncFnOnce::<ForFixed<bool>, ForFixed<usize>, S>::new(async_closure()),
+    [storage::Box, Raw, RawOrBoxInline, RawOrBoxBoxed]
+);
+async_matrix!(
+    local_dyn_async_fn_once_sync_matrix,
+    LocalDynAsyncFnOnce::<ForFixed<bool>, ForFixed<usize>, S>::new_sync(sync_closure()),
+    [storage::Box, Raw, RawOrBoxInline, RawOrBoxBoxed]
+);
+async_matrix!(
+    dyn_async_fn_once_matrix,
+    DynAsyncFnOnce::<ForFixed<bool>, ForFixed<usize>, S>::new(AsyncCallback::new()),
+    [storage::Box, Raw, RawOrBoxInline, RawOrBoxBoxed]
+);
+async_matrix!(
+    dyn_async_fn_once_sync_matrix,
+    DynAsyncFnOnce::<ForFixed<bool>, ForFixed<usize>, S>::new_sync(sync_closure()),
+    [storage::Box, Raw, RawOrBoxInline, RawOrBoxBoxed]
+);
+async_matrix!(
+    local_dyn_async_fn_box_cached_matrix,
+    LocalDynAsyncFn::<ForFixed<bool>, ForFixed<usize>, S, storage::BoxCached>::new(async_closure()),
+    [storage::Box, storage::BoxCached]
+);
+async_matrix!(
+    dyn_async_fn_box_cached_matrix,
+    DynAsyncFn::<ForFixed<bool>, ForFixed<usize>, S, storage::BoxCached>::new(AsyncCallback::new()),
+    [storage::Box, storage::BoxCached]
+);
+async_matrix!(
+    local_dyn_async_fn_mut_box_cached_matrix,
+    LocalDynAsyncFnMut::<ForFixed<bool>, ForFixed<usize>, S, storage::BoxCached>::new(
+        async_closure()
+    ),
+    [storage::Box, storage::BoxCached]
+);
+async_matrix!(
+    local_dyn_async_fn_once_box_cached_matrix,
+    LocalDynAsyncFnOnce::<ForFixed<bool>, ForFixed<usize>, S, storage::BoxCached>::new(
+        async_closure()
+    ),
+    [storage::Box, storage::BoxCached]
+);
+
+/// Runs `f`, returning its result and the number of allocations it left alive.
+fn count_allocs<R>(f: impl FnOnce() -> R) -> (R, isize) {
+    let allocs = LIVE_ALLOCS.with(Cell::get);
+    let res = f();
+    (res, LIVE_ALLOCS.with(Cell::get) - allocs)
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn box_cached_reuse() {
+    type Callback<'a> =
+        LocalDynAsyncFn<'a, ForFixed<bool>, ForFixed<usize>, storage::Box, storage::BoxCached>;
+    assert_balanced! {
+        let f = Callback::new(async_closure());
+        // the first future allocation is kept in the function once the future is dropped
+        let (res, allocs) = count_allocs(|| block_on(f.call(false)));
+        assert_eq!((res, allocs), (8, 1));
+        // then it's reused by the next calls
+        for _ in 0..3 {
+            let (res, allocs) = count_allocs(|| block_on(f.call(false)));
+            assert_eq!((res, allocs), (8, 0));
+        }
+        // including when the future is dropped before completion
+        let (_, allocs) = count_allocs(|| {
+            let mut fut = pin!(f.call(false));
+            assert!(poll_once(fut.as_mut()).is_pending());
+        });
+        assert_eq!(allocs, 0);
+        // concurrent calls miss the cache, and only one allocation is kept
+        let (_, allocs) = count_allocs(|| {
+            let mut fut1 = pin!(f.call(false));
+            let mut fut2 = pin!(f.call(false));
+            assert!(poll_once(fut1.as_mut()).is_pending());
+            assert!(poll_once(fut2.as_mut()).is_pending());
+            assert_eq!(block_on(fut1), 8);
+            assert_eq!(block_on(fut2), 8);
+        });
+        assert_eq!(allocs, 0);
+        // the cached allocation is freed with the function
+        drop(f);
+    }
+}
+
+#[test]
+fn arc_into_waker() {
+    assert_balanced! {
+        let x = Counted::new();
+        let f = DynFn0::<(), storage::Arc>::new0(move || {
+            let _ = &x;
+        });
+        // with `arc-waker`, the waker and its clones share the storage reference count
+        let (waker, allocs) = count_allocs(|| {
+            let waker = f.into_waker();
+            waker.clone().wake();
+            waker.wake_by_ref();
+            waker.clone()
+        });
+        assert_eq!(allocs, if cfg!(feature = "arc-waker") { 0 } else { 1 });
+        drop(waker);
+        // other storages are moved into a new `Arc`
+        let f = DynFn0::<(), storage::Box>::new0(|| {});
+        let (waker, allocs) = count_allocs(|| f.into_waker());
+        assert_eq!(allocs, 1);
+        drop(waker);
+    }
+}
+
+#[test]
+fn try_new_alloc_failed() {
+    fn check<S: storage::StorageMut>() {
+        let x = (Counted::new(), [0u64; 2]);
+        FAIL_ALLOCS.with(|f| f.set(true));
+        let res = LocalDynFnMut::<ForFixed<()>, ForFixed<u64>, S>::try_new(move |_, _| x.1[0]);
+        FAIL_ALLOCS.with(|f| f.set(false));
+        // the function is dropped with the error
+        assert!(matches!(
+            res,
+            Err(DynFnError::AllocFailed { layout }) if layout.size() == size_of::<[u64; 2]>()
+        ));
+    }
+    assert_no_leak(check::<storage::Box>);
+    assert_no_leak(check::<storage::BoxCached>);
+    assert_no_leak(check::<storage::RawOrBox<0>>);
+}
@@ -0,0 +1,208 @@
+//! Model checks of the concurrent state machines, run with
+//! `RUSTFLAGS="--cfg loom" cargo test --release --test loom`.
+#![cfg(all(loom, feature = "alloc"))]
+
+use dyn_fn::{
+    ArcDynFnOnce, AtomicDynFn, CancellableDynFn, DynFn, DynFnMut, DynFnOnce, IsrCallback,
+    SpinDynFnMut,
+    hkt::ForFixed,
+    storage::{Storage, StorageSend},
+};
+use loom::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+};
+
+/// Counts the drops of the functions capturing it.
+#[derive(Clone)]
+struct Drops(Arc<AtomicUsize>);
+
+impl Drops {
+    fn new() -> Self {
+        Self(Arc::new(AtomicUsize::new(0)))
+    }
+
+    fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn add<S: Storage + StorageSend>(
+        &self,
+        value: u32,
+    ) -> DynFn<'static, ForFixed<u32>, ForFixed<u32>, S> {
+        struct Guard(Arc<AtomicUsize>);
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        let guard = Guard(self.0.clone());
+        DynFn::new(move |x, _| {
+            let _ = &guard;
+            x + value
+        })
+    }
+}
+
+type Callback = IsrCallback<ForFixed<u32>, ForFixed<u32>>;
+
+#[test]
+fn swap_while_calling() {
+    loom::model(|| {
+        let drops = Drops::new();
+        let callback = Arc::new(Callback::new());
+        assert!(callback.swap(drops.add(1)).is_none());
+        let caller = thread::spawn({
+            let callback = callback.clone();
+            move || callback.call_if_set(0)
+        });
+        let prev = callback.swap(drops.add(2)).unwrap();
+        // the previous function is only returned once its calls have completed
+        assert_eq!(prev.call(0), 1);
+        drop(prev);
+        assert_eq!(drops.get(), 1);
+        assert!(matches!(caller.join().unwrap(), Some(1 | 2)));
+        assert_eq!(callback.call_if_set(0), Some(2));
+        drop(callback);
+        assert_eq!(drops.get(), 2);
+    });
+}
+
+#[test]
+fn take_while_calling() {
+    loom::model(|| {
+        let drops = Drops::new();
+        let callback = Arc::new(Callback::new());
+        callback.swap(drops.add(1));
+        let caller = thread::spawn({
+            let callback = callback.clone();
+            move || callback.call_if_set(0)
+        });
+        drop(callback.take());
+        assert_eq!(drops.get(), 1);
+        assert!(!callback.is_set());
+        assert!(matches!(caller.join().unwrap(), None | Some(1)));
+        assert_eq!(callback.call_if_set(0), None);
+    });
+}
+
+#[test]
+fn concurrent_swaps() {
+    let mut builder = loom::model::Builder::new();
+    // writers spin on each other, which makes the state space explode without bound
+    builder.preemption_bound = Some(3);
+    builder.check(|| {
+        let drops = Drops::new();
+        let callback = Arc::new(Callback::new());
+        let swapper = thread::spawn({
+            let callback = callback.clone();
+            let drops = drops.clone();
+            move || callback.swap(drops.add(1)).map(|f| f.call(0))
+        });
+        let prev = callback.swap(drops.add(2)).map(|f| f.call(0));
+        let prev2 = swapper.join().unwrap();
+        // exactly one swap has replaced the other's function
+        assert!(matches!((prev, prev2), (None, Some(2)) | (Some(1), None)));
+        assert_eq!(drops.get(), 1);
+        drop(callback);
+        assert_eq!(drops.get(), 2);
+    });
+}
+
+#[test]
+fn load_while_swapping() {
+    loom::model(|| {
+        let drops = Drops::new();
+        let cell = Arc::new(AtomicDynFn::new());
+        cell.store(drops.add(1));
+        let loader = thread::spawn({
+            let cell = cell.clone();
+            move || cell.load().map(|f| f.call(0))
+        });
+        drop(cell.swap(drops.add(2)));
+        assert!(matches!(loader.join().unwrap(), Some(1 | 2)));
+        // the previous function is dropped with its last loaded clone
+        assert_eq!(drops.get(), 1);
+        assert_eq!(cell.load().unwrap().call(0), 2);
+        drop(cell);
+        assert_eq!(drops.get(), 2);
+    });
+}
+
+#[test]
+fn cancel_while_calling() {
+    loom::model(|| {
+        let drops = Drops::new();
+        let (f, token) = CancellableDynFn::<_, _>::new(drops.add(1));
+        let caller = thread::spawn(move || f.call(0));
+        token.cancel();
+        // cancellation has waited for the call in flight, if any
+        assert_eq!(drops.get(), 1);
+        assert!(matches!(caller.join().unwrap(), None | Some(1)));
+    });
+}
+
+type Once = ArcDynFnOnce<'static, ForFixed<u32>, ForFixed<u32>>;
+
+#[test]
+fn first_wins_race() {
+    loom::model(|| {
+        let drops = Drops::new();
+        let add: DynFn<ForFixed<u32>, ForFixed<u32>> = drops.add(1);
+        let f = Once::new(DynFnOnce::new(move |x, _| add.call(x)));
+        let racer = thread::spawn({
+            let f = f.clone();
+            move || f.call_first_wins(0)
+        });
+        let first = f.call_first_wins(0);
+        let second = racer.join().unwrap();
+        // exactly one call wins, and the function is dropped once
+        assert!(matches!((first, second), (Some(1), None) | (None, Some(1))));
+        assert!(f.is_called());
+        assert_eq!(drops.get(), 1);
+    });
+}
+
+#[test]
+fn try_call_while_dropping() {
+    loom::model(|| {
+        let drops = Drops::new();
+        let add: DynFn<ForFixed<u32>, ForFixed<u32>> = drops.add(1);
+        let f = Once::new(DynFnOnce::new(move |x, _| add.call(x)));
+        let dropper = thread::spawn({
+            let f = f.clone();
+            move || drop(f)
+        });
+        let res = f.try_call(0).map_err(|f| f.call_first_wins(0));
+        dropper.join().unwrap();
+        assert!(matches!(res, Ok(1) | Err(Some(1))));
+        assert_eq!(drops.get(), 1);
+    });
+}
+
+#[test]
+fn spin_calls_exclusion() {
+    loom::model(|| {
+        let mut count = 0;
+        let f = Arc::new(SpinDynFnMut::<ForFixed<u32>, ForFixed<u32>>::new(
+            DynFnMut::new(move |x, _| {
+                count += x;
+                count
+            }),
+        ));
+        let caller = thread::spawn({
+            let f = f.clone();
+            move || f.call(1)
+        });
+        let mine = f.try_call(2);
+        let theirs = caller.join().unwrap();
+        // the calls are serialized, so each one sees the state left by the other
+        match mine {
+            Some(mine) => assert!((mine, theirs) == (2, 3) || (mine, theirs) == (3, 1)),
+            None => assert_eq!(theirs, 1),
+        }
+    });
+}
@@ -2,7 +2,17 @@ use core::sync::atomic::{AtomicUsize, Ordering};
 
 use dyn_fn::{hkt::*, *};
 
+/// Increments its counter when dropped, to check that captures are dropped exactly once.
+struct Guard<'a>(&'a AtomicUsize);
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "async")]
 struct F<'a>(&'a AtomicUsize);
+#[cfg(feature = "async")]
 impl<'capture> AsyncFnSend<'capture, ForRef<str>, ForFixed<usize>> for F<'capture> {
     async fn call<'a>(
         &self,
@@ -12,6 +22,7 @@ impl<'capture> AsyncFnSend<'capture, ForRef<str>, ForFixed<usize>> for F<'captur
         arg.len()
     }
 }
+#[cfg(feature = "async")]
 impl<'capture> AsyncFnMutSend<'capture, ForRef<str>, ForFixed<usize>> for F<'capture> {
     async fn call<'a>(
         &mut self,
@@ -20,6 +31,7 @@ impl<'capture> AsyncFnMutSend<'capture, ForRef<str>, ForFixed<usize>> for F<'cap
         <Self as AsyncFnSend<_, _>>::call(self, arg).await
     }
 }
+#[cfg(feature = "async")]
 impl<'capture> AsyncFnOnceSend<'capture, ForRef<str>, ForFixed<usize>> for F<'capture> {
     async fn call<'a>(
         self,
@@ -102,13 +114,3645 @@ macro_rules! test {
 
 test!(sync(clone) dyn_fn, DynFn);
 test!(sync(clone) local_dyn_fn, LocalDynFn);
+test!(sync send_dyn_fn, SendDynFn);
 test!(sync dyn_fn_mut, DynFnMut);
 test!(sync local_dyn_fn_mut, LocalDynFnMut);
 test!(sync dyn_fn_once, DynFnOnce);
 test!(sync local_dyn_fn_once, LocalDynFnOnce);
+#[cfg(feature = "async")]
 test!(async-send(clone) dyn_async_fn, DynAsyncFn);
+#[cfg(feature = "async")]
 test!(async(clone) local_dyn_async_fn, LocalDynAsyncFn);
+#[cfg(feature = "async")]
+test!(async send_dyn_async_fn, SendDynAsyncFn);
+#[cfg(feature = "async")]
 test!(async-send dyn_async_fn_mut, DynAsyncFnMut);
+#[cfg(feature = "async")]
 test!(async local_dyn_async_fn_mut, LocalDynAsyncFnMut);
+#[cfg(feature = "async")]
 test!(async-send dyn_async_fn_once, DynAsyncFnOnce);
+#[cfg(feature = "async")]
 test!(async local_dyn_async_fn_once, LocalDynAsyncFnOnce);
+
+#[cfg(feature = "nightly")]
+#[test]
+fn fn_traits() {
+    let double = DynFn::<ForFixed<usize>, ForFixed<usize>>::new(|x, _| 2 * x);
+    assert_eq!((1..4).map(&double).collect::<Vec<_>>(), [2, 4, 6]);
+    assert_eq!(double(21), 42);
+    let neg = LocalDynFn::<ForRef<i32>, ForFixed<i32>>::new(|x: &i32, _| -x);
+    let mut array = [2, 3, 1];
+    array.sort_by_key(&neg);
+    assert_eq!(array, [3, 2, 1]);
+    let mut sum = 0;
+    let mut acc = DynFnMut::<ForFixed<usize>, ForFixed<usize>>::new(|x, _| {
+        sum += x;
+        sum
+    });
+    assert_eq!((1..4).map(&mut acc).collect::<Vec<_>>(), [1, 3, 6]);
+    drop(acc);
+    assert_eq!(sum, 6);
+    let len = LocalDynFnOnce::<ForRef<str>, ForFixed<usize>>::new(|s: &str, _| s.len());
+    let arg = Some("test");
+    assert_eq!(arg.map(len), Some(4));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn with_arg_validation() {
+    fn validate(s: &&str) -> Result<(), &'static str> {
+        if s.is_empty() { Err("empty") } else { Ok(()) }
+    }
+    let called = AtomicUsize::new(0);
+    let callback = LocalDynFn::<ForRef<str>, ForRef<str>>::new(|s: &str, _| {
+        called.fetch_add(1, Ordering::Relaxed);
+        s.trim()
+    })
+    .with_arg_validation(validate);
+    assert_eq!(callback.call(""), Err("empty"));
+    assert_eq!(called.load(Ordering::Relaxed), 0);
+    assert_eq!(callback.call(" test "), Ok("test"));
+    assert_eq!(called.load(Ordering::Relaxed), 1);
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+
+        let callback = LocalDynAsyncFn::<ForRef<str>, ForRef<str>>::new_sync(|s: &str, _| {
+            called.fetch_add(1, Ordering::Relaxed);
+            s.trim()
+        })
+        .with_async_arg_validation(validate);
+        assert!(callback.is_sync());
+        assert_eq!(callback.call_sync(""), Some(Err("empty")));
+        assert_eq!(called.load(Ordering::Relaxed), 1);
+        assert_eq!(callback.call_sync(" test "), Some(Ok("test")));
+        assert_eq!(called.load(Ordering::Relaxed), 2);
+
+        let callback = LocalDynAsyncFn::<ForRef<str>, ForRef<str>>::new(async |s: &str, _| {
+            called.fetch_add(1, Ordering::Relaxed);
+            s.trim()
+        })
+        .with_async_arg_validation(validate);
+        assert!(!callback.is_sync());
+        assert_eq!(callback.call("").now_or_never().unwrap(), Err("empty"));
+        assert_eq!(called.load(Ordering::Relaxed), 2);
+        let res = callback.call(" test ").now_or_never().unwrap();
+        assert_eq!(res, Ok("test"));
+        assert_eq!(called.load(Ordering::Relaxed), 3);
+    }
+}
+
+#[test]
+fn multi_args() {
+    use dyn_fn::args::{ForTuple2, ForTuple3};
+    let starts_with =
+        DynFn::<ForTuple2<ForRef<str>, ForRef<str>>, ForFixed<bool>>::new2(|s, prefix, _| {
+            s.starts_with(prefix)
+        });
+    assert!(starts_with.call2("test", "te"));
+    assert!(!starts_with.call(("test", "st")));
+
+    let mut total = 0;
+    let mut split =
+        LocalDynFnMut::<ForTuple2<ForFixed<usize>, ForRef<str>>, ForRef<str>>::new2(|n, s, _| {
+            total += n;
+            &s[n..]
+        });
+    assert_eq!(split.call2(2, "test"), "st");
+    assert_eq!(split.call2(1, "test"), "est");
+    drop(split);
+    assert_eq!(total, 3);
+
+    let clamp = LocalDynFnOnce::<
+        ForTuple3<ForFixed<i32>, ForFixed<i32>, ForFixed<i32>>,
+        ForFixed<i32>,
+    >::new3(|x, min, max, _| x.clamp(min, max));
+    assert_eq!(clamp.call3(42, 0, 10), 10);
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+
+        let concat = DynAsyncFn::<ForTuple2<ForRef<str>, ForRef<str>>, ForFixed<usize>>::new_sync2(
+            |a, b, _| a.len() + b.len(),
+        );
+        assert!(concat.is_sync());
+        assert_eq!(concat.call_sync2("a", "bc"), Some(3));
+        assert_eq!(concat.call_try_sync2("a", "bc").now_or_never(), Some(3));
+
+        let longest = LocalDynAsyncFn::<ForTuple2<ForRef<str>, ForRef<str>>, ForRef<str>>::new2(
+            async |a, b, _| if a.len() >= b.len() { a } else { b },
+        );
+        assert!(!longest.is_sync());
+        assert_eq!(longest.call_sync2("a", "bc"), None);
+        assert_eq!(longest.call2("a", "bc").now_or_never(), Some("bc"));
+    }
+}
+
+#[cfg(all(feature = "std", feature = "fn_type_name"))]
+#[test]
+fn metrics_hook() {
+    use std::{
+        panic::{AssertUnwindSafe, catch_unwind},
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    #[derive(Default)]
+    struct MockHook {
+        calls: Mutex<Vec<(&'static str, String)>>,
+    }
+    impl MetricsHook for MockHook {
+        fn on_call_start(&self, fn_name: &str) {
+            self.calls.lock().unwrap().push(("start", fn_name.into()));
+        }
+        fn on_call_end(&self, fn_name: &str, _duration: Duration) {
+            self.calls.lock().unwrap().push(("end", fn_name.into()));
+        }
+        fn on_call_error(&self, fn_name: &str) {
+            self.calls.lock().unwrap().push(("error", fn_name.into()));
+        }
+    }
+
+    let hook = Arc::new(MockHook::default());
+    let double = LocalDynFn::<ForFixed<i32>, ForFixed<i32>>::new(|x, _| {
+        assert_ne!(x, 0, "zero");
+        2 * x
+    });
+    let fn_name = double.type_name();
+    assert!(fn_name.contains("metrics_hook"));
+    let double = double.metrics_hook(hook.clone());
+    assert_eq!(double.call(21), 42);
+    assert!(catch_unwind(AssertUnwindSafe(|| double.call(0))).is_err());
+    let calls = hook.calls.lock().unwrap();
+    let expected = ["start", "end", "start", "error"].map(|kind| (kind, fn_name.into()));
+    assert_eq!(*calls, expected);
+}
+
+#[test]
+fn as_fn() {
+    let double = LocalDynFn::<ForFixed<i32>, ForFixed<i32>>::new(|x, _| 2 * x);
+    assert_eq!([1, 2].map(double.as_fn()), [2, 4]);
+    let len = DynFn::<ForRef<str>, ForFixed<usize>>::new(|s, _| s.len());
+    let lens: Vec<_> = ["a", "bc"].into_iter().map(len.as_fn()).collect();
+    assert_eq!(lens, [1, 2]);
+
+    let mut total = 0;
+    let mut add = LocalDynFnMut::<ForRef<i32>, ForFixed<()>>::new(|x, _| total += x);
+    [1, 2, 3].iter().for_each(add.as_fn_mut());
+    drop(add);
+    assert_eq!(total, 6);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn assert_invariant() {
+    fn non_empty(s: &&str) -> Result<(), &'static str> {
+        if s.is_empty() { Err("empty") } else { Ok(()) }
+    }
+    let trim =
+        LocalDynFn::<ForRef<str>, ForRef<str>>::new(|s, _| s.trim()).assert_invariant(non_empty);
+    assert_eq!(trim.call(" test "), Ok("test"));
+    assert_eq!(trim.call("  "), Err("empty"));
+
+    let trim = LocalDynFn::<ForRef<str>, ForRef<str>>::new(|s, _| s.trim())
+        .assert_invariant_debug(non_empty);
+    assert_eq!(trim.call(" test "), "test");
+    #[cfg(debug_assertions)]
+    {
+        let res = std::panic::catch_unwind(|| trim.call("  "));
+        assert!(res.is_err());
+    }
+}
+
+#[test]
+fn stateful() {
+    let counter = AtomicUsize::new(0);
+    let cached = LocalDynStatefulFn::<ForFixed<usize>, ForRef<str>, storage::Raw<64>>::new(
+        String::from("cached value"),
+        |s, n| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            &s[..n]
+        },
+    );
+    let res = cached.call(6);
+    assert_eq!(res, "cached");
+    assert_eq!(cached.call(12), "cached value");
+    assert_eq!(counter.load(Ordering::Relaxed), 2);
+
+    let get = DynStatefulFn::<ForRef<str>, ForFixed<Option<&'static str>>, storage::Raw<64>>::new(
+        ["a", "b"],
+        |keys, key| keys.iter().copied().find(|k| *k == key),
+    );
+    assert_eq!(get.call("b"), Some("b"));
+    assert_eq!(get.call("c"), None);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn time_out_if_sync() {
+    use std::time::Duration;
+
+    use futures_util::FutureExt;
+
+    // the blocking call waits until released, instead of sleeping past the timeout
+    let (release, released) = std::sync::mpsc::channel::<()>();
+    let released = std::sync::Mutex::new(released);
+    let wait = DynFn::<ForFixed<bool>, ForFixed<bool>>::new(move |block, _| {
+        if block {
+            released.lock().unwrap().recv().unwrap();
+        }
+        block
+    })
+    .time_out_if_sync(Duration::from_millis(50));
+    assert!(!wait.is_sync());
+    assert_eq!(wait.call(false).await, Some(false));
+    assert_eq!(wait.call(true).await, None);
+    // the blocking task keeps running until released, which lets the runtime shut down
+    release.send(()).unwrap();
+
+    let panic = DynFn::<ForFixed<()>, ForFixed<()>>::new(|_, _| panic!("panic"))
+        .time_out_if_sync(Duration::from_millis(50));
+    let res = std::panic::AssertUnwindSafe(panic.call(()))
+        .catch_unwind()
+        .await;
+    assert!(res.is_err());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn tokio_spawn() {
+    use std::{
+        sync::{Arc, Mutex, mpsc},
+        time::Duration,
+    };
+
+    struct Yield;
+    impl AsyncFnSend<'static, ForFixed<u64>, ForFixed<u64>> for Yield {
+        async fn call<'a>(&self, arg: <ForFixed<u64> as ForLt>::Of<'a>) -> u64 {
+            tokio::task::yield_now().await;
+            arg + 1
+        }
+    }
+    impl AsyncFnOnceSend<'static, ForFixed<u64>, ForFixed<u64>> for Yield {
+        async fn call(self, arg: <ForFixed<u64> as ForLt>::Of<'_>) -> u64 {
+            AsyncFnSend::call(&self, arg).await
+        }
+    }
+    struct Pending(mpsc::Sender<()>);
+    impl Drop for Pending {
+        fn drop(&mut self) {
+            self.0.send(()).unwrap();
+        }
+    }
+    impl AsyncFnOnceSend<'static, ForFixed<u64>, ForFixed<u64>> for Pending {
+        async fn call(self, _: <ForFixed<u64> as ForLt>::Of<'_>) -> u64 {
+            std::future::pending().await
+        }
+    }
+
+    type Callback = DynAsyncFn<'static, ForFixed<u64>, ForFixed<u64>, storage::Arc>;
+    type CallbackOnce = DynAsyncFnOnce<'static, ForFixed<u64>, ForFixed<u64>>;
+
+    assert_eq!(CallbackOnce::new(Yield).spawn(41).await.unwrap(), 42);
+    assert_eq!(CallbackOnce::new(Yield).spawn_auto(41).await.unwrap(), 42);
+    let f = Callback::new(Yield);
+    let tasks = [f.spawn_call(1), f.spawn_call_auto(41)];
+    for (task, res) in tasks.into_iter().zip([2, 42]) {
+        assert_eq!(task.await.unwrap(), res);
+    }
+
+    // synchronous functions don't block the only worker, which can run the unblocking task
+    fn wait(rx: &Mutex<mpsc::Receiver<()>>, x: u64) -> u64 {
+        let unblocked = rx.lock().unwrap().recv_timeout(Duration::from_secs(10));
+        x + u64::from(unblocked.is_ok())
+    }
+    let (tx, rx) = mpsc::channel();
+    let rx = Arc::new(Mutex::new(rx));
+    let rx2 = rx.clone();
+    let task = Callback::new_sync(move |x, _| wait(&rx2, x)).spawn_call_auto(41);
+    let tx2 = tx.clone();
+    tokio::spawn(async move { tx2.send(()).unwrap() });
+    assert_eq!(task.await.unwrap(), 42);
+    let task = CallbackOnce::new_sync(move |x, _| wait(&rx, x)).spawn_auto(41);
+    tokio::spawn(async move { tx.send(()).unwrap() });
+    assert_eq!(task.await.unwrap(), 42);
+
+    // aborting the handle drops the pending call
+    let (tx, rx) = mpsc::channel();
+    let task = CallbackOnce::new(Pending(tx)).spawn(0);
+    tokio::task::yield_now().await;
+    task.abort();
+    assert!(task.await.unwrap_err().is_cancelled());
+    rx.recv_timeout(Duration::from_secs(10)).unwrap();
+}
+
+#[test]
+fn iter() {
+    let mut count = 0;
+    let mut counter = LocalDynFnMut::<ForFixed<()>, ForFixed<Option<i32>>>::new(|(), _| {
+        count += 1;
+        (count <= 5).then_some(count)
+    });
+    let mut iter = counter.iter_mut();
+    assert_eq!(iter.by_ref().collect::<Vec<_>>(), [1, 2, 3, 4, 5]);
+    // fused, the function is no longer called
+    assert_eq!(iter.next(), None);
+    assert_eq!(counter.into_iter().next(), None);
+    assert_eq!(count, 7);
+
+    let items = DynFnMut::<ForFixed<()>, ForFixed<Option<i32>>, storage::Raw<64>>::from_iter_fn(
+        [1, 2, 3].into_iter(),
+    );
+    assert_eq!(items.into_iter().collect::<Vec<_>>(), [1, 2, 3]);
+}
+
+#[test]
+fn nullary() {
+    let ticks = AtomicUsize::new(0);
+    let tick = LocalDynFn0::<()>::new0(|| {
+        ticks.fetch_add(1, Ordering::Relaxed);
+    });
+    tick.call0();
+    tick.call(());
+    assert_eq!(ticks.load(Ordering::Relaxed), 2);
+
+    let mut count = 0;
+    let mut next = DynFnMut0::<i32>::new0(|| {
+        count += 1;
+        count
+    });
+    assert_eq!(next.call0(), 1);
+    assert_eq!(next.call0(), 2);
+
+    let value = LocalDynFnOnce0::<&str>::new0(|| "value");
+    assert_eq!(value.call0(), "value");
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+
+        let sync = DynAsyncFn0::<i32>::new_sync0(|| 42);
+        assert_eq!(sync.call_sync0(), Some(42));
+        assert_eq!(sync.call_try_sync0().now_or_never(), Some(42));
+
+        let mut not_sync = LocalDynAsyncFnMut0::<i32>::new0(async || 42);
+        assert_eq!(not_sync.call_sync0(), None);
+        assert_eq!(not_sync.call0().now_or_never(), Some(42));
+    }
+}
+
+#[test]
+fn resize() {
+    let drops = AtomicUsize::new(0);
+    let (guard, padding) = (Guard(&drops), [1u8; 8]);
+    let f = LocalDynFn::<ForFixed<u8>, ForFixed<u8>, storage::Raw<16>>::new(move |x, _| {
+        let _guard = &guard;
+        x + padding[0]
+    });
+    let f = f.resize::<64>();
+    assert_eq!(f.call(41), 42);
+    let f = f.resize::<16>();
+    assert_eq!(f.call(41), 42);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(f);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+    let mut count = 0u64;
+    let mut f = DynFnMut::<ForFixed<()>, ForFixed<u64>, storage::Raw<8>>::new(move |_, _| {
+        count += 1;
+        count
+    });
+    assert_eq!(f.call(()), 1);
+    let mut f = f.resize::<32>();
+    assert_eq!(f.call(()), 2);
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+
+        let f = DynAsyncFn::<ForFixed<u8>, ForFixed<u8>, storage::Raw<0>>::new_sync(|x, _| x);
+        assert_eq!(f.resize::<8>().call(1).now_or_never(), Some(1));
+    }
+
+    let big = [0u64; 2];
+    let f = DynFn::<ForFixed<()>, ForFixed<usize>, storage::Raw<16>>::new(move |_, _| big.len());
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f.resize::<8>()));
+    assert!(res.is_err());
+}
+
+#[test]
+fn panic_context() {
+    use std::{
+        cell::RefCell,
+        panic::{self, AssertUnwindSafe, catch_unwind},
+        sync::Once,
+    };
+
+    thread_local! {
+        static LOCATION: RefCell<Option<(String, u32)>> = const { RefCell::new(None) };
+    }
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let location = info.location().map(|loc| (loc.file().into(), loc.line()));
+            LOCATION.set(location);
+            hook(info);
+        }));
+    });
+
+    let big = [0u64; 2];
+    let f = DynFn::<ForFixed<()>, ForFixed<usize>, storage::Raw<16>>::new(move |_, _| big.len());
+    let (res, line) = (catch_unwind(AssertUnwindSafe(|| f.resize::<8>())), line!());
+    let msg = res.unwrap_err().downcast::<String>().unwrap();
+    assert!(msg.starts_with("stored data size (16) exceeds storage size (8)"));
+    #[cfg(feature = "fn_type_name")]
+    assert!(msg.contains("panic_context::{{closure}}"));
+    assert_eq!(LOCATION.take(), Some((file!().into(), line)));
+
+    #[cfg(all(feature = "alloc", debug_assertions))]
+    {
+        let trim = LocalDynFn::<ForRef<str>, ForRef<str>>::new(|s, _| s.trim());
+        let non_empty = |s: &&str| if s.is_empty() { Err("empty") } else { Ok(()) };
+        let (trim, line) = (trim.assert_invariant_debug(non_empty), line!());
+        let res = catch_unwind(AssertUnwindSafe(|| trim.call("  ")));
+        let msg = res.unwrap_err().downcast::<String>().unwrap();
+        let location = format!("{}:{line}:", file!());
+        assert!(msg.starts_with(&format!("invariant asserted at {location}")));
+        assert!(msg.contains("violated: \"empty\""));
+        #[cfg(feature = "fn_type_name")]
+        assert!(msg.contains("panic_context::{{closure}}"));
+    }
+}
+
+#[test]
+fn constructed_at() {
+    type Callback = DynFn<'static, ForFixed<u32>, ForFixed<u32>, storage::Raw<8>>;
+    let (f, line) = (Callback::new(|x, _| x + 1), line!());
+    #[cfg(feature = "track-construction")]
+    {
+        let location = f.constructed_at().unwrap();
+        assert_eq!((location.file(), location.line()), (file!(), line));
+        assert!(format!("{f:?}").contains(&format!("constructed_at: {}:{line}:", file!())));
+        // conversions keep the construction site
+        let f = f.resize::<16>();
+        assert_eq!(f.constructed_at(), Some(location));
+        #[cfg(feature = "async")]
+        {
+            let (g, line) = (
+                LocalDynAsyncFnOnce::<ForFixed<()>>::new_sync(|_, _| ()),
+                line!(),
+            );
+            assert_eq!(g.constructed_at().map(|l| l.line()), Some(line));
+        }
+        let size = size_of::<storage::Raw<8>>() + 2 * size_of::<usize>();
+        assert_eq!(size_of::<Callback>(), size);
+    }
+    #[cfg(not(feature = "track-construction"))]
+    {
+        let _ = line;
+        assert_eq!(f.constructed_at(), None);
+        // the feature compiles away when disabled
+        let size = size_of::<storage::Raw<8>>() + size_of::<usize>();
+        assert_eq!(size_of::<Callback>(), size);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn id() {
+    use std::collections::{HashMap, HashSet};
+
+    let (x, y) = (1u64, 2u64);
+    let a = LocalDynFn::<ForFixed<()>, ForFixed<u64>, storage::Box>::new(move |_, _| x);
+    let b = LocalDynFn::<ForFixed<()>, ForFixed<u64>, storage::Box>::new(move |_, _| y);
+    assert_eq!(a.id(), a.id());
+    assert_ne!(a.id(), b.id());
+    // zero-sized functions share the same dangling address, but not the same vtable
+    let zst = || LocalDynFn::<ForFixed<()>, ForFixed<u64>, storage::Box>::new(|_, _| 1);
+    let a = zst();
+    let b = LocalDynFn::<ForFixed<()>, ForFixed<u64>, storage::Box>::new(|_, _| 2);
+    assert_ne!(a.id(), b.id());
+    assert_eq!(a.id(), zst().id());
+
+    let a = LocalDynFn::<ForFixed<()>, ForFixed<u64>, storage::Rc>::new(move |_, _| x);
+    let b = LocalDynFn::<ForFixed<()>, ForFixed<u64>, storage::Rc>::new(move |_, _| y);
+    assert_eq!(a.id(), a.clone().id());
+    assert_ne!(a.id(), b.id());
+
+    let a = DynFn::<ForFixed<()>, ForFixed<u64>, storage::Arc>::new(move |_, _| x);
+    let b = DynFn::<ForFixed<()>, ForFixed<u64>, storage::Arc>::new(move |_, _| y);
+    assert_eq!(a.id(), a.clone().id());
+    assert_ne!(a.id(), b.id());
+
+    let a = DynFnMut::<ForFixed<()>, ForFixed<u64>, storage::Raw<8>>::new(move |_, _| x);
+    let b = DynFnMut::<ForFixed<()>, ForFixed<u64>, storage::Raw<8>>::new(move |_, _| y);
+    assert_eq!(a.id(), a.id());
+    assert_ne!(a.id(), b.id());
+
+    #[cfg(feature = "async")]
+    {
+        let a = DynAsyncFn::<ForFixed<()>, ForFixed<u64>, storage::Arc>::new_sync(move |_, _| x);
+        let b = LocalDynStatefulFn::<ForFixed<()>, ForFixed<u64>>::new(y, |y, _| *y);
+        assert_eq!(a.id(), a.clone().id());
+        assert_ne!(a.id(), b.id());
+    }
+
+    type Callback = DynFn<'static, ForFixed<()>, ForFixed<u64>, storage::Arc>;
+    let callbacks = [Callback::new(move |_, _| x), Callback::new(move |_, _| y)];
+    let mut registry = HashMap::new();
+    for cb in callbacks.iter().chain(&callbacks) {
+        registry.entry(cb.id()).or_insert_with(|| cb.clone());
+    }
+    assert_eq!(registry.len(), 2);
+    let ids = registry.keys().copied().collect::<HashSet<_>>();
+    assert_eq!(registry.remove(&callbacks[0].id()).unwrap().call(()), 1);
+    assert!(!registry.contains_key(&callbacks[0].id()));
+    assert_eq!(registry[&callbacks[1].id()].call(()), 2);
+    assert!(ids.iter().min() < ids.iter().max());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn pointer() {
+    fn addrs<F: core::fmt::Pointer>(f: &F) -> (String, String) {
+        let ptr = format!("{:p}", *f);
+        let (data, vtable) = ptr.split_once(' ').unwrap();
+        let data = data.strip_prefix("data@0x").unwrap();
+        (
+            data.into(),
+            vtable.strip_prefix("vtable@0x").unwrap().into(),
+        )
+    }
+    let (x, y) = (1u64, 2u64);
+
+    let a = DynFn::<ForFixed<()>, ForFixed<u64>, storage::Arc>::new(move |_, _| x);
+    assert_eq!(addrs(&a), addrs(&a.clone()));
+
+    // same closure type, different data
+    let new = |x: u64| LocalDynFn::<ForFixed<()>, ForFixed<u64>, storage::Box>::new(move |_, _| x);
+    let (a, b) = (new(x), new(y));
+    assert_ne!(addrs(&a).0, addrs(&b).0);
+    assert_eq!(addrs(&a).1, addrs(&b).1);
+
+    // different closure types
+    let a = LocalDynFn::<ForFixed<()>, ForFixed<u64>, storage::Box>::new(move |_, _| x);
+    let b = LocalDynFn::<ForFixed<()>, ForFixed<u64>, storage::Box>::new(move |_, _| y + 1);
+    assert_ne!(addrs(&a).1, addrs(&b).1);
+
+    #[cfg(feature = "async")]
+    {
+        let a = DynAsyncFn::<ForFixed<()>, ForFixed<u64>, storage::Arc>::new_sync(move |_, _| x);
+        assert_eq!(addrs(&a), addrs(&a.clone()));
+    }
+}
+
+#[test]
+fn into_storage() {
+    let x = 42u64;
+    let f = LocalDynFn::<ForFixed<()>, ForFixed<u64>, storage::Raw<8>>::new(move |_, _| x);
+    let f = f.into_storage::<storage::RawOrBox<8>>();
+    assert_eq!(f.call(()), 42);
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+
+        let f =
+            DynAsyncFnMut::<ForFixed<()>, ForFixed<u64>, storage::Raw<8>>::new_sync(move |_, _| x);
+        let mut f = f.into_storage::<storage::RawOrBox<8>>();
+        assert_eq!(f.call(()).now_or_never(), Some(42));
+    }
+
+    #[cfg(feature = "alloc")]
+    {
+        let drops = AtomicUsize::new(0);
+        let guard = Guard(&drops);
+        let f = DynFn::<ForFixed<()>, ForFixed<u64>, storage::Box>::new(move |_, _| {
+            let _guard = &guard;
+            x
+        });
+        let f = f.into_storage::<storage::RawOrBox<8>>();
+        assert_eq!(f.call(()), 42);
+        assert_eq!(drops.load(Ordering::Relaxed), 0);
+        drop(f);
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+    }
+}
+
+#[test]
+fn debug() {
+    let f = LocalDynFn::<ForFixed<()>, ForFixed<()>, storage::Raw<0>>::new(|_, _| ());
+    let debug = format!("{f:?}");
+    assert!(debug.starts_with("LocalDynFn {"));
+    assert!(debug.contains(r#"storage: "Raw""#));
+    #[cfg(feature = "debug-type-names")]
+    assert!(debug.contains("debug::{{closure}}"));
+
+    #[cfg(feature = "async")]
+    {
+        let f = DynAsyncFn::<ForFixed<()>, ForFixed<()>, storage::RawOrBox<0>>::new_sync(|_, _| ());
+        let debug = format!("{f:?}");
+        assert!(debug.contains("is_sync: true"));
+        assert!(debug.contains(r#"storage: "Raw""#));
+    }
+
+    #[cfg(all(feature = "alloc", feature = "async"))]
+    {
+        let x = 0u64;
+        let f = LocalDynAsyncFnOnce::<ForFixed<()>, ForFixed<u64>, storage::RawOrBox<0>>::new(
+            async move |_, _| x,
+        );
+        let debug = format!("{f:?}");
+        assert!(debug.contains("is_sync: false"));
+        assert!(debug.contains(r#"storage: "Box""#));
+    }
+}
+
+#[cfg(feature = "defmt")]
+#[test]
+fn defmt_format() {
+    // there is no global logger on the host, so only the implementations are checked
+    fn assert_format<T: defmt::Format>() {}
+    type Unit = ForFixed<()>;
+    assert_format::<LocalDynFn<Unit, Unit>>();
+    assert_format::<LocalDynFnMut<Unit, Unit>>();
+    assert_format::<LocalDynFnOnce<Unit, Unit>>();
+    assert_format::<DynFn<Unit, Unit>>();
+    assert_format::<DynFnMut<Unit, Unit>>();
+    assert_format::<DynFnOnce<Unit, Unit>>();
+    #[cfg(feature = "async")]
+    {
+        assert_format::<LocalDynAsyncFn<Unit, Unit>>();
+        assert_format::<LocalDynAsyncFnMut<Unit, Unit>>();
+        assert_format::<LocalDynAsyncFnOnce<Unit, Unit>>();
+        assert_format::<DynAsyncFn<Unit, Unit>>();
+        assert_format::<DynAsyncFnMut<Unit, Unit>>();
+        assert_format::<DynAsyncFnOnce<Unit, Unit>>();
+    }
+    assert_format::<DynFnError>();
+}
+
+#[cfg(feature = "tower")]
+#[test]
+fn tower_service() {
+    use futures_util::FutureExt;
+    use tower::ServiceExt;
+
+    type Callback = DynAsyncFn<'static, ForFixed<u64>, ForFixed<Result<u64, &'static str>>>;
+    let f = Callback::new_sync(|x, _| x.checked_sub(1).ok_or("underflow"));
+    let service = f.into_service();
+    assert_eq!(service.clone().oneshot(1).now_or_never(), Some(Ok(0)));
+    assert_eq!(
+        service.clone().oneshot(0).now_or_never(),
+        Some(Err("underflow"))
+    );
+
+    let f = Callback::from_service(tower::service_fn(async |x: u64| Ok(x + 1)));
+    assert_eq!(f.call(41).now_or_never(), Some(Ok(42)));
+    // round trip
+    let f = Callback::from_service(service);
+    assert_eq!(f.into_service().oneshot(42).now_or_never(), Some(Ok(41)));
+}
+
+#[cfg(all(feature = "alloc", feature = "async"))]
+#[test]
+fn from_dyn_method() {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use futures_util::FutureExt;
+
+    #[async_trait]
+    trait Bar: Send + Sync {
+        async fn call(&self, arg: &str) -> usize;
+    }
+    struct Foo(AtomicUsize);
+    #[async_trait]
+    impl Bar for Foo {
+        async fn call(&self, arg: &str) -> usize {
+            self.0.fetch_add(arg.len(), Ordering::Relaxed) + arg.len()
+        }
+    }
+
+    type Callback =
+        DynAsyncFn<'static, ForRef<str>, ForFixed<usize>, storage::Raw<{ 3 * size_of::<usize>() }>>;
+    let bar: Arc<dyn Bar> = Arc::new(Foo(AtomicUsize::new(0)));
+    let f = Callback::from_dyn_method(bar.clone(), |b, s| Box::pin(async move { b.call(s).await }));
+    assert!(!f.is_sync());
+    assert_eq!(f.call("test").now_or_never(), Some(4));
+    assert_eq!(f.call("hello").now_or_never(), Some(9));
+    assert_eq!(Arc::strong_count(&bar), 2);
+    drop(f);
+    assert_eq!(Arc::strong_count(&bar), 1);
+    assert_eq!(bar.call("!").now_or_never(), Some(10));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn from_box() {
+    let drops = AtomicUsize::new(0);
+    let guard = || Guard(&drops);
+
+    let g = guard();
+    let b: Box<dyn Fn(u64) -> u64 + '_> = Box::new(move |x| {
+        let _g = &g;
+        x + 1
+    });
+    let f = LocalDynFn::<ForFixed<u64>, ForFixed<u64>, storage::Box>::from(b);
+    assert_eq!(f.call(41), 42);
+    drop(f);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+    let g = guard();
+    let b: Box<dyn Fn(&str) -> usize + Send + Sync + '_> = Box::new(move |s| {
+        let _g = &g;
+        s.len()
+    });
+    let f = DynFn::<ForRef<str>, ForFixed<usize>, storage::Box>::from_ref_box(b);
+    assert_eq!(f.call("test"), 4);
+    drop(f);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+
+    let (g, mut count) = (guard(), 0);
+    let b: Box<dyn FnMut(u64) -> u64 + '_> = Box::new(move |x| {
+        let _g = &g;
+        count += x;
+        count
+    });
+    let mut f = LocalDynFnMut::<ForFixed<u64>, ForFixed<u64>, storage::Box>::from(b);
+    f.call(1);
+    assert_eq!(f.call(2), 3);
+    drop(f);
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+
+    let (g, mut v) = (guard(), Vec::new());
+    let b: Box<dyn FnMut(&str) + Send + Sync + '_> = Box::new(move |s| {
+        let _g = &g;
+        v.push(s.len());
+    });
+    let mut f = DynFnMut::<ForRef<str>, ForFixed<()>, storage::Box>::from_ref_box(b);
+    f.call("test");
+    drop(f);
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+
+    let g = guard();
+    let b: Box<dyn FnOnce(u64) -> u64 + '_> = Box::new(move |x| {
+        drop(g);
+        x + 1
+    });
+    let f = LocalDynFnOnce::<ForFixed<u64>, ForFixed<u64>, storage::Box>::from(b);
+    assert_eq!(f.call(41), 42);
+    assert_eq!(drops.load(Ordering::Relaxed), 5);
+
+    let g = guard();
+    let b: Box<dyn FnOnce(&str) -> usize + Send + Sync + '_> = Box::new(move |s| {
+        let _g = g;
+        s.len()
+    });
+    let f = DynFnOnce::<ForRef<str>, ForFixed<usize>, storage::Box>::from_ref_box(b);
+    drop(f);
+    assert_eq!(drops.load(Ordering::Relaxed), 6);
+
+    // the boxed function fits inline in a storage of two pointers
+    let b: Box<dyn Fn(u64) -> u64 + Send + Sync> = Box::new(|x| x * 2);
+    let f: DynFn<ForFixed<u64>, ForFixed<u64>, storage::Raw<{ 2 * size_of::<usize>() }>> = b.into();
+    assert_eq!(f.call(21), 42);
+}
+
+#[test]
+fn ffi() {
+    use core::ffi::c_void;
+
+    /// A C-style dispatcher, calling the registered callback with each argument.
+    fn dispatch(cb: FfiFn<u32, u32>, ctx: *mut c_void, args: &[u32]) -> u32 {
+        // SAFETY: the context is the one returned with the callback
+        args.iter().map(|&arg| unsafe { cb(ctx, arg) }).sum()
+    }
+
+    let calls = AtomicUsize::new(0);
+    let f = DynFn::<ForFixed<u32>, ForFixed<u32>, storage::Raw<8>>::new(|x, _| {
+        calls.fetch_add(1, Ordering::Relaxed);
+        x * 2
+    });
+    let (cb, ctx) = f.as_ffi();
+    assert_eq!(dispatch(cb, ctx, &[1, 2, 3]), 12);
+    assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+    #[cfg(feature = "alloc")]
+    {
+        let drops = AtomicUsize::new(0);
+        let guard = Guard(&drops);
+        let cb = FfiCallback::new(DynFn::<ForFixed<u32>, ForFixed<u32>>::new(move |x, _| {
+            let _guard = &guard;
+            x + 1
+        }));
+        let (f, ctx) = cb.as_ffi();
+        // the context pointer is stable when the callback is moved
+        let cb = [cb];
+        assert_eq!(dispatch(f, ctx, &[1, 2]), 5);
+        drop(cb);
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+        let guard = Guard(&DROPS);
+        let cb = FfiCallback::new(DynFn::<ForFixed<u32>, ForFixed<u32>>::new(move |x, _| {
+            let _guard = &guard;
+            x
+        }));
+        let (f, ctx) = cb.leak();
+        assert_eq!(dispatch(f, ctx, &[1, 2]), 3);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 0);
+        // SAFETY: the context comes from `leak`
+        let cb = unsafe { FfiCallback::<u32, u32>::from_raw(ctx) };
+        assert_eq!(cb.into_inner().call(42), 42);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+    }
+
+    #[cfg(feature = "std")]
+    {
+        let f = DynFn::<ForFixed<u32>, ForFixed<u32>>::new(|x, _| {
+            assert_ne!(x, 0, "zero");
+            x
+        });
+        let cb = FfiCallback::with_panic_return(f, u32::MAX);
+        let (f, ctx) = cb.as_ffi();
+        assert_eq!(dispatch(f, ctx, &[1]), 1);
+        assert_eq!(dispatch(f, ctx, &[0]), u32::MAX);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn abi_dyn_fn() {
+    static PLUGIN_DROPS: AtomicUsize = AtomicUsize::new(0);
+    static HOST_DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    // the plugin side, with its own drop counter
+    #[unsafe(no_mangle)]
+    extern "C" fn dyn_fn_test_plugin_make(offset: u32) -> AbiDynFn<u32, u32> {
+        let guard = Guard(&PLUGIN_DROPS);
+        DynFn::<ForFixed<u32>, ForFixed<u32>>::new(move |x, _| {
+            let _guard = &guard;
+            x + offset
+        })
+        .into()
+    }
+    #[unsafe(no_mangle)]
+    extern "C" fn dyn_fn_test_plugin_call(f: AbiDynFn<u32, u32>, arg: u32) -> u32 {
+        assert_eq!(f.version(), AbiDynFn::<u32, u32>::VERSION);
+        f.call(arg)
+    }
+
+    // the version is padded to pointer alignment
+    assert_eq!(size_of::<AbiDynFn<u32, u32>>(), 4 * size_of::<usize>());
+    let f = dyn_fn_test_plugin_make(1);
+    assert_eq!(f.version(), AbiDynFn::<u32, u32>::VERSION);
+    assert_eq!(f.call(41), 42);
+    let f = DynFn::<ForFixed<u32>, ForFixed<u32>>::from(f);
+    assert_eq!(f.call(1), 2);
+    assert_eq!(PLUGIN_DROPS.load(Ordering::Relaxed), 0);
+    drop(f);
+    assert_eq!(PLUGIN_DROPS.load(Ordering::Relaxed), 1);
+
+    let guard = Guard(&HOST_DROPS);
+    let f = DynFn::<ForFixed<u32>, ForFixed<u32>, storage::Raw<8>>::new(move |x, _| {
+        let _guard = &guard;
+        x * 2
+    });
+    assert_eq!(dyn_fn_test_plugin_call(f.into(), 21), 42);
+    assert_eq!(HOST_DROPS.load(Ordering::Relaxed), 1);
+    assert_eq!(PLUGIN_DROPS.load(Ordering::Relaxed), 1);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn waker() {
+    use std::{
+        pin::pin,
+        sync::Arc,
+        task::{Context, Poll},
+    };
+
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    let (wakes, drops) = (Arc::new(AtomicUsize::new(0)), &DROPS);
+    let (wakes2, guard) = (wakes.clone(), Guard(drops));
+    let waker = DynFn0::<(), storage::Arc>::new0(move || {
+        let _guard = &guard;
+        wakes2.fetch_add(1, Ordering::Relaxed);
+    })
+    .into_waker();
+
+    // `wake_by_ref` doesn't consume, clones share the function
+    waker.wake_by_ref();
+    let clone = waker.clone();
+    clone.wake();
+    assert_eq!(wakes.load(Ordering::Relaxed), 2);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+
+    // a future waking itself is polled again
+    let mut yielded = false;
+    let mut fut = pin!(std::future::poll_fn(|cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }));
+    let mut cx = Context::from_waker(&waker);
+    let mut polls = 0;
+    loop {
+        polls += 1;
+        if fut.as_mut().poll(&mut cx).is_ready() {
+            break;
+        }
+        assert_eq!(wakes.load(Ordering::Relaxed), 2 + polls);
+    }
+    assert_eq!(polls, 2);
+
+    drop(waker);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn arc_waker() {
+    use std::sync::Arc;
+
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    let (wakes, drops) = (Arc::new(AtomicUsize::new(0)), &DROPS);
+    let (wakes2, guard) = (wakes.clone(), Guard(drops));
+    let waker = DynFn0::<()>::new0(move || {
+        let _guard = &guard;
+        wakes2.fetch_add(1, Ordering::Relaxed);
+    })
+    .into_arc_waker();
+    let other = DynFn0::<()>::new0(|| ()).into_arc_waker();
+
+    let clone = waker.clone();
+    assert!(waker.will_wake(&clone));
+    assert!(!waker.will_wake(&other));
+    waker.wake_by_ref();
+    clone.wake();
+    assert_eq!(wakes.load(Ordering::Relaxed), 2);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(waker);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn callback_set() {
+    let (order, drops) = (AtomicUsize::new(0), AtomicUsize::new(0));
+    type Callback<'a> = DynFn<'a, ForFixed<usize>, ForFixed<()>, storage::Raw<32>>;
+    let callback = |id: usize| {
+        let (order, guard) = (&order, Guard(&drops));
+        Callback::new(move |x, _| {
+            let _guard = &guard;
+            order.store(
+                order.load(Ordering::Relaxed) * 10 + id * x,
+                Ordering::Relaxed,
+            );
+        })
+    };
+
+    let mut set = CallbackSet::<ForFixed<usize>, ForFixed<()>, storage::Raw<32>, 3>::new();
+    let h1 = set.register(callback(1)).unwrap();
+    let h2 = set.register(callback(2)).unwrap();
+    let h3 = set.register(callback(3)).unwrap();
+    assert!(set.register(callback(4)).is_err());
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    set.call_all(1);
+    assert_eq!(order.swap(0, Ordering::Relaxed), 123);
+
+    // unregistering keeps registration order, and the freed slot is reused
+    drop(set.unregister(h2).unwrap());
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+    let h4 = set.register(callback(4)).unwrap();
+    assert_eq!(set.len(), 3);
+    set.call_all(2);
+    assert_eq!(order.swap(0, Ordering::Relaxed), 268);
+
+    // stale handles are rejected, even for a reused slot
+    assert_ne!(h2, h4);
+    assert!(!set.contains(h2) && set.contains(h4));
+    assert!(set.unregister(h2).is_none());
+    drop(set.unregister(h1).unwrap());
+    assert!(set.unregister(h1).is_none());
+    set.call_all(1);
+    assert_eq!(order.swap(0, Ordering::Relaxed), 34);
+    assert!(set.contains(h3));
+
+    // remaining callbacks are dropped with the set
+    drop(set);
+    assert_eq!(drops.load(Ordering::Relaxed), 5);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn callback_slab() {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::{Rc, Weak},
+    };
+
+    let (calls, drops) = (RefCell::new(Vec::new()), AtomicUsize::new(0));
+    type Callback<'a> = LocalDynFn<'a, ForFixed<usize>, ForFixed<()>>;
+    type Slab<'a> = CallbackSlab<Callback<'a>>;
+    let slab = Rc::new(Slab::new());
+    let key = Rc::new(Cell::new(None));
+    // callbacks record their id, then run `then` on the slab
+    let callback = |id: usize, then: fn(&Slab, &Cell<Option<CallbackKey>>)| {
+        let (calls, guard) = (&calls, Guard(&drops));
+        let (slab, key) = (Rc::downgrade(&slab), key.clone());
+        Callback::new(move |x, _| {
+            let _guard = &guard;
+            calls.borrow_mut().push(id * x);
+            then(&Weak::upgrade(&slab).unwrap(), &key);
+        })
+    };
+
+    let k1 = slab.register(callback(1, |_, _| {}));
+    // unregisters itself during dispatch
+    let k2 = slab.register(callback(2, |slab, key| {
+        assert!(slab.unregister(key.get().unwrap()));
+    }));
+    key.set(Some(k2));
+    // registers a callback during dispatch, not called until the next one
+    let k3 = slab.register(callback(3, |slab, _| {
+        if slab.len() < 4 {
+            slab.register(Callback::new(|_, _| {}));
+        }
+    }));
+    slab.call_all(1);
+    assert_eq!(calls.take(), [1, 2, 3]);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    assert!(!slab.contains(k2) && !slab.unregister(k2));
+    assert_eq!(slab.len(), 3);
+    slab.call_all(2);
+    assert_eq!(calls.take(), [2, 6]);
+
+    // unregistering a callback not yet called during dispatch skips it
+    let k4 = slab.register(callback(4, |_, _| {}));
+    let k5 = slab.register(callback(5, |slab, key| {
+        if let Some(key) = key.take() {
+            assert!(slab.unregister(key));
+        }
+    }));
+    let k6 = slab.register(callback(6, |_, _| {}));
+    key.set(Some(k6));
+    assert!(slab.unregister(k3));
+    assert!(slab.unregister(k4));
+    // the freed slot is reused with a bumped generation
+    let k7 = slab.register(callback(7, |_, _| {}));
+    assert!(k7 != k4 && !slab.contains(k4) && slab.contains(k7));
+    assert!(!slab.unregister(k4));
+    // dispatch keeps insertion order after compaction, with keys still valid
+    slab.call_all(1);
+    assert_eq!(calls.take(), [1, 5, 7]);
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+    assert!(slab.contains(k1) && slab.contains(k5) && !slab.contains(k6));
+
+    // removed callbacks being the majority, the slab is compacted
+    assert!(slab.unregister(k7));
+    assert!(slab.contains(k1) && slab.contains(k5));
+    slab.call_all(1);
+    assert_eq!(calls.take(), [1, 5]);
+
+    // remaining callbacks are dropped with the slab, and removed ones are returned
+    let mut slab = Rc::into_inner(slab).unwrap();
+    drop(slab.remove(k1).unwrap());
+    assert!(slab.remove(k1).is_none());
+    assert_eq!(drops.load(Ordering::Relaxed), 6);
+    drop(slab);
+    assert_eq!(drops.load(Ordering::Relaxed), 7);
+}
+
+#[cfg(all(feature = "alloc", feature = "async"))]
+#[test]
+fn callback_slab_async() {
+    use futures_util::FutureExt;
+
+    let calls = AtomicUsize::new(0);
+    let calls = &calls;
+    type Callback<'a> = LocalDynAsyncFn<'a, ForRef<str>, ForFixed<()>>;
+    let slab = CallbackSlab::<Callback>::new();
+    slab.register(Callback::new_sync(move |s, _| {
+        calls.fetch_add(s.len(), Ordering::Relaxed);
+    }));
+    let key = slab.register(Callback::new(async move |s, _| {
+        calls.fetch_add(10 * s.len(), Ordering::Relaxed);
+    }));
+    assert_eq!(slab.call_all_async("ab").now_or_never(), Some(()));
+    assert_eq!(calls.swap(0, Ordering::Relaxed), 22);
+    assert!(slab.unregister(key));
+    assert_eq!(slab.call_all_async("ab").now_or_never(), Some(()));
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn signal() {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::{Rc, Weak},
+    };
+
+    let (calls, drops) = (RefCell::new(Vec::new()), AtomicUsize::new(0));
+    type Slot<'a> = LocalDynFn<'a, ForRef<str>, ForFixed<()>, storage::Raw<48>>;
+    let signal = Rc::new(Signal::<Slot, 3>::new());
+    let key = Rc::new(Cell::new(None));
+    // slots record their id, then disconnect the key if any
+    let slot = |id: usize| {
+        let (calls, guard) = (&calls, Guard(&drops));
+        let (signal, key) = (Rc::downgrade(&signal), key.clone());
+        Slot::new(move |s, _| {
+            let _guard = &guard;
+            calls.borrow_mut().push((id, s.len()));
+            if let Some(key) = key.take() {
+                assert!(Weak::upgrade(&signal).unwrap().disconnect(key));
+            }
+        })
+    };
+
+    let c1 = signal.connect(slot(1)).unwrap();
+    let c2 = signal.connect(slot(2)).unwrap();
+    let c3 = signal.connect(slot(3)).unwrap();
+    assert!(signal.connect(slot(4)).is_err());
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    signal.emit("a");
+    assert_eq!(calls.take(), [(1, 1), (2, 1), (3, 1)]);
+
+    // RAII disconnection
+    drop(c2);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+    signal.emit("ab");
+    assert_eq!(calls.take(), [(1, 2), (3, 2)]);
+
+    // re-entrant disconnection of a slot not yet called, then of the calling slot
+    key.set(Some(c3.key()));
+    signal.emit("abc");
+    assert_eq!(calls.take(), [(1, 3)]);
+    assert!(!c3.is_connected());
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+    let c1 = c1.detach();
+    key.set(Some(c1));
+    signal.emit("abc");
+    assert_eq!(calls.take(), [(1, 3)]);
+    assert!(signal.is_empty());
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+    drop(c3);
+
+    // disconnected slots free their place
+    let connections: Vec<_> = (5..8).map(|id| signal.connect(slot(id)).unwrap()).collect();
+    signal.emit("");
+    assert_eq!(calls.take(), [(5, 0), (6, 0), (7, 0)]);
+    drop(connections);
+    assert_eq!(drops.load(Ordering::Relaxed), 7);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn slab_signal() {
+    use std::{cell::Cell, rc::Rc};
+
+    let sum = Rc::new(Cell::new(0));
+    type Slot = LocalDynFn<'static, ForFixed<u32>, ForFixed<()>>;
+    let signal = Rc::new(SlabSignal::<Slot>::new());
+    let slot = |i| {
+        let sum = sum.clone();
+        Slot::new(move |x, _| sum.set(sum.get() + i * x))
+    };
+    let connections: Vec<_> = (1..=3).map(|i| signal.connect(slot(i))).collect();
+    // a slot connecting another one, only called by the next emission
+    let weak = Rc::downgrade(&signal);
+    let connecting = signal.connect(Slot::new(move |_, _| {
+        let signal = weak.upgrade().unwrap();
+        if signal.len() < 5 {
+            signal.connect(Slot::new(|_, _| {})).detach();
+        }
+    }));
+    signal.emit(2);
+    assert_eq!(sum.get(), 12);
+    assert_eq!(signal.len(), 5);
+    drop(connections);
+    drop(connecting);
+    signal.emit(2);
+    assert_eq!(sum.get(), 12);
+    assert_eq!(signal.len(), 1);
+}
+
+#[cfg(feature = "async")]
+struct Gate(
+    std::cell::Cell<bool>,
+    std::cell::Cell<Option<std::task::Waker>>,
+);
+
+#[cfg(feature = "async")]
+impl Gate {
+    fn new() -> Self {
+        Self(Default::default(), Default::default())
+    }
+
+    async fn wait(&self) {
+        std::future::poll_fn(|cx| {
+            if self.0.get() {
+                return std::task::Poll::Ready(());
+            }
+            self.1.set(Some(cx.waker().clone()));
+            std::task::Poll::Pending
+        })
+        .await;
+    }
+
+    fn open(&self) {
+        self.0.set(true);
+        self.1.take().into_iter().for_each(std::task::Waker::wake);
+    }
+}
+
+#[cfg(feature = "async")]
+macro_rules! test_async_signal {
+    ($signal:expr, $connect:ident) => {{
+        use std::{cell::RefCell, future::Future, pin::pin, task};
+
+        let (calls, drops, gate) = (RefCell::new(Vec::new()), AtomicUsize::new(0), Gate::new());
+        let (calls, drops, gate) = (&calls, &drops, &gate);
+        let signal = $signal;
+        let _c1 =
+            $connect!(signal.connect(Slot::new_sync(move |x, _| { calls.borrow_mut().push(x) })));
+        let _c2 = $connect!(signal.connect(Slot::new(async move |x, _| {
+            let _guard = Guard(drops);
+            gate.wait().await;
+            calls.borrow_mut().push(10 * x);
+        })));
+        let _c3 = $connect!(signal.connect(Slot::new(async move |x, _| {
+            calls.borrow_mut().push(100 * x)
+        })));
+        let cx = &mut task::Context::from_waker(task::Waker::noop());
+
+        // one subscriber pending while others complete
+        let mut emit = pin!(signal.emit(1));
+        assert!(emit.as_mut().poll(cx).is_pending());
+        assert_eq!(calls.take(), [1, 100]);
+        gate.open();
+        assert!(emit.as_mut().poll(cx).is_ready());
+        assert_eq!(calls.take(), [10]);
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+        // serial emission
+        gate.0.set(false);
+        let mut emit = pin!(signal.emit_serial(2));
+        assert!(emit.as_mut().poll(cx).is_pending());
+        assert_eq!(calls.take(), [2]);
+        gate.open();
+        assert!(emit.as_mut().poll(cx).is_ready());
+        assert_eq!(calls.take(), [20, 200]);
+
+        // cancellation drops in-flight futures, keeping slots connected
+        gate.0.set(false);
+        let mut emit = Box::pin(signal.emit(3));
+        assert!(emit.as_mut().poll(cx).is_pending());
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+        drop(emit);
+        assert_eq!(drops.load(Ordering::Relaxed), 3);
+        assert_eq!(calls.take(), [3, 300]);
+        assert_eq!(signal.len(), 3);
+    }};
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_signal() {
+    type Slot<'a> = LocalDynAsyncFn<'a, ForFixed<u32>, ForFixed<()>>;
+    macro_rules! connect {
+        ($connection:expr) => {
+            $connection.unwrap()
+        };
+    }
+    test_async_signal!(AsyncSignal::<Slot, 3>::new(), connect);
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn async_slab_signal() {
+    type Slot<'a> = LocalDynAsyncFn<'a, ForFixed<u32>, ForFixed<()>>;
+    macro_rules! connect {
+        ($connection:expr) => {
+            $connection
+        };
+    }
+    test_async_signal!(AsyncSlabSignal::<Slot>::new(), connect);
+}
+
+#[test]
+fn priority_dispatcher() {
+    use core::ops::ControlFlow;
+    use std::cell::RefCell;
+
+    let calls = RefCell::new(Vec::new());
+    type Handler<'a> = LocalDynFn<'a, ForFixed<u8>, ForFixed<ControlFlow<u8>>, storage::Raw<16>>;
+    // handlers record their id, and break if it is the argument
+    let handler = |id: u8| {
+        let calls = &calls;
+        Handler::new(move |x, _| {
+            calls.borrow_mut().push(id);
+            if x == id {
+                ControlFlow::Break(id)
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+    };
+
+    let mut dispatcher = PriorityDispatcher::<Handler, 5>::new();
+    dispatcher.register(0, handler(1)).unwrap();
+    let k2 = dispatcher.register(10, handler(2)).unwrap();
+    dispatcher.register(-5, handler(3)).unwrap();
+    dispatcher.register(10, handler(4)).unwrap();
+    let k5 = dispatcher.register(0, handler(5)).unwrap();
+    assert!(dispatcher.register(0, handler(6)).is_err());
+    // ties broken by insertion order
+    assert_eq!(dispatcher.dispatch(0), ControlFlow::Continue(()));
+    assert_eq!(calls.take(), [2, 4, 1, 5, 3]);
+    // short-circuit
+    assert_eq!(dispatcher.dispatch(1), ControlFlow::Break(1));
+    assert_eq!(calls.take(), [2, 4, 1]);
+
+    // unregistration by key
+    assert!(dispatcher.unregister(k2).is_some());
+    assert!(dispatcher.unregister(k2).is_none() && !dispatcher.contains(k2));
+    assert!(dispatcher.unregister(k5).is_some());
+    dispatcher.register(5, handler(6)).unwrap();
+    assert_eq!(dispatcher.dispatch(0), ControlFlow::Continue(()));
+    assert_eq!(calls.take(), [4, 6, 1, 3]);
+}
+
+#[cfg(all(feature = "alloc", feature = "async"))]
+#[test]
+fn slab_priority_dispatcher_async() {
+    use core::ops::ControlFlow;
+    use std::cell::RefCell;
+
+    use futures_util::FutureExt;
+
+    let calls = RefCell::new(Vec::new());
+    type Handler<'a> = LocalDynAsyncFn<'a, ForFixed<u8>, ForFixed<ControlFlow<u8>>>;
+    let calls = &calls;
+    let mut dispatcher = SlabPriorityDispatcher::<Handler>::new();
+    dispatcher.register(
+        0,
+        Handler::new_sync(move |x, _| {
+            calls.borrow_mut().push(0);
+            ControlFlow::Break(x)
+        }),
+    );
+    let key = dispatcher.register(
+        1,
+        Handler::new(async move |x, _| {
+            calls.borrow_mut().push(1);
+            if x > 0 {
+                ControlFlow::Break(x + 1)
+            } else {
+                ControlFlow::Continue(())
+            }
+        }),
+    );
+    assert_eq!(
+        dispatcher.dispatch_async(0).now_or_never(),
+        Some(ControlFlow::Break(0))
+    );
+    assert_eq!(calls.take(), [1, 0]);
+    assert_eq!(
+        dispatcher.dispatch_async(1).now_or_never(),
+        Some(ControlFlow::Break(2))
+    );
+    assert_eq!(calls.take(), [1]);
+    assert!(dispatcher.unregister(key).is_some());
+    assert_eq!(
+        dispatcher.dispatch_async(1).now_or_never(),
+        Some(ControlFlow::Break(1))
+    );
+    assert_eq!(calls.take(), [0]);
+}
+
+#[test]
+fn isr_callback() {
+    type Handler = DynFn<'static, ForFixed<usize>, ForFixed<usize>, storage::Raw<16>>;
+    static HANDLER: IsrCallback<ForFixed<usize>, ForFixed<usize>, storage::Raw<16>> =
+        IsrCallback::new();
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    let handler = |id: usize| {
+        let guard = Guard(&DROPS);
+        Handler::new(move |x, _| {
+            let _guard = &guard;
+            id + x
+        })
+    };
+    let swaps = if cfg!(miri) { 20 } else { 10_000 };
+    let stop = std::sync::atomic::AtomicBool::new(false);
+    assert_eq!(HANDLER.call_if_set(0), None);
+    std::thread::scope(|s| {
+        let isr = s.spawn(|| {
+            while !stop.load(Ordering::Relaxed) {
+                if let Some(id) = HANDLER.call_if_set(1) {
+                    assert!((2..=swaps + 1).contains(&id));
+                }
+            }
+        });
+        for id in 1..=swaps {
+            let prev = HANDLER.swap(handler(id));
+            assert_eq!(prev.is_some(), id > 1);
+            assert!(HANDLER.is_set());
+        }
+        stop.store(true, Ordering::Relaxed);
+        isr.join().unwrap();
+    });
+    assert_eq!(HANDLER.call_if_set(1), Some(swaps + 1));
+    assert!(HANDLER.take().is_some());
+    assert!(!HANDLER.is_set());
+    assert_eq!(HANDLER.call_if_set(1), None);
+    assert!(HANDLER.take().is_none());
+    assert_eq!(DROPS.load(Ordering::Relaxed), swaps);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn atomic_dyn_fn() {
+    type Handler = DynFn<'static, ForFixed<usize>, ForFixed<usize>, storage::Arc>;
+    let refs = std::sync::Arc::new(());
+    let handler = |id: usize| {
+        let refs = refs.clone();
+        Handler::new(move |x, _| {
+            let _refs = &refs;
+            id + x
+        })
+    };
+    let cell = AtomicDynFn::default();
+    assert!(cell.load().is_none());
+    cell.store(handler(0));
+    let swaps = if cfg!(miri) { 20 } else { 10_000 };
+    let stop = std::sync::atomic::AtomicBool::new(false);
+    std::thread::scope(|s| {
+        let readers = [(); 2].map(|_| {
+            s.spawn(|| {
+                let mut last = 0;
+                while !stop.load(Ordering::Relaxed) {
+                    // loaded functions are called outside the cell, and are never torn
+                    let id = cell.load().unwrap().call(0);
+                    assert!(id >= last && id <= swaps);
+                    last = id;
+                }
+            })
+        });
+        for id in 1..=swaps {
+            cell.swap(handler(id)).unwrap();
+        }
+        stop.store(true, Ordering::Relaxed);
+        for reader in readers {
+            reader.join().unwrap();
+        }
+    });
+    let f = cell.load().unwrap();
+    assert_eq!(f.call(1), swaps + 1);
+    assert_eq!(std::sync::Arc::strong_count(&refs), 2);
+    drop(cell.take());
+    assert!(!cell.is_set());
+    assert_eq!(std::sync::Arc::strong_count(&refs), 2);
+    drop(f);
+    assert_eq!(std::sync::Arc::strong_count(&refs), 1);
+    drop(AtomicDynFn::from(handler(0)));
+    assert_eq!(std::sync::Arc::strong_count(&refs), 1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn cancellable_dyn_fn() {
+    let drops = AtomicUsize::new(0);
+    let guard = Guard(&drops);
+    let (f, token) =
+        CancellableDynFn::new(DynFn::<ForFixed<u32>, ForFixed<u32>>::new(move |x, _| {
+            let _g = &guard;
+            x + 1
+        }));
+    let f2 = f.clone();
+    assert!(!token.is_cancelled());
+    assert_eq!(f.call(1), Some(2));
+    token.clone().cancel();
+    // the function is dropped at cancellation, while the handles are still alive
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    assert!(token.is_cancelled() && f.is_cancelled() && f2.is_cancelled());
+    assert_eq!(f2.call(1), None);
+    token.cancel();
+    drop((f, f2, token));
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn cancel_during_call() {
+    use std::sync::Barrier;
+    let drops = AtomicUsize::new(0);
+    let (entered, release) = (Barrier::new(2), Barrier::new(2));
+    let guard = Guard(&drops);
+    let (entered, release) = (&entered, &release);
+    let (f, token) =
+        CancellableDynFn::new(DynFn::<ForFixed<u32>, ForFixed<u32>>::new(move |x, _| {
+            let _g = &guard;
+            entered.wait();
+            release.wait();
+            x + 1
+        }));
+    std::thread::scope(|s| {
+        let caller = s.spawn(|| f.call(1));
+        entered.wait();
+        let canceller = s.spawn(|| token.cancel());
+        // the call in flight keeps the function alive
+        while !token.is_cancelled() {
+            std::thread::yield_now();
+        }
+        assert_eq!(drops.load(Ordering::Relaxed), 0);
+        assert_eq!(f.call(1), None);
+        release.wait();
+        canceller.join().unwrap();
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+        assert_eq!(caller.join().unwrap(), Some(2));
+    });
+    assert_eq!(f.call(1), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn arc_dyn_fn_once() {
+    let drops = AtomicUsize::new(0);
+    let new = || {
+        let guard = Guard(&drops);
+        ArcDynFnOnce::new(DynFnOnce::<ForFixed<u32>, ForFixed<u32>>::new(
+            move |x, _| {
+                let _g = guard;
+                x + 1
+            },
+        ))
+    };
+    // unique handle
+    assert_eq!(new().try_call(41).unwrap(), 42);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    // shared handle
+    let f = new();
+    let f2 = f.clone();
+    let f = f.try_call(41).unwrap_err();
+    assert!(!f.is_called());
+    let f2 = f2.try_call(41).unwrap_err();
+    drop(f);
+    assert_eq!(f2.try_call(41).unwrap(), 42);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+    // called through another handle
+    let f = new();
+    assert_eq!(f.clone().call_first_wins(41), Some(42));
+    assert!(f.is_called());
+    assert_eq!(f.call_first_wins(41), None);
+    let f = f.try_call(41).unwrap_err();
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+    // never called
+    drop((new(), f));
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn arc_dyn_fn_once_first_wins() {
+    use std::sync::Barrier;
+    let calls = AtomicUsize::new(0);
+    let f = ArcDynFnOnce::new(DynFnOnce::<ForFixed<usize>, ForFixed<usize>>::new(
+        |x, _| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            x
+        },
+    ));
+    let start = Barrier::new(8);
+    let winners: Vec<_> = std::thread::scope(|s| {
+        let racers: Vec<_> = (0..8)
+            .map(|i| {
+                let (f, start) = (f.clone(), &start);
+                s.spawn(move || {
+                    start.wait();
+                    f.call_first_wins(i)
+                })
+            })
+            .collect();
+        racers
+            .into_iter()
+            .filter_map(|r| r.join().unwrap())
+            .collect()
+    });
+    assert_eq!(winners.len(), 1);
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+    assert!(f.is_called());
+}
+
+#[cfg(all(feature = "std", feature = "async"))]
+#[tokio::test]
+async fn cancellable_dyn_async_fn() {
+    struct Gated<'a> {
+        _guard: Guard<'a>,
+        release: &'a tokio::sync::Semaphore,
+    }
+    impl<'capture> AsyncFnSend<'capture, ForFixed<u32>, ForFixed<u32>> for Gated<'capture> {
+        async fn call<'a>(&self, arg: <ForFixed<u32> as ForLt>::Of<'a>) -> u32 {
+            self.release.acquire().await.unwrap().forget();
+            arg + 1
+        }
+    }
+    let drops = AtomicUsize::new(0);
+    let release = tokio::sync::Semaphore::new(0);
+    let (f, token) = CancellableDynAsyncFn::new(DynAsyncFn::<_, _, storage::Arc>::new(Gated {
+        _guard: Guard(&drops),
+        release: &release,
+    }));
+    let mut call = std::pin::pin!(f.call(1));
+    assert!(futures_util::poll!(call.as_mut()).is_pending());
+    token.cancel();
+    assert!(f.is_cancelled());
+    assert_eq!(f.call(1).await, None);
+    // the call in flight holds the function until it completes
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    release.add_permits(1);
+    assert_eq!(call.await, Some(2));
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn padded() {
+    type Callback<const ALIGN: usize> =
+        PaddedDynFn<'static, ForFixed<u32>, ForFixed<u32>, storage::Raw<8>, ALIGN>;
+    assert_eq!(size_of::<Callback<64>>(), 64);
+    assert_eq!(align_of::<Callback<64>>(), 64);
+    assert_eq!(size_of::<Callback<128>>(), 128);
+    assert_eq!(align_of::<Callback<128>>(), 128);
+    // bigger values are padded to a multiple of the alignment
+    assert_eq!(size_of::<Padded<[u8; 65]>>(), 128);
+    assert_eq!(size_of::<[Padded<u8, 64>; 4]>(), 256);
+
+    let mut callbacks: [Callback<64>; 2] =
+        [1, 2].map(|i| Padded::new(DynFn::new(move |x, _| x + i)));
+    assert_eq!(callbacks[1].call(40), 42);
+    *callbacks[0] = DynFn::new(|x, _| x * 2);
+    assert_eq!(callbacks[0].call(21), 42);
+    let [f, _] = callbacks;
+    assert_eq!(f.into_inner().call(1), 2);
+    assert_eq!(*Padded::<_, 128>::from(42), 42);
+}
+
+fn_name!(Incr = "incr");
+
+#[test]
+fn named() {
+    type Callback = DynFn<'static, ForFixed<u32>, ForFixed<u32>, storage::Raw<8>>;
+    let callbacks: [Callback; 2] = [
+        DynFn::new_named(Incr, |x, _| x + 1),
+        DynFn::new(|x, _| x * 2),
+    ];
+    assert_eq!(callbacks[0].name(), Some("incr"));
+    assert_eq!(callbacks[1].name(), None);
+    assert_eq!(callbacks[0].call(41), 42);
+    assert!(format!("{:?}", callbacks[0]).contains(r#"name: Some("incr")"#));
+    assert!(format!("{:?}", callbacks[1]).contains("name: None"));
+    let [f, _] = callbacks;
+    assert_eq!(f.resize::<16>().name(), Some("incr"));
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+        fn_name!(AsyncIncr = "async_incr");
+        let f = LocalDynAsyncFnMut::<ForFixed<u32>, ForFixed<u32>>::new_sync_named(
+            AsyncIncr,
+            |x, _| x + 1,
+        );
+        assert_eq!(f.name(), Some("async_incr"));
+        assert_eq!(f.call(41).now_or_never(), Some(42));
+    }
+}
+
+#[test]
+#[should_panic(expected = "(function `incr`)")]
+fn named_panic_message() {
+    let padding = [1u8; 16];
+    let f = DynFn::<ForFixed<u8>, ForFixed<u8>, storage::Raw<16>>::new_named(Incr, move |x, _| {
+        x + padding[0]
+    });
+    let _ = f.resize::<8>();
+}
+
+#[cfg(feature = "poll-stats")]
+#[test]
+fn poll_stats() {
+    use futures_util::FutureExt;
+
+    struct PendTwice;
+    impl AsyncFnSend<'static, ForFixed<u32>, ForFixed<u32>> for PendTwice {
+        async fn call<'a>(&self, arg: <ForFixed<u32> as ForLt>::Of<'a>) -> u32 {
+            let mut pendings = 0;
+            std::future::poll_fn(|cx| {
+                if pendings == 2 {
+                    return std::task::Poll::Ready(arg);
+                }
+                pendings += 1;
+                cx.waker().wake_by_ref();
+                std::task::Poll::Pending
+            })
+            .await
+        }
+    }
+    let f = DynAsyncFn::<ForFixed<u32>, ForFixed<u32>, storage::Arc>::new(PendTwice);
+    assert_eq!(f.poll_stats(), PollStats::default());
+    assert_eq!(embassy_futures::block_on(f.call(42)), 42);
+    let stats = f.poll_stats();
+    assert_eq!((stats.polls, stats.pendings), (3, 2));
+    assert_eq!((stats.completions, stats.max_polls_to_complete), (1, 3));
+    // a dropped pending future is not completed
+    assert!(f.call(0).now_or_never().is_none());
+    let stats = f.poll_stats();
+    assert_eq!((stats.polls, stats.pendings), (4, 3));
+    assert_eq!((stats.completions, stats.max_polls_to_complete), (1, 3));
+    // clones start from scratch, moves keep the statistics
+    assert_eq!(f.clone().poll_stats(), PollStats::default());
+    assert_eq!(f.into_storage::<storage::Arc>().poll_stats(), stats);
+
+    let mut f = LocalDynAsyncFnMut::<ForFixed<u32>, ForFixed<u32>>::new_sync(|x, _| x);
+    assert_eq!(f.call(1).now_or_never(), Some(1));
+    assert_eq!(f.poll_stats().max_polls_to_complete, 1);
+}
+
+#[test]
+fn deferred_dyn_fn_mut() {
+    // arguments are `'static`
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    let drops = &DROPS;
+    let ids = core::cell::Cell::new(0);
+    let guard = || (ids.replace(ids.get() + 1), Guard(drops));
+    let calls = core::cell::RefCell::new(Vec::new());
+    type Arg = (usize, Guard<'static>);
+    let deferred =
+        DeferredDynFnMut::<Arg, 3, storage::Raw<8>>::new(LocalDynFnMut::new(|(id, _): Arg, _| {
+            calls.borrow_mut().push(id);
+        }));
+    assert!(deferred.is_empty());
+    deferred.flush();
+    // the ring buffer wraps around, keeping the arguments in order
+    for round in 0..3 {
+        for _ in 0..3 {
+            assert!(deferred.enqueue(guard()).is_ok());
+        }
+        // overflowing arguments are rejected
+        assert!(deferred.enqueue(guard()).is_err());
+        assert_eq!(deferred.len(), 3);
+        deferred.flush();
+        assert!(deferred.is_empty());
+        assert_eq!(drops.load(Ordering::Relaxed), 4 * round + 4);
+    }
+    assert_eq!(*calls.borrow(), [0, 1, 2, 4, 5, 6, 8, 9, 10]);
+    // unflushed arguments are dropped with the queue
+    deferred.enqueue(guard()).ok().unwrap();
+    deferred.enqueue(guard()).ok().unwrap();
+    drop(deferred);
+    assert_eq!(drops.load(Ordering::Relaxed), 14);
+    assert_eq!(calls.borrow().len(), 9);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn deferred_enqueue_during_flush() {
+    use std::rc::{Rc, Weak};
+
+    type Deferred = DeferredDynFnMut<'static, u32, 2>;
+    let calls = Rc::new(core::cell::RefCell::new(Vec::new()));
+    let deferred = Rc::new_cyclic(|this: &Weak<Deferred>| {
+        let (this, calls) = (this.clone(), calls.clone());
+        Deferred::new(LocalDynFnMut::new(move |x, _| {
+            calls.borrow_mut().push(x);
+            let this = this.upgrade().unwrap();
+            // arguments enqueued by the callback are flushed by the running flush,
+            // and nested flushes do nothing
+            if x < 5 {
+                this.enqueue(x + 10).unwrap();
+            }
+            this.flush();
+        }))
+    });
+    deferred.enqueue(1).unwrap();
+    deferred.enqueue(2).unwrap();
+    deferred.flush();
+    assert!(deferred.is_empty());
+    assert_eq!(*calls.borrow(), [1, 2, 11, 12]);
+
+    let sum = core::cell::Cell::new(0);
+    let unbounded =
+        UnboundedDeferredDynFnMut::<u32>::new(LocalDynFnMut::new(|x, _| sum.set(sum.get() + x)));
+    (1..=100).for_each(|x| unbounded.enqueue(x));
+    assert_eq!(unbounded.len(), 100);
+    unbounded.flush();
+    assert!(unbounded.is_empty());
+    assert_eq!(sum.get(), 5050);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn deferred_dyn_async_fn_mut() {
+    use futures_util::FutureExt;
+
+    type Callback<'a> = LocalDynAsyncFnMut<'a, ForFixed<u32>, ForFixed<()>>;
+    let calls = core::cell::RefCell::new(Vec::new());
+    let deferred = DeferredDynAsyncFnMut::<u32, 4>::new(Callback::new(async |x, _| {
+        if x % 2 == 0 {
+            futures_util::pending!();
+        }
+        calls.borrow_mut().push(x);
+    }));
+    (1..=4).for_each(|x| deferred.enqueue(x).unwrap());
+    // a cancelled flush drops the argument being passed, the following ones stay queued
+    assert!(deferred.flush().now_or_never().is_none());
+    assert_eq!(deferred.len(), 2);
+    assert_eq!(*calls.borrow(), [1]);
+    deferred.enqueue(5).unwrap();
+    embassy_futures::block_on(deferred.flush());
+    assert_eq!(*calls.borrow(), [1, 3, 4, 5]);
+
+    let deferred = DeferredDynAsyncFnMut::<u32, 4>::new(Callback::new_sync(|x, _| {
+        calls.borrow_mut().push(x);
+    }));
+    deferred.enqueue(6).unwrap();
+    // synchronous callbacks are called without polling
+    assert!(deferred.flush().now_or_never().is_some());
+    assert_eq!(*calls.borrow(), [1, 3, 4, 5, 6]);
+}
+
+#[cfg(all(feature = "std", feature = "async"))]
+#[tokio::test]
+async fn serialized_async_fn_mut() {
+    use std::sync::{Arc, Mutex};
+
+    use futures_util::poll;
+
+    struct Counter {
+        log: Arc<Mutex<Vec<u32>>>,
+        total: u32,
+    }
+    impl AsyncFnMutSend<'static, ForFixed<u32>, ForFixed<u32>> for Counter {
+        async fn call<'a>(&mut self, x: <ForFixed<u32> as ForLt>::Of<'a>) -> u32 {
+            // interleaved calls would lose updates
+            let total = self.total + x;
+            tokio::task::yield_now().await;
+            self.total = total;
+            self.log.lock().unwrap().push(x);
+            total
+        }
+    }
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let f = SerializedAsyncFnMut::new(DynAsyncFnMut::<ForFixed<u32>, ForFixed<u32>>::new(
+        Counter {
+            log: log.clone(),
+            total: 0,
+        },
+    ));
+    // calls run in FIFO order
+    let (mut c1, mut c2, mut c3) = (
+        Box::pin(f.call(1)),
+        Box::pin(f.call(2)),
+        Box::pin(f.call(3)),
+    );
+    assert!(poll!(c2.as_mut()).is_pending());
+    assert!(poll!(c3.as_mut()).is_pending());
+    assert!(poll!(c1.as_mut()).is_pending());
+    assert_eq!(tokio::join!(c1, c2, c3), (6, 2, 5));
+    assert_eq!(*log.lock().unwrap(), [2, 3, 1]);
+
+    // a cancelled waiter is removed from the queue
+    let (mut c4, mut c5, mut c6) = (
+        Box::pin(f.call(4)),
+        Box::pin(f.call(5)),
+        Box::pin(f.call(6)),
+    );
+    assert!(poll!(c4.as_mut()).is_pending());
+    assert!(poll!(c5.as_mut()).is_pending());
+    assert!(poll!(c6.as_mut()).is_pending());
+    drop(c5);
+    assert_eq!(tokio::join!(c4, c6), (10, 16));
+
+    // a cancelled waiter the lock has been handed over to hands it over to the next one
+    let (mut c7, mut c8, mut c9) = (
+        Box::pin(f.call(7)),
+        Box::pin(f.call(8)),
+        Box::pin(f.call(9)),
+    );
+    assert!(poll!(c7.as_mut()).is_pending());
+    assert!(poll!(c8.as_mut()).is_pending());
+    assert!(poll!(c9.as_mut()).is_pending());
+    assert_eq!(c7.await, 23);
+    drop(c8);
+    assert_eq!(c9.await, 32);
+
+    // a cancelled call releases the lock
+    let mut c10 = Box::pin(f.call(10));
+    assert!(poll!(c10.as_mut()).is_pending());
+    drop(c10);
+    assert_eq!(f.call(11).await, 43);
+    assert_eq!(*log.lock().unwrap(), [2, 3, 1, 4, 6, 7, 9, 11]);
+    // the call of the cancelled future is not run
+    assert_eq!(f.into_inner().call(0).await, 43);
+}
+
+#[cfg(all(feature = "std", feature = "async"))]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn serialized_async_fn_mut_concurrency() {
+    use std::sync::Arc;
+
+    struct Counter(u32);
+    impl AsyncFnMutSend<'static, ForFixed<u32>, ForFixed<u32>> for Counter {
+        async fn call<'a>(&mut self, x: <ForFixed<u32> as ForLt>::Of<'a>) -> u32 {
+            // interleaved calls would lose updates
+            let total = self.0 + x;
+            tokio::task::yield_now().await;
+            self.0 = total;
+            total
+        }
+    }
+    let add = Arc::new(SerializedAsyncFnMut::new(DynAsyncFnMut::<
+        ForFixed<u32>,
+        ForFixed<u32>,
+    >::new(Counter(0))));
+    let tasks: Vec<_> = (0..100)
+        .map(|_| {
+            let add = add.clone();
+            tokio::spawn(async move { add.call(1).await })
+        })
+        .collect();
+    let mut totals = Vec::new();
+    for task in tasks {
+        totals.push(task.await.unwrap());
+    }
+    totals.sort_unstable();
+    assert_eq!(totals, (1..=100).collect::<Vec<_>>());
+}
+
+#[cfg(all(feature = "std", feature = "async"))]
+#[test]
+fn leak() {
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    fn call_twice(f: &'static DynFn<ForFixed<usize>, ForFixed<usize>>) -> usize {
+        f.call(1) + f.call(2)
+    }
+    let offset = 40;
+    let f: &'static DynFn<ForFixed<usize>, ForFixed<usize>> = DynFn::new(move |x, _| {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        x + offset
+    })
+    .leak();
+    let threads: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(move || call_twice(f)))
+        .collect();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), 83);
+    }
+    assert_eq!(f.call(2), 42);
+    assert_eq!(CALLS.load(Ordering::Relaxed), 9);
+
+    let mut count = 0;
+    let g = DynFnMut::<ForFixed<()>, ForFixed<usize>>::new(move |(), _| {
+        count += 1;
+        count
+    })
+    .leak();
+    g.call(());
+    assert_eq!(g.call(()), 2);
+
+    let h = DynAsyncFn::<ForFixed<usize>, ForFixed<usize>>::new_sync(|x, _| x + 1).leak();
+    assert_eq!(embassy_futures::block_on(h.call(41)), 42);
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "fault 7")]
+fn diverging() {
+    use std::{convert::Infallible, sync::atomic::AtomicBool};
+    static RELEASED: AtomicBool = AtomicBool::new(false);
+    fn fault_handler(code: &u32) -> ! {
+        while !RELEASED.load(Ordering::Acquire) {
+            std::hint::spin_loop();
+        }
+        panic!("fault {code}");
+    }
+    let mut count = 0;
+    let _ = LocalDynFnMut::<ForFixed<()>, ForFixed<Infallible>>::new_diverging(move |()| {
+        count += 1;
+        panic!("{count}")
+    });
+    let _ = DynFnOnce::<ForFixed<()>, ForFixed<Infallible>>::new_diverging(|()| {
+        loop {
+            std::thread::park();
+        }
+    });
+    let handler = DynFn::<ForRef<u32>, ForFixed<Infallible>>::new_diverging(fault_handler);
+    std::thread::spawn(|| RELEASED.store(true, Ordering::Release));
+    handler.call_never(&7);
+}
+
+#[cfg(all(feature = "std", feature = "async"))]
+#[test]
+fn instrumented() {
+    use std::{num::ParseIntError, time::Instant};
+
+    use embassy_futures::block_on;
+
+    let start = Instant::now();
+    let f = DynFn::<ForFixed<u32>, ForFixed<u32>, storage::Arc>::new(|x, _| x + 1).instrumented();
+    assert_eq!(f.stats().calls, 0);
+    assert_eq!(f.stats().last_call, None);
+    let mut g = f.clone();
+    assert_eq!(DynCallable::call(&f, 1), 2);
+    assert_eq!(g.call_mut(1), 2);
+    assert_eq!(g.call_once(1), 2);
+    let stats = f.stats();
+    assert_eq!((stats.calls, stats.errors), (3, 0));
+    assert!(stats.last_call.is_some_and(|last| last >= start));
+    // calls through the wrapped function are not counted
+    assert_eq!(f.get_ref().call(1), 2);
+    assert_eq!(f.into_inner().call(1), 2);
+
+    let mut parse =
+        LocalDynFnMut::<ForRef<str>, ForFixed<Result<u32, ParseIntError>>>::new(|s, _| s.parse())
+            .instrumented()
+            .count_errors();
+    assert_eq!(parse.call_mut("42"), Ok(42));
+    assert!(parse.call_mut("-1").is_err());
+    assert!(parse.call_mut("x").is_err());
+    assert_eq!((parse.stats().calls, parse.stats().errors), (3, 2));
+
+    let h = DynAsyncFn::<ForFixed<u32>, ForFixed<u32>>::new_sync(|x, _| x + 1).instrumented();
+    assert_eq!(DynAsyncCallable::call_sync(&h, 1), Some(2));
+    assert_eq!(block_on(DynAsyncCallable::call(&h, 1)), 2);
+    assert_eq!(h.stats().calls, 2);
+    let h = LocalDynAsyncFn::<ForFixed<u32>, ForFixed<u32>>::new(async |x, _| x + 1).instrumented();
+    // asynchronous functions cannot be called synchronously, so the call is not counted
+    assert_eq!(DynAsyncCallable::call_sync(&h, 1), None);
+    assert_eq!(block_on(DynAsyncCallable::call_try_sync(&h, 1)), 2);
+    assert_eq!(h.stats().calls, 1);
+}
+
+#[test]
+fn spin_dyn_fn_mut() {
+    use std::{
+        panic::{AssertUnwindSafe, catch_unwind},
+        sync::{
+            Barrier,
+            atomic::{AtomicBool, AtomicU64},
+        },
+        thread,
+    };
+
+    let busy = AtomicBool::new(false);
+    let mut count = 0u64;
+    let f = SpinDynFnMut::<ForFixed<()>, ForFixed<u64>, storage::Raw<16>>::new(DynFnMut::new(
+        |_, _| {
+            assert!(!busy.swap(true, Ordering::Acquire), "concurrent calls");
+            count += 1;
+            busy.store(false, Ordering::Release);
+            count
+        },
+    ));
+    let max = AtomicU64::new(0);
+    thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    max.fetch_max(f.call(()), Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    assert_eq!(max.load(Ordering::Relaxed), 8000);
+    assert_eq!(f.into_inner().call(()), 8001);
+
+    let barrier = Barrier::new(2);
+    let f = SpinDynFnMut::<ForFixed<bool>, ForFixed<bool>>::new(DynFnMut::new(|block, _| {
+        if block {
+            barrier.wait();
+            barrier.wait();
+        }
+        block
+    }));
+    thread::scope(|s| {
+        let call = s.spawn(|| f.call(true));
+        barrier.wait();
+        assert!(f.is_locked());
+        assert_eq!(f.try_call(false), None);
+        barrier.wait();
+        assert!(call.join().unwrap());
+    });
+    assert_eq!(f.try_call(false), Some(false));
+
+    let f = SpinDynFnMut::<ForFixed<u32>, ForFixed<u32>>::from(DynFnMut::new(|x, _| {
+        assert_ne!(x, 0, "zero");
+        x
+    }));
+    assert!(catch_unwind(AssertUnwindSafe(|| f.call(0))).is_err());
+    assert!(!f.is_locked());
+    assert_eq!(f.call(1), 1);
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn dyn_fn_spy() {
+    let spy = DynFnSpy::<ForFixed<u32>, ForFixed<u32>, _>::returning(|x| x * 2);
+    let f = spy.as_dyn_fn();
+    assert_eq!(f.call(1), 2);
+    assert_eq!(f.call(21), 42);
+    let mut f_mut = spy.clone().as_dyn_fn_mut();
+    assert_eq!(f_mut.call(3), 6);
+    assert_eq!(spy.times_called(), 3);
+    assert_eq!(spy.calls(), [1, 21, 3]);
+    assert!(spy.was_called_with(|&x| x == 21));
+    assert!(!spy.was_called_with(|&x| x == 42));
+
+    let spy = DynFnSpy::<ForFixed<String>, ForFixed<&str>, _>::return_const("ok");
+    assert_eq!(spy.times_called(), 0);
+    assert_eq!(spy.as_dyn_fn().call("a".into()), "ok");
+    assert_eq!(spy.calls(), ["a"]);
+
+    let spy = DynFnSpy::<ForRef<str>, ForFixed<usize>, String>::returning_projected(
+        |s| s.to_uppercase(),
+        |s| s.len(),
+    );
+    assert_eq!(spy.as_dyn_fn().call("hello"), 5);
+    assert_eq!(spy.calls(), ["HELLO"]);
+
+    // borrowed return values are supported by the generic constructor
+    let spy = DynFnSpy::<ForRef<str>, ForRef<str>, ()>::with_projection(
+        |_| (),
+        DynFn::new(|s: &str, _| s.trim()),
+    );
+    assert_eq!(spy.as_dyn_fn().call(" hello "), "hello");
+    assert_eq!(spy.times_called(), 1);
+
+    // calls are still recorded after a panicking call
+    let spy = DynFnSpy::<ForFixed<u32>, ForFixed<()>, _>::returning(|x| assert_ne!(x, 0));
+    let f = spy.as_dyn_fn();
+    assert!(std::panic::catch_unwind(|| f.call(0)).is_err());
+    f.call(1);
+    assert_eq!(spy.calls(), [0, 1]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn send_not_sync_capture() {
+    use core::cell::Cell;
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+    // `Cell` is `Send` but not `Sync`
+    let counter = Cell::new(0);
+    let mut f = DynFnMut::<ForFixed<u64>, ForFixed<u64>>::new(move |x, _| {
+        counter.set(counter.get() + x);
+        counter.get()
+    });
+    assert_send_sync(&f);
+    let cell = Cell::new(1);
+    let g = DynFnOnce::<ForFixed<u64>, ForFixed<u64>>::new(move |x, _| cell.get() + x);
+    assert_send_sync(&g);
+    let res = std::thread::spawn(move || {
+        f.call(1);
+        f.call(2) + g.call(3)
+    });
+    assert_eq!(res.join().unwrap(), 7);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn async_send_not_sync_capture() {
+    use core::cell::Cell;
+
+    use tokio::sync::mpsc;
+
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+    // `Cell` is `Send` but not `Sync`
+    struct Forward {
+        tx: mpsc::Sender<u64>,
+        sent: Cell<u64>,
+    }
+    impl AsyncFnMutSend<'static, ForFixed<u64>, ForFixed<u64>> for Forward {
+        async fn call<'a>(&mut self, arg: <ForFixed<u64> as ForLt>::Of<'a>) -> u64 {
+            self.tx.send(arg).await.unwrap();
+            self.sent.set(self.sent.get() + 1);
+            self.sent.get()
+        }
+    }
+    impl AsyncFnOnceSend<'static, ForFixed<u64>, ForFixed<u64>> for Forward {
+        async fn call(mut self, arg: <ForFixed<u64> as ForLt>::Of<'_>) -> u64 {
+            AsyncFnMutSend::call(&mut self, arg).await
+        }
+    }
+
+    let (tx, mut rx) = mpsc::channel(8);
+    let forward = || Forward {
+        tx: tx.clone(),
+        sent: Cell::new(0),
+    };
+    let mut f = DynAsyncFnMut::<ForFixed<u64>, ForFixed<u64>>::new(forward());
+    assert_send_sync(&f);
+    let res = tokio::spawn(async move {
+        f.call(1).await;
+        f.call(2).await
+    });
+    assert_eq!(res.await.unwrap(), 2);
+    let g = DynAsyncFnOnce::<ForFixed<u64>, ForFixed<u64>>::new(forward());
+    assert_send_sync(&g);
+    assert_eq!(tokio::spawn(g.call(3)).await.unwrap(), 1);
+    let cell = Cell::new(4);
+    let h = DynAsyncFnMut::<ForFixed<u64>, ForFixed<u64>>::new_sync(move |x, _| {
+        cell.set(cell.get() + x);
+        cell.get()
+    });
+    assert_send_sync(&h);
+    let res = tokio::spawn(async move {
+        let mut h = h;
+        h.call(1).await
+    });
+    assert_eq!(res.await.unwrap(), 5);
+    for x in 1..=3 {
+        assert_eq!(rx.recv().await, Some(x));
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn send_dyn_fn_thread() {
+    use core::cell::Cell;
+    use std::sync::mpsc;
+
+    let (tx, rx) = mpsc::channel();
+    // `Cell` is `Send` but not `Sync`
+    let count = Cell::new(0);
+    let f = SendDynFn::<ForFixed<u64>>::new(move |x, _| {
+        count.set(count.get() + x);
+        tx.send(count.get()).unwrap();
+    });
+    let double = SendDynFn::from(DynFn::<ForFixed<u64>, ForFixed<u64>>::new(|x, _| 2 * x));
+    std::thread::spawn(move || {
+        f.call(double.call(1));
+        f.call(double.call(2));
+    })
+    .join()
+    .unwrap();
+    assert_eq!(rx.iter().collect::<Vec<_>>(), [2, 6]);
+}
+
+#[cfg(all(feature = "std", feature = "async"))]
+#[test]
+fn send_dyn_async_fn_thread() {
+    use core::cell::Cell;
+
+    use futures_util::FutureExt;
+
+    let count = Cell::new(0);
+    let f = SendDynAsyncFn::<ForFixed<u64>, ForFixed<u64>>::new(async move |x, _| {
+        count.set(count.get() + x);
+        count.get()
+    });
+    let g = SendDynAsyncFn::<ForFixed<u64>, ForFixed<u64>>::new_sync(|x, _| x + 1);
+    let res = std::thread::spawn(move || {
+        let x = f.call(1).now_or_never().unwrap();
+        f.call(g.call_sync(x).unwrap()).now_or_never().unwrap()
+    });
+    assert_eq!(res.join().unwrap(), 3);
+}
+
+#[test]
+fn eq_fn_ptr() {
+    use core::marker::PhantomData;
+
+    fn default_handler(x: u64, _: PhantomData<&()>) -> u64 {
+        x
+    }
+    fn other_handler(x: u64, _: PhantomData<&()>) -> u64 {
+        x + 1
+    }
+    type Handler = DynFn<'static, ForFixed<u64>, ForFixed<u64>, storage::Raw<8>>;
+    let mut slots = [
+        Handler::new_fn_ptr(default_handler),
+        Handler::new_fn_ptr(default_handler),
+    ];
+    assert!(slots.iter().all(|slot| slot.eq_fn_ptr(default_handler)));
+    slots[1] = Handler::new_fn_ptr(other_handler);
+    assert!(slots[0].eq_fn_ptr(default_handler));
+    assert!(!slots[1].eq_fn_ptr(default_handler));
+    assert!(slots[1].is_fn_ptr());
+    assert_eq!(slots[1].call(0), 1);
+    slots[1] = Handler::new(|x, _| x + 2);
+    assert!(!slots[1].is_fn_ptr());
+    assert!(!slots[1].eq_fn_ptr(default_handler));
+    // a closure doesn't compare equal, even if coerced from the same function
+    assert!(!Handler::new(default_handler).eq_fn_ptr(default_handler));
+}
+
+#[test]
+fn unsafe_dyn_fn() {
+    use core::marker::PhantomData;
+
+    /// # Safety
+    ///
+    /// `s` must start with an ASCII character.
+    unsafe fn first<'a>(s: &'a str, _: PhantomData<&'a ()>) -> &'a str {
+        // SAFETY: the first character is one byte long, so `1` is a char boundary
+        unsafe { s.get_unchecked(..1) }
+    }
+    let f = UnsafeDynFn::<ForRef<str>, ForRef<str>, storage::Raw<8>>::new_unsafe(first);
+    let s = String::from("test");
+    // SAFETY: `s` starts with an ASCII character
+    assert_eq!(unsafe { f.call_unchecked(&s) }, "t");
+    let len = AtomicUsize::new(0);
+    let g = UnsafeDynFn::<ForFixed<*const u64>, ForFixed<u64>, storage::Raw<8>>::new(|ptr, _| {
+        len.fetch_add(1, Ordering::Relaxed);
+        // SAFETY: `call_unchecked` is called with a pointer valid for reads
+        unsafe { ptr.read() }
+    });
+    // SAFETY: `&42` is valid for reads
+    assert_eq!(unsafe { g.call_unchecked(&42) }, 42);
+    assert_eq!(len.load(Ordering::Relaxed), 1);
+    let h = UnsafeDynFn::from(DynFn::<ForFixed<u64>, ForFixed<u64>>::new(|x, _| x + 1));
+    // SAFETY: the function is safe
+    assert_eq!(unsafe { h.call_unchecked(1) }, 2);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn call_catch_unwind() {
+    let drops = AtomicUsize::new(0);
+
+    let f = DynFn::<ForFixed<bool>, ForFixed<i32>>::new(|panic, _| {
+        assert!(!panic, "panic");
+        42
+    });
+    assert_eq!(f.call_catch_unwind(false).unwrap(), 42);
+    let err = f.call_catch_unwind(true).unwrap_err();
+    assert_eq!(err.downcast_ref::<&str>(), Some(&"panic"));
+    assert_eq!(f.call(false), 42);
+
+    let mut count = 0;
+    let mut f = LocalDynFnMut::<ForFixed<bool>, ForFixed<i32>>::new(|panic, _| {
+        count += 1;
+        assert!(!panic);
+        count
+    });
+    assert!(f.call_catch_unwind(true).is_err());
+    assert_eq!(f.call_catch_unwind(false).unwrap(), 2);
+    drop(f);
+
+    let guard = Guard(&drops);
+    let f = DynFnOnce::<ForFixed<bool>, ForFixed<i32>>::new(move |panic, _| {
+        let _guard = guard;
+        assert!(!panic);
+        42
+    });
+    assert!(f.call_catch_unwind(true).is_err());
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+    let guard = Guard(&drops);
+    let f =
+        LocalDynFnOnce::<ForFixed<bool>, ForFixed<i32>, storage::Raw<8>>::new(move |panic, _| {
+            let _guard = guard;
+            assert!(!panic);
+            42
+        });
+    assert_eq!(f.call_catch_unwind(false).unwrap(), 42);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn dyn_fn_slot() {
+    type Slot = DynFnSlot<'static, ForFixed<i32>, ForFixed<i32>, storage::Raw<32>>;
+    assert_eq!(
+        size_of::<Slot>(),
+        size_of::<DynFn<ForFixed<i32>, ForFixed<i32>, storage::Raw<32>>>()
+    );
+    macro_rules! assert_niche {
+        ($($ty:ty),*) => {$(
+            assert_eq!(size_of::<Option<$ty>>(), size_of::<$ty>());
+        )*};
+    }
+    assert_niche!(
+        LocalDynFn<ForFixed<()>, ForFixed<()>, storage::Raw<32>>,
+        LocalDynFnMut<ForFixed<()>, ForFixed<()>, storage::Raw<0>>,
+        LocalDynFnOnce<ForFixed<()>, ForFixed<()>, storage::Raw<32>>
+    );
+    #[cfg(feature = "alloc")]
+    assert_niche!(
+        DynFn<ForFixed<()>, ForFixed<()>, storage::Box>,
+        DynFn<ForFixed<()>, ForFixed<()>, storage::Arc>,
+        LocalDynFn<ForFixed<()>, ForFixed<()>, storage::Rc>,
+        DynFn<ForFixed<()>, ForFixed<()>, storage::RawOrBox<32>>
+    );
+    #[cfg(feature = "async")]
+    assert_niche!(
+        LocalDynAsyncFn<ForFixed<()>, ForFixed<()>, storage::Raw<32>, storage::Raw<32>>,
+        DynAsyncFnOnce<ForFixed<()>, ForFixed<()>, storage::Raw<32>, storage::Raw<32>>
+    );
+
+    let mut slot = Slot::default();
+    assert!(!slot.is_set());
+    assert_eq!(slot.call_if_set(0), None);
+    assert!(slot.set(DynFn::new(|x, _| x + 1)).is_none());
+    assert!(slot.is_set());
+    assert_eq!(slot.call_if_set(41), Some(42));
+    assert_eq!(slot.call_if_set(41), Some(42));
+    assert_eq!(slot.set(DynFn::new(|x, _| x - 1)).unwrap().call(41), 42);
+    assert_eq!(slot.clear().unwrap().call(43), 42);
+    assert!(slot.clear().is_none());
+
+    let drops = AtomicUsize::new(0);
+    let new_fn = || {
+        let guard = Guard(&drops);
+        DynFn::<ForFixed<()>, ForFixed<()>, storage::Raw<8>>::new(move |_, _| {
+            let _ = &guard;
+        })
+    };
+    let mut slot = DynFnSlot::from(new_fn());
+    drop(slot.set(new_fn()));
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    assert_eq!(slot.call_if_set(()), Some(()));
+    drop(slot.clear());
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+    assert!(slot.set(new_fn()).is_none());
+    drop(slot);
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn once_slot() {
+    type Slot = OnceSlot<'static, ForFixed<i32>, ForFixed<i32>, storage::Raw<8>>;
+    assert_eq!(
+        size_of::<Slot>(),
+        size_of::<DynFnOnce<ForFixed<i32>, ForFixed<i32>, storage::Raw<8>>>()
+    );
+    let mut slot = Slot::default();
+    assert!(!slot.is_armed());
+    assert_eq!(slot.fire(0), None);
+    slot.arm(DynFnOnce::new(|x, _| x + 1));
+    assert!(slot.is_armed());
+    assert_eq!(slot.fire(41), Some(42));
+    assert_eq!(slot.fire(41), None);
+    slot.arm(DynFnOnce::new(|x, _| x - 1));
+    assert_eq!(slot.fire(43), Some(42));
+
+    let drops = AtomicUsize::new(0);
+    let mut slot = OnceSlot::<ForFixed<()>, ForFixed<()>, storage::Raw<8>>::new();
+    let guard = Guard(&drops);
+    slot.arm(DynFnOnce::new(move |_, _| drop(guard)));
+    drop(slot.disarm());
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    assert!(slot.disarm().is_none());
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+
+        type AsyncSlot = AsyncOnceSlot<'static, ForFixed<i32>, ForFixed<i32>, storage::Raw<0>>;
+        assert_eq!(
+            size_of::<AsyncSlot>(),
+            size_of::<DynAsyncFnOnce<ForFixed<i32>, ForFixed<i32>, storage::Raw<0>>>()
+        );
+        let mut slot = AsyncSlot::new();
+        assert_eq!(slot.fire(0).now_or_never(), Some(None));
+        slot.arm(DynAsyncFnOnce::new_sync(|x, _| x + 1));
+        assert!(slot.is_armed());
+        assert_eq!(slot.fire(41).now_or_never(), Some(Some(42)));
+        assert!(!slot.is_armed());
+        slot.arm(DynAsyncFnOnce::new_sync(|x, _| x + 1));
+        assert!(slot.disarm().is_some());
+    }
+}
+
+#[test]
+fn same_callee() {
+    fn new_raw(x: u64) -> DynFn<'static, ForFixed<()>, ForFixed<u64>, storage::Raw<8>> {
+        DynFn::new(move |_, _| x)
+    }
+    let (a, b) = (new_raw(0), new_raw(1));
+    assert!(a.same_callee(&a));
+    assert!(a.same_callee(&b));
+    let c = DynFn::new(|_, _| 42);
+    assert!(!a.same_callee(&c));
+
+    #[cfg(feature = "alloc")]
+    {
+        fn new_arc(x: u64) -> DynFn<'static, ForFixed<()>, ForFixed<u64>, storage::Arc> {
+            DynFn::new(move |_, _| x)
+        }
+        let (a, b) = (new_arc(0), new_arc(0));
+        assert!(a.same_callee(&a.clone()));
+        assert!(!a.same_callee(&b));
+
+        fn new_rc(x: u64) -> LocalDynFn<'static, ForFixed<()>, ForFixed<u64>, storage::Rc> {
+            LocalDynFn::new(move |_, _| x)
+        }
+        let (a, b) = (new_rc(0), new_rc(0));
+        assert!(a.same_callee(&a.clone()));
+        assert!(!a.same_callee(&b));
+
+        fn new_box(x: u64) -> LocalDynFnMut<'static, ForFixed<()>, ForFixed<u64>, storage::Box> {
+            LocalDynFnMut::new(move |_, _| x)
+        }
+        let (a, b) = (new_box(0), new_box(0));
+        assert!(a.same_callee(&a));
+        assert!(!a.same_callee(&b));
+
+        #[cfg(feature = "async")]
+        {
+            fn new_raw_or_box<T: Copy + Send + Sync + 'static>(
+                x: T,
+            ) -> DynAsyncFn<'static, ForFixed<()>, ForFixed<T>, storage::RawOrBox<8>> {
+                DynAsyncFn::new_sync(move |_, _| x)
+            }
+            let (a, b) = (new_raw_or_box(0u64), new_raw_or_box(1u64));
+            assert!(a.same_callee(&b));
+            let (a, b) = (new_raw_or_box([0u64; 2]), new_raw_or_box([0u64; 2]));
+            assert!(a.same_callee(&a));
+            assert!(!a.same_callee(&b));
+        }
+    }
+}
+
+#[test]
+fn with_state() {
+    // Only the state is stored, the zero-sized function being called by the vtable.
+    const U64: usize = size_of::<u64>();
+    const STRING: usize = size_of::<String>();
+    let add =
+        DynFn::<ForFixed<u64>, ForFixed<u64>, storage::Raw<U64>>::with_state(40, |n, x, _| n + x);
+    assert_eq!(add.call(2), 42);
+    let mul =
+        DynFn::<ForFixed<u64>, ForFixed<u64>, storage::Raw<U64>>::with_state(21, |n, x, _| n * x);
+    assert_eq!(mul.call(2), 42);
+
+    let mut counter =
+        LocalDynFnMut::<ForFixed<u64>, ForFixed<u64>, storage::Raw<U64>>::with_state_mut(
+            0,
+            |n, x, _| {
+                *n += x;
+                *n
+            },
+        );
+    assert_eq!(counter.call(40), 40);
+    assert_eq!(counter.call(2), 42);
+
+    let len = DynFnOnce::<ForRef<str>, ForFixed<usize>, storage::Raw<STRING>>::with_state_once(
+        String::from("state"),
+        |s, x, _| s.len() + x.len(),
+    );
+    assert_eq!(len.call("arg"), 8);
+
+    let first =
+        LocalDynFn::<ForRef<str>, ForRef<str>, storage::Raw<0>>::with_state((), |_, s, _| &s[..1]);
+    assert_eq!(first.call("arg"), "a");
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+
+        let add = DynAsyncFn::<ForFixed<u64>, ForFixed<u64>, storage::Raw<U64>>::with_state(
+            40,
+            |n, x, _| n + x,
+        );
+        assert!(add.is_sync());
+        assert_eq!(add.call(2).now_or_never(), Some(42));
+        let mut counter =
+            LocalDynAsyncFnMut::<ForFixed<u64>, ForFixed<u64>, storage::Raw<U64>>::with_state_mut(
+                0,
+                |n, x, _| {
+                    *n += x;
+                    *n
+                },
+            );
+        assert_eq!(counter.call_sync(40), Some(40));
+        assert_eq!(counter.call_sync(2), Some(42));
+        let once =
+            DynAsyncFnOnce::<ForFixed<u64>, ForFixed<u64>, storage::Raw<U64>>::with_state_once(
+                40,
+                |n, x, _| n + x,
+            );
+        assert_eq!(once.call_sync(2), Some(42));
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn result_combinators() {
+    type Parse = DynFn<'static, ForRef<str>, ForFixed<Result<i32, String>>>;
+    let parse = Parse::new(|s, _| s.parse::<i32>().map_err(|err| err.to_string()));
+    let parse = parse.map_err(|err| format!("invalid: {err}"));
+    assert_eq!(parse.call("42"), Ok(42));
+    assert!(parse.call("x").unwrap_err().starts_with("invalid: "));
+    let parse = parse.unwrap_or(0);
+    assert_eq!(parse.call("42"), 42);
+    assert_eq!(parse.call("x"), 0);
+
+    let calls = AtomicUsize::new(0);
+    type Len<'a> = LocalDynFnMut<'a, ForFixed<String>, ForFixed<Result<usize, ()>>>;
+    let primary = Len::new(|s: String, _| if s.is_empty() { Err(()) } else { Ok(s.len()) });
+    let fallback = Len::new(|s: String, _| {
+        calls.fetch_add(1, Ordering::Relaxed);
+        assert!(s.is_empty());
+        Ok(usize::MAX)
+    });
+    let mut len = primary.or_else(fallback);
+    assert_eq!(len.call("arg".into()), Ok(3));
+    assert_eq!(calls.load(Ordering::Relaxed), 0);
+    assert_eq!(len.call(String::new()), Ok(usize::MAX));
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    let drops = AtomicUsize::new(0);
+    type Once<'a> = LocalDynFnOnce<'a, ForFixed<bool>, ForFixed<Result<i32, i32>>>;
+    let new_once = |value| {
+        let guard = Guard(&drops);
+        Once::new(move |ok, _| {
+            let _guard = guard;
+            if ok { Ok(value) } else { Err(value) }
+        })
+    };
+    assert_eq!(new_once(1).or_else(new_once(2)).call(true), Ok(1));
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+    assert_eq!(new_once(1).or_else(new_once(2)).call(false), Err(2));
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+    let once = new_once(1).map_err(|err| err * 10).unwrap_or(0);
+    assert_eq!(once.call(false), 0);
+    assert_eq!(drops.load(Ordering::Relaxed), 5);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn fanout() {
+    let drops = AtomicUsize::new(0);
+    let metrics = AtomicUsize::new(0);
+    let (guard1, guard2) = (Guard(&drops), Guard(&drops));
+    let metrics = &metrics;
+    let count = LocalDynFn::<ForRef<str>, ForFixed<()>>::new(move |_, _| {
+        let _guard = &guard1;
+        metrics.fetch_add(1, Ordering::Relaxed);
+    });
+    let first = LocalDynFn::<ForRef<str>, ForRef<str>>::new(move |s, _| {
+        let _guard = &guard2;
+        &s[..1]
+    });
+    let both = count.fanout(first);
+    assert_eq!(both.call("arg"), ((), "a"));
+    assert_eq!(metrics.load(Ordering::Relaxed), 1);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(both);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+
+    let len = DynFn::<ForFixed<String>, ForFixed<usize>>::new(|s, _| s.len());
+    let upper = DynFn::<ForFixed<String>, ForFixed<String>>::new(|s, _| s.to_uppercase());
+    assert_eq!(len.fanout(upper).call("arg".into()), (3, "ARG".into()));
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+
+        let len = LocalDynAsyncFn::<ForRef<str>, ForFixed<usize>>::new_sync(|s, _| s.len());
+        let first = LocalDynAsyncFn::<ForRef<str>, ForRef<str>>::new_sync(|s, _| &s[..1]);
+        let both = len.fanout(first);
+        assert!(both.is_sync());
+        assert_eq!(both.call_sync("arg"), Some((3, "a")));
+        let len = LocalDynAsyncFn::<ForRef<str>, ForFixed<usize>>::new(async |s, _| s.len());
+        let first = LocalDynAsyncFn::<ForRef<str>, ForRef<str>>::new_sync(|s, _| &s[..1]);
+        let both = len.fanout(first);
+        assert!(!both.is_sync());
+        assert_eq!(both.call("arg").now_or_never(), Some((3, "a")));
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn inspect() {
+    use std::{cell::RefCell, sync::Mutex};
+
+    let log = RefCell::new(Vec::new());
+    let trim = LocalDynFn::<ForRef<str>, ForRef<str>>::new(|s, _| {
+        log.borrow_mut().push(format!("call {s:?}"));
+        s.trim()
+    })
+    .inspect(|s: &&str| log.borrow_mut().push(format!("arg {s:?}")))
+    .inspect_output(|s: &&str| log.borrow_mut().push(format!("ret {s:?}")));
+    assert_eq!(trim.call(" a "), "a");
+    assert_eq!(trim.call("b"), "b");
+    assert_eq!(
+        *log.borrow(),
+        [
+            "arg \" a \"",
+            "call \" a \"",
+            "ret \"a\"",
+            "arg \"b\"",
+            "call \"b\"",
+            "ret \"b\""
+        ]
+    );
+
+    let log = Mutex::new(Vec::new());
+    let len = DynFn::<ForFixed<String>, ForFixed<usize>, storage::Box>::new(|s, _| s.len())
+        .inspect(|s| log.lock().unwrap().push(s.clone()))
+        .inspect_output(|len| log.lock().unwrap().push(len.to_string()));
+    assert_eq!(len.call("arg".into()), 3);
+    assert_eq!(*log.lock().unwrap(), ["arg", "3"]);
+
+    let mut args = Vec::new();
+    let mut rets = Vec::new();
+    let mut total = 0;
+    let mut sum = LocalDynFnMut::<ForFixed<usize>, ForFixed<usize>>::new(|x, _| {
+        total += x;
+        total
+    })
+    .inspect(|x| args.push(*x))
+    .inspect_output(|x| rets.push(*x));
+    assert_eq!(sum.call(1), 1);
+    assert_eq!(sum.call(2), 3);
+    drop(sum);
+    assert_eq!((args, rets), (vec![1, 2], vec![1, 3]));
+
+    let count = AtomicUsize::new(0);
+    let mut push =
+        DynFnMut::<ForRefMut<Vec<u8>>, ForFixed<usize>, storage::RawOrBox<8>>::new(|v, _| {
+            v.push(0);
+            v.len()
+        })
+        .inspect(|v| assert_eq!(v.len(), count.fetch_add(1, Ordering::Relaxed)))
+        .inspect_output(|&len| assert_eq!(len, count.load(Ordering::Relaxed)));
+    let mut v = Vec::new();
+    assert_eq!(push.call(&mut v), 1);
+    assert_eq!(push.call(&mut v), 2);
+    assert_eq!(count.load(Ordering::Relaxed), 2);
+}
+
+#[cfg(all(feature = "alloc", feature = "async"))]
+#[test]
+fn async_inspect() {
+    use std::{cell::RefCell, sync::Mutex};
+
+    use futures_util::FutureExt;
+
+    let log = RefCell::new(Vec::new());
+    let len = LocalDynAsyncFn::<ForRef<str>, ForFixed<usize>>::new(async |s, _| {
+        futures_util::pending!();
+        s.len()
+    })
+    .inspect(
+        |s: &&str| log.borrow_mut().push(format!("start {s}")),
+        |len| log.borrow_mut().push(format!("complete {len}")),
+    )
+    .inspect_cancel(|| log.borrow_mut().push("cancel".into()));
+    assert!(!len.is_sync());
+    assert_eq!(embassy_futures::block_on(len.call("arg")), 3);
+    assert_eq!(*log.borrow(), ["start arg", "complete 3"]);
+    log.borrow_mut().clear();
+    assert_eq!(len.call("arg").now_or_never(), None);
+    assert_eq!(*log.borrow(), ["start arg", "cancel"]);
+    log.borrow_mut().clear();
+    // a future dropped before being polled has not started
+    drop(len.call("arg"));
+    assert!(log.borrow().is_empty());
+
+    struct Pending;
+    impl AsyncFnSend<'static, ForFixed<u64>, ForFixed<u64>> for Pending {
+        async fn call<'a>(&self, arg: <ForFixed<u64> as ForLt>::Of<'a>) -> u64 {
+            futures_util::pending!();
+            arg + 1
+        }
+    }
+    let log = Mutex::new(Vec::new());
+    let incr = DynAsyncFn::<ForFixed<u64>, ForFixed<u64>>::new(Pending)
+        .inspect(
+            |x| log.lock().unwrap().push(format!("start {x}")),
+            |x| log.lock().unwrap().push(format!("complete {x}")),
+        )
+        .inspect_cancel(|| log.lock().unwrap().push("cancel".into()));
+    assert_eq!(incr.call(1).now_or_never(), None);
+    assert_eq!(embassy_futures::block_on(incr.call(2)), 3);
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["start 1", "cancel", "start 2", "complete 3"]
+    );
+
+    let log = Mutex::new(Vec::new());
+    let incr = DynAsyncFn::<ForFixed<u64>, ForFixed<u64>>::new_sync(|x, _| x + 1)
+        .inspect(
+            |x| log.lock().unwrap().push(format!("start {x}")),
+            |x| log.lock().unwrap().push(format!("complete {x}")),
+        )
+        .inspect_cancel(|| unreachable!());
+    assert!(incr.is_sync());
+    assert_eq!(incr.call_sync(1), Some(2));
+    assert_eq!(incr.call(2).now_or_never(), Some(3));
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["start 1", "complete 2", "start 2", "complete 3"]
+    );
+}
+
+#[cfg(all(feature = "alloc", feature = "async"))]
+#[test]
+fn zip() {
+    use std::{
+        cell::Cell,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use futures_util::FutureExt;
+
+    let (ready, drops) = (Cell::new(false), AtomicUsize::new(0));
+    // `wait` completes only once `set` has run, so they must run concurrently
+    let wait =
+        LocalDynAsyncFn::<ForRef<str>, ForRef<str>, storage::Box, storage::RawOrBox<64>>::new(
+            async |s, _| {
+                let _guard = Guard(&drops);
+                std::future::poll_fn(|_| {
+                    if ready.get() {
+                        Poll::Ready(())
+                    } else {
+                        Poll::Pending
+                    }
+                })
+                .await;
+                &s[..1]
+            },
+        );
+    let set = LocalDynAsyncFn::<ForRef<str>, ForFixed<usize>, _, _>::new(async |s, _| {
+        let _guard = Guard(&drops);
+        ready.set(true);
+        futures_util::pending!();
+        s.len()
+    });
+    // both futures fit in the future storage, but the combined one spills to the heap
+    let both = wait.zip(set);
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut fut = pin!(both.call("arg"));
+    assert!(fut.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(("a", 3)));
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+
+    // dropping the combined future drops both futures
+    ready.set(false);
+    assert_eq!(both.call("arg").now_or_never(), None);
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+
+    let len = DynAsyncFn::<ForRef<str>, ForFixed<usize>>::new_sync(|s, _| s.len());
+    let first = DynAsyncFn::<ForRef<str>, ForRef<str>>::new_sync(|s, _| &s[..1]);
+    let both = len.zip(first);
+    assert!(both.is_sync());
+    assert_eq!(both.call_sync("arg"), Some((3, "a")));
+}
+
+#[cfg(feature = "sync-to-async")]
+#[test]
+fn dyn_fn_once_into_async() {
+    use futures_util::FutureExt;
+
+    let drops = AtomicUsize::new(0);
+    let new = || {
+        let guard = Guard(&drops);
+        DynFnOnce::<ForRef<str>, ForRef<str>, storage::Raw<8>>::new(move |s, _| {
+            let _guard = guard;
+            s.trim()
+        })
+    };
+
+    let f: DynAsyncFnOnce<_, _, _, storage::Raw<0>> = new().into();
+    assert!(f.is_sync());
+    assert_eq!(f.call_sync(" a "), Some("a"));
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    let f: DynAsyncFnOnce<_, _, _, storage::Raw<0>> = new().into();
+    assert_eq!(f.call(" b ").now_or_never(), Some("b"));
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+    let f: DynAsyncFnOnce<_, _, _, storage::Raw<0>> = new().into();
+    assert_eq!(f.call_try_sync(" c ").now_or_never(), Some("c"));
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+    drop(DynAsyncFnOnce::<_, _, _, storage::Raw<0>>::from(new()));
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+
+    let f = LocalDynFnOnce::<ForFixed<u32>, ForFixed<u32>>::new(|x, _| x + 1);
+    let f = LocalDynAsyncFnOnce::<_, _, _, storage::Raw<0>>::from(f);
+    assert!(f.is_sync());
+    assert_eq!(f.call(41).now_or_never(), Some(42));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn dyn_async_fn_once_try_into_sync() {
+    use futures_util::FutureExt;
+
+    let drops = AtomicUsize::new(0);
+
+    let guard = Guard(&drops);
+    let f =
+        DynAsyncFnOnce::<ForFixed<u32>, ForFixed<u32>, storage::Raw<8>>::new_sync(move |x, _| {
+            let _guard = guard;
+            x + 1
+        });
+    let f = f.try_into_sync().unwrap();
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    assert_eq!(f.call(41), 42);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+    let guard = Guard(&drops);
+    let f = LocalDynAsyncFnOnce::<ForFixed<u32>, ForFixed<u32>, storage::Raw<8>>::new(
+        async move |x, _| {
+            let _guard = guard;
+            x + 1
+        },
+    );
+    let f = f.try_into_sync().unwrap_err();
+    assert!(!f.is_sync());
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    assert_eq!(f.call(41).now_or_never(), Some(42));
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+
+    #[cfg(feature = "sync-to-async")]
+    {
+        let guard = Guard(&drops);
+        let f =
+            LocalDynFnOnce::<ForFixed<u32>, ForFixed<u32>, storage::Raw<8>>::new(move |x, _| {
+                let _guard = guard;
+                x + 1
+            });
+        let f = LocalDynAsyncFnOnce::<_, _, _, storage::Raw<0>>::from(f);
+        drop(f.try_into_sync().unwrap());
+        assert_eq!(drops.load(Ordering::Relaxed), 3);
+    }
+}
+
+#[cfg(feature = "sync-to-async")]
+#[test]
+fn dyn_fn_mut_into_async() {
+    use futures_util::FutureExt;
+
+    let mut count = 0;
+    let f = DynFnMut::<ForFixed<u32>, ForFixed<u32>, storage::Raw<8>>::new(move |x, _| {
+        count += x;
+        count
+    });
+    let mut f: DynAsyncFnMut<_, _, _, storage::Raw<0>> = f.into();
+    assert!(f.is_sync());
+    assert_eq!(f.call_sync(1), Some(1));
+    assert_eq!(f.call_sync(2), Some(3));
+    assert_eq!(f.call(3).now_or_never(), Some(6));
+    assert_eq!(f.call_try_sync(4).now_or_never(), Some(10));
+
+    let mut count = 0;
+    let f = LocalDynFnMut::<ForFixed<()>, ForFixed<u32>>::new(|_, _| {
+        count += 1;
+        count
+    });
+    let mut f = LocalDynAsyncFnMut::<_, _, _, storage::Raw<0>>::from(f);
+    assert!(f.is_sync());
+    assert_eq!(f.call_sync(()), Some(1));
+    assert_eq!(f.call(()).now_or_never(), Some(2));
+    drop(f);
+    assert_eq!(count, 2);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn dyn_async_fn_mut_try_into_sync() {
+    use futures_util::FutureExt;
+
+    let mut count = 0;
+    let mut f =
+        DynAsyncFnMut::<ForFixed<u32>, ForFixed<u32>, storage::Raw<8>>::new_sync(move |x, _| {
+            count += x;
+            count
+        });
+    assert_eq!(f.call_sync(1), Some(1));
+    assert_eq!(f.call_sync(2), Some(3));
+    let mut f = f.try_into_sync().unwrap();
+    assert_eq!(f.call(3), 6);
+    assert_eq!(f.call(4), 10);
+
+    let mut count = 0;
+    let f = LocalDynAsyncFnMut::<ForFixed<u32>, ForFixed<u32>, storage::Raw<8>>::new(
+        async move |x, _| {
+            count += x;
+            count
+        },
+    );
+    let mut f = f.try_into_sync().unwrap_err();
+    assert!(!f.is_sync());
+    assert_eq!(f.call(1).now_or_never(), Some(1));
+    assert_eq!(f.call(2).now_or_never(), Some(3));
+
+    #[cfg(feature = "sync-to-async")]
+    {
+        let mut count = 0;
+        let f = LocalDynFnMut::<ForFixed<()>, ForFixed<u32>>::new(|_, _| {
+            count += 1;
+            count
+        });
+        let mut f = LocalDynAsyncFnMut::<_, _, _, storage::Raw<0>>::from(f);
+        assert_eq!(f.call_sync(()), Some(1));
+        let mut f = f.try_into_sync().unwrap();
+        assert_eq!(f.call(()), 2);
+        drop(f);
+        assert_eq!(count, 2);
+    }
+}
+
+#[test]
+fn constant() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    static CLONE_DROPS: AtomicUsize = AtomicUsize::new(0);
+    #[derive(Debug)]
+    struct Value(u32, &'static AtomicUsize);
+    impl Clone for Value {
+        fn clone(&self) -> Self {
+            Self(self.0, &CLONE_DROPS)
+        }
+    }
+    impl Drop for Value {
+        fn drop(&mut self) {
+            self.1.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let f =
+        LocalDynFn::<ForRef<str>, ForFixed<Value>, storage::Raw<16>>::constant(Value(42, &DROPS));
+    assert_eq!(f.call("a").0, 42);
+    assert_eq!(f.call("b").0, 42);
+    assert_eq!(CLONE_DROPS.load(Ordering::Relaxed), 2);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 0);
+    drop(f);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+
+    let f = DynFn::<ForFixed<()>, ForFixed<u8>, storage::RawOrBox<1>>::constant(42);
+    assert_eq!(f.call(()), 42);
+    assert_eq!(f.call(()), 42);
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+
+        let f = DynAsyncFn::<ForFixed<()>, ForFixed<&str>, storage::Raw<16>>::ready("ready");
+        assert!(f.is_sync());
+        assert_eq!(f.call_sync(()), Some("ready"));
+        assert_eq!(f.call_try_sync(()).now_or_never(), Some("ready"));
+        let f = LocalDynAsyncFn::<ForFixed<()>, ForFixed<u8>, storage::Raw<1>>::ready(42);
+        assert_eq!(f.call(()).now_or_never(), Some(42));
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn once_map() {
+    let drops = AtomicUsize::new(0);
+
+    type Trim<'a> = LocalDynFnOnce<'a, ForRef<str>, ForRef<str>, storage::RawOrBox<16>>;
+    let trim = Trim::new(|s, _| s.trim());
+    let first = trim.map::<ForRef<str>>(|s, _| &s[..1]);
+    assert_eq!(first.call("  arg  "), "a");
+
+    let (guard1, guard2) = (Guard(&drops), Guard(&drops));
+    let len = DynFnOnce::<ForRef<str>, ForFixed<usize>, storage::RawOrBox<16>>::new(move |s, _| {
+        drop(guard1);
+        s.len()
+    });
+    let double = len.map::<ForFixed<usize>>(move |n, _| {
+        drop(guard2);
+        2 * n
+    });
+    drop(double);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+
+    let (guard1, guard2) = (Guard(&drops), Guard(&drops));
+    let len = DynFnOnce::<ForRef<str>, ForFixed<usize>, storage::RawOrBox<16>>::new(move |s, _| {
+        drop(guard1);
+        s.len()
+    });
+    let double = len.map::<ForFixed<usize>>(move |n, _| {
+        drop(guard2);
+        2 * n
+    });
+    assert_eq!(double.call("arg"), 6);
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+}
+
+#[cfg(all(feature = "alloc", feature = "async"))]
+#[test]
+fn async_once_map() {
+    use futures_util::FutureExt;
+
+    let drops = AtomicUsize::new(0);
+
+    type Trim<'a> = DynAsyncFnOnce<'a, ForRef<str>, ForRef<str>, storage::RawOrBox<16>>;
+    let first = Trim::new_sync(|s, _| s.trim()).map::<ForRef<str>>(|s, _| &s[..1]);
+    assert!(first.is_sync());
+    assert_eq!(first.call_try_sync("  arg  ").now_or_never(), Some("a"));
+
+    let trim = LocalDynAsyncFnOnce::<ForRef<str>, ForRef<str>>::new(async |s, _| s.trim());
+    let first = trim.map::<ForRef<str>>(|s, _| &s[..1]);
+    assert!(!first.is_sync());
+    assert_eq!(first.call("  arg  ").now_or_never(), Some("a"));
+
+    let (guard1, guard2) = (Guard(&drops), Guard(&drops));
+    let len = LocalDynAsyncFnOnce::<ForRef<str>, ForFixed<usize>>::new(async move |s, _| {
+        drop(guard1);
+        s.len()
+    });
+    let double = len.map::<ForFixed<usize>>(move |n, _| {
+        drop(guard2);
+        2 * n
+    });
+    drop(double);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn call_into() {
+    use core::mem::MaybeUninit;
+
+    let f = DynFn::<ForRef<str>, ForRef<str>, storage::Raw<0>>::new(|s, _| &s[..1]);
+    let mut out = MaybeUninit::uninit();
+    assert_eq!(*f.call_into("arg", &mut out), "a");
+
+    let mut count = 0u8;
+    let mut f = LocalDynFnMut::<ForFixed<()>, ForFixed<[u8; 512]>, storage::Raw<8>>::new(|_, _| {
+        count += 1;
+        [count; 512]
+    });
+    let mut out = MaybeUninit::uninit();
+    assert_eq!(*f.call_into((), &mut out), [1; 512]);
+    let res = f.call_into((), &mut out);
+    res[0] = 0;
+    assert_eq!(res[..2], [0, 2]);
+
+    let s = String::from("value");
+    let f = DynFnOnce::<ForFixed<()>, ForFixed<String>, storage::Raw<32>>::new(move |_, _| s);
+    let mut out = MaybeUninit::uninit();
+    assert_eq!(f.call_into((), &mut out), "value");
+    // SAFETY: `out` has been initialized by `call_into`
+    drop(unsafe { out.assume_init() });
+}
+
+#[test]
+fn fast_dyn_fn() {
+    let drops = AtomicUsize::new(0);
+
+    let guard = Guard(&drops);
+    let f = LocalFastDynFn::<ForRef<str>, ForRef<str>, storage::Raw<8>>::new(move |s, _| {
+        let _guard = &guard;
+        &s[..1]
+    });
+    assert_eq!(f.call("arg"), "a");
+    let f = f.into_inner();
+    assert_eq!(f.call("arg"), "a");
+    drop(f);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+    let f =
+        FastDynFn::<ForFixed<u8>, ForFixed<u8>, storage::Raw<0>>::from(DynFn::new(|x, _| x + 1));
+    assert_eq!(f.call(41), 42);
+    assert_eq!(f.into_inner().call(41), 42);
+    assert_eq!(
+        size_of::<FastDynFn<ForFixed<u8>, ForFixed<u8>, storage::Raw<0>>>(),
+        size_of::<DynFn<ForFixed<u8>, ForFixed<u8>, storage::Raw<0>>>() + size_of::<usize>()
+    );
+
+    #[cfg(feature = "alloc")]
+    {
+        let guard = Guard(&drops);
+        let f = FastDynFn::<ForFixed<u8>, ForFixed<u8>, storage::Arc>::new(move |x, _| {
+            let _guard = &guard;
+            x + 1
+        });
+        let f2 = f.clone();
+        drop(f);
+        assert_eq!(f2.call(41), 42);
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+        drop(f2);
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+        let f = LocalFastDynFn::<ForFixed<u8>, ForFixed<u8>, storage::Rc>::new(|x, _| x + 1);
+        assert_eq!(f.clone().call(41), 42);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn compact_dyn_fn() {
+    assert_eq!(
+        size_of::<CompactDynFn<ForRef<str>, ForFixed<usize>>>(),
+        size_of::<usize>()
+    );
+    assert_eq!(
+        size_of::<Option<LocalCompactDynFn<ForRef<str>, ForFixed<usize>>>>(),
+        size_of::<usize>()
+    );
+    assert_eq!(
+        size_of::<DynFn<ForRef<str>, ForFixed<usize>, storage::Box>>(),
+        2 * size_of::<CompactDynFn<ForRef<str>, ForFixed<usize>>>()
+    );
+
+    let drops = AtomicUsize::new(0);
+
+    let guard = Guard(&drops);
+    let f = LocalCompactDynFn::<ForRef<str>, ForRef<str>>::new(move |s, _| {
+        let _guard = &guard;
+        &s[..1]
+    });
+    assert_eq!(f.call("arg"), "a");
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(f);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+    #[repr(align(64))]
+    struct OverAligned(u8);
+    let data = OverAligned(41);
+    let f = CompactDynFn::<ForFixed<u8>, ForFixed<u8>>::new(move |x, _| x + data.0 - 40);
+    assert_eq!(f.call(41), 42);
+
+    let f = CompactDynFn::<ForFixed<()>, ForFixed<u8>>::new(|_, _| 42);
+    assert_eq!(f.call(()), 42);
+    #[cfg(feature = "fn_type_name")]
+    assert!(f.type_name().contains("compact_dyn_fn"));
+    drop(f);
+}
+
+#[test]
+fn dyn_callable() {
+    use core::marker::PhantomData;
+
+    fn call_ref(f: impl DynCallable<ForRef<str>, ForFixed<usize>>) -> usize {
+        f.call("a") + call_mut(f)
+    }
+    fn call_mut(mut f: impl DynCallableMut<ForRef<str>, ForFixed<usize>>) -> usize {
+        f.call_mut("ab") + call_once(f)
+    }
+    fn call_once(f: impl DynCallableOnce<ForRef<str>, ForFixed<usize>>) -> usize {
+        f.call_once("abc")
+    }
+
+    assert_eq!(call_ref(|s: &str, _: PhantomData<&()>| s.len()), 6);
+    assert_eq!(
+        call_ref(LocalDynFn::<_, _, storage::Raw<0>>::new(
+            |s: &str, _| s.len()
+        )),
+        6
+    );
+    assert_eq!(
+        call_ref(DynFn::<_, _, CloneStorage>::new(|s: &str, _| s.len())),
+        6
+    );
+    assert_eq!(
+        call_ref(LocalFastDynFn::<_, _>::new(|s: &str, _| s.len())),
+        6
+    );
+    assert_eq!(call_ref(FastDynFn::<_, _>::new(|s: &str, _| s.len())), 6);
+    #[cfg(feature = "alloc")]
+    assert_eq!(call_ref(LocalCompactDynFn::new(|s: &str, _| s.len())), 6);
+    #[cfg(feature = "alloc")]
+    assert_eq!(call_ref(CompactDynFn::new(|s: &str, _| s.len())), 6);
+
+    let mut calls = 0;
+    assert_eq!(
+        call_mut(|s: &str, _: PhantomData<&()>| {
+            calls += 1;
+            s.len()
+        }),
+        5
+    );
+    let f = LocalDynFnMut::<_, _>::new(|s: &str, _| {
+        calls += 1;
+        s.len()
+    });
+    assert_eq!(call_mut(f), 5);
+    let f = DynFnMut::<_, _>::new(|s: &str, _| {
+        calls += 1;
+        s.len()
+    });
+    assert_eq!(call_mut(f), 5);
+    assert_eq!(calls, 6);
+
+    let s = String::from("owned");
+    assert_eq!(call_once(move |_: &str, _: PhantomData<&()>| s.len()), 5);
+    assert_eq!(
+        call_once(LocalDynFnOnce::<_, _>::new(|s: &str, _| s.len())),
+        3
+    );
+    assert_eq!(call_once(DynFnOnce::<_, _>::new(|s: &str, _| s.len())), 3);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn dyn_async_callable() {
+    use futures_util::FutureExt;
+
+    fn dispatch(f: impl DynAsyncCallable<ForRef<str>, ForFixed<usize>>) -> usize {
+        let sync = f.call_sync("a");
+        assert_eq!(sync.is_some(), f.is_sync());
+        let try_sync = f.call_try_sync("ab").now_or_never();
+        assert_eq!(f.call("ab").now_or_never(), try_sync);
+        sync.unwrap_or(1) + try_sync.unwrap() + dispatch_mut(f)
+    }
+    fn dispatch_mut(mut f: impl DynAsyncCallableMut<ForRef<str>, ForFixed<usize>>) -> usize {
+        let sync = f.call_sync_mut("abc");
+        assert_eq!(sync.is_some(), f.is_sync());
+        let try_sync = f.call_try_sync_mut("abc").now_or_never().unwrap();
+        f.call_mut("abc").now_or_never().unwrap() + try_sync + dispatch_once(f)
+    }
+    fn dispatch_once(f: impl DynAsyncCallableOnce<ForRef<str>, ForFixed<usize>>) -> usize {
+        f.call_once("abcd").now_or_never().unwrap()
+    }
+
+    let len = AtomicUsize::new(0);
+    assert_eq!(dispatch(DynAsyncFn::<_, _>::new(F(&len))), 13);
+    assert_eq!(
+        dispatch(DynAsyncFn::<_, _, CloneStorage>::new_sync(
+            |s: &str, _| s.len()
+        )),
+        13
+    );
+    let f = LocalDynAsyncFn::<ForRef<str>, ForFixed<usize>>::new(async |s, _| s.len());
+    assert_eq!(dispatch(&f), 13);
+    assert_eq!(dispatch(f), 13);
+    let f = LocalDynAsyncFn::<ForRef<str>, _, storage::Raw<0>>::new_sync(|s, _| s.len());
+    assert_eq!(dispatch(&f), 13);
+
+    let mut calls = 0;
+    let f = LocalDynAsyncFnMut::<ForRef<str>, ForFixed<usize>>::new(async |s, _| {
+        calls += 1;
+        s.len()
+    });
+    assert_eq!(dispatch_mut(f), 10);
+    let f = DynAsyncFnMut::<ForRef<str>, _>::new_sync(|s, _| s.len());
+    assert_eq!(dispatch_mut(f), 10);
+    assert_eq!(calls, 3);
+
+    assert_eq!(
+        dispatch_once(LocalDynAsyncFnOnce::<ForRef<str>, ForFixed<usize>>::new(
+            async |s, _| s.len()
+        )),
+        4
+    );
+    assert_eq!(
+        dispatch_once(DynAsyncFnOnce::<ForRef<str>, _>::new_sync(|s, _| s.len())),
+        4
+    );
+}
+
+#[test]
+fn dyn_fn_macros() {
+    use dyn_fn::args::ForTuple2;
+    let f = DynFn::<ForRef<str>, ForRef<str>>::new(dyn_fn!(|s| &s[1..]));
+    assert_eq!(f.call("test"), "est");
+    let c = dyn_fn!(|s: &str| s.len());
+    let f = LocalDynFn::<ForRef<str>, ForFixed<usize>>::new(c);
+    assert_eq!(f.call("test"), 4);
+    let f = LocalDynFn::<ForTuple2<ForFixed<u8>, ForFixed<u8>>, ForFixed<u8>>::new(dyn_fn!(
+        |(a, b)| a + b
+    ));
+    assert_eq!(f.call((1, 2)), 3);
+    let mut v = Vec::new();
+    let mut f = LocalDynFnMut::<ForFixed<u8>, ForFixed<usize>>::new(dyn_fn!(|mut x| -> usize {
+        x += 1;
+        v.push(x);
+        v.len()
+    }));
+    assert_eq!(f.call(1), 1);
+    drop(f);
+    assert_eq!(v, [2]);
+    let f = LocalDynFnOnce::<ForFixed<()>, ForFixed<Vec<u8>>, storage::Raw<32>>::new(dyn_fn!(
+        move || v
+    ));
+    assert_eq!(f.call(()), [2]);
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+
+        let s = String::from("owned");
+        let f = new_dyn_fn!(
+            LocalDynAsyncFn<ForFixed<()>, ForFixed<usize>, storage::Raw<32>>,
+            async move || s.len()
+        );
+        assert_eq!(f.call(()).now_or_never(), Some(5));
+        let mut f = new_dyn_fn!(
+            LocalDynAsyncFnMut<ForRef<str>, ForFixed<usize>>,
+            async |s: &str| s.len()
+        );
+        assert_eq!(f.call("test").now_or_never(), Some(4));
+    }
+}
+
+#[cfg(feature = "macros")]
+#[test]
+fn callback_attribute() {
+    mod handlers {
+        use dyn_fn::callback;
+
+        /// Returns the first word.
+        #[callback]
+        pub fn first_word<'a>(msg: &'a str) -> &'a str {
+            let first: &'a str = msg.split(' ').next().unwrap_or_default();
+            first
+        }
+
+        #[callback]
+        pub fn push(v: &mut Vec<u8>, x: u8) {
+            v.push(x);
+        }
+
+        #[callback]
+        pub fn split(s: &str, n: usize) -> Option<(&str, &str)> {
+            if n > s.len() {
+                return None;
+            }
+            Some(s.split_at(n))
+        }
+
+        #[callback]
+        pub fn answer() -> u8 {
+            42
+        }
+
+        #[cfg(feature = "async")]
+        #[callback]
+        pub async fn on_message(msg: &str, (a, b): (usize, usize)) -> usize {
+            async {}.await;
+            msg.len() + a + b
+        }
+
+        #[cfg(feature = "async")]
+        #[callback]
+        pub async fn first_char(msg: &str) -> &str {
+            &msg[..1]
+        }
+    }
+
+    let registry: Vec<DynFn<ForRef<str>, ForRef<str>, storage::Raw<0>>> =
+        vec![handlers::first_word(), DynFn::new(|s, _| s)];
+    assert_eq!(registry[0].call("hello world"), "hello");
+    assert_eq!(registry[1].call("hello world"), "hello world");
+
+    let push = handlers::push::<CloneStorage>();
+    let mut v = Vec::new();
+    push.call((&mut v, 42));
+    assert_eq!(v, [42]);
+    let split = handlers::split::<storage::Raw<0>>();
+    assert_eq!(split.call(("test", 1)), Some(("t", "est")));
+    assert_eq!(split.call(("test", 5)), None);
+    assert_eq!(handlers::answer::<storage::Raw<0>>().call(()), 42);
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+
+        let on_message: DynAsyncFn<_, _, storage::Raw<0>, storage::Raw<256>> =
+            handlers::on_message();
+        assert!(!on_message.is_sync());
+        assert_eq!(on_message.call(("test", (1, 2))).now_or_never(), Some(7));
+        let first_char = handlers::first_char::<CloneStorage, storage::DefaultFutureStorage>();
+        assert_eq!(first_char.call("test").now_or_never(), Some("t"));
+    }
+}
+
+#[test]
+fn try_new() {
+    use core::alloc::Layout;
+
+    let capacity = |size| Layout::from_size_align(size, align_of::<usize>()).unwrap();
+
+    let data = [1u8; 16];
+    let res = DynFn::<ForFixed<()>, ForFixed<u8>, storage::Raw<8>>::try_new(move |_, _| data[0]);
+    let err = res.unwrap_err();
+    assert_eq!(
+        err,
+        DynFnError::TooLarge {
+            required: Layout::new::<[u8; 16]>(),
+            capacity: capacity(8),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "function size 16 exceeds storage capacity 8"
+    );
+
+    let f = DynFn::<ForFixed<()>, ForFixed<u8>, storage::Raw<16>>::try_new(move |_, _| data[0]);
+    assert_eq!(f.unwrap().call(()), 1);
+
+    let x = 1u64;
+    let res =
+        LocalDynFnMut::<ForFixed<()>, ForFixed<u64>, storage::Raw<8, 1>>::try_new(move |_, _| x);
+    let err = res.unwrap_err();
+    assert_eq!(
+        err,
+        DynFnError::Misaligned {
+            required: Layout::new::<u64>(),
+            capacity: Layout::from_size_align(8, 1).unwrap(),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "function alignment 8 exceeds storage alignment 1"
+    );
+
+    #[cfg(feature = "async")]
+    {
+        let res = DynAsyncFnOnce::<ForFixed<()>, ForFixed<u8>, storage::Raw<8>>::try_new_sync(
+            move |_, _| data[0],
+        );
+        assert!(matches!(res, Err(DynFnError::TooLarge { .. })));
+    }
+
+    let f = LocalDynFn::<ForFixed<()>, ForFixed<u8>, storage::RawOrBox<8>>::try_new(move |_, _| {
+        data[0]
+    });
+    #[cfg(feature = "alloc")]
+    assert_eq!(f.unwrap().call(()), 1);
+    #[cfg(not(feature = "alloc"))]
+    assert!(matches!(f, Err(DynFnError::TooLarge { .. })));
+
+    #[cfg(feature = "alloc")]
+    {
+        let f = DynFn::<ForFixed<()>, ForFixed<u8>, storage::Box>::try_new(|_, _| 0);
+        assert_eq!(f.unwrap().call(()), 0);
+    }
+    #[cfg(all(feature = "alloc", feature = "async"))]
+    {
+        use futures_util::FutureExt;
+
+        let f =
+            DynAsyncFn::<ForFixed<()>, ForFixed<u8>, storage::Box>::try_new_sync(move |_, _| {
+                data[0]
+            });
+        assert_eq!(f.unwrap().call(()).now_or_never(), Some(1));
+    }
+}
+
+#[test]
+fn any_dyn_fn() {
+    use std::{collections::HashMap, sync::Arc};
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let mut handlers = HashMap::<&'static str, AnyDynFn>::new();
+    let c = counter.clone();
+    let incr = DynFn::<ForFixed<usize>, ForFixed<usize>>::new(move |x, _| {
+        c.fetch_add(x, Ordering::Relaxed) + x
+    });
+    handlers.insert("incr", incr.into());
+    let len = DynFn::<ForRef<str>, ForFixed<usize>>::new(|s: &str, _| s.len());
+    handlers.insert("len", len.into());
+
+    let incr = &handlers["incr"];
+    assert!(incr.is::<ForFixed<usize>, ForFixed<usize>>());
+    assert!(
+        incr.downcast::<ForFixed<usize>, ForFixed<usize>>()
+            .is_some()
+    );
+    assert!(incr.downcast::<ForRef<str>, ForFixed<usize>>().is_none());
+    assert!(incr.downcast::<ForFixed<usize>, ForFixed<u32>>().is_none());
+    assert_eq!(incr.call_as::<ForFixed<usize>, ForFixed<usize>>(2), Ok(2));
+    assert_eq!(
+        handlers["len"].call_as::<ForRef<str>, ForFixed<usize>>("test"),
+        Ok(4)
+    );
+    assert_eq!(
+        handlers["len"].call_as::<ForFixed<&'static str>, ForFixed<usize>>("test"),
+        Err(SignatureMismatch)
+    );
+    assert_eq!(SignatureMismatch.to_string(), "function signature mismatch");
+
+    let incr = handlers.remove("incr").unwrap();
+    let incr = incr
+        .downcast_into::<ForRef<str>, ForFixed<usize>>()
+        .unwrap_err();
+    let incr = incr
+        .downcast_into::<ForFixed<usize>, ForFixed<usize>>()
+        .unwrap();
+    assert_eq!(incr.call(3), 5);
+    drop(incr);
+    drop(handlers);
+    assert_eq!(Arc::strong_count(&counter), 1);
+
+    let c = counter.clone();
+    drop(AnyDynFn::from(DynFn::<ForFixed<()>>::new(move |_, _| {
+        assert!(Arc::strong_count(&c) > 1)
+    })));
+    assert_eq!(Arc::strong_count(&counter), 1);
+
+    #[cfg(feature = "async")]
+    {
+        use futures_util::FutureExt;
+
+        let double = AnyDynAsyncFn::from(DynAsyncFn::<ForFixed<u8>, ForFixed<u8>>::new_sync(
+            |x, _| 2 * x,
+        ));
+        let res = double.call_as::<ForFixed<u8>, ForFixed<u8>>(21);
+        assert_eq!(res.now_or_never(), Some(Ok(42)));
+        let res = double.call_as::<ForFixed<u8>, ForFixed<()>>(21);
+        assert_eq!(res.now_or_never(), Some(Err(SignatureMismatch)));
+        assert!(double.downcast_into::<ForFixed<u8>, ForFixed<u8>>().is_ok());
+    }
+}